@@ -21,9 +21,10 @@ use crate::executor2::{
     BoxedExecutor2, BoxedExecutor2Builder, DeleteExecutor2, FilterExecutor2,
     GenerateSeriesExecutor2Builder, GenericExchangeExecutor2Builder, HashAggExecutor2Builder,
     HashJoinExecutor2Builder, HopWindowExecutor2, InsertExecutor2, LimitExecutor2,
-    MergeSortExchangeExecutor2Builder, NestedLoopJoinExecutor2, OrderByExecutor2, ProjectExecutor2,
-    RowSeqScanExecutor2Builder, SortAggExecutor2, SortMergeJoinExecutor2, TopNExecutor2,
-    TraceExecutor2, UpdateExecutor, ValuesExecutor2,
+    MergeSortExchangeExecutor2Builder, NestedLoopJoinExecutor2, OrderByExecutor2,
+    OverWindowExecutor2, ProjectExecutor2, RowSeqScanExecutor2Builder, SortAggExecutor2,
+    SortMergeJoinExecutor2, SysRowSeqScanExecutor2Builder, TopNExecutor2, TraceExecutor2,
+    UnionExecutor2, UpdateExecutor, ValuesExecutor2,
 };
 use crate::task::{BatchTaskContext, TaskId};
 
@@ -115,6 +116,9 @@ impl<'a, C: BatchTaskContext> ExecutorBuilder<'a, C> {
             NodeBody::MergeSortExchange => MergeSortExchangeExecutor2Builder,
             NodeBody::GenerateSeries => GenerateSeriesExecutor2Builder,
             NodeBody::HopWindow => HopWindowExecutor2,
+            NodeBody::Union => UnionExecutor2,
+            NodeBody::OverWindow => OverWindowExecutor2,
+            NodeBody::SysRowSeqScan => SysRowSeqScanExecutor2Builder,
         }?;
         let input_desc = real_executor.identity().to_string();
         Ok(Box::new(TraceExecutor2::new(real_executor, input_desc)))
@@ -24,11 +24,14 @@ mod limit;
 mod merge_sort_exchange;
 pub mod monitor;
 mod order_by;
+mod over_window;
 mod project;
 mod row_seq_scan;
 mod sort_agg;
+mod sys_row_seq_scan;
 mod top_n;
 mod trace;
+mod union;
 mod update;
 mod values;
 
@@ -45,14 +48,17 @@ pub use limit::*;
 pub use merge_sort_exchange::*;
 pub use monitor::*;
 pub use order_by::*;
+pub use over_window::*;
 pub use project::*;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 pub use row_seq_scan::*;
 pub use sort_agg::*;
+pub use sys_row_seq_scan::*;
 pub use top_n::*;
 pub use trace::*;
+pub use union::*;
 pub use update::*;
 pub use values::*;
 
@@ -0,0 +1,282 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::util::chunk_coalesce::{DataChunkBuilder, DEFAULT_CHUNK_BUFFER_SIZE};
+use risingwave_common::util::sort_util::OrderPair;
+use risingwave_expr::window_function::{evaluate_partition, WindowFunctionEvaluator};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::ExecutorBuilder;
+use crate::executor2::{BoxedDataChunkStream, BoxedExecutor2, BoxedExecutor2Builder, Executor2};
+use crate::task::BatchTaskContext;
+
+/// `OverWindowExecutor2` computes the window functions over the partitions of its child, whose
+/// output must be sorted by the partition keys and then the order keys. A partition is buffered
+/// until its last row arrives, then each row is output with the results of the window functions
+/// appended.
+pub struct OverWindowExecutor2 {
+    child: BoxedExecutor2,
+    calls: Vec<WindowFunctionEvaluator>,
+    partition_by: Vec<usize>,
+    order_by: Vec<usize>,
+    schema: Schema,
+    identity: String,
+    chunk_size: usize,
+}
+
+impl OverWindowExecutor2 {
+    pub fn new(
+        child: BoxedExecutor2,
+        calls: Vec<WindowFunctionEvaluator>,
+        partition_by: Vec<usize>,
+        order_by: Vec<usize>,
+        identity: String,
+        chunk_size: usize,
+    ) -> Self {
+        let mut schema = child.schema().clone();
+        schema
+            .fields
+            .extend(calls.iter().map(|call| Field::unnamed(call.return_type())));
+        Self {
+            child,
+            calls,
+            partition_by,
+            order_by,
+            schema,
+            identity,
+            chunk_size,
+        }
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let Self {
+            child,
+            calls,
+            partition_by,
+            order_by,
+            schema,
+            chunk_size,
+            ..
+        } = *self;
+        let input_types = child.schema().data_types();
+        let mut builder = DataChunkBuilder::new(schema.data_types(), chunk_size);
+        let mut partition: Vec<Row> = vec![];
+
+        #[for_await]
+        for data_chunk in child.execute() {
+            let data_chunk = data_chunk?;
+            for row in data_chunk.rows() {
+                let row = row.to_owned_row();
+                if let Some(last) = partition.last()
+                    && partition_by.iter().any(|idx| last[*idx] != row[*idx])
+                {
+                    let results = evaluate_partition(&calls, &partition, &input_types, &order_by)?;
+                    for chunk in Self::output_partition(&mut builder, partition, results)? {
+                        yield chunk;
+                    }
+                    partition = vec![];
+                }
+                partition.push(row);
+            }
+        }
+
+        let results = evaluate_partition(&calls, &partition, &input_types, &order_by)?;
+        for chunk in Self::output_partition(&mut builder, partition, results)? {
+            yield chunk;
+        }
+        if let Some(chunk) = builder.consume_all()? {
+            yield chunk;
+        }
+    }
+
+    /// Appends the rows of a partition with the `results` of the window functions to `builder`,
+    /// returning the chunks filled up.
+    fn output_partition(
+        builder: &mut DataChunkBuilder,
+        partition: Vec<Row>,
+        results: Vec<Row>,
+    ) -> Result<Vec<DataChunk>> {
+        let mut chunks = vec![];
+        for (row, result) in partition.into_iter().zip_eq(results) {
+            if let Some(chunk) =
+                builder.append_one_row_from_datums(row.0.iter().chain(result.0.iter()))?
+            {
+                chunks.push(chunk);
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+impl BoxedExecutor2Builder for OverWindowExecutor2 {
+    fn new_boxed_executor2<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+    ) -> Result<BoxedExecutor2> {
+        ensure!(source.plan_node().get_children().len() == 1);
+        let over_window_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::OverWindow
+        )?;
+
+        let calls = over_window_node
+            .get_calls()
+            .iter()
+            .map(WindowFunctionEvaluator::new)
+            .collect::<Result<Vec<_>>>()?;
+        let partition_by = over_window_node
+            .get_partition_by()
+            .iter()
+            .map(|idx| *idx as usize)
+            .collect();
+        let order_by = over_window_node
+            .get_order_by()
+            .iter()
+            .map(|order| OrderPair::from_prost(order).column_idx)
+            .collect();
+
+        let child = source
+            .clone_for_plan(&source.plan_node().get_children()[0])
+            .build2()?;
+        Ok(Box::new(Self::new(
+            child,
+            calls,
+            partition_by,
+            order_by,
+            source.plan_node().get_identity().clone(),
+            DEFAULT_CHUNK_BUFFER_SIZE,
+        )))
+    }
+}
+
+impl Executor2 for OverWindowExecutor2 {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::agg_call::Arg;
+    use risingwave_pb::expr::window_frame::{Bound, BoundType, Type as FrameType};
+    use risingwave_pb::expr::window_function::Type as WindowFunctionType;
+    use risingwave_pb::expr::{InputRefExpr, WindowFrame, WindowFunction};
+
+    use super::*;
+    use crate::executor::test_utils::{diff_executor_output, MockExecutor};
+
+    fn window_function(r#type: WindowFunctionType) -> WindowFunctionEvaluator {
+        let int32_type = ProstDataType {
+            type_name: TypeName::Int32 as i32,
+            ..Default::default()
+        };
+        let int64_type = ProstDataType {
+            type_name: TypeName::Int64 as i32,
+            ..Default::default()
+        };
+        let bound = |r#type: BoundType| {
+            Some(Bound {
+                r#type: r#type as i32,
+                offset: 0,
+            })
+        };
+        WindowFunctionEvaluator::new(&WindowFunction {
+            r#type: r#type as i32,
+            agg_call: None,
+            args: vec![Arg {
+                input: Some(InputRefExpr { column_idx: 1 }),
+                r#type: Some(int32_type.clone()),
+            }],
+            offset: 1,
+            return_type: Some(match r#type {
+                WindowFunctionType::Lag => int32_type,
+                _ => int64_type,
+            }),
+            frame: Some(WindowFrame {
+                r#type: FrameType::Range as i32,
+                start: bound(BoundType::UnboundedPreceding),
+                end: bound(BoundType::CurrentRow),
+            }),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_over_window_executor() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int32),
+            ],
+        };
+        // The input is sorted by the partition key and then the order key.
+        let mut child = MockExecutor::new(schema);
+        child.add(DataChunk::from_pretty(
+            "i i
+             1 1
+             1 2
+             1 2",
+        ));
+        child.add(DataChunk::from_pretty(
+            "i i
+             1 3
+             2 5
+             3 1",
+        ));
+        let over_window_executor = Box::new(OverWindowExecutor2::new(
+            Box::new(child),
+            vec![
+                window_function(WindowFunctionType::RowNumber),
+                window_function(WindowFunctionType::Rank),
+                window_function(WindowFunctionType::Lag),
+            ],
+            vec![0],
+            vec![1],
+            "OverWindowExecutor2".to_string(),
+            DEFAULT_CHUNK_BUFFER_SIZE,
+        ));
+
+        let expected = MockExecutor::with_chunk(
+            DataChunk::from_pretty(
+                "i i I I i
+                 1 1 1 1 .
+                 1 2 2 2 1
+                 1 2 3 2 2
+                 1 3 4 4 2
+                 2 5 1 1 .
+                 3 1 1 1 .",
+            ),
+            over_window_executor.schema().clone(),
+        );
+        diff_executor_output(over_window_executor, Box::new(expected)).await;
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use risingwave_common::array::{DataChunk, Row, RowDeserializer};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::ExecutorBuilder;
+use crate::executor2::{BoxedDataChunkStream, BoxedExecutor2, BoxedExecutor2Builder, Executor2};
+use crate::task::BatchTaskContext;
+
+/// `SysRowSeqScanExecutor2` scans the rows of a system catalog table, e.g., `pg_catalog.pg_class`.
+/// The rows are generated by the frontend from its catalog, so the executor only decodes them.
+pub struct SysRowSeqScanExecutor2 {
+    rows: Vec<Row>,
+    schema: Schema,
+    identity: String,
+    chunk_size: usize,
+}
+
+impl SysRowSeqScanExecutor2 {
+    pub fn new(rows: Vec<Row>, schema: Schema, identity: String, chunk_size: usize) -> Self {
+        Self {
+            rows,
+            schema,
+            identity,
+            chunk_size,
+        }
+    }
+}
+
+impl Executor2 for SysRowSeqScanExecutor2 {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl SysRowSeqScanExecutor2 {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let data_types = self.schema.data_types();
+        for rows in self.rows.chunks(self.chunk_size) {
+            if data_types.is_empty() {
+                // All the columns are pruned, e.g., `SELECT count(*) FROM pg_class`.
+                yield DataChunk::new_dummy(rows.len());
+            } else {
+                yield DataChunk::from_rows(rows, &data_types)?;
+            }
+        }
+    }
+}
+
+pub struct SysRowSeqScanExecutor2Builder {}
+
+impl BoxedExecutor2Builder for SysRowSeqScanExecutor2Builder {
+    fn new_boxed_executor2<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+    ) -> Result<BoxedExecutor2> {
+        let node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::SysRowSeqScan
+        )?;
+
+        let fields = node.get_fields().iter().map(Field::from).collect();
+        let schema = Schema { fields };
+
+        let deserializer = RowDeserializer::new(schema.data_types());
+        let rows = node
+            .get_rows()
+            .iter()
+            .map(|row| deserializer.value_decode(row.as_slice()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Box::new(Self::new(
+            rows,
+            schema,
+            source.plan_node().get_identity().clone(),
+            DEFAULT_CHUNK_BUFFER_SIZE,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::Array;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sys_row_seq_scan_executor() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "oid"),
+            Field::with_name(DataType::Varchar, "nspname"),
+        ]);
+        let rows = ["dev", "pg_catalog", "information_schema"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Row(vec![
+                    Some(ScalarImpl::Int32(i as i32)),
+                    Some(ScalarImpl::Utf8(name.to_string())),
+                ])
+            })
+            .collect();
+
+        let executor = Box::new(SysRowSeqScanExecutor2::new(
+            rows,
+            schema,
+            "SysRowSeqScanExecutor2".to_string(),
+            2,
+        ));
+        let mut stream = executor.execute();
+
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(chunk.cardinality(), 2);
+        assert_eq!(chunk.column_at(0).array().as_int32().value_at(1), Some(1));
+        assert_eq!(
+            chunk.column_at(1).array().as_utf8().value_at(1),
+            Some("pg_catalog")
+        );
+
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(chunk.cardinality(), 1);
+        assert_eq!(
+            chunk.column_at(1).array().as_utf8().value_at(0),
+            Some("information_schema")
+        );
+
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::sync::Arc;
 use std::vec::Vec;
 
 use futures_async_stream::try_stream;
-use risingwave_common::array::DataChunk;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Row};
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
@@ -37,6 +38,15 @@ struct TopNHeap {
 }
 
 impl TopNHeap {
+    /// Creates an empty heap with the same order and size.
+    fn empty_clone(&self) -> Self {
+        Self {
+            order_pairs: self.order_pairs.clone(),
+            min_heap: BinaryHeap::new(),
+            size: self.size,
+        }
+    }
+
     fn insert(&mut self, elem: HeapElem) {
         if self.min_heap.len() < self.size {
             self.min_heap.push(Reverse(elem));
@@ -71,6 +81,9 @@ impl TopNHeap {
             .drain_sorted()
             .map(|e| e.0.chunk)
             .collect::<Vec<_>>();
+        if chunks.len() <= offset {
+            return None;
+        }
         chunks.reverse();
 
         // Skip the first `offset` elements
@@ -83,9 +96,12 @@ impl TopNHeap {
     }
 }
 
+/// `TopNExecutor2` outputs the top N rows of its child from `offset`, or the top N rows in each
+/// group if `group_key` is not empty.
 pub struct TopNExecutor2 {
     child: BoxedExecutor2,
     top_n_heap: TopNHeap,
+    group_key: Vec<usize>,
     identity: String,
     chunk_size: usize,
     offset: usize,
@@ -112,6 +128,11 @@ impl BoxedExecutor2Builder for TopNExecutor2 {
                 order_pairs,
                 top_n_node.get_limit() as usize,
                 top_n_node.get_offset() as usize,
+                top_n_node
+                    .get_group_key()
+                    .iter()
+                    .map(|idx| *idx as usize)
+                    .collect(),
                 source.plan_node().get_identity().clone(),
                 DEFAULT_CHUNK_BUFFER_SIZE,
             )));
@@ -126,6 +147,7 @@ impl TopNExecutor2 {
         order_pairs: Vec<OrderPair>,
         limit: usize,
         offset: usize,
+        group_key: Vec<usize>,
         identity: String,
         chunk_size: usize,
     ) -> Self {
@@ -135,6 +157,7 @@ impl TopNExecutor2 {
                 size: limit + offset,
                 order_pairs: Arc::new(order_pairs),
             },
+            group_key,
             child,
             identity,
             chunk_size,
@@ -160,14 +183,35 @@ impl Executor2 for TopNExecutor2 {
 impl TopNExecutor2 {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(mut self: Box<Self>) {
+        let mut group_heaps: HashMap<Row, TopNHeap> = HashMap::new();
+
         #[for_await]
         for data_chunk in self.child.execute() {
             let data_chunk = data_chunk?;
-            self.top_n_heap.fit(data_chunk);
+            if self.group_key.is_empty() {
+                self.top_n_heap.fit(data_chunk);
+                continue;
+            }
+            for chunk in DataChunk::rechunk(&[data_chunk], 1)? {
+                let row = chunk.row_at(0)?.0.to_owned_row();
+                let key = Row(self.group_key.iter().map(|idx| row[*idx].clone()).collect());
+                group_heaps
+                    .entry(key)
+                    .or_insert_with(|| self.top_n_heap.empty_clone())
+                    .fit(chunk);
+            }
         }
 
-        if let Some(data_chunk) = self.top_n_heap.dump(self.offset) {
-            let batch_chunks = DataChunk::rechunk(&[data_chunk], DEFAULT_CHUNK_BUFFER_SIZE)?;
+        let data_chunks = if self.group_key.is_empty() {
+            self.top_n_heap.dump(self.offset).into_iter().collect_vec()
+        } else {
+            group_heaps
+                .into_values()
+                .filter_map(|mut heap| heap.dump(self.offset))
+                .collect_vec()
+        };
+        if !data_chunks.is_empty() {
+            let batch_chunks = DataChunk::rechunk(&data_chunks, DEFAULT_CHUNK_BUFFER_SIZE)?;
             for ret_chunk in batch_chunks {
                 yield ret_chunk
             }
@@ -179,7 +223,7 @@ impl TopNExecutor2 {
 mod tests {
     use futures::stream::StreamExt;
     use itertools::Itertools;
-    use risingwave_common::array::{Array, DataChunk, I32Array};
+    use risingwave_common::array::{Array, DataChunk, DataChunkTestExt, I32Array};
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::types::DataType;
     use risingwave_common::util::sort_util::OrderType;
@@ -215,6 +259,7 @@ mod tests {
             order_pairs,
             3,
             1,
+            vec![],
             "TopNExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
         ));
@@ -238,4 +283,53 @@ mod tests {
         let res = stream.next().await;
         assert!(matches!(res, None));
     }
+
+    #[tokio::test]
+    async fn test_group_top_n_executor() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int32),
+            ],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        mock_executor.add(DataChunk::from_pretty(
+            "i i
+             1 5
+             2 4
+             1 3
+             2 2
+             1 1
+             3 7",
+        ));
+        let top_n_executor = Box::new(TopNExecutor2::new(
+            Box::new(mock_executor),
+            vec![OrderPair::new(1, OrderType::Ascending)],
+            2,
+            0,
+            vec![0],
+            "TopNExecutor2".to_string(),
+            DEFAULT_CHUNK_BUFFER_SIZE,
+        ));
+
+        let mut stream = top_n_executor.execute();
+        let res = stream.next().await.unwrap().unwrap();
+        let rows = (0..res.cardinality())
+            .map(|idx| res.row_at(idx).unwrap().0.to_owned_row())
+            .sorted()
+            .collect_vec();
+        let expected = DataChunk::from_pretty(
+            "i i
+             1 1
+             1 3
+             2 2
+             2 4
+             3 7",
+        );
+        let expected_rows = (0..expected.cardinality())
+            .map(|idx| expected.row_at(idx).unwrap().0.to_owned_row())
+            .collect_vec();
+        assert_eq!(rows, expected_rows);
+        assert!(stream.next().await.is_none());
+    }
 }
@@ -0,0 +1,140 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::ExecutorBuilder;
+use crate::executor2::{BoxedDataChunkStream, BoxedExecutor2, BoxedExecutor2Builder, Executor2};
+use crate::task::BatchTaskContext;
+
+/// `UnionExecutor2` outputs all the rows of its children one after another, i.e., `UNION ALL`.
+pub struct UnionExecutor2 {
+    children: Vec<BoxedExecutor2>,
+    schema: Schema,
+    identity: String,
+}
+
+impl UnionExecutor2 {
+    pub fn new(children: Vec<BoxedExecutor2>, identity: String) -> Self {
+        let schema = children[0].schema().clone();
+        Self {
+            children,
+            schema,
+            identity,
+        }
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        for child in self.children {
+            #[for_await]
+            for data_chunk in child.execute() {
+                yield data_chunk?;
+            }
+        }
+    }
+}
+
+impl BoxedExecutor2Builder for UnionExecutor2 {
+    fn new_boxed_executor2<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+    ) -> Result<BoxedExecutor2> {
+        ensure!(!source.plan_node().get_children().is_empty());
+        try_match_expand!(source.plan_node().get_node_body().unwrap(), NodeBody::Union)?;
+
+        let children = source
+            .plan_node()
+            .get_children()
+            .iter()
+            .map(|child_plan| source.clone_for_plan(child_plan).build2())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(Self::new(
+            children,
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+impl Executor2 for UnionExecutor2 {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::{diff_executor_output, MockExecutor};
+
+    #[tokio::test]
+    async fn test_union_executor() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Float32),
+            ],
+        };
+        let left = MockExecutor::with_chunk(
+            DataChunk::from_pretty(
+                "i f
+                 1 6.1
+                 2 8.4",
+            ),
+            schema.clone(),
+        );
+        let mut right = MockExecutor::new(schema.clone());
+        right.add(DataChunk::from_pretty(
+            "i f
+             2 8.4
+             3 3.9",
+        ));
+        right.add(DataChunk::from_pretty(
+            "i f
+             4 .",
+        ));
+        let union_executor = Box::new(UnionExecutor2::new(
+            vec![Box::new(left), Box::new(right)],
+            "UnionExecutor2".to_string(),
+        ));
+
+        let expected = MockExecutor::with_chunk(
+            DataChunk::from_pretty(
+                "i f
+                 1 6.1
+                 2 8.4
+                 2 8.4
+                 3 3.9
+                 4 .",
+            ),
+            schema,
+        );
+        diff_executor_output(union_executor, Box::new(expected)).await;
+    }
+}
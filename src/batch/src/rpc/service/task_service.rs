@@ -70,8 +70,19 @@ impl TaskService for BatchServiceImpl {
     #[cfg_attr(coverage, no_coverage)]
     async fn abort_task(
         &self,
-        _: Request<AbortTaskRequest>,
+        request: Request<AbortTaskRequest>,
     ) -> Result<Response<AbortTaskResponse>, Status> {
-        todo!()
+        let req = request.into_inner();
+
+        let res = self
+            .mgr
+            .abort_task(req.get_task_id().expect("no task id found"));
+        match res {
+            Ok(_) => Ok(Response::new(AbortTaskResponse { status: None })),
+            Err(e) => {
+                error!("failed to abort task {}", e);
+                Err(e.to_grpc_status())
+            }
+        }
     }
 }
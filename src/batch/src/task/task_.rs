@@ -24,6 +24,7 @@ use risingwave_pb::batch_plan::{
 };
 use risingwave_pb::task_service::task_info::TaskStatus;
 use risingwave_pb::task_service::GetDataResponse;
+use tokio::sync::oneshot;
 use tracing_futures::Instrument;
 
 use crate::executor::ExecutorBuilder;
@@ -182,6 +183,9 @@ pub struct BatchTaskExecution<C> {
     /// The execution failure.
     failure: Arc<Mutex<Option<RwError>>>,
 
+    /// Notifies the running task to stop. Taken when the task is aborted.
+    shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
+
     epoch: u64,
 }
 
@@ -199,6 +203,7 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             receivers: Mutex::new(Vec::new()),
             context,
             failure: Arc::new(Mutex::new(None)),
+            shutdown_tx: Mutex::new(None),
             epoch,
         })
     }
@@ -232,6 +237,8 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
         self.receivers
             .lock()
             .extend(receivers.into_iter().map(Some));
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        *self.shutdown_tx.lock() = Some(shutdown_tx);
         let failure = self.failure.clone();
         let task_id = self.task_id.clone();
         tokio::spawn(async move {
@@ -243,15 +250,21 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             let join_handle = tokio::spawn(async move {
                 // We should only pass a reference of sender to execution because we should only
                 // close it after task error has been set.
-                if let Err(e) = try_execute(exec, &mut sender)
-                    .instrument(tracing::trace_span!(
-                        "batch_execute",
-                        task_id = ?task_id.task_id,
-                        stage_id = ?task_id.stage_id,
-                        query_id = ?task_id.query_id,
-                    ))
-                    .await
-                {
+                let execution = try_execute(exec, &mut sender).instrument(tracing::trace_span!(
+                    "batch_execute",
+                    task_id = ?task_id.task_id,
+                    stage_id = ?task_id.stage_id,
+                    query_id = ?task_id.query_id,
+                ));
+                let result = tokio::select! {
+                    result = execution => result,
+                    // The branch is disabled if the sender is dropped without aborting the task.
+                    Ok(_) = shutdown_rx => {
+                        info!("Batch task {:?} aborted", &task_id);
+                        Err(ErrorCode::QueryCancelled("abort request".to_string()).into())
+                    }
+                };
+                if let Err(e) = result {
                     // Prints the entire backtrace of error.
                     error!("Execution failed [{:?}]: {:?}", &task_id, &e);
                     *failure.lock() = Some(e);
@@ -284,6 +297,16 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
         Ok(task_output)
     }
 
+    /// Stops the running task, whose consumers will then receive the abort error. It's a no-op if
+    /// the task has finished or has been aborted.
+    pub fn abort(&self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.lock().take() {
+            // The task may have finished, which drops the receiver.
+            let _ = shutdown_tx.send(());
+            *self.state.lock() = TaskStatus::Cancelling;
+        }
+    }
+
     pub fn get_error(&self) -> Option<RwError> {
         self.failure.lock().clone()
     }
@@ -72,6 +72,19 @@ impl BatchManager {
             .get_task_output(output_id)
     }
 
+    /// Aborts the task if it's still running. The task is kept so that its consumers can get the
+    /// abort error.
+    pub fn abort_task(&self, sid: &ProstTaskId) -> Result<()> {
+        let task_id = TaskId::from(sid);
+        match self.tasks.lock().get(&task_id) {
+            Some(task) => {
+                task.abort();
+                Ok(())
+            }
+            None => Err(TaskNotFound.into()),
+        }
+    }
+
     #[cfg(test)]
     pub fn remove_task(
         &self,
@@ -180,4 +193,42 @@ mod tests {
             .to_string()
             .contains("can not create duplicate task with the same id"));
     }
+
+    #[tokio::test]
+    async fn test_abort_task() {
+        use risingwave_pb::batch_plan::*;
+
+        let manager = BatchManager::new();
+        let plan = PlanFragment {
+            root: Some(PlanNode {
+                children: vec![],
+                identity: "".to_string(),
+                node_body: Some(NodeBody::Values(ValuesNode {
+                    tuples: vec![],
+                    fields: vec![],
+                })),
+            }),
+            exchange_info: Some(ExchangeInfo {
+                mode: DistributionMode::Single as i32,
+                distribution: None,
+            }),
+        };
+        let context = ComputeNodeContext::new_for_test();
+        let task_id = TaskId {
+            ..Default::default()
+        };
+        manager.fire_task(&task_id, plan, 0, context).unwrap();
+        manager.abort_task(&task_id).unwrap();
+        // Aborting a task again is a no-op.
+        manager.abort_task(&task_id).unwrap();
+        assert!(manager
+            .check_if_task_running(&crate::task::TaskId::from(&task_id))
+            .is_err());
+
+        let unknown_task_id = TaskId {
+            query_id: "unknown".to_string(),
+            ..Default::default()
+        };
+        assert!(manager.abort_task(&unknown_task_id).is_err());
+    }
 }
@@ -145,6 +145,8 @@ async fn main() {
         bloom_false_positive: opts.bloom_false_positive,
         sstable_size_mb: opts.table_size_mb,
         block_size_kb: opts.block_size_kb,
+        zstd_dictionary_size_kb: 0,
+        compression_algorithm_per_level: vec![],
         share_buffers_sync_parallelism: opts.share_buffers_sync_parallelism,
         data_directory: "hummock_001".to_string(),
         write_conflict_detection_enabled: opts.write_conflict_detection_enabled,
@@ -153,6 +155,9 @@ async fn main() {
         disable_remote_compactor: true,
         enable_local_spill: false,
         local_object_store: "memory".to_string(),
+        disk_cache_dir: "".to_string(),
+        disk_cache_capacity_mb: 0,
+        disk_cache_admit_on_first_read: false,
     });
 
     let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
@@ -115,6 +115,18 @@ pub struct StorageConfig {
     #[serde(default = "default::bloom_false_positive")]
     pub bloom_false_positive: f64,
 
+    /// Maximum size of the dictionary trained for each SST, when the blocks are compressed with
+    /// zstd. 0 disables the dictionary.
+    #[serde(default = "default::zstd_dictionary_size_kb")]
+    pub zstd_dictionary_size_kb: u32,
+
+    /// Compression algorithm of the SSTs written to each level by compactions, among `none`,
+    /// `lz4` and `zstd`, e.g., `["none", "none", "lz4", "lz4", "lz4", "lz4", "zstd"]`. Levels
+    /// beyond the end use the last one. SSTs are not compressed if it's empty, which is the
+    /// default.
+    #[serde(default = "default::compression_algorithm_per_level")]
+    pub compression_algorithm_per_level: Vec<String>,
+
     /// parallelism while syncing share buffers into L0 SST. Should NOT be 0.
     #[serde(default = "default::share_buffers_sync_parallelism")]
     pub share_buffers_sync_parallelism: u32,
@@ -152,6 +164,20 @@ pub struct StorageConfig {
     /// Local object store root. We should call `get_local_object_store` to get the object store.
     #[serde(default = "default::local_object_store")]
     pub local_object_store: String,
+
+    /// Directory of the block cache on local disk. Empty to disable the disk cache.
+    #[serde(default = "default::disk_cache_dir")]
+    pub disk_cache_dir: String,
+
+    /// Capacity of the block cache on local disk.
+    #[serde(default = "default::disk_cache_capacity_mb")]
+    pub disk_cache_capacity_mb: usize,
+
+    /// Whether to admit a block to the disk cache on its first read from the object store.
+    /// Otherwise only the blocks read twice are admitted, so that one-off scans don't flush the
+    /// disk cache.
+    #[serde(default = "default::disk_cache_admit_on_first_read")]
+    pub disk_cache_admit_on_first_read: bool,
 }
 
 impl Default for StorageConfig {
@@ -212,6 +238,14 @@ mod default {
         0.01
     }
 
+    pub fn zstd_dictionary_size_kb() -> u32 {
+        0
+    }
+
+    pub fn compression_algorithm_per_level() -> Vec<String> {
+        vec![]
+    }
+
     pub fn share_buffers_sync_parallelism() -> u32 {
         2
     }
@@ -253,6 +287,19 @@ mod default {
         "tempdisk".to_string()
     }
 
+    pub fn disk_cache_dir() -> String {
+        "".to_string()
+    }
+
+    pub fn disk_cache_capacity_mb() -> usize {
+        // 10GB
+        10240
+    }
+
+    pub fn disk_cache_admit_on_first_read() -> bool {
+        false
+    }
+
     pub fn checkpoint_interval_ms() -> u32 {
         100
     }
@@ -106,6 +106,11 @@ pub enum ErrorCode {
     ParseError(String),
     #[error("Bind error: {0}")]
     BindError(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    /// The query is cancelled by the user or by a timeout, where the reason is given.
+    #[error("canceling statement due to {0}")]
+    QueryCancelled(String),
     #[error("Catalog error: {0}")]
     CatalogError(BoxedError),
     #[error("Out of range")]
@@ -304,6 +309,8 @@ impl ErrorCode {
             ErrorCode::BindError(_) => 23,
             ErrorCode::UnknownWorker => 24,
             ErrorCode::ConnectorError(_) => 25,
+            ErrorCode::PermissionDenied(_) => 26,
+            ErrorCode::QueryCancelled(_) => 27,
             ErrorCode::UnknownError(_) => 101,
         }
     }
@@ -156,6 +156,8 @@ async fn test_table_v2_materialize() -> Result<()> {
         keyspace.clone(),
         vec![OrderPair::new(0, OrderType::Ascending)],
         all_column_ids.clone(),
+        false,
+        1,
         2,
     )
     .boxed()
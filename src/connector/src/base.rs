@@ -155,6 +155,8 @@ pub struct Column {
 /// The third-party message structs will eventually be transformed into this struct.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SourceMessage {
+    /// The key of the message. Only set by the connectors whose messages are keyed, like Kafka.
+    pub key: Option<Bytes>,
     pub payload: Option<Bytes>,
     pub offset: String,
     pub split_id: String,
@@ -82,6 +82,7 @@ impl DatagenEventGenerator {
 
             let value = Value::Object(map);
             let msg = SourceMessage {
+                key: None,
                 payload: Some(Bytes::from(value.to_string())),
                 offset: (self.last_offset + i).to_string(),
                 split_id: 0.to_string(),
@@ -33,4 +33,8 @@ pub struct S3Properties {
     pub access: String,
     #[serde(rename = "s3.credentials.secret", default)]
     pub secret: String,
+    /// The quote of `ROW FORMAT CSV`, inside which a record may span lines. It's not a `WITH`
+    /// option, and every line is a record if it's `None`.
+    #[serde(skip)]
+    pub csv_quote: Option<char>,
 }
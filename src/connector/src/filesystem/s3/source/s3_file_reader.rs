@@ -26,11 +26,10 @@ use itertools::Itertools;
 use log::{error, info};
 use mpsc::Sender;
 use serde::{Deserialize, Serialize};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 use tokio::sync::{mpsc, watch};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::io;
-use tokio_util::io::ReaderStream;
 
 use crate::aws_utils::{default_conn_config, s3_client, AwsConfigV2, AwsCredentialV2};
 use crate::base::{SourceMessage, SplitReader};
@@ -44,7 +43,46 @@ use crate::{Column, ConnectorState, ConnectorStateV2, SplitMetaData};
 
 const MAX_CHANNEL_BUFFER_SIZE: usize = 2048;
 const READ_CHUNK_SIZE: usize = 1024;
-const STREAM_READER_CAPACITY: usize = 4096;
+
+/// Strips the trailing `\n` or `\r\n` of a line.
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Returns whether a quoted CSV field is still open at the end of `line`, given whether it is open
+/// at the start. A quote inside a quoted field is escaped by doubling it, which toggles twice, so
+/// only the parity of the quotes matters. A backslash is an ordinary character.
+fn is_in_quotes(line: &[u8], quote: &[u8], in_quotes: bool) -> bool {
+    // UTF-8 is self-synchronizing, so a multi-byte quote never matches in the middle of a
+    // character.
+    let quotes = line.windows(quote.len()).filter(|w| *w == quote).count();
+    in_quotes ^ (quotes % 2 == 1)
+}
+
+/// Reads the next record into `record`, which is cleared first, and returns whether there is one.
+/// Records are separated by newlines, except the newlines inside quoted fields if the CSV `quote`
+/// is given.
+async fn read_record(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    quote: Option<&[u8]>,
+    record: &mut Vec<u8>,
+) -> std::io::Result<bool> {
+    record.clear();
+    let mut in_quotes = false;
+    loop {
+        let line_start = record.len();
+        if reader.read_until(b'\n', record).await? == 0 {
+            return Ok(!record.is_empty());
+        }
+        if let Some(quote) = quote {
+            in_quotes = is_in_quotes(&record[line_start..], quote, in_quotes);
+        }
+        if !in_quotes {
+            return Ok(true);
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 struct S3InnerMessage {
@@ -148,7 +186,7 @@ impl Drop for S3FileReader {
 }
 
 impl S3FileReader {
-    fn build_from_config(s3_source_config: S3SourceConfig) -> Self {
+    fn build_from_config(s3_source_config: S3SourceConfig, csv_quote: Option<char>) -> Self {
         let (tx, rx) = mpsc::channel(MAX_CHANNEL_BUFFER_SIZE);
         let (split_s, mut split_r) = mpsc::unbounded_channel();
         let (signal_s, mut signal_r) = watch::channel(StatusWatch::Running);
@@ -168,7 +206,7 @@ impl S3FileReader {
                 tokio::select! {
                     s3_split = split_r.recv() => {
                         if let Some(s3_split) = s3_split {
-                            let _rs =S3FileReader::stream_read(s3_client.clone(), s3_split.clone(), tx.clone()).await;
+                            let _rs =S3FileReader::stream_read(s3_client.clone(), s3_split.clone(), tx.clone(), csv_quote).await;
                         } else {
                             continue;
                         }
@@ -205,6 +243,7 @@ impl S3FileReader {
         client_for_s3: s3_client::Client,
         s3_file_split: S3FileSplit,
         s3_msg_sender: Sender<S3InnerMessage>,
+        csv_quote: Option<char>,
     ) -> Result<()> {
         let bucket = s3_file_split.bucket.clone();
         let s3_file = s3_file_split.s3_file.clone();
@@ -245,30 +284,27 @@ impl S3FileReader {
                         return Err(anyhow::Error::from(err));
                     }
                 };
-                let mut stream = ReaderStream::with_capacity(reader, STREAM_READER_CAPACITY);
-
-                while let Some(result) = stream.next().await {
-                    match result {
-                        Ok(read_bytes) => {
-                            let msg_id = format!(
-                                "s3://{}/{}",
-                                s3_file_split.clone().bucket.clone(),
-                                s3_file_split.clone().s3_file.object.path
-                            );
-                            let s3_inner_msg = S3InnerMessage {
-                                msg_id,
-                                payload: read_bytes,
-                            };
-                            if s3_msg_sender.send(s3_inner_msg).await.is_err() {
-                                return Err(anyhow::Error::from(crate::filesystem::s3::s3_dir::FileSystemOptError::GetS3ObjectError(
-                                    bucket.clone(),
-                                    s3_file.clone().object.path,
-                                )));
-                            }
-                        }
-                        Err(read_bytes_err) => {
-                            return Err(anyhow::Error::from(read_bytes_err));
-                        }
+                // Each record of the object is a message, so that a record of a line-delimited
+                // format such as CSV or JSON is never split across messages.
+                let msg_id = format!("s3://{}/{}", bucket, s3_file.object.path);
+                let quote = csv_quote.map(|quote| quote.to_string().into_bytes());
+                let mut record = Vec::new();
+                while read_record(&mut reader, quote.as_deref(), &mut record).await? {
+                    let payload = trim_line_ending(&record);
+                    if payload.is_empty() {
+                        continue;
+                    }
+                    let s3_inner_msg = S3InnerMessage {
+                        msg_id: msg_id.clone(),
+                        payload: Bytes::copy_from_slice(payload),
+                    };
+                    if s3_msg_sender.send(s3_inner_msg).await.is_err() {
+                        return Err(anyhow::Error::from(
+                            crate::filesystem::s3::s3_dir::FileSystemOptError::GetS3ObjectError(
+                                bucket.clone(),
+                                s3_file.clone().object.path,
+                            ),
+                        ));
                     }
                 }
                 Ok(())
@@ -327,6 +363,7 @@ impl SplitReader for S3FileReader {
     where
         Self: Sized,
     {
+        let csv_quote = props.csv_quote;
         let s3_basic_config = S3SourceBasicConfig::from(props);
         let credential = if s3_basic_config.secret.is_empty() || s3_basic_config.access.is_empty() {
             AwsCredentialV2::None
@@ -351,7 +388,7 @@ impl SplitReader for S3FileReader {
             custom_config: Some(AwsCustomConfig::default()),
             sqs_config: SqsReceiveMsgConfig::default(),
         };
-        let mut s3_file_reader = S3FileReader::build_from_config(s3_source_config);
+        let mut s3_file_reader = S3FileReader::build_from_config(s3_source_config, csv_quote);
         if let ConnectorStateV2::State(s3_state) = state {
             if let Err(err) = s3_file_reader.add_s3_split(s3_state) {
                 Err(err)
@@ -382,6 +419,7 @@ impl SplitReader for S3FileReader {
                     };
                     self.split_offset.insert(msg_id.clone(), new_offset);
                     SourceMessage {
+                        key: None,
                         payload: Some(msg.payload),
                         offset: new_offset.to_string(),
                         split_id: msg_id,
@@ -398,7 +436,9 @@ mod test {
     use bytes::Bytes;
 
     use crate::base::SplitReader;
-    use crate::filesystem::s3::source::s3_file_reader::{S3FileReader, S3FileSplit};
+    use crate::filesystem::s3::source::s3_file_reader::{
+        is_in_quotes, read_record, trim_line_ending, S3FileReader, S3FileSplit,
+    };
     use crate::filesystem::s3::S3Properties;
     use crate::{ConnectorState, ConnectorStateV2};
 
@@ -417,6 +457,7 @@ mod test {
             match_pattern: None,
             access: "".to_string(),
             secret: "".to_string(),
+            csv_quote: None,
         }
     }
 
@@ -444,6 +485,62 @@ mod test {
         s3_file_reader.await.unwrap()
     }
 
+    #[test]
+    fn test_trim_line_ending() {
+        assert_eq!(trim_line_ending(b"a,b\n"), b"a,b");
+        assert_eq!(trim_line_ending(b"a,b\r\n"), b"a,b");
+        assert_eq!(trim_line_ending(b"a,b"), b"a,b");
+    }
+
+    #[test]
+    fn test_is_in_quotes() {
+        assert!(!is_in_quotes(b"a,\"b,c\",d\n", b"\"", false));
+        assert!(is_in_quotes(b"a,\"b\n", b"\"", false));
+        assert!(!is_in_quotes(b"c\",d\n", b"\"", true));
+        assert!(is_in_quotes(b"a,\"b \"\"c\"\"\n", b"\"", false));
+        assert!(is_in_quotes(b"a,'b\n", b"'", false));
+        assert!(!is_in_quotes(b"a,\"b\n", b"'", false));
+        // A backslash doesn't escape the quote.
+        assert!(!is_in_quotes(b"\"C:\\\",x\n", b"\"", false));
+        assert!(is_in_quotes("a,«b\n".as_bytes(), "«".as_bytes(), false));
+    }
+
+    async fn read_records(mut data: &[u8], quote: Option<char>) -> Vec<Vec<u8>> {
+        let quote = quote.map(|quote| quote.to_string().into_bytes());
+        let mut records = vec![];
+        let mut record = vec![];
+        while read_record(&mut data, quote.as_deref(), &mut record)
+            .await
+            .unwrap()
+        {
+            records.push(trim_line_ending(&record).to_vec());
+        }
+        records
+    }
+
+    #[tokio::test]
+    async fn test_read_record() {
+        // A newline inside a quoted field belongs to the record.
+        assert_eq!(
+            read_records(b"a,\"b\nc\"\nd,e\n", Some('"')).await,
+            vec![b"a,\"b\nc\"".to_vec(), b"d,e".to_vec()]
+        );
+        assert_eq!(
+            read_records(b"a,'b\nc'\nd,e", Some('\'')).await,
+            vec![b"a,'b\nc'".to_vec(), b"d,e".to_vec()]
+        );
+        // A backslash at the end of a field doesn't keep the field open.
+        assert_eq!(
+            read_records(b"\"C:\\\",x\ny,z\n", Some('"')).await,
+            vec![b"\"C:\\\",x".to_vec(), b"y,z".to_vec()]
+        );
+        // Every line is a record without a quote, e.g., for JSON.
+        assert_eq!(
+            read_records(b"{\"a\": \"b\\\"\"}\n{\"a\": \"c\"}\n", None).await,
+            vec![b"{\"a\": \"b\\\"\"}".to_vec(), b"{\"a\": \"c\"}".to_vec()]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     #[ignore]
     async fn test_s3_file_reader() {
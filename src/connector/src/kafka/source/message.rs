@@ -21,6 +21,7 @@ use crate::base::SourceMessage;
 impl<'a> From<BorrowedMessage<'a>> for SourceMessage {
     fn from(message: BorrowedMessage<'a>) -> Self {
         SourceMessage {
+            key: message.key().map(Bytes::copy_from_slice),
             // TODO(TaoWu): Possible performance improvement: avoid memory copying here.
             payload: message.payload().map(Bytes::copy_from_slice),
             offset: message.offset().to_string(),
@@ -29,6 +29,7 @@ pub struct KinesisMessage {
 impl From<KinesisMessage> for SourceMessage {
     fn from(msg: KinesisMessage) -> Self {
         SourceMessage {
+            key: None,
             payload: msg
                 .payload
                 .as_ref()
@@ -28,6 +28,7 @@ pub struct NexmarkMessage {
 impl From<NexmarkMessage> for SourceMessage {
     fn from(msg: NexmarkMessage) -> Self {
         SourceMessage {
+            key: None,
             payload: msg
                 .payload
                 .as_ref()
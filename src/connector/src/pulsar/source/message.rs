@@ -21,6 +21,7 @@ impl From<Message<Vec<u8>>> for SourceMessage {
         let message_id = msg.message_id.id;
 
         SourceMessage {
+            key: None,
             payload: Some(bytes::Bytes::from(msg.payload.data)),
             offset: format!(
                 "{}:{}:{}:{}",
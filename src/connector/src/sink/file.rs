@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use risingwave_common::array::StreamChunk;
+use risingwave_common::catalog::Schema;
+use serde::Deserialize;
+
+use crate::sink::{chunk_to_json, Sink};
+
+pub const FILE_SINK: &str = "file";
+pub const CONSOLE_SINK: &str = "console";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FileSinkConfig {
+    /// The file to append the records to. The records are written to stdout if it's `None`.
+    #[serde(rename = "file.path")]
+    pub path: Option<String>,
+}
+
+impl FileSinkConfig {
+    pub fn from_hashmap(properties: HashMap<String, String>) -> Result<Self> {
+        let config: Self = serde_json::from_value(serde_json::to_value(properties)?)
+            .map_err(|e| anyhow!(e.to_string()))?;
+        if config.path.is_none() {
+            return Err(anyhow!("Must specify 'file.path' for file sink"));
+        }
+        Ok(config)
+    }
+}
+
+/// [`FileSink`] writes the changes as JSON lines into a local file or stdout, which is mainly for
+/// testing and debugging.
+pub struct FileSink {
+    schema: Schema,
+    writer: BufWriter<Box<dyn Write + Send>>,
+}
+
+impl FileSink {
+    pub fn new(config: FileSinkConfig, schema: Schema) -> Result<Self> {
+        let writer: Box<dyn Write + Send> = match &config.path {
+            Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+            None => Box::new(std::io::stdout()),
+        };
+        Ok(Self {
+            schema,
+            writer: BufWriter::new(writer),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for FileSink {
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()> {
+        for record in chunk_to_json(&chunk, &self.schema) {
+            writeln!(self.writer, "{}", record)?;
+        }
+        Ok(())
+    }
+
+    async fn begin_epoch(&mut self, _epoch: u64) -> Result<()> {
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sink.json");
+        let config = FileSinkConfig {
+            path: Some(path.to_str().unwrap().to_string()),
+        };
+        let schema = Schema::new(vec![Field::with_name(DataType::Int32, "v1")]);
+        let mut sink = FileSink::new(config, schema).unwrap();
+
+        sink.begin_epoch(1).await.unwrap();
+        sink.write_batch(StreamChunk::from_pretty(
+            " i
+            + 1
+            - 2",
+        ))
+        .await
+        .unwrap();
+        sink.commit().await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "{\"data\":{\"v1\":1},\"op\":\"insert\"}\n{\"data\":{\"v1\":2},\"op\":\"delete\"}\n"
+        );
+    }
+}
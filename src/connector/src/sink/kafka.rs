@@ -0,0 +1,405 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, KafkaResult, RDKafkaErrorCode};
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use risingwave_common::array::StreamChunk;
+use risingwave_common::catalog::Schema;
+use serde::Deserialize;
+
+use crate::sink::{chunk_to_json, Sink};
+
+pub const KAFKA_SINK: &str = "kafka";
+
+/// The interval to wait for the deliveries in flight when the local queue of the producer is full.
+const KAFKA_QUEUE_FULL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The timeout of the transactional operations of the producers.
+const KAFKA_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The partition whose offset committed by the consumer group of an exactly-once sink records the
+/// last epoch committed to the topic.
+const KAFKA_EPOCH_MARKER_PARTITION: i32 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KafkaSinkSemantic {
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+impl Default for KafkaSinkSemantic {
+    fn default() -> Self {
+        Self::AtLeastOnce
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct KafkaSinkConfig {
+    #[serde(rename = "kafka.brokers")]
+    pub brokers: String,
+
+    #[serde(rename = "kafka.topic")]
+    pub topic: String,
+
+    #[serde(rename = "kafka.semantic", default)]
+    pub semantic: KafkaSinkSemantic,
+
+    /// The transactional id of the producer in exactly-once mode, which also names the consumer
+    /// group recording the last committed epoch. It must be unique for each sink actor and stable
+    /// across recovery, so it is set by the executor instead of the `WITH` options.
+    #[serde(skip)]
+    pub transactional_id: Option<String>,
+}
+
+impl KafkaSinkConfig {
+    pub fn from_hashmap(properties: HashMap<String, String>) -> Result<Self> {
+        serde_json::from_value(serde_json::to_value(properties)?)
+            .map_err(|e| anyhow!(e.to_string()))
+    }
+}
+
+/// Runs a blocking transactional operation on `producer`.
+async fn run_transactional<F>(producer: &FutureProducer, op: F) -> Result<()>
+where
+    F: FnOnce(&FutureProducer) -> KafkaResult<()> + Send + 'static,
+{
+    let producer = producer.clone();
+    tokio::task::spawn_blocking(move || op(&producer))
+        .await?
+        .map_err(|e| anyhow!("kafka transaction failed {}", e))
+}
+
+/// The transactional producer of an exactly-once sink, which writes the changes of each epoch in a
+/// transaction.
+///
+/// Along with the changes, each transaction commits the epoch as the offset of the consumer group
+/// named by the transactional id, so that the last epoch committed to the topic survives failures,
+/// and the epochs written again after recovery are skipped.
+struct KafkaTransaction {
+    topic: String,
+    producer: FutureProducer,
+    /// The consumer of the group recording the last committed epoch.
+    consumer: Arc<BaseConsumer>,
+    /// The last epoch committed to the topic.
+    committed_epoch: u64,
+    /// The epoch of the open transaction, or `None` if the changes are skipped.
+    current: Option<u64>,
+}
+
+impl KafkaTransaction {
+    async fn new(config: &KafkaSinkConfig) -> Result<Self> {
+        let transactional_id = config
+            .transactional_id
+            .clone()
+            .ok_or_else(|| anyhow!("transactional id of exactly-once kafka sink is not set"))?;
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("transactional.id", &transactional_id)
+            .create()
+            .map_err(|e| anyhow!("producer creation failed {}", e))?;
+        // This fences the producer of the failed actor with the same transactional id and aborts
+        // its open transaction, whose changes are still in the log of the sink executor.
+        run_transactional(&producer, |p| {
+            p.init_transactions(KAFKA_TRANSACTION_TIMEOUT)
+        })
+        .await?;
+
+        let consumer: Arc<BaseConsumer> = Arc::new(
+            ClientConfig::new()
+                .set("bootstrap.servers", &config.brokers)
+                .set("group.id", &transactional_id)
+                .set("enable.auto.commit", "false")
+                .create()
+                .map_err(|e| anyhow!("consumer creation failed {}", e))?,
+        );
+        let committed_epoch = {
+            let consumer = consumer.clone();
+            let topic = config.topic.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut partitions = TopicPartitionList::new();
+                partitions.add_partition(&topic, KAFKA_EPOCH_MARKER_PARTITION);
+                let committed =
+                    consumer.committed_offsets(partitions, KAFKA_TRANSACTION_TIMEOUT)?;
+                Ok::<_, KafkaError>(
+                    match committed
+                        .find_partition(&topic, KAFKA_EPOCH_MARKER_PARTITION)
+                        .map(|partition| partition.offset())
+                    {
+                        Some(Offset::Offset(epoch)) => epoch as u64,
+                        _ => 0,
+                    },
+                )
+            })
+            .await?
+            .map_err(|e| anyhow!("failed to fetch committed epoch {}", e))?
+        };
+
+        Ok(Self {
+            topic: config.topic.clone(),
+            producer,
+            consumer,
+            committed_epoch,
+            current: None,
+        })
+    }
+
+    async fn begin(&mut self, epoch: u64) -> Result<()> {
+        if epoch <= self.committed_epoch {
+            // Already committed before recovery.
+            self.current = None;
+            return Ok(());
+        }
+        run_transactional(&self.producer, |p| p.begin_transaction()).await?;
+        self.current = Some(epoch);
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        let epoch = match self.current.take() {
+            Some(epoch) => epoch,
+            None => return Ok(()),
+        };
+        let mut offsets = TopicPartitionList::new();
+        offsets
+            .add_partition_offset(
+                &self.topic,
+                KAFKA_EPOCH_MARKER_PARTITION,
+                Offset::Offset(epoch as i64),
+            )
+            .map_err(|e| anyhow!("invalid epoch marker {}", e))?;
+        let consumer = self.consumer.clone();
+        run_transactional(&self.producer, move |p| {
+            let group_metadata = consumer
+                .group_metadata()
+                .ok_or(KafkaError::ConsumerCommit(RDKafkaErrorCode::InvalidGroupId))?;
+            p.send_offsets_to_transaction(&offsets, &group_metadata, KAFKA_TRANSACTION_TIMEOUT)?;
+            p.commit_transaction(KAFKA_TRANSACTION_TIMEOUT)
+        })
+        .await?;
+        self.committed_epoch = epoch;
+        Ok(())
+    }
+}
+
+enum KafkaProducer {
+    AtLeastOnce(FutureProducer),
+    ExactlyOnce(KafkaTransaction),
+}
+
+/// [`KafkaSink`] sends the changes as JSON records to a Kafka topic. The records are sent
+/// asynchronously, and all of them are waited to be acknowledged by the brokers on commit.
+///
+/// In exactly-once mode, the records of each epoch are sent in a producer transaction, which is
+/// committed on [`Sink::commit`] together with the epoch, see [`KafkaTransaction`]. The records
+/// of the epochs committed before are dropped.
+pub struct KafkaSink {
+    config: KafkaSinkConfig,
+    schema: Schema,
+    producer: KafkaProducer,
+    /// The deliveries of the records sent since the last commit.
+    in_flight: Vec<DeliveryFuture>,
+}
+
+impl KafkaSink {
+    pub async fn new(config: KafkaSinkConfig, schema: Schema) -> Result<Self> {
+        let producer = match config.semantic {
+            KafkaSinkSemantic::AtLeastOnce => KafkaProducer::AtLeastOnce(
+                ClientConfig::new()
+                    .set("bootstrap.servers", &config.brokers)
+                    .create()
+                    .map_err(|e| anyhow!("producer creation failed {}", e))?,
+            ),
+            KafkaSinkSemantic::ExactlyOnce => {
+                KafkaProducer::ExactlyOnce(KafkaTransaction::new(&config).await?)
+            }
+        };
+        Ok(Self {
+            config,
+            schema,
+            producer,
+            in_flight: vec![],
+        })
+    }
+
+    /// Returns the producer to send the records of the current epoch, or `None` if the records
+    /// should be dropped.
+    fn current_producer(&self) -> Option<FutureProducer> {
+        match &self.producer {
+            KafkaProducer::AtLeastOnce(producer) => Some(producer.clone()),
+            KafkaProducer::ExactlyOnce(transaction) => {
+                transaction.current.map(|_| transaction.producer.clone())
+            }
+        }
+    }
+
+    async fn wait_in_flight(&mut self) -> Result<()> {
+        for delivery in self.in_flight.drain(..) {
+            delivery
+                .await
+                .map_err(|_| anyhow!("kafka delivery canceled"))?
+                .map_err(|(e, _)| anyhow!("kafka delivery failed {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn send(&mut self, producer: &FutureProducer, payload: &str) -> Result<()> {
+        let topic = self.config.topic.clone();
+        let mut record = FutureRecord::<[u8], str>::to(&topic).payload(payload);
+        loop {
+            match producer.send_result(record) {
+                Ok(delivery) => {
+                    self.in_flight.push(delivery);
+                    return Ok(());
+                }
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), rejected)) => {
+                    record = rejected;
+                    // Free the local queue before retrying.
+                    self.wait_in_flight().await?;
+                    tokio::time::sleep(KAFKA_QUEUE_FULL_BACKOFF).await;
+                }
+                Err((e, _)) => return Err(anyhow!("kafka send failed {}", e)),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()> {
+        let producer = match self.current_producer() {
+            Some(producer) => producer,
+            None => return Ok(()),
+        };
+        for record in chunk_to_json(&chunk, &self.schema) {
+            self.send(&producer, &record).await?;
+        }
+        Ok(())
+    }
+
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()> {
+        match &mut self.producer {
+            KafkaProducer::AtLeastOnce(_) => Ok(()),
+            KafkaProducer::ExactlyOnce(transaction) => transaction.begin(epoch).await,
+        }
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.wait_in_flight().await?;
+        match &mut self.producer {
+            KafkaProducer::AtLeastOnce(_) => Ok(()),
+            KafkaProducer::ExactlyOnce(transaction) => transaction.commit().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::mocking::MockCluster;
+    use rdkafka::Message;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    /// Polls the committed records in the topic until no more arrives.
+    fn poll_committed(consumer: &BaseConsumer) -> Vec<String> {
+        let mut records = vec![];
+        while let Some(message) = consumer.poll(Duration::from_secs(1)) {
+            let message = message.unwrap();
+            records.push(message.payload_view::<str>().unwrap().unwrap().to_string());
+        }
+        records
+    }
+
+    #[tokio::test]
+    async fn test_exactly_once_kafka_sink() {
+        let cluster = MockCluster::new(1).unwrap();
+        cluster.create_topic("test", 1, 1).unwrap();
+        let config = KafkaSinkConfig::from_hashmap(HashMap::from([
+            ("kafka.brokers".to_string(), cluster.bootstrap_servers()),
+            ("kafka.topic".to_string(), "test".to_string()),
+            ("kafka.semantic".to_string(), "exactly_once".to_string()),
+        ]))
+        .unwrap();
+        assert_eq!(config.semantic, KafkaSinkSemantic::ExactlyOnce);
+        let config = KafkaSinkConfig {
+            transactional_id: Some("test-sink".to_string()),
+            ..config
+        };
+        let schema = Schema::new(vec![Field::with_name(DataType::Int32, "v1")]);
+
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &cluster.bootstrap_servers())
+            .set("group.id", "test")
+            .set("isolation.level", "read_committed")
+            .create()
+            .unwrap();
+        let mut partitions = TopicPartitionList::new();
+        partitions
+            .add_partition_offset("test", 0, Offset::Beginning)
+            .unwrap();
+        consumer.assign(&partitions).unwrap();
+
+        let mut sink = KafkaSink::new(config.clone(), schema.clone())
+            .await
+            .unwrap();
+        sink.begin_epoch(1).await.unwrap();
+        sink.write_batch(StreamChunk::from_pretty(
+            " i
+            + 1",
+        ))
+        .await
+        .unwrap();
+        sink.commit().await.unwrap();
+        assert_eq!(
+            poll_committed(&consumer),
+            vec![r#"{"data":{"v1":1},"op":"insert"}"#]
+        );
+
+        // The open transaction is aborted on recovery.
+        sink.begin_epoch(2).await.unwrap();
+        sink.write_batch(StreamChunk::from_pretty(
+            " i
+            + 2",
+        ))
+        .await
+        .unwrap();
+        drop(sink);
+        let mut sink = KafkaSink::new(config, schema).await.unwrap();
+        assert!(poll_committed(&consumer).is_empty());
+
+        // Both epochs are written again after recovery, while epoch 1 has been committed.
+        for (epoch, chunk) in [(1, " i\n + 1"), (2, " i\n + 2")] {
+            sink.begin_epoch(epoch).await.unwrap();
+            sink.write_batch(StreamChunk::from_pretty(chunk))
+                .await
+                .unwrap();
+            sink.commit().await.unwrap();
+        }
+        assert_eq!(
+            poll_committed(&consumer),
+            vec![r#"{"data":{"v1":2},"op":"insert"}"#]
+        );
+    }
+}
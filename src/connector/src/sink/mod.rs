@@ -0,0 +1,245 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sinks deliver the changes of a materialized view to an external system.
+
+pub mod file;
+pub mod kafka;
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use itertools::Itertools;
+use risingwave_common::array::{Op, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::{DatumRef, ScalarRefImpl};
+use serde_json::{Map, Value};
+
+pub use self::file::{FileSink, FileSinkConfig, CONSOLE_SINK, FILE_SINK};
+pub use self::kafka::{KafkaSink, KafkaSinkConfig, KafkaSinkSemantic, KAFKA_SINK};
+
+/// The key of the connector in the `WITH` options of `CREATE SINK`.
+pub const SINK_CONNECTOR_KEY: &str = "connector";
+
+/// [`Sink`] is an abstraction of the external connector write interface. The changes written in
+/// an epoch are only guaranteed to be delivered after [`Sink::commit`] returns, which is called on
+/// every barrier, so that the sink provides at-least-once delivery: after recovery, the changes
+/// since the last checkpoint are written again.
+///
+/// A sink delivering exactly once, see [`SinkConfig::is_exactly_once`], is written with the changes
+/// of an epoch only after the checkpoint of the epoch is committed, and the executor keeps them in
+/// the state store before that. Such a sink must make the changes of an epoch visible atomically on
+/// [`Sink::commit`] and remember the last epoch committed, so that the epochs written again after
+/// recovery are skipped.
+#[async_trait]
+pub trait Sink {
+    /// Writes a chunk of changes into the sink. The changes may be buffered until
+    /// [`Sink::commit`].
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()>;
+
+    /// Called before the changes of `epoch` are written.
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()>;
+
+    /// Flushes all the changes written since the last commit.
+    async fn commit(&mut self) -> Result<()>;
+}
+
+#[derive(Clone, Debug)]
+pub enum SinkConfig {
+    Kafka(KafkaSinkConfig),
+    File(FileSinkConfig),
+}
+
+impl SinkConfig {
+    /// Parses the `WITH` options of `CREATE SINK`.
+    pub fn from_hashmap(mut properties: HashMap<String, String>) -> Result<Self> {
+        let connector = properties
+            .remove(SINK_CONNECTOR_KEY)
+            .ok_or_else(|| anyhow!("Must specify 'connector' in WITH clause"))?;
+        match connector.to_lowercase().as_str() {
+            KAFKA_SINK => Ok(SinkConfig::Kafka(KafkaSinkConfig::from_hashmap(
+                properties,
+            )?)),
+            FILE_SINK => Ok(SinkConfig::File(FileSinkConfig::from_hashmap(properties)?)),
+            CONSOLE_SINK => Ok(SinkConfig::File(FileSinkConfig { path: None })),
+            _ => Err(anyhow!("sink connector '{}' is not supported", connector)),
+        }
+    }
+
+    /// Returns whether the sink delivers the changes exactly once.
+    pub fn is_exactly_once(&self) -> bool {
+        match self {
+            SinkConfig::Kafka(config) => config.semantic == KafkaSinkSemantic::ExactlyOnce,
+            SinkConfig::File(_) => false,
+        }
+    }
+}
+
+pub enum SinkImpl {
+    Kafka(Box<KafkaSink>),
+    File(FileSink),
+}
+
+impl SinkImpl {
+    /// Creates a sink of `config`, writing the changes of the columns in `schema`.
+    pub async fn new(config: SinkConfig, schema: Schema) -> Result<Self> {
+        Ok(match config {
+            SinkConfig::Kafka(config) => {
+                SinkImpl::Kafka(Box::new(KafkaSink::new(config, schema).await?))
+            }
+            SinkConfig::File(config) => SinkImpl::File(FileSink::new(config, schema)?),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for SinkImpl {
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()> {
+        match self {
+            SinkImpl::Kafka(sink) => sink.write_batch(chunk).await,
+            SinkImpl::File(sink) => sink.write_batch(chunk).await,
+        }
+    }
+
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()> {
+        match self {
+            SinkImpl::Kafka(sink) => sink.begin_epoch(epoch).await,
+            SinkImpl::File(sink) => sink.begin_epoch(epoch).await,
+        }
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        match self {
+            SinkImpl::Kafka(sink) => sink.commit().await,
+            SinkImpl::File(sink) => sink.commit().await,
+        }
+    }
+}
+
+fn op_to_str(op: Op) -> &'static str {
+    match op {
+        Op::Insert => "insert",
+        Op::Delete => "delete",
+        Op::UpdateInsert => "update_insert",
+        Op::UpdateDelete => "update_delete",
+    }
+}
+
+fn datum_to_json(datum: DatumRef) -> Value {
+    match datum {
+        None => Value::Null,
+        Some(ScalarRefImpl::Bool(v)) => Value::from(v),
+        Some(ScalarRefImpl::Int16(v)) => Value::from(v),
+        Some(ScalarRefImpl::Int32(v)) => Value::from(v),
+        Some(ScalarRefImpl::Int64(v)) => Value::from(v),
+        Some(ScalarRefImpl::Float32(v)) => Value::from(v.0),
+        Some(ScalarRefImpl::Float64(v)) => Value::from(v.0),
+        Some(ScalarRefImpl::Utf8(v)) => Value::from(v),
+        Some(scalar) => Value::from(scalar.to_string()),
+    }
+}
+
+/// Encodes the visible rows in `chunk` into JSON records like
+/// `{"op": "insert", "data": {"v1": 1, "v2": "a"}}`, keyed by the column names in `schema`.
+pub fn chunk_to_json(chunk: &StreamChunk, schema: &Schema) -> Vec<String> {
+    let mut records = Vec::with_capacity(chunk.cardinality());
+    for (idx, op) in chunk.ops().iter().enumerate() {
+        let visible = chunk
+            .visibility()
+            .as_ref()
+            .map(|x| x.is_set(idx).unwrap())
+            .unwrap_or(true);
+        if !visible {
+            continue;
+        }
+        let data = schema
+            .fields()
+            .iter()
+            .zip_eq(chunk.columns())
+            .map(|(field, column)| {
+                (
+                    field.name.clone(),
+                    datum_to_json(column.array_ref().value_at(idx)),
+                )
+            })
+            .collect::<Map<String, Value>>();
+        let mut record = Map::with_capacity(2);
+        record.insert("op".to_string(), Value::from(op_to_str(*op)));
+        record.insert("data".to_string(), Value::Object(data));
+        records.push(Value::Object(record).to_string());
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    #[test]
+    fn test_sink_config() {
+        let config = SinkConfig::from_hashmap(HashMap::from([
+            ("connector".to_string(), "kafka".to_string()),
+            ("kafka.brokers".to_string(), "localhost:9092".to_string()),
+            ("kafka.topic".to_string(), "test".to_string()),
+        ]))
+        .unwrap();
+        assert!(matches!(config, SinkConfig::Kafka(config) if config.topic == "test"));
+
+        let config = SinkConfig::from_hashmap(HashMap::from([(
+            "connector".to_string(),
+            "console".to_string(),
+        )]))
+        .unwrap();
+        assert!(matches!(
+            config,
+            SinkConfig::File(FileSinkConfig { path: None })
+        ));
+
+        assert!(SinkConfig::from_hashmap(HashMap::new()).is_err());
+        assert!(SinkConfig::from_hashmap(HashMap::from([(
+            "connector".to_string(),
+            "kafka".to_string(),
+        )]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_chunk_to_json() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "v1"),
+            Field::with_name(DataType::Float64, "v2"),
+        ]);
+        let chunk = StreamChunk::from_pretty(
+            " i  F
+            + 1  1.5
+            - 2  .
+            U- 3 2.5 D
+            U+ 4 3.5",
+        );
+        let records = chunk_to_json(&chunk, &schema);
+        assert_eq!(
+            records,
+            vec![
+                r#"{"data":{"v1":1,"v2":1.5},"op":"insert"}"#,
+                r#"{"data":{"v1":2,"v2":null},"op":"delete"}"#,
+                r#"{"data":{"v1":4,"v2":3.5},"op":"update_insert"}"#,
+            ]
+        );
+    }
+}
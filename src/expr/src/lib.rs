@@ -33,3 +33,4 @@
 
 pub mod expr;
 pub mod vector_op;
+pub mod window_function;
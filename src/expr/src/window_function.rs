@@ -0,0 +1,428 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of the window functions over a partition of rows, shared by the batch and streaming
+//! over window executors.
+
+use risingwave_common::array::{ArrayImpl, DataChunk, Row};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_pb::expr::window_frame::{Bound as ProstBound, BoundType, Type as FrameType};
+use risingwave_pb::expr::window_function::Type as WindowFunctionType;
+use risingwave_pb::expr::WindowFunction as ProstWindowFunction;
+
+use crate::vector_op::agg::AggStateFactory;
+
+/// A bound of the window frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(usize),
+    CurrentRow,
+    Following(usize),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    fn from_prost(bound: &ProstBound) -> Result<Self> {
+        let offset = bound.offset as usize;
+        Ok(match bound.get_type()? {
+            BoundType::UnboundedPreceding => Self::UnboundedPreceding,
+            BoundType::Preceding => Self::Preceding(offset),
+            BoundType::CurrentRow => Self::CurrentRow,
+            BoundType::Following => Self::Following(offset),
+            BoundType::UnboundedFollowing => Self::UnboundedFollowing,
+        })
+    }
+}
+
+enum WindowFunctionKind {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag(usize),
+    Lead(usize),
+    Aggregate(AggStateFactory),
+}
+
+/// `WindowFunctionEvaluator` computes a window function over the sorted rows of a partition.
+///
+/// Only `ROWS` frames support `PRECEDING` and `FOLLOWING` bounds with an offset, while `RANGE`
+/// frames are limited to the unbounded and `CURRENT ROW` bounds, where the current row extends to
+/// its peers, i.e., the rows with the same order keys.
+pub struct WindowFunctionEvaluator {
+    kind: WindowFunctionKind,
+    offset: usize,
+    rows_frame: bool,
+    start: FrameBound,
+    end: FrameBound,
+    return_type: DataType,
+}
+
+impl WindowFunctionEvaluator {
+    pub fn new(prost: &ProstWindowFunction) -> Result<Self> {
+        let return_type = DataType::from(prost.get_return_type()?);
+        let arg_idx = || -> Result<usize> {
+            match &prost.args[..] {
+                [arg] => Ok(arg.get_input()?.column_idx as usize),
+                _ => Err(ErrorCode::InternalError(
+                    "lag and lead require exactly one argument".to_string(),
+                )
+                .into()),
+            }
+        };
+        let kind = match prost.get_type()? {
+            WindowFunctionType::RowNumber => WindowFunctionKind::RowNumber,
+            WindowFunctionType::Rank => WindowFunctionKind::Rank,
+            WindowFunctionType::DenseRank => WindowFunctionKind::DenseRank,
+            WindowFunctionType::Lag => WindowFunctionKind::Lag(arg_idx()?),
+            WindowFunctionType::Lead => WindowFunctionKind::Lead(arg_idx()?),
+            WindowFunctionType::Aggregate => {
+                WindowFunctionKind::Aggregate(AggStateFactory::new(prost.get_agg_call()?)?)
+            }
+            WindowFunctionType::Invalid => {
+                return Err(ErrorCode::InternalError("invalid window function".to_string()).into())
+            }
+        };
+
+        let frame = prost.get_frame()?;
+        let rows_frame = frame.get_type()? == FrameType::Rows;
+        let start = FrameBound::from_prost(frame.get_start()?)?;
+        let end = FrameBound::from_prost(frame.get_end()?)?;
+        if start == FrameBound::UnboundedFollowing || end == FrameBound::UnboundedPreceding {
+            return Err(ErrorCode::InternalError(format!(
+                "invalid window frame from {:?} to {:?}",
+                start, end
+            ))
+            .into());
+        }
+        let range_bound = |bound| {
+            matches!(
+                bound,
+                FrameBound::UnboundedPreceding
+                    | FrameBound::CurrentRow
+                    | FrameBound::UnboundedFollowing
+            )
+        };
+        if !rows_frame && !(range_bound(start) && range_bound(end)) {
+            return Err(ErrorCode::InternalError(format!(
+                "unsupported range frame from {:?} to {:?}",
+                start, end
+            ))
+            .into());
+        }
+
+        Ok(Self {
+            kind,
+            offset: prost.offset as usize,
+            rows_frame,
+            start,
+            end,
+            return_type,
+        })
+    }
+
+    pub fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    /// Gets the frame `[start, end)` of the `idx`-th row of a partition with `len` rows, where
+    /// `peers` is the range of the rows with the same order keys as the row.
+    fn frame(&self, idx: usize, len: usize, peers: (usize, usize)) -> (usize, usize) {
+        let (current_start, current_end) = if self.rows_frame {
+            (idx, idx + 1)
+        } else {
+            peers
+        };
+        let start = match self.start {
+            FrameBound::UnboundedPreceding => 0,
+            FrameBound::Preceding(n) => idx.saturating_sub(n),
+            FrameBound::CurrentRow => current_start,
+            FrameBound::Following(n) => (idx + n).min(len),
+            FrameBound::UnboundedFollowing => unreachable!(),
+        };
+        let end = match self.end {
+            FrameBound::UnboundedPreceding => unreachable!(),
+            FrameBound::Preceding(n) => (idx + 1).saturating_sub(n),
+            FrameBound::CurrentRow => current_end,
+            FrameBound::Following(n) => (idx + n + 1).min(len),
+            FrameBound::UnboundedFollowing => len,
+        };
+        (start, end.max(start))
+    }
+
+    fn evaluate(
+        &self,
+        rows: &[Row],
+        chunk: Option<&DataChunk>,
+        peers: &[(usize, usize)],
+    ) -> Result<ArrayImpl> {
+        let len = rows.len();
+        let mut builder = self.return_type.create_array_builder(len)?;
+        match &self.kind {
+            WindowFunctionKind::RowNumber => {
+                for idx in 0..len {
+                    builder.append_datum(&Some(ScalarImpl::Int64(idx as i64 + 1)))?;
+                }
+            }
+            WindowFunctionKind::Rank => {
+                for peer in peers {
+                    builder.append_datum(&Some(ScalarImpl::Int64(peer.0 as i64 + 1)))?;
+                }
+            }
+            WindowFunctionKind::DenseRank => {
+                let mut rank = 0;
+                for (idx, peer) in peers.iter().enumerate() {
+                    if peer.0 == idx {
+                        rank += 1;
+                    }
+                    builder.append_datum(&Some(ScalarImpl::Int64(rank)))?;
+                }
+            }
+            WindowFunctionKind::Lag(arg) => {
+                for idx in 0..len {
+                    let datum: Datum = match idx.checked_sub(self.offset) {
+                        Some(lag) => rows[lag][*arg].clone(),
+                        None => None,
+                    };
+                    builder.append_datum(&datum)?;
+                }
+            }
+            WindowFunctionKind::Lead(arg) => {
+                for idx in 0..len {
+                    let datum: Datum = match rows.get(idx + self.offset) {
+                        Some(lead) => lead[*arg].clone(),
+                        None => None,
+                    };
+                    builder.append_datum(&datum)?;
+                }
+            }
+            WindowFunctionKind::Aggregate(factory) => {
+                let chunk = chunk.unwrap();
+                if self.start == FrameBound::UnboundedPreceding {
+                    // The frames only grow as the end of the frames never moves backward, so the
+                    // aggregation can be done incrementally.
+                    let mut state = factory.create_agg_state()?;
+                    let mut aggregated = 0;
+                    for (idx, peer) in peers.iter().enumerate() {
+                        let (_, end) = self.frame(idx, len, *peer);
+                        for row_idx in aggregated..end {
+                            state.update_with_row(chunk, row_idx)?;
+                        }
+                        aggregated = aggregated.max(end);
+                        state.output(&mut builder)?;
+                    }
+                } else {
+                    for (idx, peer) in peers.iter().enumerate() {
+                        let (start, end) = self.frame(idx, len, *peer);
+                        let mut state = factory.create_agg_state()?;
+                        for row_idx in start..end {
+                            state.update_with_row(chunk, row_idx)?;
+                        }
+                        state.output(&mut builder)?;
+                    }
+                }
+            }
+        }
+        builder.finish()
+    }
+}
+
+/// Evaluates the window functions `calls` over a partition, whose `rows` of `data_types` are sorted
+/// by the columns of `order_key_indices`. Returns the results of the calls for each row.
+pub fn evaluate_partition(
+    calls: &[WindowFunctionEvaluator],
+    rows: &[Row],
+    data_types: &[DataType],
+    order_key_indices: &[usize],
+) -> Result<Vec<Row>> {
+    let mut peers = Vec::with_capacity(rows.len());
+    let mut peer_start = 0;
+    for idx in 1..=rows.len() {
+        let is_peer = idx < rows.len()
+            && order_key_indices
+                .iter()
+                .all(|key| rows[idx][*key] == rows[peer_start][*key]);
+        if !is_peer {
+            peers.extend(std::iter::repeat((peer_start, idx)).take(idx - peer_start));
+            peer_start = idx;
+        }
+    }
+
+    let chunk = if calls
+        .iter()
+        .any(|call| matches!(call.kind, WindowFunctionKind::Aggregate(_)))
+    {
+        Some(DataChunk::from_rows(rows, data_types)?)
+    } else {
+        None
+    };
+    let columns = calls
+        .iter()
+        .map(|call| call.evaluate(rows, chunk.as_ref(), &peers))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((0..rows.len())
+        .map(|idx| Row(columns.iter().map(|column| column.datum_at(idx)).collect()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::Row;
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::agg_call::{Arg, Type as AggType};
+    use risingwave_pb::expr::window_frame::{Bound, BoundType, Type as FrameType};
+    use risingwave_pb::expr::window_function::Type as WindowFunctionType;
+    use risingwave_pb::expr::{AggCall, InputRefExpr, WindowFrame, WindowFunction};
+
+    use super::*;
+
+    fn int64_type() -> ProstDataType {
+        ProstDataType {
+            type_name: TypeName::Int64 as i32,
+            ..Default::default()
+        }
+    }
+
+    fn bound(r#type: BoundType, offset: u64) -> Option<Bound> {
+        Some(Bound {
+            r#type: r#type as i32,
+            offset,
+        })
+    }
+
+    fn window_function(
+        r#type: WindowFunctionType,
+        frame_type: FrameType,
+        start: Option<Bound>,
+        end: Option<Bound>,
+    ) -> WindowFunction {
+        let arg = Arg {
+            input: Some(InputRefExpr { column_idx: 1 }),
+            r#type: Some(int64_type()),
+        };
+        WindowFunction {
+            r#type: r#type as i32,
+            agg_call: Some(AggCall {
+                r#type: AggType::Sum as i32,
+                args: vec![arg.clone()],
+                return_type: Some(ProstDataType {
+                    type_name: TypeName::Decimal as i32,
+                    ..Default::default()
+                }),
+                distinct: false,
+            }),
+            args: vec![arg],
+            offset: 1,
+            return_type: Some(match r#type {
+                WindowFunctionType::Aggregate => ProstDataType {
+                    type_name: TypeName::Decimal as i32,
+                    ..Default::default()
+                },
+                _ => int64_type(),
+            }),
+            frame: Some(WindowFrame {
+                r#type: frame_type as i32,
+                start,
+                end,
+            }),
+        }
+    }
+
+    fn default_frame(r#type: WindowFunctionType) -> WindowFunction {
+        window_function(
+            r#type,
+            FrameType::Range,
+            bound(BoundType::UnboundedPreceding, 0),
+            bound(BoundType::CurrentRow, 0),
+        )
+    }
+
+    fn column(results: &[Row], idx: usize) -> Vec<Option<i64>> {
+        results
+            .iter()
+            .map(|row| match &row[idx] {
+                Some(ScalarImpl::Int64(v)) => Some(*v),
+                Some(ScalarImpl::Decimal(v)) => Some(v.to_string().parse().unwrap()),
+                None => None,
+                other => panic!("unexpected datum {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_partition() {
+        // Rows of (order key, value), sorted by the order key.
+        let rows = [(1, 10), (2, 20), (2, 30), (3, 40)]
+            .into_iter()
+            .map(|(k, v)| Row(vec![Some(ScalarImpl::Int64(k)), Some(ScalarImpl::Int64(v))]))
+            .collect::<Vec<_>>();
+        let data_types = [DataType::Int64, DataType::Int64];
+        let calls = [
+            default_frame(WindowFunctionType::RowNumber),
+            default_frame(WindowFunctionType::Rank),
+            default_frame(WindowFunctionType::DenseRank),
+            default_frame(WindowFunctionType::Lag),
+            default_frame(WindowFunctionType::Lead),
+            // The default frame includes the peers of the current row.
+            default_frame(WindowFunctionType::Aggregate),
+            window_function(
+                WindowFunctionType::Aggregate,
+                FrameType::Rows,
+                bound(BoundType::Preceding, 1),
+                bound(BoundType::Following, 1),
+            ),
+            window_function(
+                WindowFunctionType::Aggregate,
+                FrameType::Rows,
+                bound(BoundType::UnboundedPreceding, 0),
+                bound(BoundType::Preceding, 1),
+            ),
+        ]
+        .iter()
+        .map(WindowFunctionEvaluator::new)
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+        let results = evaluate_partition(&calls, &rows, &data_types, &[0]).unwrap();
+        assert_eq!(column(&results, 0), [Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(column(&results, 1), [Some(1), Some(2), Some(2), Some(4)]);
+        assert_eq!(column(&results, 2), [Some(1), Some(2), Some(2), Some(3)]);
+        assert_eq!(column(&results, 3), [None, Some(10), Some(20), Some(30)]);
+        assert_eq!(column(&results, 4), [Some(20), Some(30), Some(40), None]);
+        assert_eq!(
+            column(&results, 5),
+            [Some(10), Some(60), Some(60), Some(100)]
+        );
+        assert_eq!(
+            column(&results, 6),
+            [Some(30), Some(60), Some(90), Some(70)]
+        );
+        assert_eq!(column(&results, 7), [None, Some(10), Some(30), Some(60)]);
+    }
+
+    #[test]
+    fn test_invalid_range_frame() {
+        let call = window_function(
+            WindowFunctionType::Aggregate,
+            FrameType::Range,
+            bound(BoundType::Preceding, 1),
+            bound(BoundType::CurrentRow, 0),
+        );
+        assert!(WindowFunctionEvaluator::new(&call).is_err());
+    }
+}
@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Display;
+use std::rc::Rc;
 
-use risingwave_common::catalog::Field;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::plan_common::JoinType;
+use risingwave_sqlparser::ast::{Query, TableAlias};
 
 #[derive(Debug, Clone)]
 pub struct ColumnBinding {
@@ -41,6 +45,8 @@ impl ColumnBinding {
 pub enum Clause {
     Where,
     Values,
+    GroupBy,
+    Having,
 }
 
 impl Display for Clause {
@@ -48,10 +54,70 @@ impl Display for Clause {
         match self {
             Clause::Where => write!(f, "WHERE"),
             Clause::Values => write!(f, "VALUES"),
+            Clause::GroupBy => write!(f, "GROUP BY"),
+            Clause::Having => write!(f, "HAVING"),
         }
     }
 }
 
+/// Columns merged by `JOIN ... USING` or `NATURAL JOIN`, which are referred to as a single column
+/// by the unqualified name.
+#[derive(Default, Debug, Clone)]
+pub struct ColumnGroup {
+    /// Indexes of the merged columns in `columns` of the context.
+    pub indexs: BTreeSet<usize>,
+    /// A column that always holds the merged value. It's `None` when the merged value is the first
+    /// non-null value of the columns, e.g., in a full outer join.
+    pub non_nullable_column: Option<usize>,
+}
+
+#[derive(Default, Debug)]
+pub struct ColumnGroupContext {
+    // Mapping the index of a merged column in `columns` of the context to its group in `groups`.
+    pub mapping: HashMap<usize, usize>,
+    pub groups: Vec<ColumnGroup>,
+}
+
+impl ColumnGroupContext {
+    pub fn group_of(&self, index: usize) -> Option<&ColumnGroup> {
+        self.mapping.get(&index).map(|group| &self.groups[*group])
+    }
+
+    /// Whether the columns are all merged into one, so that their name is not ambiguous.
+    fn in_same_group(&self, indexs: &[usize]) -> bool {
+        let group = self.mapping.get(&indexs[0]);
+        group.is_some() && indexs.iter().all(|index| self.mapping.get(index) == group)
+    }
+
+    /// Merges the `right` column into the `left` one, which may have been merged with other
+    /// columns, as they are the common columns of a join.
+    pub fn merge(&mut self, left: usize, right: usize, join_type: JoinType) {
+        let group = match self.mapping.get(&left) {
+            Some(group) => *group,
+            None => {
+                self.groups.push(ColumnGroup {
+                    indexs: BTreeSet::from([left]),
+                    non_nullable_column: Some(left),
+                });
+                self.mapping.insert(left, self.groups.len() - 1);
+                self.groups.len() - 1
+            }
+        };
+        let left_non_nullable_column = self.groups[group].non_nullable_column;
+        // The merged value takes the value of the side that is preserved by the join.
+        let non_nullable_column = match join_type {
+            // Columns of both sides hold the same value, as all rows are matched.
+            JoinType::Inner => left_non_nullable_column.or(Some(right)),
+            JoinType::LeftOuter => left_non_nullable_column,
+            JoinType::RightOuter => Some(right),
+            _ => None,
+        };
+        self.groups[group].indexs.insert(right);
+        self.groups[group].non_nullable_column = non_nullable_column;
+        self.mapping.insert(right, group);
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct BindContext {
     // Columns of all tables.
@@ -62,6 +128,26 @@ pub struct BindContext {
     pub range_of: HashMap<String, (usize, usize)>,
     // `clause` identifies in what clause we are binding.
     pub clause: Option<Clause>,
+    // Mapping the name of a CTE in scope to its definition.
+    pub cte_to_relation: HashMap<String, Rc<BindingCte>>,
+    // Columns merged by `USING` or `NATURAL` joins.
+    pub column_group_context: ColumnGroupContext,
+    // Indexes of the columns that `SELECT *` expands to in order, where the columns merged by a
+    // join appear once at the front of the columns of the join.
+    pub wildcard_columns: Vec<usize>,
+}
+
+/// The definition of a CTE, which is bound wherever the CTE is referenced.
+#[derive(Debug)]
+pub enum BindingCte {
+    /// A non-recursive CTE, bound as a subquery which only sees the CTEs defined before it.
+    Query {
+        query: Query,
+        alias: TableAlias,
+        ctes: HashMap<String, Rc<BindingCte>>,
+    },
+    /// A recursive CTE, whose rows are evaluated before binding since it can't be planned.
+    Rows { schema: Schema, rows: Vec<Row> },
 }
 
 impl BindContext {
@@ -81,8 +167,11 @@ impl BindContext {
             .indexs_of
             .get(column_name)
             .ok_or_else(|| ErrorCode::ItemNotFound(format!("Invalid column: {}", column_name)))?;
-        if columns.len() > 1 {
-            Err(ErrorCode::InternalError("Ambiguous column name".into()).into())
+        if columns.len() > 1 && !self.column_group_context.in_same_group(columns) {
+            Err(
+                ErrorCode::BindError(format!("column reference \"{}\" is ambiguous", column_name))
+                    .into(),
+            )
         } else {
             Ok(columns[0])
         }
@@ -119,6 +208,9 @@ impl BindContext {
             indexs_of: HashMap::new(),
             range_of: HashMap::new(),
             clause: None,
+            cte_to_relation: HashMap::new(),
+            column_group_context: ColumnGroupContext::default(),
+            wildcard_columns: Vec::new(),
         }
     }
 }
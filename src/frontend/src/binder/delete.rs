@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::user::grant_privilege::Privilege;
 use risingwave_sqlparser::ast::{Expr, ObjectName};
 
 use super::{Binder, BoundBaseTable, BoundTableSource};
@@ -36,7 +37,7 @@ impl Binder {
         selection: Option<Expr>,
     ) -> Result<BoundDelete> {
         let (schema_name, table_name) = Self::resolve_table_name(source_name.clone())?;
-        let table_source = self.bind_table_source(source_name)?;
+        let table_source = self.bind_table_source(source_name, Privilege::Delete)?;
         let table = self.bind_table(&schema_name, &table_name, None)?;
         let delete = BoundDelete {
             table_source,
@@ -15,8 +15,8 @@
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::Ident;
 
-use crate::binder::Binder;
-use crate::expr::{CorrelatedInputRef, ExprImpl, InputRef};
+use crate::binder::{BindContext, Binder};
+use crate::expr::{CorrelatedInputRef, ExprImpl, ExprType, FunctionCall, InputRef};
 
 impl Binder {
     pub fn bind_column(&mut self, idents: &[Ident]) -> Result<ExprImpl> {
@@ -32,12 +32,18 @@ impl Binder {
             }
         };
 
-        if let Ok(index) = self
+        match self
             .context
             .get_column_binding_index(table_name, column_name)
         {
-            let column = &self.context.columns[index];
-            return Ok(InputRef::new(column.index, column.field.data_type.clone()).into());
+            Ok(index) => {
+                return Self::bind_column_by_index(&self.context, index, table_name.is_some(), 0)
+            }
+            // An ambiguous column name can't refer to a column of the upper contexts.
+            Err(e) if table_name.is_none() && self.context.indexs_of.contains_key(column_name) => {
+                return Err(e)
+            }
+            Err(_) => {}
         }
 
         // Try to find a correlated column in `upper_contexts`, starting from the innermost context.
@@ -47,13 +53,7 @@ impl Binder {
             let depth = i + 1;
             match context.get_column_binding_index(table_name, column_name) {
                 Ok(index) => {
-                    let column = &context.columns[index];
-                    return Ok(CorrelatedInputRef::new(
-                        column.index,
-                        column.field.data_type.clone(),
-                        depth,
-                    )
-                    .into());
+                    return Self::bind_column_by_index(context, index, table_name.is_some(), depth)
                 }
                 Err(e) => {
                     err = e;
@@ -62,4 +62,38 @@ impl Binder {
         }
         Err(err)
     }
+
+    /// Binds the column at `index` of the context, which is `depth` levels above the current one.
+    /// A column merged by `JOIN ... USING` or `NATURAL JOIN` is bound to the merged value, unless
+    /// it's `qualified` by the table name.
+    pub(in crate::binder) fn bind_column_by_index(
+        context: &BindContext,
+        index: usize,
+        qualified: bool,
+        depth: usize,
+    ) -> Result<ExprImpl> {
+        let column_ref = |index: usize| -> ExprImpl {
+            let column = &context.columns[index];
+            match depth {
+                0 => InputRef::new(column.index, column.field.data_type.clone()).into(),
+                _ => CorrelatedInputRef::new(column.index, column.field.data_type.clone(), depth)
+                    .into(),
+            }
+        };
+        match context.column_group_context.group_of(index) {
+            Some(group) if !qualified => match group.non_nullable_column {
+                Some(index) => Ok(column_ref(index)),
+                None => Ok(FunctionCall::new(
+                    ExprType::Coalesce,
+                    group
+                        .indexs
+                        .iter()
+                        .map(|index| column_ref(*index))
+                        .collect(),
+                )?
+                .into()),
+            },
+            _ => Ok(column_ref(index)),
+        }
+    }
 }
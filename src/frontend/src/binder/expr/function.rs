@@ -13,14 +13,21 @@
 // limitations under the License.
 
 use itertools::Itertools;
-use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_expr::expr::AggKind;
-use risingwave_sqlparser::ast::{Function, FunctionArg, FunctionArgExpr};
+use risingwave_sqlparser::ast::{
+    Function, FunctionArg, FunctionArgExpr, ObjectName, WindowFrame as SqlWindowFrame,
+    WindowFrameBound, WindowFrameUnits, WindowSpec,
+};
 
 use crate::binder::bind_context::Clause;
 use crate::binder::Binder;
-use crate::expr::{AggCall, Expr, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::expr::{
+    AggCall, Expr, ExprImpl, ExprType, FrameBound, FunctionCall, Literal, WindowFrame,
+    WindowFunction, WindowFunctionKind,
+};
+use crate::optimizer::property::Direction;
 
 impl Binder {
     pub(super) fn bind_function(&mut self, f: Function) -> Result<ExprImpl> {
@@ -31,6 +38,10 @@ impl Binder {
             .flatten_ok()
             .try_collect()?;
 
+        if let Some(window_spec) = f.over {
+            return self.bind_window_function(f.name, inputs, f.distinct, window_spec);
+        }
+
         if f.name.0.len() == 1 {
             let function_name = f.name.0.get(0).unwrap().value.as_str();
             let function_name = function_name.to_lowercase();
@@ -44,6 +55,12 @@ impl Binder {
             };
             if let Some(kind) = agg_kind {
                 self.ensure_aggregate_allowed()?;
+                if inputs.iter().any(ExprImpl::has_window_function) {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "aggregate function calls cannot contain window function calls".into(),
+                    )
+                    .into());
+                }
                 return Ok(ExprImpl::AggCall(Box::new(AggCall::new(
                     kind, inputs, f.distinct,
                 )?)));
@@ -95,6 +112,154 @@ impl Binder {
         }
     }
 
+    fn bind_window_function(
+        &mut self,
+        name: ObjectName,
+        mut inputs: Vec<ExprImpl>,
+        distinct: bool,
+        window_spec: WindowSpec,
+    ) -> Result<ExprImpl> {
+        self.ensure_window_function_allowed()?;
+        let function_name = match &name.0[..] {
+            [ident] => ident.value.to_lowercase(),
+            _ => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("unsupported window function: {:?}", name),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        if distinct {
+            return Err(ErrorCode::NotImplemented(
+                "DISTINCT is not implemented for window functions".into(),
+                None.into(),
+            )
+            .into());
+        }
+
+        let mut offset = 0;
+        let kind = match function_name.as_str() {
+            "row_number" => WindowFunctionKind::RowNumber,
+            "rank" => WindowFunctionKind::Rank,
+            "dense_rank" => WindowFunctionKind::DenseRank,
+            "lag" | "lead" => {
+                offset = match inputs.len() {
+                    2 => Self::bind_window_offset(inputs.pop().unwrap())?,
+                    3 => {
+                        return Err(ErrorCode::NotImplemented(
+                            format!("default value of {}", function_name),
+                            None.into(),
+                        )
+                        .into())
+                    }
+                    _ => 1,
+                };
+                match function_name.as_str() {
+                    "lag" => WindowFunctionKind::Lag,
+                    _ => WindowFunctionKind::Lead,
+                }
+            }
+            "count" => WindowFunctionKind::Aggregate(AggKind::Count),
+            "sum" => WindowFunctionKind::Aggregate(AggKind::Sum),
+            "min" => WindowFunctionKind::Aggregate(AggKind::Min),
+            "max" => WindowFunctionKind::Aggregate(AggKind::Max),
+            "avg" => WindowFunctionKind::Aggregate(AggKind::Avg),
+            _ => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("unsupported window function: {:?}", function_name),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+
+        let partition_by: Vec<_> = window_spec
+            .partition_by
+            .into_iter()
+            .map(|expr| self.bind_expr(expr))
+            .try_collect()?;
+        let order_by: Vec<_> = window_spec
+            .order_by
+            .into_iter()
+            .map(|order_by_expr| {
+                let direct = match order_by_expr.asc {
+                    None | Some(true) => Direction::Asc,
+                    Some(false) => Direction::Desc,
+                };
+                Ok::<_, RwError>((self.bind_expr(order_by_expr.expr)?, direct))
+            })
+            .try_collect()?;
+        if inputs
+            .iter()
+            .chain(partition_by.iter())
+            .chain(order_by.iter().map(|(expr, _)| expr))
+            .any(ExprImpl::has_window_function)
+        {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "window function calls cannot be nested".into(),
+            )
+            .into());
+        }
+        let frame = match window_spec.window_frame {
+            Some(frame) => Self::bind_window_frame(frame)?,
+            None => WindowFrame::default(),
+        };
+
+        Ok(WindowFunction::new(kind, inputs, offset, partition_by, order_by, frame)?.into())
+    }
+
+    /// The offset of `lag` and `lead` must be a non-negative integer constant.
+    fn bind_window_offset(offset: ExprImpl) -> Result<usize> {
+        match offset.as_int_constant() {
+            Some(offset) if offset >= 0 => Ok(offset as usize),
+            _ => Err(ErrorCode::NotImplemented(
+                "the offset of lag and lead must be a non-negative integer constant".into(),
+                None.into(),
+            )
+            .into()),
+        }
+    }
+
+    fn bind_window_frame(frame: SqlWindowFrame) -> Result<WindowFrame> {
+        let rows = match frame.units {
+            WindowFrameUnits::Rows => true,
+            WindowFrameUnits::Range => false,
+            WindowFrameUnits::Groups => {
+                return Err(ErrorCode::NotImplemented("GROUPS frame".into(), None.into()).into())
+            }
+        };
+        let bind_bound = |bound| match bound {
+            WindowFrameBound::Preceding(None) => Ok(FrameBound::UnboundedPreceding),
+            WindowFrameBound::Following(None) => Ok(FrameBound::UnboundedFollowing),
+            WindowFrameBound::CurrentRow => Ok(FrameBound::CurrentRow),
+            WindowFrameBound::Preceding(Some(offset)) if rows => Ok(FrameBound::Preceding(offset)),
+            WindowFrameBound::Following(Some(offset)) if rows => Ok(FrameBound::Following(offset)),
+            _ => Err(RwError::from(ErrorCode::NotImplemented(
+                "RANGE frame with offset".into(),
+                None.into(),
+            ))),
+        };
+        let start = bind_bound(frame.start_bound)?;
+        let end = match frame.end_bound {
+            Some(bound) => bind_bound(bound)?,
+            None => FrameBound::CurrentRow,
+        };
+        if start == FrameBound::UnboundedFollowing {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "frame start cannot be UNBOUNDED FOLLOWING".into(),
+            )
+            .into());
+        }
+        if end == FrameBound::UnboundedPreceding {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "frame end cannot be UNBOUNDED PRECEDING".into(),
+            )
+            .into());
+        }
+        Ok(WindowFrame { rows, start, end })
+    }
+
     /// Make sure inputs only have 2 value and rewrite the arguments.
     /// Nullif(expr1,expr2) -> Case(Equal(expr1 = expr2),null,expr1).
     fn rewrite_nullif_to_case_when(inputs: Vec<ExprImpl>) -> Result<Vec<ExprImpl>> {
@@ -169,6 +334,17 @@ impl Binder {
         Ok(())
     }
 
+    fn ensure_window_function_allowed(&self) -> Result<()> {
+        if let Some(clause) = self.context.clause {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "window functions are not allowed in {}",
+                clause
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
     pub(in crate::binder) fn bind_function_expr_arg(
         &mut self,
         arg_expr: FunctionArgExpr,
@@ -40,6 +40,7 @@ impl Binder {
                 s.cast_explicit(bind_data_type(&data_type)?)
             }
             Expr::Row(exprs) => Ok(ExprImpl::Literal(Box::new(self.bind_row(&exprs)?))),
+            Expr::Parameter { index } => self.bind_parameter(index),
             // input ref
             Expr::Identifier(ident) => self.bind_column(&[ident]),
             Expr::CompoundIdentifier(idents) => self.bind_column(&idents),
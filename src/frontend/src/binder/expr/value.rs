@@ -18,7 +18,7 @@ use risingwave_expr::vector_op::cast::str_parse;
 use risingwave_sqlparser::ast::{DateTimeField, Expr, Value};
 
 use crate::binder::Binder;
-use crate::expr::{align_types, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::expr::{align_types, Expr as _, ExprImpl, ExprType, FunctionCall, Literal, Parameter};
 
 impl Binder {
     pub fn bind_value(&mut self, value: Value) -> Result<Literal> {
@@ -41,6 +41,37 @@ impl Binder {
         }
     }
 
+    /// Binds a parameter `$n` of a prepared statement. If the values of the parameters are known,
+    /// it is substituted with the value cast to the parameter type. Otherwise a [`Parameter`] is
+    /// bound, whose type will be inferred according to its context.
+    pub(super) fn bind_parameter(&mut self, index: u64) -> Result<ExprImpl> {
+        let param_values = match &self.param_values {
+            None => return Ok(Parameter::new(index, self.param_types.clone()).into()),
+            Some(param_values) => param_values,
+        };
+        let value = param_values
+            .get(index as usize - 1)
+            .ok_or_else(|| {
+                ErrorCode::BindError(format!("No value found for parameter ${}", index))
+            })?
+            .clone();
+        let data_type = self
+            .param_types
+            .read_type(index)
+            .unwrap_or(DataType::Varchar);
+        match value {
+            Some(value) => {
+                let literal: ExprImpl = self.bind_string(value)?.into();
+                if literal.return_type() == data_type {
+                    Ok(literal)
+                } else {
+                    literal.cast_explicit(data_type)
+                }
+            }
+            None => Ok(Literal::new(None, data_type).into()),
+        }
+    }
+
     pub(super) fn bind_string(&mut self, s: String) -> Result<Literal> {
         Ok(Literal::new(Some(ScalarImpl::Utf8(s)), DataType::Varchar))
     }
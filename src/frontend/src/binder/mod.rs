@@ -12,7 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_pb::user::UserInfo;
 use risingwave_sqlparser::ast::Statement;
 
 pub mod bind_context;
@@ -28,13 +37,13 @@ mod struct_field;
 mod update;
 mod values;
 
-pub use bind_context::BindContext;
+pub use bind_context::{BindContext, BindingCte};
 pub use delete::BoundDelete;
 pub use insert::BoundInsert;
-pub use query::BoundQuery;
+pub use query::{is_recursive_cte, BoundQuery};
 pub use relation::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundTableSource,
-    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundTableSource, BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
 pub use select::BoundSelect;
 pub use set_expr::BoundSetExpr;
@@ -43,12 +52,15 @@ pub use update::BoundUpdate;
 pub use values::BoundValues;
 
 use crate::catalog::catalog_service::CatalogReadGuard;
+use crate::session::SessionImpl;
 
 /// `Binder` binds the identifiers in AST to columns in relations
 pub struct Binder {
     // TODO: maybe we can only lock the database, but not the whole catalog.
     catalog: CatalogReadGuard,
     db_name: String,
+    /// The user of the session, whose privileges are checked on the relations being bound.
+    user: UserInfo,
     context: BindContext,
     /// A stack holding contexts of outer queries when binding a subquery.
     ///
@@ -56,16 +68,124 @@ pub struct Binder {
     upper_contexts: Vec<BindContext>,
 
     next_subquery_id: usize,
+
+    /// Types of the parameters (`$1`, `$2`, ...) of a prepared statement, either specified by
+    /// the client or inferred during binding.
+    param_types: ParameterTypes,
+    /// Values of the parameters in text format. `None` if the statement is bound only to infer
+    /// its parameter types and output schema.
+    param_values: Option<Vec<Option<String>>>,
+}
+
+/// The types of the parameters of a prepared statement, shared by the binder and all the
+/// [`crate::expr::Parameter`]s bound by it, so that a type inferred from one occurrence of a
+/// parameter is seen by the others.
+#[derive(Clone, Debug, Default)]
+pub struct ParameterTypes(Arc<RwLock<HashMap<u64, Option<DataType>>>>);
+
+impl ParameterTypes {
+    pub fn new(specified_param_types: Vec<Option<DataType>>) -> Self {
+        let map = specified_param_types
+            .into_iter()
+            .enumerate()
+            .map(|(index, data_type)| ((index + 1) as u64, data_type))
+            .collect::<HashMap<u64, Option<DataType>>>();
+        Self(Arc::new(RwLock::new(map)))
+    }
+
+    pub fn has_infer(&self, index: u64) -> bool {
+        self.0.read().get(&index).unwrap().is_some()
+    }
+
+    pub fn read_type(&self, index: u64) -> Option<DataType> {
+        self.0.read().get(&index).unwrap().clone()
+    }
+
+    pub fn record_new_param(&self, index: u64) {
+        self.0.write().entry(index).or_insert(None);
+    }
+
+    pub fn record_infer_type(&self, index: u64, data_type: DataType) {
+        assert!(
+            !self.has_infer(index),
+            "The type of the parameter ${} has been inferred",
+            index
+        );
+        self.0.write().insert(index, Some(data_type));
+    }
+
+    /// Returns the types of `$1` up to the largest parameter referenced or specified. Parameters
+    /// whose types can not be inferred are treated as varchar.
+    pub fn export(&self) -> Vec<DataType> {
+        let types = self.0.read();
+        let max_index = types.keys().max().copied().unwrap_or(0);
+        (1..=max_index)
+            .map(|index| {
+                types
+                    .get(&index)
+                    .cloned()
+                    .flatten()
+                    .unwrap_or(DataType::Varchar)
+            })
+            .collect()
+    }
 }
 
 impl Binder {
-    pub fn new(catalog: CatalogReadGuard, db_name: String) -> Binder {
+    pub fn new(session: &SessionImpl) -> Binder {
+        Self::new_inner(session, ParameterTypes::default(), Some(vec![]))
+    }
+
+    /// Creates a binder which infers the types of the parameters of a prepared statement, given
+    /// the types specified by the client. `None` means the type is left unspecified.
+    pub fn new_with_param_types(
+        session: &SessionImpl,
+        param_types: Vec<Option<DataType>>,
+    ) -> Binder {
+        Self::new_inner(session, ParameterTypes::new(param_types), None)
+    }
+
+    /// Creates a binder which substitutes the parameters of a prepared statement with the given
+    /// values in text format.
+    pub fn new_with_params(
+        session: &SessionImpl,
+        param_types: Vec<DataType>,
+        param_values: Vec<Option<String>>,
+    ) -> Binder {
+        let param_types = ParameterTypes::new(param_types.into_iter().map(Some).collect());
+        Self::new_inner(session, param_types, Some(param_values))
+    }
+
+    fn new_inner(
+        session: &SessionImpl,
+        param_types: ParameterTypes,
+        param_values: Option<Vec<Option<String>>>,
+    ) -> Binder {
+        Self::new_with_user(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+            session.user_info(),
+            param_types,
+            param_values,
+        )
+    }
+
+    fn new_with_user(
+        catalog: CatalogReadGuard,
+        db_name: String,
+        user: UserInfo,
+        param_types: ParameterTypes,
+        param_values: Option<Vec<Option<String>>>,
+    ) -> Binder {
         Binder {
             catalog,
             db_name,
+            user,
             context: BindContext::new(),
             upper_contexts: vec![],
             next_subquery_id: 0,
+            param_types,
+            param_values,
         }
     }
 
@@ -74,11 +194,35 @@ impl Binder {
         self.bind_statement(stmt)
     }
 
+    /// Returns the types of the parameters referenced by the statements bound so far.
+    pub fn export_param_types(&self) -> Vec<DataType> {
+        self.param_types.export()
+    }
+
+    /// Adds the evaluated rows of a recursive CTE, which are bound wherever the CTE is referenced.
+    pub fn add_recursive_cte_rows(&mut self, name: String, schema: Schema, rows: Vec<Row>) {
+        self.context
+            .cte_to_relation
+            .insert(name, Rc::new(BindingCte::Rows { schema, rows }));
+    }
+
+    /// Pushes the current context to the stack and creates a new one, which still sees the CTEs
+    /// in scope.
     fn push_context(&mut self) {
         let new_context = std::mem::take(&mut self.context);
+        self.context.cte_to_relation = new_context.cte_to_relation.clone();
         self.upper_contexts.push(new_context);
     }
 
+    /// Replaces the current context with an empty one, which still sees the CTEs in scope.
+    fn reset_context(&mut self) {
+        let cte_to_relation = std::mem::take(&mut self.context.cte_to_relation);
+        self.context = BindContext {
+            cte_to_relation,
+            ..BindContext::new()
+        };
+    }
+
     fn pop_context(&mut self) {
         let old_context = self.upper_contexts.pop();
         self.context = old_context.unwrap();
@@ -96,8 +240,10 @@ pub mod test_utils {
     use std::sync::Arc;
 
     use parking_lot::RwLock;
+    use risingwave_common::catalog::DEFAULT_SUPPER_USER;
+    use risingwave_pb::user::UserInfo;
 
-    use super::Binder;
+    use super::{Binder, ParameterTypes};
     use crate::catalog::catalog_service::CatalogReader;
     use crate::catalog::root_catalog::Catalog;
 
@@ -105,7 +251,18 @@ pub mod test_utils {
     pub fn mock_binder_with_catalog(catalog: Catalog, db_name: String) -> Binder {
         let catalog = Arc::new(RwLock::new(catalog));
         let catalog_reader = CatalogReader::new(catalog);
-        Binder::new(catalog_reader.read_guard(), db_name)
+        let user = UserInfo {
+            name: DEFAULT_SUPPER_USER.to_string(),
+            is_supper: true,
+            ..Default::default()
+        };
+        Binder::new_with_user(
+            catalog_reader.read_guard(),
+            db_name,
+            user,
+            ParameterTypes::default(),
+            Some(vec![]),
+        )
     }
     #[cfg(test)]
     pub fn mock_binder() -> Binder {
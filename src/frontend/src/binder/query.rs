@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
-use risingwave_sqlparser::ast::{Expr, OrderByExpr, Query, Value};
+use risingwave_sqlparser::ast::{
+    Cte, Expr, OrderByExpr, Query, SetExpr, TableFactor, TableWithJoins, Value, With,
+};
 
-use crate::binder::{Binder, BoundSetExpr};
+use crate::binder::{Binder, BindingCte, BoundSetExpr};
 use crate::expr::ExprImpl;
 use crate::optimizer::property::{Direction, FieldOrder};
 
@@ -68,6 +71,9 @@ impl Binder {
     pub(super) fn bind_query_inner(&mut self, query: Query) -> Result<BoundQuery> {
         let limit = query.get_limit_value();
         let offset = query.get_offset_value();
+        if let Some(with) = query.with {
+            self.bind_with(with)?;
+        }
         let body = self.bind_set_expr(query.body)?;
         let mut name_to_index = HashMap::new();
         body.schema()
@@ -106,6 +112,59 @@ impl Binder {
         })
     }
 
+    /// Adds the CTEs of a `WITH` clause to the current context, which are bound as subqueries
+    /// wherever they are referenced later.
+    ///
+    /// The recursive CTEs must have been evaluated by
+    /// [`add_recursive_cte_rows`](Self::add_recursive_cte_rows), which is only done for the
+    /// top-level batch queries.
+    fn bind_with(&mut self, with: With) -> Result<()> {
+        let mut names = HashSet::new();
+        for cte in with.cte_tables {
+            let name = cte.alias.name.value.clone();
+            if !names.insert(name.clone()) {
+                return Err(ErrorCode::BindError(format!(
+                    "WITH query name \"{}\" specified more than once",
+                    name
+                ))
+                .into());
+            }
+            if cte.from.is_some() {
+                return Err(ErrorCode::NotImplemented(
+                    format!("CTE with FROM: {}", cte),
+                    None.into(),
+                )
+                .into());
+            }
+            if with.recursive && is_recursive_cte(&cte) {
+                // Only the recursive CTEs of the top-level query are evaluated before binding.
+                if self.upper_contexts.len() > 1 {
+                    return Err(ErrorCode::NotImplemented(
+                        format!("recursive CTE \"{}\" outside of the top-level query", name),
+                        None.into(),
+                    )
+                    .into());
+                }
+                match self.context.cte_to_relation.get(&name).map(Rc::as_ref) {
+                    Some(BindingCte::Rows { .. }) => continue,
+                    _ => {
+                        return Err(ErrorCode::NotImplemented(
+                            format!("recursive CTE \"{}\" outside of batch queries", name),
+                            None.into(),
+                        )
+                        .into())
+                    }
+                }
+            }
+            let Cte { alias, query, .. } = cte;
+            let ctes = self.context.cte_to_relation.clone();
+            self.context
+                .cte_to_relation
+                .insert(name, Rc::new(BindingCte::Query { query, alias, ctes }));
+        }
+        Ok(())
+    }
+
     fn bind_order_by_expr(
         &mut self,
         order_by_expr: OrderByExpr,
@@ -140,3 +199,34 @@ impl Binder {
         Ok(FieldOrder { index, direct })
     }
 }
+
+/// Whether the query of `cte` references the CTE itself. A recursive reference can only appear
+/// in the `FROM` clauses of the query, rather than in its subqueries.
+pub fn is_recursive_cte(cte: &Cte) -> bool {
+    fn refers_to(set_expr: &SetExpr, name: &str) -> bool {
+        match set_expr {
+            SetExpr::Select(select) => select.from.iter().any(|t| table_refers_to(t, name)),
+            SetExpr::Query(query) => refers_to(&query.body, name),
+            SetExpr::SetOperation { left, right, .. } => {
+                refers_to(left, name) || refers_to(right, name)
+            }
+            _ => false,
+        }
+    }
+
+    fn table_refers_to(table: &TableWithJoins, name: &str) -> bool {
+        std::iter::once(&table.relation)
+            .chain(table.joins.iter().map(|join| &join.relation))
+            .any(|relation| match relation {
+                TableFactor::Table {
+                    name: table_name,
+                    args,
+                    ..
+                } => args.is_empty() && table_name.0.len() == 1 && table_name.0[0].value == name,
+                TableFactor::NestedJoin(table) => table_refers_to(table, name),
+                _ => false,
+            })
+    }
+
+    refers_to(&cte.query.body, &cte.alias.name.value)
+}
@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_pb::plan_common::JoinType;
-use risingwave_sqlparser::ast::{JoinConstraint, JoinOperator, TableWithJoins};
+use risingwave_sqlparser::ast::{Expr, JoinConstraint, JoinOperator, TableWithJoins};
 
 use crate::binder::{Binder, Relation};
-use crate::expr::{Expr as _, ExprImpl};
+use crate::expr::{Expr as _, ExprImpl, ExprType, FunctionCall};
 
 #[derive(Debug)]
 pub struct BoundJoin {
@@ -52,8 +54,11 @@ impl Binder {
     }
 
     fn bind_table_with_joins(&mut self, table: TableWithJoins) -> Result<Relation> {
+        // The columns of the left side of each join start from here in `wildcard_columns`.
+        let left_begin = self.context.wildcard_columns.len();
         let mut root = self.bind_table_factor(table.relation)?;
         for join in table.joins {
+            let right_begin = self.context.wildcard_columns.len();
             let right = self.bind_table_factor(join.relation)?;
             let (constraint, join_type) = match join.join_operator {
                 JoinOperator::Inner(constraint) => (constraint, JoinType::Inner),
@@ -63,7 +68,18 @@ impl Binder {
                 // Cross join equals to inner join with with no constraint.
                 JoinOperator::CrossJoin => (JoinConstraint::None, JoinType::Inner),
             };
-            let cond = self.bind_join_constraint(constraint)?;
+            let cond = match constraint {
+                JoinConstraint::None => ExprImpl::literal_bool(true),
+                JoinConstraint::On(expr) => self.bind_on_constraint(expr)?,
+                JoinConstraint::Using(columns) => {
+                    let column_names = columns.into_iter().map(|c| c.value).collect();
+                    self.bind_using_constraint(column_names, join_type, left_begin, right_begin)?
+                }
+                JoinConstraint::Natural => {
+                    let column_names = self.natural_join_columns(left_begin, right_begin);
+                    self.bind_using_constraint(column_names, join_type, left_begin, right_begin)?
+                }
+            };
             let join = BoundJoin {
                 join_type,
                 left: root,
@@ -76,26 +92,125 @@ impl Binder {
         Ok(root)
     }
 
-    fn bind_join_constraint(&mut self, constraint: JoinConstraint) -> Result<ExprImpl> {
-        Ok(match constraint {
-            JoinConstraint::None => ExprImpl::literal_bool(true),
-            JoinConstraint::Natural => {
-                return Err(ErrorCode::NotImplemented("Natural join".into(), 1633.into()).into())
-            }
-            JoinConstraint::On(expr) => {
-                let bound_expr = self.bind_expr(expr)?;
-                if bound_expr.return_type() != DataType::Boolean {
-                    return Err(ErrorCode::InternalError(format!(
-                        "argument of ON must be boolean, not type {:?}",
-                        bound_expr.return_type()
-                    ))
-                    .into());
-                }
-                bound_expr
-            }
-            JoinConstraint::Using(_columns) => {
-                return Err(ErrorCode::NotImplemented("USING".into(), 1636.into()).into())
+    fn bind_on_constraint(&mut self, expr: Expr) -> Result<ExprImpl> {
+        let bound_expr = self.bind_expr(expr)?;
+        if bound_expr.return_type() != DataType::Boolean {
+            return Err(ErrorCode::InternalError(format!(
+                "argument of ON must be boolean, not type {:?}",
+                bound_expr.return_type()
+            ))
+            .into());
+        }
+        Ok(bound_expr)
+    }
+
+    /// Binds `USING (column_names)` of a join, whose left and right sides start from `left_begin`
+    /// and `right_begin` in `wildcard_columns` respectively. The columns of the same name are
+    /// compared for equality, and then merged into one column of the join.
+    fn bind_using_constraint(
+        &mut self,
+        column_names: Vec<String>,
+        join_type: JoinType,
+        left_begin: usize,
+        right_begin: usize,
+    ) -> Result<ExprImpl> {
+        let right_end = self.context.wildcard_columns.len();
+        let mut seen = HashSet::new();
+        let mut merged_columns = vec![];
+        let mut cond = ExprImpl::literal_bool(true);
+        for (i, column_name) in column_names.into_iter().enumerate() {
+            if !seen.insert(column_name.clone()) {
+                return Err(ErrorCode::BindError(format!(
+                    "column name \"{}\" appears more than once in USING clause",
+                    column_name
+                ))
+                .into());
             }
-        })
+            let left = self.find_using_column(&column_name, left_begin, right_begin, "left")?;
+            let right = self.find_using_column(&column_name, right_begin, right_end, "right")?;
+            // The left column may have been merged by a previous join.
+            let eq: ExprImpl = FunctionCall::new(
+                ExprType::Equal,
+                vec![
+                    Self::bind_column_by_index(&self.context, left, false, 0)?,
+                    Self::bind_column_by_index(&self.context, right, false, 0)?,
+                ],
+            )?
+            .into();
+            cond = match i {
+                0 => eq,
+                _ => FunctionCall::new(ExprType::And, vec![cond, eq])?.into(),
+            };
+            self.context
+                .column_group_context
+                .merge(left, right, join_type);
+            merged_columns.push((left, right));
+        }
+
+        // `SELECT *` lists the merged columns first, followed by the other columns of the left and
+        // the right side.
+        let wildcard_columns = &mut self.context.wildcard_columns;
+        let right_columns = wildcard_columns.split_off(right_begin);
+        let left_columns = wildcard_columns.split_off(left_begin);
+        wildcard_columns.extend(merged_columns.iter().map(|(left, _)| *left));
+        wildcard_columns.extend(
+            left_columns
+                .into_iter()
+                .filter(|index| !merged_columns.iter().any(|(left, _)| left == index)),
+        );
+        wildcard_columns.extend(
+            right_columns
+                .into_iter()
+                .filter(|index| !merged_columns.iter().any(|(_, right)| right == index)),
+        );
+        Ok(cond)
+    }
+
+    /// Finds the visible column named `column_name` among `wildcard_columns[begin..end]`, which
+    /// are the columns of the `side` of a join.
+    fn find_using_column(
+        &self,
+        column_name: &str,
+        begin: usize,
+        end: usize,
+        side: &str,
+    ) -> Result<usize> {
+        let mut found = self.context.wildcard_columns[begin..end]
+            .iter()
+            .filter(|index| {
+                let column = &self.context.columns[**index];
+                !column.is_hidden && column.field.name == column_name
+            });
+        match (found.next(), found.next()) {
+            (Some(index), None) => Ok(*index),
+            (None, _) => Err(ErrorCode::BindError(format!(
+                "column \"{}\" specified in USING clause does not exist in {} table",
+                column_name, side
+            ))
+            .into()),
+            (Some(_), Some(_)) => Err(ErrorCode::BindError(format!(
+                "common column name \"{}\" appears more than once in {} table",
+                column_name, side
+            ))
+            .into()),
+        }
+    }
+
+    /// Returns the names of the visible columns that appear on both sides of a natural join, in
+    /// the order of the left side.
+    fn natural_join_columns(&self, left_begin: usize, right_begin: usize) -> Vec<String> {
+        let visible_names = |begin: usize, end: usize| {
+            self.context.wildcard_columns[begin..end]
+                .iter()
+                .map(|index| &self.context.columns[*index])
+                .filter(|column| !column.is_hidden)
+                .map(|column| column.field.name.clone())
+        };
+        let right_names: HashSet<_> =
+            visible_names(right_begin, self.context.wildcard_columns.len()).collect();
+        let mut seen = HashSet::new();
+        visible_names(left_begin, right_begin)
+            .filter(|name| right_names.contains(name) && seen.insert(name.clone()))
+            .collect()
     }
 }
@@ -30,7 +30,7 @@ mod window_table_function;
 pub use generate_series::BoundGenerateSeriesFunction;
 pub use join::BoundJoin;
 pub use subquery::BoundSubquery;
-pub use table_or_source::{BoundBaseTable, BoundSource, BoundTableSource};
+pub use table_or_source::{BoundBaseTable, BoundSource, BoundSystemTable, BoundTableSource};
 pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKind};
 
 /// A validated item that refers to a table-like entity, including base table, subquery, join, etc.
@@ -43,6 +43,7 @@ pub enum Relation {
     Join(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
     GenerateSeriesFunction(Box<BoundGenerateSeriesFunction>),
+    SystemTable(Box<BoundSystemTable>),
 }
 
 impl Binder {
@@ -71,6 +72,14 @@ impl Binder {
         Self::resolve_double_name(name.0, "empty table name", DEFAULT_SCHEMA_NAME)
     }
 
+    /// return the (`schema_name`, `table_name`), where `schema_name` is `None` if the name is not
+    /// qualified by a schema
+    pub fn resolve_qualified_table_name(name: ObjectName) -> Result<(Option<String>, String)> {
+        let is_qualified = name.0.len() > 1;
+        let (schema_name, table_name) = Self::resolve_table_name(name)?;
+        Ok((is_qualified.then(|| schema_name), table_name))
+    }
+
     /// return the ( `database_name`, `schema_name`)
     pub fn resolve_schema_name(
         default_db_name: &str,
@@ -93,6 +102,20 @@ impl Binder {
         Ok(database_name)
     }
 
+    /// return the `user_name`
+    pub fn resolve_user_name(name: ObjectName) -> Result<String> {
+        let mut identifiers = name.0;
+        if identifiers.len() > 1 {
+            return Err(internal_error("user name must contain 1 argument"));
+        }
+        let user_name = identifiers
+            .pop()
+            .ok_or_else(|| internal_error("empty user name"))?
+            .value;
+
+        Ok(user_name)
+    }
+
     /// Fill the [`BindContext`](super::BindContext) for table.
     pub(super) fn bind_context(
         &mut self,
@@ -139,6 +162,9 @@ impl Binder {
             ))
             .into());
         }
+        self.context
+            .wildcard_columns
+            .extend(begin..self.context.columns.len());
 
         match self.context.range_of.entry(table_name.clone()) {
             Entry::Occupied(_) => Err(ErrorCode::InternalError(format!(
@@ -157,8 +183,17 @@ impl Binder {
         match table_factor {
             TableFactor::Table { name, alias, args } => {
                 if args.is_empty() {
-                    let (schema_name, table_name) = Self::resolve_table_name(name)?;
-                    self.bind_table_or_source(&schema_name, &table_name, alias)
+                    if let [ident] = name.0.as_slice()
+                        && let Some(cte) = self.context.cte_to_relation.get(&ident.value).cloned()
+                    {
+                        return Ok(Relation::Subquery(Box::new(self.bind_cte_relation(
+                            ident.value.clone(),
+                            &cte,
+                            alias,
+                        )?)));
+                    }
+                    let (schema_name, table_name) = Self::resolve_qualified_table_name(name)?;
+                    self.bind_table_or_source(schema_name.as_deref(), &table_name, alias)
                 } else {
                     let func_name = &name.0[0].value;
                     if func_name.eq_ignore_ascii_case("generate_series") {
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::error::Result;
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::{Query, TableAlias};
 
-use crate::binder::{Binder, BoundQuery, UNNAMED_SUBQUERY};
+use crate::binder::{Binder, BindingCte, BoundQuery, BoundSetExpr, BoundValues, UNNAMED_SUBQUERY};
+use crate::expr::Literal;
 
 #[derive(Debug)]
 pub struct BoundSubquery {
@@ -47,4 +49,71 @@ impl Binder {
         )?;
         Ok(BoundSubquery { query })
     }
+
+    /// Binds a reference to the CTE `name` as a subquery, which is named after the CTE unless
+    /// aliased.
+    ///
+    /// The query of a non-recursive CTE is bound wherever it is referenced, seeing only the CTEs
+    /// defined before it. So a relation named after the CTE itself in it refers to the table.
+    pub(super) fn bind_cte_relation(
+        &mut self,
+        name: String,
+        cte: &BindingCte,
+        alias: Option<TableAlias>,
+    ) -> Result<BoundSubquery> {
+        let (query, column_aliases) = match cte {
+            BindingCte::Query {
+                query,
+                alias: cte_alias,
+                ctes,
+            } => {
+                let ctes = std::mem::replace(&mut self.context.cte_to_relation, ctes.clone());
+                let query = self.bind_query(query.clone());
+                self.context.cte_to_relation = ctes;
+                (query?, cte_alias.columns.clone())
+            }
+            BindingCte::Rows { schema, rows } => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.0
+                            .iter()
+                            .zip_eq(schema.fields())
+                            .map(|(datum, field)| {
+                                Literal::new(datum.clone(), field.data_type()).into()
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let query = BoundQuery {
+                    body: BoundSetExpr::Values(Box::new(BoundValues {
+                        rows,
+                        schema: schema.clone(),
+                    })),
+                    order: vec![],
+                    limit: None,
+                    offset: None,
+                    extra_order_exprs: vec![],
+                };
+                (query, vec![])
+            }
+        };
+
+        // The columns are named by the column aliases of the CTE, and then those of the reference.
+        let mut fields = query.body.schema().fields().to_vec();
+        if column_aliases.len() > fields.len() {
+            return Err(ErrorCode::BindError(format!(
+                "WITH query \"{}\" has {} columns available but {} columns specified",
+                name,
+                fields.len(),
+                column_aliases.len()
+            ))
+            .into());
+        }
+        for (index, column) in column_aliases.into_iter().enumerate() {
+            fields[index].name = column.value;
+        }
+        self.bind_context(fields.into_iter().map(|f| (false, f)), name, alias)?;
+        Ok(BoundSubquery { query })
+    }
 }
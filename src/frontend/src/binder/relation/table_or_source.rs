@@ -14,14 +14,20 @@
 
 use std::sync::Arc;
 
-use risingwave_common::catalog::ColumnDesc;
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{ColumnDesc, DEFAULT_SCHEMA_NAME};
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::user::grant_privilege::Privilege;
 use risingwave_sqlparser::ast::{ObjectName, TableAlias};
 
 use crate::binder::{Binder, Relation};
 use crate::catalog::source_catalog::SourceCatalog;
+use crate::catalog::system_catalog::{
+    get_system_table, is_system_schema, SystemTable, PG_CATALOG_SCHEMA_NAME,
+};
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{CatalogError, TableId};
+use crate::user::user_privilege::{check_privilege, PrivilegeObject};
 
 #[derive(Debug)]
 pub struct BoundBaseTable {
@@ -44,6 +50,14 @@ pub struct BoundSource {
     pub catalog: SourceCatalog,
 }
 
+/// A read-only system table, e.g., `pg_catalog.pg_class`, whose rows are generated from the
+/// catalog when it's bound.
+#[derive(Debug)]
+pub struct BoundSystemTable {
+    pub sys_table: &'static SystemTable,
+    pub rows: Vec<Row>,
+}
+
 impl From<&SourceCatalog> for BoundSource {
     fn from(s: &SourceCatalog) -> Self {
         Self { catalog: s.clone() }
@@ -53,24 +67,17 @@ impl From<&SourceCatalog> for BoundSource {
 impl Binder {
     pub(super) fn bind_table_or_source(
         &mut self,
-        schema_name: &str,
+        schema_name: Option<&str>,
         table_name: &str,
         alias: Option<TableAlias>,
     ) -> Result<Relation> {
-        if schema_name == "pg_catalog" {
-            // TODO: support pg_catalog.
-            return Err(ErrorCode::NotImplemented(
-                // TODO: We can ref the document of `SHOW` commands here if ready.
-                r###"pg_catalog is not supported, please use `SHOW` commands for now.
-`SHOW TABLES`,
-`SHOW MATERIALIZED VIEWS`,
-`DESCRIBE <table>`,
-`SHOW COLUMNS FROM [table]`
-"###
-                .into(),
-                1695.into(),
-            )
-            .into());
+        let is_qualified = schema_name.is_some();
+        let schema_name = schema_name.unwrap_or(DEFAULT_SCHEMA_NAME);
+        if is_system_schema(schema_name) {
+            return match get_system_table(schema_name, table_name) {
+                Some(sys_table) => self.bind_system_table(sys_table, alias),
+                None => Err(CatalogError::NotFound("table", table_name.to_string()).into()),
+            };
         }
 
         let (ret, columns) = {
@@ -79,6 +86,12 @@ impl Binder {
                 catalog.get_table_by_name(&self.db_name, schema_name, table_name)
             {
                 let table_id = table_catalog.id();
+                self.check_relation_privilege(
+                    schema_name,
+                    table_name,
+                    table_id.table_id,
+                    Privilege::Select,
+                )?;
                 let table_catalog = table_catalog.clone();
                 let columns = table_catalog.columns.clone();
                 let table_indexes = self.resolve_table_indexes(schema_name, table_id)?;
@@ -93,7 +106,13 @@ impl Binder {
                 (Relation::BaseTable(Box::new(table)), columns)
             } else if let Ok(s) = catalog.get_source_by_name(&self.db_name, schema_name, table_name)
             {
+                self.check_relation_privilege(schema_name, table_name, s.id, Privilege::Select)?;
                 (Relation::Source(Box::new(s.into())), s.columns.clone())
+            } else if !is_qualified
+                && let Some(sys_table) = get_system_table(PG_CATALOG_SCHEMA_NAME, table_name)
+            {
+                // `pg_catalog` is implicitly searched for unqualified names, as in Postgres.
+                return self.bind_system_table(sys_table, alias);
             } else {
                 return Err(RwError::from(CatalogError::NotFound(
                     "table or source",
@@ -112,6 +131,23 @@ impl Binder {
         Ok(ret)
     }
 
+    fn bind_system_table(
+        &mut self,
+        sys_table: &'static SystemTable,
+        alias: Option<TableAlias>,
+    ) -> Result<Relation> {
+        let rows = sys_table.generate_rows(&self.catalog, &self.db_name)?;
+        self.bind_context(
+            sys_table.fields().into_iter().map(|f| (false, f)),
+            sys_table.name.to_string(),
+            alias,
+        )?;
+        Ok(Relation::SystemTable(Box::new(BoundSystemTable {
+            sys_table,
+            rows,
+        })))
+    }
+
     fn resolve_table_indexes(
         &mut self,
         schema_name: &str,
@@ -158,12 +194,24 @@ impl Binder {
         })
     }
 
-    pub(crate) fn bind_table_source(&mut self, name: ObjectName) -> Result<BoundTableSource> {
+    /// Binds the source of a table for DML, checking that the user has `privilege` on the table.
+    pub(crate) fn bind_table_source(
+        &mut self,
+        name: ObjectName,
+        privilege: Privilege,
+    ) -> Result<BoundTableSource> {
         let (schema_name, source_name) = Self::resolve_table_name(name)?;
         let source = self
             .catalog
             .get_source_by_name(&self.db_name, &schema_name, &source_name)?;
 
+        // Privileges are granted on the table rather than its associated source.
+        let relation_id = self
+            .catalog
+            .get_table_by_name(&self.db_name, &schema_name, &source_name)
+            .map_or(source.id, |table| table.id().table_id);
+        self.check_relation_privilege(&schema_name, &source_name, relation_id, privilege)?;
+
         let source_id = TableId::new(source.id);
 
         let columns = source
@@ -181,4 +229,29 @@ impl Binder {
             columns,
         })
     }
+
+    /// Checks that the user has `privilege` on the table, materialized view or source with id
+    /// `relation_id`.
+    fn check_relation_privilege(
+        &self,
+        schema_name: &str,
+        relation_name: &str,
+        relation_id: u32,
+        privilege: Privilege,
+    ) -> Result<()> {
+        if self.user.is_supper {
+            return Ok(());
+        }
+        let database_id = self.catalog.get_database_by_name(&self.db_name)?.id();
+        let schema_id = self
+            .catalog
+            .get_schema_by_name(&self.db_name, schema_name)?
+            .id();
+        let object = PrivilegeObject::Table {
+            database_id,
+            schema_id,
+            table_id: relation_id,
+        };
+        check_privilege(&self.user, &object, privilege, relation_name)
+    }
 }
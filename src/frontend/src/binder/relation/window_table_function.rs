@@ -78,7 +78,7 @@ impl Binder {
         }?;
         let (schema_name, table_name) = Self::resolve_table_name(table_name)?;
 
-        let base = self.bind_table_or_source(&schema_name, &table_name, None)?;
+        let base = self.bind_table_or_source(Some(&schema_name), &table_name, None)?;
 
         let Some(time_col_arg) = args.next() else {
             return Err(ErrorCode::BindError(
@@ -69,14 +69,18 @@ impl Binder {
         Self::require_bool_clause(&selection, "WHERE")?;
 
         // Bind GROUP BY clause.
+        self.context.clause = Some(Clause::GroupBy);
         let group_by = select
             .group_by
             .into_iter()
             .map(|expr| self.bind_expr(expr))
             .try_collect()?;
+        self.context.clause = None;
 
         // Bind HAVING clause.
+        self.context.clause = Some(Clause::Having);
         let having = select.having.map(|expr| self.bind_expr(expr)).transpose()?;
+        self.context.clause = None;
         Self::require_bool_clause(&having, "HAVING")?;
 
         // Bind SELECT clause.
@@ -153,11 +157,19 @@ impl Binder {
                     aliases.extend(names);
                 }
                 SelectItem::Wildcard => {
-                    let (exprs, names) = Self::iter_bound_columns(
-                        self.context.columns[..].iter().filter(|c| !c.is_hidden),
-                    );
-                    select_list.extend(exprs);
-                    aliases.extend(names);
+                    for index in &self.context.wildcard_columns {
+                        let column = &self.context.columns[*index];
+                        if column.is_hidden {
+                            continue;
+                        }
+                        select_list.push(Self::bind_column_by_index(
+                            &self.context,
+                            *index,
+                            false,
+                            0,
+                        )?);
+                        aliases.push(Some(column.field.name.clone()));
+                    }
                 }
             }
         }
@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::catalog::Schema;
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::SetExpr;
+use risingwave_sqlparser::ast::{SetExpr, SetOperator};
 
 use crate::binder::{Binder, BoundSelect, BoundValues};
+use crate::expr::least_restrictive;
 
 /// Part of a validated query, without order or limit clause. It may be composed of smaller
 /// `BoundSetExpr`s via set operators (e.g. union).
@@ -24,6 +26,15 @@ use crate::binder::{Binder, BoundSelect, BoundValues};
 pub enum BoundSetExpr {
     Select(Box<BoundSelect>),
     Values(Box<BoundValues>),
+    /// UNION/EXCEPT/INTERSECT of two `BoundSetExpr`s. The columns of both sides are implicitly
+    /// casted to the types in `schema` when planning.
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<BoundSetExpr>,
+        right: Box<BoundSetExpr>,
+        schema: Schema,
+    },
 }
 
 impl BoundSetExpr {
@@ -33,6 +44,7 @@ impl BoundSetExpr {
         match self {
             BoundSetExpr::Select(s) => s.schema(),
             BoundSetExpr::Values(v) => v.schema(),
+            BoundSetExpr::SetOperation { schema, .. } => schema,
         }
     }
 
@@ -40,6 +52,9 @@ impl BoundSetExpr {
         match self {
             BoundSetExpr::Select(s) => s.is_correlated(),
             BoundSetExpr::Values(_) => false,
+            BoundSetExpr::SetOperation { left, right, .. } => {
+                left.is_correlated() || right.is_correlated()
+            }
         }
     }
 }
@@ -49,7 +64,72 @@ impl Binder {
         match set_expr {
             SetExpr::Select(s) => Ok(BoundSetExpr::Select(Box::new(self.bind_select(*s)?))),
             SetExpr::Values(v) => Ok(BoundSetExpr::Values(Box::new(self.bind_values(v, None)?))),
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => self.bind_set_operation(op, all, *left, *right),
+            // A parenthesized query without any clause other than the body, e.g. `(SELECT 1)` in
+            // `SELECT 1 UNION (SELECT 1)`.
+            SetExpr::Query(query)
+                if query.with.is_none()
+                    && query.order_by.is_empty()
+                    && query.limit.is_none()
+                    && query.offset.is_none()
+                    && query.fetch.is_none() =>
+            {
+                self.bind_set_expr(query.body)
+            }
             _ => Err(ErrorCode::NotImplemented(format!("{:?}", set_expr), None.into()).into()),
         }
     }
+
+    fn bind_set_operation(
+        &mut self,
+        op: SetOperator,
+        all: bool,
+        left: SetExpr,
+        right: SetExpr,
+    ) -> Result<BoundSetExpr> {
+        // Both sides have their own scopes, and the columns of neither side are visible to the
+        // order by clause.
+        let left = self.bind_set_expr(left)?;
+        self.reset_context();
+        let right = self.bind_set_expr(right)?;
+        self.reset_context();
+
+        if left.schema().len() != right.schema().len() {
+            return Err(ErrorCode::BindError(format!(
+                "each {} query must have the same number of columns",
+                op
+            ))
+            .into());
+        }
+        let fields = left
+            .schema()
+            .fields()
+            .iter()
+            .zip_eq(right.schema().fields())
+            .map(|(l, r)| {
+                let data_type = least_restrictive(l.data_type(), r.data_type()).map_err(|_| {
+                    ErrorCode::BindError(format!(
+                        "{} types {:?} and {:?} cannot be matched",
+                        op,
+                        l.data_type(),
+                        r.data_type()
+                    ))
+                })?;
+                Ok(Field::with_name(data_type, l.name.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(BoundSetExpr::SetOperation {
+            op,
+            all,
+            left: Box::new(left),
+            right: Box::new(right),
+            schema: Schema::new(fields),
+        })
+    }
 }
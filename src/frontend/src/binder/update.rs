@@ -19,6 +19,7 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use risingwave_common::ensure;
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::grant_privilege::Privilege;
 use risingwave_sqlparser::ast::{Assignment, Expr, TableFactor, TableWithJoins};
 
 use super::{Binder, BoundTableSource, Relation};
@@ -52,9 +53,10 @@ impl Binder {
                 TableFactor::Table { name, .. } => name.clone(),
                 _ => unreachable!(),
             };
-            self.bind_table_source(name)?
+            self.bind_table_source(name, Privilege::Update)?
         };
 
+        // The rows to update are scanned from the table, which also requires `SELECT` privilege.
         let table = self.bind_vec_table_with_joins(vec![table])?.unwrap();
         assert_matches!(table, Relation::BaseTable(_));
 
@@ -20,7 +20,8 @@ use risingwave_common::catalog::{CatalogVersion, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
 use risingwave_pb::stream_plan::StreamFragmentGraph;
 use risingwave_rpc_client::MetaClient;
@@ -68,12 +69,16 @@ pub trait CatalogWriter: Send + Sync {
 
     async fn create_source(&self, source: ProstSource) -> Result<()>;
 
+    async fn create_sink(&self, sink: ProstSink, graph: StreamFragmentGraph) -> Result<()>;
+
     async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()>;
 
     async fn drop_materialized_view(&self, table_id: TableId) -> Result<()>;
 
     async fn drop_source(&self, source_id: u32) -> Result<()>;
 
+    async fn drop_sink(&self, sink_id: u32) -> Result<()>;
+
     async fn drop_database(&self, database_id: u32) -> Result<()>;
 
     async fn drop_schema(&self, schema_id: u32) -> Result<()>;
@@ -141,6 +146,11 @@ impl CatalogWriter for CatalogWriterImpl {
         self.wait_version(version).await
     }
 
+    async fn create_sink(&self, sink: ProstSink, graph: StreamFragmentGraph) -> Result<()> {
+        let (_id, version) = self.meta_client.create_sink(sink, graph).await?;
+        self.wait_version(version).await
+    }
+
     async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()> {
         let version = self
             .meta_client
@@ -159,6 +169,11 @@ impl CatalogWriter for CatalogWriterImpl {
         self.wait_version(version).await
     }
 
+    async fn drop_sink(&self, sink_id: u32) -> Result<()> {
+        let version = self.meta_client.drop_sink(sink_id).await?;
+        self.wait_version(version).await
+    }
+
     async fn drop_schema(&self, schema_id: u32) -> Result<()> {
         let version = self.meta_client.drop_schema(schema_id).await?;
         self.wait_version(version).await
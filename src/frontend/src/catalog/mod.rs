@@ -22,10 +22,13 @@ pub(crate) mod column_catalog;
 pub(crate) mod database_catalog;
 pub(crate) mod root_catalog;
 pub(crate) mod schema_catalog;
+pub(crate) mod sink_catalog;
 pub(crate) mod source_catalog;
+pub(crate) mod system_catalog;
 pub(crate) mod table_catalog;
 
 pub(crate) type SourceId = u32;
+pub(crate) type SinkId = u32;
 
 pub(crate) type DatabaseId = u32;
 pub(crate) type SchemaId = u32;
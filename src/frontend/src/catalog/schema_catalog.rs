@@ -15,10 +15,14 @@
 use std::collections::HashMap;
 
 use risingwave_common::catalog::TableId;
-use risingwave_pb::catalog::{Schema as ProstSchema, Source as ProstSource, Table as ProstTable};
+use risingwave_pb::catalog::{
+    Schema as ProstSchema, Sink as ProstSink, Source as ProstSource, Table as ProstTable,
+};
 use risingwave_pb::stream_plan::source_node::SourceType;
 
+use super::sink_catalog::SinkCatalog;
 use super::source_catalog::SourceCatalog;
+use super::SinkId;
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::SchemaId;
 
@@ -33,6 +37,8 @@ pub struct SchemaCatalog {
     table_name_by_id: HashMap<TableId, String>,
     source_by_name: HashMap<String, SourceCatalog>,
     source_name_by_id: HashMap<SourceId, String>,
+    sink_by_name: HashMap<String, SinkCatalog>,
+    sink_name_by_id: HashMap<SinkId, String>,
 }
 
 impl SchemaCatalog {
@@ -65,6 +71,21 @@ impl SchemaCatalog {
         self.source_by_name.remove(&name).unwrap();
     }
 
+    pub fn create_sink(&mut self, prost: &ProstSink) {
+        let name = prost.name.clone();
+        let id = prost.id;
+
+        self.sink_by_name
+            .try_insert(name.clone(), SinkCatalog::from(prost))
+            .unwrap();
+        self.sink_name_by_id.try_insert(id, name).unwrap();
+    }
+
+    pub fn drop_sink(&mut self, id: SinkId) {
+        let name = self.sink_name_by_id.remove(&id).unwrap();
+        self.sink_by_name.remove(&name).unwrap();
+    }
+
     pub fn iter_table(&self) -> impl Iterator<Item = &TableCatalog> {
         self.table_by_name
             .iter()
@@ -103,6 +124,10 @@ impl SchemaCatalog {
             .map(|(_, v)| v)
     }
 
+    pub fn iter_sink(&self) -> impl Iterator<Item = &SinkCatalog> {
+        self.sink_by_name.values()
+    }
+
     pub fn get_table_by_name(&self, table_name: &str) -> Option<&TableCatalog> {
         self.table_by_name.get(table_name)
     }
@@ -111,6 +136,10 @@ impl SchemaCatalog {
         self.source_by_name.get(source_name)
     }
 
+    pub fn get_sink_by_name(&self, sink_name: &str) -> Option<&SinkCatalog> {
+        self.sink_by_name.get(sink_name)
+    }
+
     pub fn id(&self) -> SchemaId {
         self.id
     }
@@ -125,6 +154,8 @@ impl From<&ProstSchema> for SchemaCatalog {
             table_name_by_id: HashMap::new(),
             source_by_name: HashMap::new(),
             source_name_by_id: HashMap::new(),
+            sink_by_name: HashMap::new(),
+            sink_name_by_id: HashMap::new(),
         }
     }
 }
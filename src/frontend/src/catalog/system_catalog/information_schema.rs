@@ -0,0 +1,101 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::Row;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::{
+    list_relations, list_schemas, sql_type_name, RelationKind, SystemTable,
+    INFORMATION_SCHEMA_SCHEMA_NAME,
+};
+use crate::catalog::root_catalog::Catalog;
+
+/// The relations of the current database. Indexes are not listed, as in Postgres.
+pub const TABLES: SystemTable = SystemTable {
+    schema_name: INFORMATION_SCHEMA_SCHEMA_NAME,
+    name: "tables",
+    columns: &[
+        (DataType::Varchar, "table_catalog"),
+        (DataType::Varchar, "table_schema"),
+        (DataType::Varchar, "table_name"),
+        (DataType::Varchar, "table_type"),
+    ],
+    generate_rows: tables_rows,
+};
+
+/// The columns of the relations in `information_schema.tables`.
+pub const COLUMNS: SystemTable = SystemTable {
+    schema_name: INFORMATION_SCHEMA_SCHEMA_NAME,
+    name: "columns",
+    columns: &[
+        (DataType::Varchar, "table_catalog"),
+        (DataType::Varchar, "table_schema"),
+        (DataType::Varchar, "table_name"),
+        (DataType::Varchar, "column_name"),
+        (DataType::Int32, "ordinal_position"),
+        (DataType::Varchar, "is_nullable"),
+        (DataType::Varchar, "data_type"),
+    ],
+    generate_rows: columns_rows,
+};
+
+fn tables_rows(catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+    let mut rows = vec![];
+    for (_, schema_name, schema) in list_schemas(catalog, db_name)? {
+        let Some(schema) = schema else { continue };
+        for relation in list_relations(schema) {
+            let table_type = match relation.kind {
+                RelationKind::Table => "BASE TABLE",
+                RelationKind::MaterializedView => "MATERIALIZED VIEW",
+                RelationKind::Source => "SOURCE",
+                RelationKind::Index => continue,
+            };
+            rows.push(Row(vec![
+                Some(ScalarImpl::Utf8(db_name.to_string())),
+                Some(ScalarImpl::Utf8(schema_name.clone())),
+                Some(ScalarImpl::Utf8(relation.name.to_string())),
+                Some(ScalarImpl::Utf8(table_type.to_string())),
+            ]));
+        }
+    }
+    Ok(rows)
+}
+
+fn columns_rows(catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+    let mut rows = vec![];
+    for (_, schema_name, schema) in list_schemas(catalog, db_name)? {
+        let Some(schema) = schema else { continue };
+        for relation in list_relations(schema) {
+            if relation.kind == RelationKind::Index {
+                continue;
+            }
+            for (i, column) in relation.columns.iter().enumerate() {
+                rows.push(Row(vec![
+                    Some(ScalarImpl::Utf8(db_name.to_string())),
+                    Some(ScalarImpl::Utf8(schema_name.clone())),
+                    Some(ScalarImpl::Utf8(relation.name.to_string())),
+                    Some(ScalarImpl::Utf8(column.name().to_string())),
+                    Some(ScalarImpl::Int32(i as i32 + 1)),
+                    // Columns have no `NOT NULL` constraints yet.
+                    Some(ScalarImpl::Utf8("YES".to_string())),
+                    Some(ScalarImpl::Utf8(
+                        sql_type_name(column.data_type()).to_string(),
+                    )),
+                ]));
+            }
+        }
+    }
+    Ok(rows)
+}
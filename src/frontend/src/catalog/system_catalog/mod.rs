@@ -0,0 +1,210 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only system tables in `pg_catalog` and `information_schema`, which let drivers and BI
+//! tools introspect the catalog. They are not stored anywhere: their rows are
+//! generated from the frontend [`Catalog`] each time they are queried.
+//!
+//! Only the columns commonly read by drivers and BI tools are provided. `psql`'s `\d` family of
+//! commands is out of scope, as it also relies on functions like `pg_table_is_visible` and casts
+//! to `regclass` that are not supported.
+
+mod information_schema;
+mod pg_catalog;
+
+use itertools::Itertools;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+
+use super::column_catalog::ColumnCatalog;
+use super::root_catalog::Catalog;
+use super::schema_catalog::SchemaCatalog;
+
+pub const PG_CATALOG_SCHEMA_NAME: &str = "pg_catalog";
+pub const INFORMATION_SCHEMA_SCHEMA_NAME: &str = "information_schema";
+
+/// Oids of the system schemas, reserved at the top of the id space so that they never collide
+/// with the ids allocated by the meta service.
+const PG_CATALOG_SCHEMA_ID: i32 = i32::MAX - 1;
+const INFORMATION_SCHEMA_SCHEMA_ID: i32 = i32::MAX - 2;
+
+pub type SystemCatalogColumns = &'static [(DataType, &'static str)];
+
+/// A read-only system table, e.g., `pg_catalog.pg_class`.
+pub struct SystemTable {
+    pub schema_name: &'static str,
+    pub name: &'static str,
+    pub columns: SystemCatalogColumns,
+    /// Generates the rows of the table, as seen from the given database.
+    generate_rows: fn(&Catalog, &str) -> Result<Vec<Row>>,
+}
+
+impl SystemTable {
+    pub fn fields(&self) -> Vec<Field> {
+        self.columns
+            .iter()
+            .map(|(data_type, name)| Field::with_name(data_type.clone(), *name))
+            .collect()
+    }
+
+    pub fn generate_rows(&self, catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+        (self.generate_rows)(catalog, db_name)
+    }
+}
+
+impl std::fmt::Debug for SystemTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.schema_name, self.name)
+    }
+}
+
+const SYSTEM_TABLES: &[SystemTable] = &[
+    pg_catalog::PG_NAMESPACE,
+    pg_catalog::PG_CLASS,
+    pg_catalog::PG_ATTRIBUTE,
+    pg_catalog::PG_TYPE,
+    pg_catalog::PG_DATABASE,
+    information_schema::TABLES,
+    information_schema::COLUMNS,
+];
+
+pub fn is_system_schema(schema_name: &str) -> bool {
+    schema_name == PG_CATALOG_SCHEMA_NAME || schema_name == INFORMATION_SCHEMA_SCHEMA_NAME
+}
+
+pub fn get_system_table(schema_name: &str, table_name: &str) -> Option<&'static SystemTable> {
+    SYSTEM_TABLES
+        .iter()
+        .find(|table| table.schema_name == schema_name && table.name == table_name)
+}
+
+/// The kind of a relation listed in the system tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RelationKind {
+    Table,
+    MaterializedView,
+    Index,
+    Source,
+}
+
+/// A user relation with its visible columns, flattened from the different kinds of catalogs.
+struct RelationInfo<'a> {
+    id: i32,
+    name: &'a str,
+    kind: RelationKind,
+    columns: Vec<&'a ColumnCatalog>,
+}
+
+/// Returns `(schema_id, schema_name, schema)` of all the schemas in the database ordered by name,
+/// including the system schemas whose `schema` is `None`.
+fn list_schemas<'a>(
+    catalog: &'a Catalog,
+    db_name: &str,
+) -> Result<Vec<(i32, String, Option<&'a SchemaCatalog>)>> {
+    let database = catalog.get_database_by_name(db_name)?;
+    let mut schemas = database
+        .get_all_schema_names()
+        .into_iter()
+        .map(|name| {
+            let schema = database.get_schema_by_name(&name).unwrap();
+            (schema.id() as i32, name, Some(schema))
+        })
+        .collect_vec();
+    schemas.push((
+        PG_CATALOG_SCHEMA_ID,
+        PG_CATALOG_SCHEMA_NAME.to_string(),
+        None,
+    ));
+    schemas.push((
+        INFORMATION_SCHEMA_SCHEMA_ID,
+        INFORMATION_SCHEMA_SCHEMA_NAME.to_string(),
+        None,
+    ));
+    schemas.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(schemas)
+}
+
+/// Returns all the user relations in the schema, ordered by id.
+fn list_relations(schema: &SchemaCatalog) -> Vec<RelationInfo<'_>> {
+    let tables = schema.iter_table().map(|t| RelationInfo {
+        id: t.id().table_id as i32,
+        name: t.name(),
+        kind: RelationKind::Table,
+        columns: visible_columns(&t.columns),
+    });
+    let mvs = schema.iter_mv().map(|t| RelationInfo {
+        id: t.id().table_id as i32,
+        name: t.name(),
+        kind: match t.is_index_on {
+            Some(_) => RelationKind::Index,
+            None => RelationKind::MaterializedView,
+        },
+        columns: visible_columns(&t.columns),
+    });
+    let sources = schema.iter_source().map(|s| RelationInfo {
+        id: s.id as i32,
+        name: &s.name,
+        kind: RelationKind::Source,
+        columns: visible_columns(&s.columns),
+    });
+
+    tables
+        .chain(mvs)
+        .chain(sources)
+        .sorted_by_key(|r| r.id)
+        .collect()
+}
+
+fn visible_columns(columns: &[ColumnCatalog]) -> Vec<&ColumnCatalog> {
+    columns.iter().filter(|c| !c.is_hidden).collect()
+}
+
+/// The name of the type in the SQL standard, used by `information_schema.columns`.
+fn sql_type_name(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::Int16 => "smallint",
+        DataType::Int32 => "integer",
+        DataType::Int64 => "bigint",
+        DataType::Float32 => "real",
+        DataType::Float64 => "double precision",
+        DataType::Boolean => "boolean",
+        DataType::Varchar => "character varying",
+        DataType::Date => "date",
+        DataType::Time => "time without time zone",
+        DataType::Timestamp => "timestamp without time zone",
+        DataType::Timestampz => "timestamp with time zone",
+        DataType::Decimal => "numeric",
+        DataType::Interval => "interval",
+        DataType::Struct { .. } => "USER-DEFINED",
+        DataType::List { .. } => "ARRAY",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_system_table() {
+        let table = get_system_table(PG_CATALOG_SCHEMA_NAME, "pg_class").unwrap();
+        assert_eq!(table.name, "pg_class");
+        assert_eq!(table.fields()[1].name, "relname");
+
+        assert!(get_system_table(INFORMATION_SCHEMA_SCHEMA_NAME, "columns").is_some());
+        assert!(get_system_table(PG_CATALOG_SCHEMA_NAME, "columns").is_none());
+        assert!(get_system_table("dev", "pg_class").is_none());
+    }
+}
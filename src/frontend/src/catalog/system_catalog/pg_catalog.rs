@@ -0,0 +1,188 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_field_descriptor::TypeOid;
+use risingwave_common::array::Row;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::{list_relations, list_schemas, RelationKind, SystemTable, PG_CATALOG_SCHEMA_NAME};
+use crate::catalog::root_catalog::Catalog;
+use crate::handler::util::data_type_to_type_oid;
+
+/// The namespaces (schemas) of the current database.
+pub const PG_NAMESPACE: SystemTable = SystemTable {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_namespace",
+    columns: &[(DataType::Int32, "oid"), (DataType::Varchar, "nspname")],
+    generate_rows: pg_namespace_rows,
+};
+
+/// The relations of the current database, i.e., tables, materialized views, indexes and sources.
+/// Relations have no owners yet, so `relowner` is always null, and they are all permanent.
+pub const PG_CLASS: SystemTable = SystemTable {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_class",
+    columns: &[
+        (DataType::Int32, "oid"),
+        (DataType::Varchar, "relname"),
+        (DataType::Int32, "relnamespace"),
+        (DataType::Int32, "relowner"),
+        (DataType::Varchar, "relpersistence"),
+        (DataType::Varchar, "relkind"),
+        (DataType::Int16, "relnatts"),
+    ],
+    generate_rows: pg_class_rows,
+};
+
+/// The columns of the relations in `pg_class`.
+pub const PG_ATTRIBUTE: SystemTable = SystemTable {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_attribute",
+    columns: &[
+        (DataType::Int32, "attrelid"),
+        (DataType::Varchar, "attname"),
+        (DataType::Int32, "atttypid"),
+        (DataType::Int16, "attnum"),
+        (DataType::Boolean, "attnotnull"),
+        (DataType::Boolean, "attisdropped"),
+    ],
+    generate_rows: pg_attribute_rows,
+};
+
+/// The data types supported by the frontend.
+pub const PG_TYPE: SystemTable = SystemTable {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_type",
+    columns: &[
+        (DataType::Int32, "oid"),
+        (DataType::Varchar, "typname"),
+        (DataType::Int16, "typlen"),
+    ],
+    generate_rows: pg_type_rows,
+};
+
+/// All the databases of the cluster.
+pub const PG_DATABASE: SystemTable = SystemTable {
+    schema_name: PG_CATALOG_SCHEMA_NAME,
+    name: "pg_database",
+    columns: &[(DataType::Int32, "oid"), (DataType::Varchar, "datname")],
+    generate_rows: pg_database_rows,
+};
+
+/// `(oid, typname, typlen)` of the rows in `pg_type`, where `typlen` is -1 for variable-length
+/// types.
+const PG_TYPE_DATA: &[(TypeOid, &str, i16)] = &[
+    (TypeOid::Boolean, "bool", 1),
+    (TypeOid::BigInt, "int8", 8),
+    (TypeOid::SmallInt, "int2", 2),
+    (TypeOid::Int, "int4", 4),
+    (TypeOid::Float4, "float4", 4),
+    (TypeOid::Float8, "float8", 8),
+    (TypeOid::CharArray, "_char", -1),
+    (TypeOid::Varchar, "varchar", -1),
+    (TypeOid::Date, "date", 4),
+    (TypeOid::Time, "time", 8),
+    (TypeOid::Timestamp, "timestamp", 8),
+    (TypeOid::Timestampz, "timestamptz", 8),
+    (TypeOid::Decimal, "numeric", -1),
+];
+
+fn pg_namespace_rows(catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+    Ok(list_schemas(catalog, db_name)?
+        .into_iter()
+        .map(|(id, name, _)| {
+            Row(vec![
+                Some(ScalarImpl::Int32(id)),
+                Some(ScalarImpl::Utf8(name)),
+            ])
+        })
+        .collect())
+}
+
+fn pg_class_rows(catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+    let mut rows = vec![];
+    for (schema_id, _, schema) in list_schemas(catalog, db_name)? {
+        let Some(schema) = schema else { continue };
+        for relation in list_relations(schema) {
+            let relkind = match relation.kind {
+                RelationKind::Table => "r",
+                RelationKind::MaterializedView => "m",
+                RelationKind::Index => "i",
+                // Sources are exposed as foreign tables, which are also backed by external data.
+                RelationKind::Source => "f",
+            };
+            rows.push(Row(vec![
+                Some(ScalarImpl::Int32(relation.id)),
+                Some(ScalarImpl::Utf8(relation.name.to_string())),
+                Some(ScalarImpl::Int32(schema_id)),
+                None,
+                Some(ScalarImpl::Utf8("p".to_string())),
+                Some(ScalarImpl::Utf8(relkind.to_string())),
+                Some(ScalarImpl::Int16(relation.columns.len() as i16)),
+            ]));
+        }
+    }
+    Ok(rows)
+}
+
+fn pg_attribute_rows(catalog: &Catalog, db_name: &str) -> Result<Vec<Row>> {
+    let mut rows = vec![];
+    for (_, _, schema) in list_schemas(catalog, db_name)? {
+        let Some(schema) = schema else { continue };
+        for relation in list_relations(schema) {
+            for (i, column) in relation.columns.iter().enumerate() {
+                let type_oid = data_type_to_type_oid(column.data_type().clone());
+                rows.push(Row(vec![
+                    Some(ScalarImpl::Int32(relation.id)),
+                    Some(ScalarImpl::Utf8(column.name().to_string())),
+                    Some(ScalarImpl::Int32(type_oid.as_number())),
+                    // Attribute numbers of user columns start from 1.
+                    Some(ScalarImpl::Int16(i as i16 + 1)),
+                    Some(ScalarImpl::Bool(false)),
+                    Some(ScalarImpl::Bool(false)),
+                ]));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn pg_type_rows(_catalog: &Catalog, _db_name: &str) -> Result<Vec<Row>> {
+    Ok(PG_TYPE_DATA
+        .iter()
+        .map(|(oid, name, len)| {
+            Row(vec![
+                Some(ScalarImpl::Int32(oid.as_number())),
+                Some(ScalarImpl::Utf8(name.to_string())),
+                Some(ScalarImpl::Int16(*len)),
+            ])
+        })
+        .collect())
+}
+
+fn pg_database_rows(catalog: &Catalog, _db_name: &str) -> Result<Vec<Row>> {
+    let mut names = catalog.get_all_database_names();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let id = catalog.get_database_by_name(&name)?.id();
+            Ok(Row(vec![
+                Some(ScalarImpl::Int32(id as i32)),
+                Some(ScalarImpl::Utf8(name)),
+            ]))
+        })
+        .collect()
+}
@@ -123,6 +123,8 @@ impl TableCatalog {
                 .iter()
                 .map(|k| *k as i32)
                 .collect_vec(),
+            // TTL is resolved from the `WITH` options when creating the materialized view.
+            ttl: 0,
         }
     }
 }
@@ -248,6 +250,7 @@ mod tests {
             distribution_keys: vec![],
             optional_associated_source_id: OptionalAssociatedSourceId::AssociatedSourceId(233)
                 .into(),
+            ttl: 0,
         }
         .into();
 
@@ -14,10 +14,13 @@
 
 //! Contains configurations that could be accessed via "set" command.
 
+use std::time::Duration;
+
 use risingwave_common::error::ErrorCode::InvalidConfigValue;
 use risingwave_common::error::RwError;
 
 use crate::config::QueryMode::{Distributed, Local};
+use crate::handler::query::STATEMENT_TIMEOUT;
 
 pub static QUERY_MODE: &str = "query_mode";
 
@@ -51,11 +54,51 @@ impl<'a> TryFrom<&'a str> for QueryMode {
     }
 }
 
+/// Parses `statement_timeout` the way PostgreSQL does: a non-negative integer followed by an
+/// optional unit among `us`, `ms`, `s`, `min`, `h` and `d`, where milliseconds is the default.
+/// The value may be single-quoted, e.g. `'5s'`.
+pub fn parse_statement_timeout(s: &str) -> Result<Duration, RwError> {
+    let invalid = || -> RwError {
+        InvalidConfigValue {
+            config_entry: STATEMENT_TIMEOUT.to_string(),
+            config_value: s.to_string(),
+        }
+        .into()
+    };
+
+    let value = s.trim();
+    let value = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .unwrap_or(value)
+        .trim();
+    let (number, unit) = value.split_at(
+        value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len()),
+    );
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let micros_per_unit: u64 = match unit.trim_start() {
+        "us" => 1,
+        "" | "ms" => 1_000,
+        "s" => 1_000_000,
+        "min" => 60 * 1_000_000,
+        "h" => 60 * 60 * 1_000_000,
+        "d" => 24 * 60 * 60 * 1_000_000,
+        _ => return Err(invalid()),
+    };
+    number
+        .checked_mul(micros_per_unit)
+        .map(Duration::from_micros)
+        .ok_or_else(invalid)
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::time::Duration;
 
-    use crate::config::QueryMode;
+    use crate::config::{parse_statement_timeout, QueryMode};
 
     #[test]
     fn parse_query_mode() {
@@ -65,4 +108,30 @@ mod tests {
         assert_matches!("diStributed".try_into().unwrap(), QueryMode::Distributed);
         assert!(QueryMode::try_from("ab").is_err());
     }
+
+    #[test]
+    fn test_parse_statement_timeout() {
+        let parse = |s| parse_statement_timeout(s).unwrap();
+        assert_eq!(parse("0"), Duration::ZERO);
+        assert_eq!(parse("1500"), Duration::from_millis(1500));
+        assert_eq!(parse("'1500'"), Duration::from_millis(1500));
+        assert_eq!(parse("'200us'"), Duration::from_micros(200));
+        assert_eq!(parse("'20ms'"), Duration::from_millis(20));
+        assert_eq!(parse("'5s'"), Duration::from_secs(5));
+        assert_eq!(parse("'5 s'"), Duration::from_secs(5));
+        assert_eq!(parse("'2min'"), Duration::from_secs(120));
+        assert_eq!(parse("'1h'"), Duration::from_secs(3600));
+        assert_eq!(parse("'1d'"), Duration::from_secs(86400));
+        for invalid in [
+            "''",
+            "'abc'",
+            "'-1'",
+            "'5 apples'",
+            "'5S'",
+            "'1.5s'",
+            "'5s5'",
+        ] {
+            assert!(parse_statement_timeout(invalid).is_err(), "{}", invalid);
+        }
+    }
 }
@@ -135,10 +135,26 @@ impl FunctionCall {
                     .collect::<Result<Vec<_>>>()?;
                 Ok(DataType::Varchar)
             }
-            _ => infer_type(
-                func_type,
-                inputs.iter().map(|expr| expr.return_type()).collect(),
-            ),
+            _ => {
+                // For binary operators like `v1 = $1`, a parameter with unknown type takes the
+                // type of the other operand.
+                if inputs.len() == 2 {
+                    if let Some(known) = inputs.iter().find(|e| !e.is_unknown_param()) {
+                        let known_type = known.return_type();
+                        inputs = inputs
+                            .into_iter()
+                            .map(|input| match input.is_unknown_param() {
+                                true => input.cast_implicit(known_type.clone()),
+                                false => Ok(input),
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                    }
+                }
+                infer_type(
+                    func_type,
+                    inputs.iter().map(|expr| expr.return_type()).collect(),
+                )
+            }
         }?;
         Ok(Self {
             func_type,
@@ -149,6 +165,11 @@ impl FunctionCall {
 
     /// Create a cast expr over `child` to `target` type in `allows` context.
     pub fn new_cast(child: ExprImpl, target: DataType, allows: CastContext) -> Result<ExprImpl> {
+        if let ExprImpl::Parameter(param) = &child && !param.has_infer() {
+            // The type of a parameter is inferred from the type it is cast to.
+            param.cast_infer_type(target);
+            return Ok(child);
+        }
         let source = child.return_type();
         if child.is_null() {
             Ok(Literal::new(None, target).into())
@@ -16,7 +16,7 @@ use enum_as_inner::EnumAsInner;
 use fixedbitset::FixedBitSet;
 use paste::paste;
 use risingwave_common::error::Result;
-use risingwave_common::types::{DataType, Scalar};
+use risingwave_common::types::{DataType, Scalar, ScalarImpl};
 use risingwave_expr::expr::AggKind;
 use risingwave_pb::expr::ExprNode;
 
@@ -25,7 +25,9 @@ mod correlated_input_ref;
 mod function_call;
 mod input_ref;
 mod literal;
+mod parameter;
 mod subquery;
+mod window_function;
 
 mod expr_rewriter;
 mod expr_visitor;
@@ -37,7 +39,9 @@ pub use correlated_input_ref::CorrelatedInputRef;
 pub use function_call::FunctionCall;
 pub use input_ref::{as_alias_display, input_ref_to_column_indices, InputRef, InputRefDisplay};
 pub use literal::Literal;
+pub use parameter::Parameter;
 pub use subquery::{Subquery, SubqueryKind};
+pub use window_function::{FrameBound, WindowFrame, WindowFunction, WindowFunctionKind};
 
 pub type ExprType = risingwave_pb::expr::expr_node::Type;
 
@@ -64,6 +68,8 @@ pub enum ExprImpl {
     FunctionCall(Box<FunctionCall>),
     AggCall(Box<AggCall>),
     Subquery(Box<Subquery>),
+    Parameter(Box<Parameter>),
+    WindowFunction(Box<WindowFunction>),
 }
 
 impl ExprImpl {
@@ -100,6 +106,32 @@ impl ExprImpl {
         matches!(self, ExprImpl::Literal(literal) if literal.get_data().is_none())
     }
 
+    /// Check whether self is a parameter whose type has not been inferred yet.
+    pub fn is_unknown_param(&self) -> bool {
+        matches!(self, ExprImpl::Parameter(param) if !param.has_infer())
+    }
+
+    /// Returns the value if self is an integer constant, which may be cast to another integer type.
+    pub fn as_int_constant(&self) -> Option<i64> {
+        match self {
+            ExprImpl::Literal(literal) => match literal.get_data() {
+                Some(ScalarImpl::Int16(v)) => Some(*v as i64),
+                Some(ScalarImpl::Int32(v)) => Some(*v as i64),
+                Some(ScalarImpl::Int64(v)) => Some(*v),
+                _ => None,
+            },
+            ExprImpl::FunctionCall(func_call) if func_call.get_expr_type() == ExprType::Cast => {
+                match func_call.return_type() {
+                    DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+                        func_call.inputs()[0].as_int_constant()
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Shorthand to create cast expr to `target` type in implicit context.
     pub fn cast_implicit(self, target: DataType) -> Result<ExprImpl> {
         FunctionCall::new_cast(self, target, CastContext::Implicit)
@@ -148,7 +180,7 @@ macro_rules! impl_has_variant {
     };
 }
 
-impl_has_variant! {InputRef, Literal, FunctionCall, AggCall, Subquery}
+impl_has_variant! {InputRef, Literal, FunctionCall, AggCall, Subquery, Parameter, WindowFunction}
 
 impl ExprImpl {
     // We need to traverse inside subqueries.
@@ -166,10 +198,17 @@ impl ExprImpl {
             }
 
             fn visit_subquery(&mut self, subquery: &Subquery) {
+                self.depth += 1;
+                self.visit_set_expr(&subquery.query.body);
+                self.depth -= 1;
+            }
+        }
+
+        impl Has {
+            fn visit_set_expr(&mut self, set_expr: &crate::binder::BoundSetExpr) {
                 use crate::binder::BoundSetExpr;
 
-                self.depth += 1;
-                match &subquery.query.body {
+                match set_expr {
                     BoundSetExpr::Select(select) => select
                         .select_items
                         .iter()
@@ -177,8 +216,11 @@ impl ExprImpl {
                         .chain(select.where_clause.iter())
                         .for_each(|expr| self.visit_expr(expr)),
                     BoundSetExpr::Values(_) => {}
+                    BoundSetExpr::SetOperation { left, right, .. } => {
+                        self.visit_set_expr(left);
+                        self.visit_set_expr(right);
+                    }
                 }
-                self.depth -= 1;
             }
         }
 
@@ -221,6 +263,8 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(expr) => expr.return_type(),
             ExprImpl::Subquery(expr) => expr.return_type(),
             ExprImpl::CorrelatedInputRef(expr) => expr.return_type(),
+            ExprImpl::Parameter(expr) => expr.return_type(),
+            ExprImpl::WindowFunction(expr) => expr.return_type(),
         }
     }
 
@@ -232,6 +276,8 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(e) => e.to_expr_proto(),
             ExprImpl::Subquery(e) => e.to_expr_proto(),
             ExprImpl::CorrelatedInputRef(e) => e.to_expr_proto(),
+            ExprImpl::Parameter(e) => e.to_expr_proto(),
+            ExprImpl::WindowFunction(e) => e.to_expr_proto(),
         }
     }
 }
@@ -272,6 +318,18 @@ impl From<CorrelatedInputRef> for ExprImpl {
     }
 }
 
+impl From<Parameter> for ExprImpl {
+    fn from(param: Parameter) -> Self {
+        ExprImpl::Parameter(Box::new(param))
+    }
+}
+
+impl From<WindowFunction> for ExprImpl {
+    fn from(window_function: WindowFunction) -> Self {
+        ExprImpl::WindowFunction(Box::new(window_function))
+    }
+}
+
 impl From<Condition> for ExprImpl {
     fn from(c: Condition) -> Self {
         merge_expr_by_binary(
@@ -297,6 +355,8 @@ impl std::fmt::Debug for ExprImpl {
                 Self::CorrelatedInputRef(arg0) => {
                     f.debug_tuple("CorrelatedInputRef").field(arg0).finish()
                 }
+                Self::Parameter(arg0) => f.debug_tuple("Parameter").field(arg0).finish(),
+                Self::WindowFunction(arg0) => f.debug_tuple("WindowFunction").field(arg0).finish(),
             };
         }
         match self {
@@ -306,6 +366,8 @@ impl std::fmt::Debug for ExprImpl {
             Self::AggCall(x) => write!(f, "{:?}", x),
             Self::Subquery(x) => write!(f, "{:?}", x),
             Self::CorrelatedInputRef(x) => write!(f, "{:?}", x),
+            Self::Parameter(x) => write!(f, "{:?}", x),
+            Self::WindowFunction(x) => write!(f, "{:?}", x),
         }
     }
 }
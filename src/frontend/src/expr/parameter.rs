@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+
+use risingwave_common::types::DataType;
+
+use super::Expr;
+use crate::binder::ParameterTypes;
+
+/// A parameter `$n` of a prepared statement. It only appears when inferring the parameter types
+/// of a statement, and is never planned: when a prepared statement is executed, parameters are
+/// bound to literals directly.
+#[derive(Clone)]
+pub struct Parameter {
+    index: u64,
+    param_types: ParameterTypes,
+}
+
+impl Parameter {
+    pub fn new(index: u64, param_types: ParameterTypes) -> Self {
+        param_types.record_new_param(index);
+        Self { index, param_types }
+    }
+
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Whether the type of the parameter has been specified or inferred.
+    pub fn has_infer(&self) -> bool {
+        self.param_types.has_infer(self.index)
+    }
+
+    /// Infers the type of the parameter as `data_type`, which is the type it is cast to.
+    pub fn cast_infer_type(&self, data_type: DataType) {
+        self.param_types.record_infer_type(self.index, data_type);
+    }
+}
+
+impl Expr for Parameter {
+    /// A parameter whose type can not be inferred from the context is treated as varchar, same as
+    /// the `unknown` type in PG.
+    fn return_type(&self) -> DataType {
+        self.param_types
+            .read_type(self.index)
+            .unwrap_or(DataType::Varchar)
+    }
+
+    fn to_expr_proto(&self) -> risingwave_pb::expr::ExprNode {
+        unreachable!("Parameter {:?} has not been bound to a value", self)
+    }
+}
+
+impl PartialEq for Parameter {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for Parameter {}
+
+impl Hash for Parameter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl Debug for Parameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("Parameter")
+                .field("index", &self.index)
+                .field("data_type", &self.return_type())
+                .finish()
+        } else {
+            write!(f, "${}:{:?}", self.index, self.return_type())
+        }
+    }
+}
@@ -346,7 +346,7 @@ pub fn align_types<'a>(exprs: impl Iterator<Item = &'a mut ExprImpl>) -> Result<
     // Essentially a filter_map followed by a try_reduce, which is unstable.
     let mut ret_type = None;
     for e in &exprs {
-        if e.is_null() {
+        if e.is_null() || e.is_unknown_param() {
             continue;
         }
         ret_type = match ret_type {
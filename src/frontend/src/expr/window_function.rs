@@ -0,0 +1,284 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_expr::expr::AggKind;
+use risingwave_pb::expr::window_frame::{
+    Bound as ProstBound, BoundType as ProstBoundType, Type as ProstFrameType,
+};
+use risingwave_pb::expr::window_function::Type as ProstWindowFunctionType;
+use risingwave_pb::expr::WindowFrame as ProstWindowFrame;
+
+use super::{AggCall, Expr, ExprImpl};
+use crate::optimizer::property::Direction;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WindowFunctionKind {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag,
+    Lead,
+    Aggregate(AggKind),
+}
+
+impl WindowFunctionKind {
+    pub fn to_protobuf(&self) -> ProstWindowFunctionType {
+        match self {
+            Self::RowNumber => ProstWindowFunctionType::RowNumber,
+            Self::Rank => ProstWindowFunctionType::Rank,
+            Self::DenseRank => ProstWindowFunctionType::DenseRank,
+            Self::Lag => ProstWindowFunctionType::Lag,
+            Self::Lead => ProstWindowFunctionType::Lead,
+            Self::Aggregate(_) => ProstWindowFunctionType::Aggregate,
+        }
+    }
+}
+
+impl fmt::Display for WindowFunctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowNumber => write!(f, "row_number"),
+            Self::Rank => write!(f, "rank"),
+            Self::DenseRank => write!(f, "dense_rank"),
+            Self::Lag => write!(f, "lag"),
+            Self::Lead => write!(f, "lead"),
+            Self::Aggregate(agg_kind) => write!(f, "{}", agg_kind),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(u64),
+    CurrentRow,
+    Following(u64),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    fn to_protobuf(self) -> ProstBound {
+        let (r#type, offset) = match self {
+            Self::UnboundedPreceding => (ProstBoundType::UnboundedPreceding, 0),
+            Self::Preceding(offset) => (ProstBoundType::Preceding, offset),
+            Self::CurrentRow => (ProstBoundType::CurrentRow, 0),
+            Self::Following(offset) => (ProstBoundType::Following, offset),
+            Self::UnboundedFollowing => (ProstBoundType::UnboundedFollowing, 0),
+        };
+        ProstBound {
+            r#type: r#type as i32,
+            offset,
+        }
+    }
+}
+
+impl fmt::Display for FrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            Self::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+            Self::CurrentRow => write!(f, "CURRENT ROW"),
+            Self::Following(offset) => write!(f, "{} FOLLOWING", offset),
+            Self::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+/// The frame of a window function. `RANGE` frames only support `UNBOUNDED` and `CURRENT ROW`
+/// bounds, where the current row includes all its peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WindowFrame {
+    pub rows: bool,
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+impl Default for WindowFrame {
+    fn default() -> Self {
+        Self {
+            rows: false,
+            start: FrameBound::UnboundedPreceding,
+            end: FrameBound::CurrentRow,
+        }
+    }
+}
+
+impl WindowFrame {
+    pub fn to_protobuf(&self) -> ProstWindowFrame {
+        let r#type = match self.rows {
+            true => ProstFrameType::Rows,
+            false => ProstFrameType::Range,
+        };
+        ProstWindowFrame {
+            r#type: r#type as i32,
+            start: Some(self.start.to_protobuf()),
+            end: Some(self.end.to_protobuf()),
+        }
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} BETWEEN {} AND {}",
+            if self.rows { "ROWS" } else { "RANGE" },
+            self.start,
+            self.end
+        )
+    }
+}
+
+/// A window function call, e.g., `rank() OVER (PARTITION BY a ORDER BY b)`.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct WindowFunction {
+    kind: WindowFunctionKind,
+    args: Vec<ExprImpl>,
+    /// The offset of `lag` and `lead`.
+    offset: usize,
+    partition_by: Vec<ExprImpl>,
+    order_by: Vec<(ExprImpl, Direction)>,
+    frame: WindowFrame,
+    return_type: DataType,
+}
+
+impl fmt::Debug for WindowFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("WindowFunction")
+                .field("kind", &self.kind)
+                .field("return_type", &self.return_type)
+                .field("args", &self.args)
+                .field("offset", &self.offset)
+                .field("partition_by", &self.partition_by)
+                .field("order_by", &self.order_by)
+                .field("frame", &self.frame)
+                .finish()
+        } else {
+            let mut builder = f.debug_tuple(&format!("{}", self.kind));
+            self.args.iter().for_each(|child| {
+                builder.field(child);
+            });
+            builder.finish()
+        }
+    }
+}
+
+impl WindowFunction {
+    /// Returns error if the arguments are invalid for the window function.
+    pub fn new(
+        kind: WindowFunctionKind,
+        args: Vec<ExprImpl>,
+        offset: usize,
+        partition_by: Vec<ExprImpl>,
+        order_by: Vec<(ExprImpl, Direction)>,
+        frame: WindowFrame,
+    ) -> Result<Self> {
+        let return_type = match (&kind, &args[..]) {
+            (
+                WindowFunctionKind::RowNumber
+                | WindowFunctionKind::Rank
+                | WindowFunctionKind::DenseRank,
+                [],
+            ) => DataType::Int64,
+            (WindowFunctionKind::Lag | WindowFunctionKind::Lead, [arg]) => arg.return_type(),
+            (WindowFunctionKind::Aggregate(agg_kind), _) => {
+                let data_types = args.iter().map(ExprImpl::return_type).collect_vec();
+                AggCall::infer_return_type(agg_kind, &data_types)?
+            }
+            _ => {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+                    "Invalid window function: {}({})",
+                    kind,
+                    args.iter()
+                        .map(|arg| format!("{:?}", arg.return_type()))
+                        .join(", ")
+                ))))
+            }
+        };
+        Ok(Self {
+            kind,
+            args,
+            offset,
+            partition_by,
+            order_by,
+            frame,
+            return_type,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn decompose(
+        self,
+    ) -> (
+        WindowFunctionKind,
+        Vec<ExprImpl>,
+        usize,
+        Vec<ExprImpl>,
+        Vec<(ExprImpl, Direction)>,
+        WindowFrame,
+    ) {
+        (
+            self.kind,
+            self.args,
+            self.offset,
+            self.partition_by,
+            self.order_by,
+            self.frame,
+        )
+    }
+
+    pub fn kind(&self) -> &WindowFunctionKind {
+        &self.kind
+    }
+
+    pub fn args(&self) -> &[ExprImpl] {
+        &self.args
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn partition_by(&self) -> &[ExprImpl] {
+        &self.partition_by
+    }
+
+    pub fn order_by(&self) -> &[(ExprImpl, Direction)] {
+        &self.order_by
+    }
+
+    pub fn frame(&self) -> WindowFrame {
+        self.frame
+    }
+}
+
+impl Expr for WindowFunction {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn to_expr_proto(&self) -> risingwave_pb::expr::ExprNode {
+        // Like `AggCall`, window functions must have been rewritten to over window operators.
+        unreachable!(
+            "WindowFunction {:?} has not been rewritten to physical over window operators",
+            self
+        )
+    }
+}
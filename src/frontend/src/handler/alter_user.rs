@@ -0,0 +1,204 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::PermissionDenied;
+use risingwave_common::error::Result;
+use risingwave_pb::user::auth_info::EncryptionType;
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_pb::user::UserInfo;
+use risingwave_sqlparser::ast::{AlterUserMode, AlterUserStatement, ObjectName, UserOption};
+
+use super::create_user::check_super_user;
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::user::user_authentication::encrypted_password;
+
+fn alter_prost_user_info(
+    mut user_info: UserInfo,
+    options: &[UserOption],
+) -> (UserInfo, Vec<UpdateField>) {
+    let mut update_fields = Vec::new();
+    for option in options {
+        match option {
+            UserOption::SuperUser => {
+                user_info.is_supper = true;
+                update_fields.push(UpdateField::Super);
+            }
+            UserOption::NoSuperUser => {
+                user_info.is_supper = false;
+                update_fields.push(UpdateField::Super);
+            }
+            UserOption::CreateDB => {
+                user_info.can_create_db = true;
+                update_fields.push(UpdateField::CreateDb);
+            }
+            UserOption::NoCreateDB => {
+                user_info.can_create_db = false;
+                update_fields.push(UpdateField::CreateDb);
+            }
+            UserOption::Login => {
+                user_info.can_login = true;
+                update_fields.push(UpdateField::Login);
+            }
+            UserOption::NoLogin => {
+                user_info.can_login = false;
+                update_fields.push(UpdateField::Login);
+            }
+            UserOption::EncryptedPassword(password) => {
+                user_info.auth_info = encrypted_password(&user_info.name, &password.0);
+                update_fields.push(UpdateField::AuthInfo);
+            }
+            UserOption::Password(opt) => {
+                user_info.auth_info = opt
+                    .as_ref()
+                    .and_then(|password| encrypted_password(&user_info.name, &password.0));
+                update_fields.push(UpdateField::AuthInfo);
+            }
+        }
+    }
+    (user_info, update_fields)
+}
+
+fn alter_rename_prost_user_info(
+    mut user_info: UserInfo,
+    new_name: ObjectName,
+) -> Result<(UserInfo, Vec<UpdateField>, Option<String>)> {
+    let new_name = Binder::resolve_user_name(new_name)?;
+    let mut update_fields = vec![UpdateField::Rename];
+    let mut notice = None;
+    // The MD5 password is salted with the user name, so it is no longer valid after renaming.
+    if let Some(auth_info) = &user_info.auth_info
+        && auth_info.encryption_type() == EncryptionType::Md5
+    {
+        user_info.auth_info = None;
+        update_fields.push(UpdateField::AuthInfo);
+        notice = Some("MD5 password cleared because of user rename".to_string());
+    }
+    user_info.name = new_name;
+    Ok((user_info, update_fields, notice))
+}
+
+/// Only super users can alter users, except that a user can change its own password.
+fn check_alter_user_privilege(
+    session: &SessionImpl,
+    user_name: &str,
+    mode: &AlterUserMode,
+) -> Result<()> {
+    if let AlterUserMode::Options(options) = mode
+        && session.user_name() == user_name
+        && options.0.iter().all(|option| {
+            matches!(option, UserOption::EncryptedPassword(_) | UserOption::Password(_))
+        })
+    {
+        return Ok(());
+    }
+    check_super_user(session, "alter users")
+}
+
+pub async fn handle_alter_user(
+    context: OptimizerContext,
+    stmt: AlterUserStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let user_name = Binder::resolve_user_name(stmt.user_name)?;
+    check_alter_user_privilege(&session, &user_name, &stmt.mode)?;
+
+    let (user_info, update_fields, notice) = {
+        let user_reader = session.env().user_info_reader().read_guard();
+        let old_info = user_reader
+            .get_user_by_name(&user_name)
+            .ok_or_else(|| CatalogError::NotFound("user", user_name.clone()))?
+            .clone();
+
+        match stmt.mode {
+            AlterUserMode::Options(options) => {
+                let (user_info, update_fields) = alter_prost_user_info(old_info, &options.0);
+                (user_info, update_fields, None)
+            }
+            AlterUserMode::Rename(new_name) => {
+                if session.user_name() == user_name {
+                    return Err(
+                        PermissionDenied("session user cannot be renamed".to_string()).into(),
+                    );
+                }
+                let (user_info, update_fields, notice) =
+                    alter_rename_prost_user_info(old_info, new_name)?;
+                if user_reader.get_user_by_name(&user_info.name).is_some() {
+                    return Err(CatalogError::Duplicated("user", user_info.name).into());
+                }
+                (user_info, update_fields, notice)
+            }
+        }
+    };
+
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer
+        .update_user(user_info, update_fields)
+        .await?;
+    Ok(match notice {
+        Some(notice) => PgResponse::empty_result_with_notice(StatementType::ALTER_USER, notice),
+        None => PgResponse::empty_result(StatementType::ALTER_USER),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_user() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let user_info_reader = session.env().user_info_reader();
+
+        frontend
+            .run_sql("CREATE USER user1 WITH NOSUPERUSER CREATEDB PASSWORD 'password'")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("ALTER USER user1 WITH SUPERUSER NOCREATEDB PASSWORD 'md59f2fa6a30871a92249bdd2f1eeee4ef6'")
+            .await
+            .unwrap();
+
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user1")
+            .cloned()
+            .unwrap();
+        assert!(user_info.is_supper);
+        assert!(!user_info.can_create_db);
+        assert_eq!(
+            user_info.auth_info.unwrap().encrypted_value,
+            b"9f2fa6a30871a92249bdd2f1eeee4ef6".to_vec()
+        );
+
+        frontend
+            .run_sql("ALTER USER user1 RENAME TO user2")
+            .await
+            .unwrap();
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user2")
+            .cloned()
+            .unwrap();
+        assert!(user_info.auth_info.is_none());
+        assert!(user_info_reader
+            .read_guard()
+            .get_user_by_name("user1")
+            .is_none());
+    }
+}
@@ -16,6 +16,7 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::ObjectName;
 
+use super::privilege::check_create_db_privilege;
 use crate::binder::Binder;
 use crate::catalog::CatalogError;
 use crate::session::OptimizerContext;
@@ -27,6 +28,7 @@ pub async fn handle_create_database(
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
     let database_name = Binder::resolve_database_name(database_name)?;
+    check_create_db_privilege(&session, "create database")?;
 
     {
         let catalog_reader = session.env().catalog_reader();
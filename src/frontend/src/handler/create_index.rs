@@ -22,6 +22,7 @@ use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::catalog::Table as ProstTable;
 use risingwave_sqlparser::ast::{ObjectName, OrderByExpr};
 
+use super::privilege::check_schema_create_privilege;
 use crate::binder::Binder;
 use crate::optimizer::plan_node::{LogicalScan, StreamTableScan};
 use crate::optimizer::property::{Distribution, FieldOrder, Order};
@@ -135,6 +136,7 @@ pub(crate) fn gen_create_index_plan(
             &index_schema_name,
             &index_table_name,
         )?;
+    check_schema_create_privilege(session, &index_schema_name)?;
 
     let index_table = materialize
         .table()
@@ -15,8 +15,9 @@
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query};
+use risingwave_sqlparser::ast::{ObjectName, Query, SqlOption, Value};
 
+use super::privilege::check_schema_create_privilege;
 use crate::binder::{Binder, BoundSetExpr};
 use crate::optimizer::property::Distribution;
 use crate::optimizer::PlanRef;
@@ -37,12 +38,10 @@ pub fn gen_create_mv_plan(
         .catalog_reader()
         .read_guard()
         .check_relation_name_duplicated(session.database(), &schema_name, &table_name)?;
+    check_schema_create_privilege(session, &schema_name)?;
 
     let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
+        let mut binder = Binder::new(session);
         binder.bind_query(*query)?
     };
 
@@ -66,15 +65,54 @@ pub fn gen_create_mv_plan(
     Ok((plan, table))
 }
 
+/// Resolves the TTL in seconds from the `WITH` options of a materialized view, e.g., `WITH (ttl =
+/// '7 days')`. A TTL without unit is in seconds. Returns 0 if no TTL is specified.
+fn resolve_ttl(with_options: &[SqlOption]) -> Result<u32> {
+    let mut ttl = 0;
+    for option in with_options {
+        if !option.name.value.eq_ignore_ascii_case("ttl") {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "unsupported option for materialized view: {}",
+                option.name
+            ))
+            .into());
+        }
+        let value = match &option.value {
+            Value::SingleQuotedString(s) | Value::Number(s, _) => s.trim(),
+            value => {
+                return Err(ErrorCode::InvalidInputSyntax(format!("invalid ttl: {}", value)).into())
+            }
+        };
+        let invalid_ttl = || ErrorCode::InvalidInputSyntax(format!("invalid ttl: {}", value));
+        let (num, unit) = match value.split_once(char::is_whitespace) {
+            Some((num, unit)) => (num, unit.trim()),
+            None => (value, "seconds"),
+        };
+        let num = num.parse::<u32>().map_err(|_| invalid_ttl())?;
+        let unit_secs = match unit.to_lowercase().as_str() {
+            "second" | "seconds" => 1,
+            "minute" | "minutes" => 60,
+            "hour" | "hours" => 60 * 60,
+            "day" | "days" => 24 * 60 * 60,
+            _ => return Err(invalid_ttl().into()),
+        };
+        ttl = num.checked_mul(unit_secs).ok_or_else(invalid_ttl)?;
+    }
+    Ok(ttl)
+}
+
 pub async fn handle_create_mv(
     context: OptimizerContext,
     name: ObjectName,
     query: Box<Query>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    let ttl = resolve_ttl(&with_options)?;
 
     let (table, graph) = {
-        let (plan, table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        let (plan, mut table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        table.ttl = ttl;
         let stream_plan = plan.to_stream_prost();
         let graph = StreamFragmenter::build_graph(stream_plan);
 
@@ -98,7 +136,9 @@ pub mod tests {
     use itertools::Itertools;
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
+    use risingwave_sqlparser::ast::{Ident, SqlOption, Value};
 
+    use super::resolve_ttl;
     use crate::catalog::row_id_column_name;
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
@@ -196,4 +236,73 @@ pub mod tests {
             "Bind error: An alias must be specified for an expression"
         );
     }
+
+    #[tokio::test]
+    async fn test_create_mv_with_cte() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t(v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let sql = "create materialized view mv1 as \
+                   with c1(a, b) as (select v1, v2 from t where v1 > 0), \
+                   c2 as (select a from c1) \
+                   select c2.a, c1.b from c1 join c2 on c1.a = c2.a";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv1")
+            .unwrap()
+            .clone();
+        let columns = table
+            .columns
+            .iter()
+            .filter(|c| !c.is_hidden)
+            .map(|c| c.name().to_string())
+            .collect_vec();
+        assert_eq!(columns, ["a", "b"]);
+
+        // A CTE can't refer to the CTEs defined after it.
+        let sql = "create materialized view mv2 as \
+                   with c1 as (select * from c2), c2 as (select v1 from t) select * from c1";
+        assert!(frontend.run_sql(sql).await.is_err());
+
+        // Recursive CTEs are only supported in batch queries.
+        let sql = "create materialized view mv3 as \
+                   with recursive c(n) as (select 1 union all select n + 1 from c where n < 5) \
+                   select n from c";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert!(err.to_string().contains("recursive CTE"), "{}", err);
+    }
+
+    #[test]
+    fn test_resolve_ttl() {
+        let ttl = |value: Value| {
+            resolve_ttl(&[SqlOption {
+                name: Ident::new("ttl"),
+                value,
+            }])
+        };
+        assert_eq!(resolve_ttl(&[]).unwrap(), 0);
+        assert_eq!(
+            ttl(Value::SingleQuotedString("7 days".to_string())).unwrap(),
+            7 * 24 * 3600
+        );
+        assert_eq!(
+            ttl(Value::SingleQuotedString("1 hour".to_string())).unwrap(),
+            3600
+        );
+        assert_eq!(ttl(Value::Number("60".to_string(), false)).unwrap(), 60);
+        assert!(ttl(Value::SingleQuotedString("7 fortnights".to_string())).is_err());
+        assert!(ttl(Value::SingleQuotedString("-1 days".to_string())).is_err());
+        assert!(resolve_ttl(&[SqlOption {
+            name: Ident::new("retention"),
+            value: Value::Number("60".to_string(), false),
+        }])
+        .is_err());
+    }
 }
@@ -16,6 +16,7 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::ObjectName;
 
+use super::privilege::check_database_create_privilege;
 use crate::binder::Binder;
 use crate::catalog::CatalogError;
 use crate::session::OptimizerContext;
@@ -48,6 +49,7 @@ pub async fn handle_create_schema(
         }
         reader.get_database_by_name(&database_name)?.id()
     };
+    check_database_create_privilege(&session, &database_name)?;
 
     let catalog_writer = session.env().catalog_writer();
     catalog_writer.create_schema(db_id, &schema_name).await?;
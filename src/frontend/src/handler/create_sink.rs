@@ -0,0 +1,195 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::rc::Rc;
+
+use fixedbitset::FixedBitSet;
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_connector::sink::SinkConfig;
+use risingwave_pb::catalog::Sink as ProstSink;
+use risingwave_pb::user::grant_privilege::Privilege;
+use risingwave_sqlparser::ast::CreateSinkStatement;
+
+use super::create_source::handle_source_with_properties;
+use super::privilege::check_schema_create_privilege;
+use crate::binder::Binder;
+use crate::optimizer::plan_node::{LogicalScan, StreamTableScan};
+use crate::optimizer::property::{Distribution, Order};
+use crate::optimizer::{PlanRef, PlanRoot};
+use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
+use crate::stream_fragmenter::StreamFragmenter;
+use crate::user::user_privilege::{check_privilege, PrivilegeObject};
+
+/// Generate create sink plan, which scans the changes of the materialized view and delivers them
+/// to the sink. Return plan and sink info.
+pub fn gen_sink_plan(
+    session: &SessionImpl,
+    context: OptimizerContextRef,
+    stmt: CreateSinkStatement,
+) -> Result<(PlanRef, ProstSink)> {
+    let (sink_schema_name, sink_name) = Binder::resolve_table_name(stmt.sink_name)?;
+    let (database_id, schema_id) = session
+        .env()
+        .catalog_reader()
+        .read_guard()
+        .check_relation_name_duplicated(session.database(), &sink_schema_name, &sink_name)?;
+    check_schema_create_privilege(session, &sink_schema_name)?;
+
+    let (schema_name, table_name) = Binder::resolve_table_name(stmt.materialized_view)?;
+    let (table, object) = {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader
+            .get_table_by_name(session.database(), &schema_name, &table_name)?
+            .clone();
+        let object = PrivilegeObject::Table {
+            database_id: reader.get_database_by_name(session.database())?.id(),
+            schema_id: reader
+                .get_schema_by_name(session.database(), &schema_name)?
+                .id(),
+            table_id: table.id().table_id,
+        };
+        (table, object)
+    };
+    check_privilege(
+        &session.user_info(),
+        &object,
+        Privilege::Select,
+        &table_name,
+    )?;
+
+    let properties = handle_source_with_properties(stmt.with_properties.0)?;
+    // Validate the properties here, so that an invalid sink fails before reaching compute nodes.
+    SinkConfig::from_hashmap(properties.clone())
+        .map_err(|e| ErrorCode::InvalidInputSyntax(e.to_string()))?;
+
+    // Manually assemble the sink plan, which scans all the columns of the materialized view and
+    // only delivers the visible ones.
+    let sink = {
+        let table_desc = Rc::new(table.table_desc());
+        let mut out_fields = FixedBitSet::with_capacity(table.columns.len());
+        let mut out_names = vec![];
+        for (i, column) in table.columns.iter().enumerate() {
+            if !column.is_hidden {
+                out_fields.insert(i);
+                out_names.push(column.name().to_string());
+            }
+        }
+
+        let scan_node = StreamTableScan::new(LogicalScan::new(
+            table_name,
+            (0..table_desc.columns.len()).into_iter().collect(),
+            table_desc,
+            // indexes are only used by DeltaJoin rule, and we don't need to provide them here.
+            vec![],
+            context,
+        ));
+
+        PlanRoot::new(
+            scan_node.into(),
+            Distribution::any().clone(),
+            Order::any().clone(),
+            out_fields,
+            out_names,
+        )
+        .gen_create_sink_plan(properties.clone())?
+    };
+
+    let sink_info = ProstSink {
+        id: 0,
+        schema_id,
+        database_id,
+        name: sink_name,
+        associated_table_id: table.id().table_id,
+        properties,
+    };
+
+    Ok((sink.into(), sink_info))
+}
+
+pub async fn handle_create_sink(
+    context: OptimizerContext,
+    stmt: CreateSinkStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+
+    let (sink, graph) = {
+        let (plan, sink) = gen_sink_plan(&session, context.into(), stmt)?;
+        let stream_plan = plan.to_stream_prost();
+        let graph = StreamFragmenter::build_graph(stream_plan);
+
+        (sink, graph)
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer.create_sink(sink, graph).await?;
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_SINK))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_sink_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 varchar)")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv1 as select v1 from t")
+            .await
+            .unwrap();
+
+        let sql = "create sink snk from mv1 with ('connector' = 'console')";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let mv = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv1")
+            .unwrap()
+            .clone();
+        let sink = catalog_reader
+            .read_guard()
+            .get_sink_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "snk")
+            .unwrap()
+            .clone();
+        assert_eq!(sink.name, "snk");
+        assert_eq!(sink.associated_table_id, mv.id());
+        assert_eq!(sink.properties["connector"], "console");
+
+        // The name of a sink can not be used by other relations.
+        let err = frontend
+            .run_sql("create materialized view snk as select v1 from t")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Catalog error: sink with name snk exists");
+
+        // Invalid properties are rejected.
+        let err = frontend
+            .run_sql("create sink snk2 from mv1 with ('connector' = 'foo')")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input syntax: sink connector 'foo' is not supported"
+        );
+    }
+}
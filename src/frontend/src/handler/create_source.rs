@@ -14,19 +14,23 @@
 
 use std::collections::HashMap;
 
-use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::error::ErrorCode::{self, InvalidInputSyntax, ProtocolError};
 use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::{Source as ProstSource, StreamSourceInfo};
-use risingwave_pb::plan_common::{ColumnCatalog as ProstColumnCatalog, RowFormatType};
-use risingwave_source::ProtobufParser;
+use risingwave_pb::catalog::{CsvOptions, Source as ProstSource, StreamSourceInfo};
+use risingwave_pb::plan_common::{
+    ColumnCatalog as ProstColumnCatalog, ColumnDesc as ProstColumnDesc, RowFormatType,
+};
+use risingwave_source::{AvroParser, DebeziumAvroParser, ProtobufParser};
 use risingwave_sqlparser::ast::{
-    CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema, SqlOption, Value,
+    AvroSchema, ColumnDef, ColumnOption, CreateSourceStatement, ObjectName, ProtobufSchema,
+    SourceSchema, SqlOption, TableConstraint, Value,
 };
 
 use super::create_table::{bind_sql_columns, gen_materialized_source_plan};
+use super::privilege::check_schema_create_privilege;
 use crate::binder::Binder;
 use crate::catalog::column_catalog::ColumnCatalog;
 use crate::session::{OptimizerContext, SessionImpl};
@@ -44,6 +48,7 @@ pub(crate) fn make_prost_source(
         .catalog_reader()
         .read_guard()
         .check_relation_name_duplicated(session.database(), &schema_name, &name)?;
+    check_schema_create_privilege(session, &schema_name)?;
 
     Ok(ProstSource {
         id: 0,
@@ -55,20 +60,185 @@ pub(crate) fn make_prost_source(
 }
 
 /// Map a protobuf schema to a relational schema.
-fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstColumnCatalog>> {
+fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstColumnDesc>> {
     let parser = ProtobufParser::new(&schema.row_schema_location.0, &schema.message_name.0)?;
-    let column_descs = parser.map_to_columns()?;
+    parser.map_to_columns()
+}
 
-    Ok(column_descs
-        .into_iter()
-        .map(|col| ProstColumnCatalog {
+/// Map an avro schema to a relational schema.
+async fn extract_avro_table_schema(
+    schema: &AvroSchema,
+    properties: HashMap<String, String>,
+) -> Result<Vec<ProstColumnDesc>> {
+    let parser = AvroParser::new(
+        &schema.row_schema_location.0,
+        schema.use_schema_registry,
+        properties,
+    )
+    .await?;
+    parser.map_to_columns()
+}
+
+/// Returns the columns of a source whose row schema is `schema_columns`. If no column is declared
+/// in `CREATE SOURCE`, all the fields in the row schema are used, otherwise each declared column
+/// must match the field of the same name in the row schema.
+fn bind_schema_columns(
+    declared: Vec<ColumnDef>,
+    schema_columns: Vec<ProstColumnDesc>,
+) -> Result<Vec<ProstColumnCatalog>> {
+    if declared.is_empty() {
+        let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
+        columns.extend(schema_columns.into_iter().map(|col| ProstColumnCatalog {
             column_desc: Some(col),
             is_hidden: false,
+        }));
+        return Ok(columns);
+    }
+
+    let columns = bind_sql_columns(declared)?;
+    // Skip the row id column.
+    for column in columns.iter().skip(1) {
+        let column = column.column_desc.as_ref().unwrap();
+        let schema_column = schema_columns
+            .iter()
+            .find(|c| c.name == column.name)
+            .ok_or_else(|| {
+                RwError::from(InvalidInputSyntax(format!(
+                    "column \"{}\" is not found in the row schema",
+                    column.name
+                )))
+            })?;
+        let data_type = DataType::from(column.column_type.as_ref().unwrap());
+        let schema_data_type = DataType::from(schema_column.column_type.as_ref().unwrap());
+        if data_type != schema_data_type {
+            return Err(RwError::from(InvalidInputSyntax(format!(
+                "column \"{}\" is declared as {:?}, but it is {:?} in the row schema",
+                column.name, data_type, schema_data_type
+            ))));
+        }
+    }
+    Ok(columns)
+}
+
+/// Binds the columns of a CSV source, which must be declared since CSV files have no schema.
+fn bind_csv_columns(declared: Vec<ColumnDef>) -> Result<Vec<ProstColumnCatalog>> {
+    if declared.is_empty() {
+        return Err(RwError::from(InvalidInputSyntax(
+            "columns must be declared for a CSV source".to_string(),
+        )));
+    }
+    let columns = bind_sql_columns(declared)?;
+    for column in columns.iter().skip(1) {
+        let column = column.column_desc.as_ref().unwrap();
+        let data_type = DataType::from(column.column_type.as_ref().unwrap());
+        if matches!(
+            data_type,
+            DataType::Interval | DataType::Struct { .. } | DataType::List { .. }
+        ) {
+            return Err(ErrorCode::NotImplemented(
+                format!(
+                    "column \"{}\" of type {:?} in CSV source",
+                    column.name, data_type
+                ),
+                None.into(),
+            )
+            .into());
+        }
+    }
+    Ok(columns)
+}
+
+/// Binds the options of `ROW FORMAT CSV`. The delimiter and the quote default to `,` and `"`, and
+/// the null string defaults to an unquoted empty string.
+fn bind_csv_options(options: &[SqlOption]) -> Result<CsvOptions> {
+    let mut csv_options = CsvOptions {
+        delimiter: ",".to_string(),
+        quote: "\"".to_string(),
+        header: false,
+        null_string: "".to_string(),
+    };
+    for option in options {
+        match (option.name.value.to_lowercase().as_str(), &option.value) {
+            ("delimiter", Value::SingleQuotedString(s)) if s.chars().count() == 1 => {
+                csv_options.delimiter = s.clone()
+            }
+            ("quote", Value::SingleQuotedString(s)) if s.chars().count() == 1 => {
+                csv_options.quote = s.clone()
+            }
+            ("header", Value::Boolean(header)) => csv_options.header = *header,
+            ("null_string", Value::SingleQuotedString(s)) => csv_options.null_string = s.clone(),
+            _ => {
+                return Err(RwError::from(InvalidInputSyntax(format!(
+                    "invalid CSV option: {}",
+                    option
+                ))))
+            }
+        }
+    }
+    if csv_options.delimiter == csv_options.quote || csv_options.delimiter == "\n" {
+        return Err(RwError::from(InvalidInputSyntax(format!(
+            "invalid CSV delimiter '{}'",
+            csv_options.delimiter
+        ))));
+    }
+    Ok(csv_options)
+}
+
+/// Binds the primary key declared either as a column option or as a table constraint to the ids
+/// of `columns`.
+fn bind_pk_column_ids(
+    columns: &[ProstColumnCatalog],
+    column_defs: &[ColumnDef],
+    constraints: &[TableConstraint],
+) -> Result<Vec<i32>> {
+    let mut pk_names = column_defs
+        .iter()
+        .filter(|column| {
+            column
+                .options
+                .iter()
+                .any(|option| matches!(option.option, ColumnOption::Unique { is_primary: true }))
         })
-        .collect_vec())
+        .map(|column| column.name.value.clone())
+        .collect::<Vec<_>>();
+    for constraint in constraints {
+        if let TableConstraint::Unique {
+            columns,
+            is_primary: true,
+            ..
+        } = constraint
+        {
+            if !pk_names.is_empty() {
+                return Err(RwError::from(InvalidInputSyntax(
+                    "multiple primary keys are not allowed".to_string(),
+                )));
+            }
+            pk_names = columns.iter().map(|c| c.value.clone()).collect();
+        }
+    }
+
+    pk_names
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .skip(1)
+                .map(|c| c.column_desc.as_ref().unwrap())
+                .find(|c| &c.name == name)
+                .map(|c| c.column_id)
+                .ok_or_else(|| {
+                    RwError::from(InvalidInputSyntax(format!(
+                        "column \"{}\" named in primary key does not exist",
+                        name
+                    )))
+                })
+        })
+        .collect()
 }
 
-fn handle_source_with_properties(options: Vec<SqlOption>) -> Result<HashMap<String, String>> {
+pub(super) fn handle_source_with_properties(
+    options: Vec<SqlOption>,
+) -> Result<HashMap<String, String>> {
     options
         .into_iter()
         .map(|x| match x.value {
@@ -87,8 +257,10 @@ pub async fn handle_create_source(
 ) -> Result<PgResponse> {
     let source = match &stmt.source_schema {
         SourceSchema::Protobuf(protobuf_schema) => {
-            let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
-            columns.extend(extract_protobuf_table_schema(protobuf_schema)?.into_iter());
+            let columns = bind_schema_columns(
+                stmt.columns,
+                extract_protobuf_table_schema(protobuf_schema)?,
+            )?;
             StreamSourceInfo {
                 properties: handle_source_with_properties(stmt.with_properties.0)?,
                 row_format: RowFormatType::Protobuf as i32,
@@ -96,6 +268,8 @@ pub async fn handle_create_source(
                 row_id_index: 0,
                 columns,
                 pk_column_ids: vec![0],
+                use_schema_registry: false,
+                csv_options: None,
             }
         }
         SourceSchema::Json => StreamSourceInfo {
@@ -105,6 +279,92 @@ pub async fn handle_create_source(
             row_id_index: 0,
             columns: bind_sql_columns(stmt.columns)?,
             pk_column_ids: vec![0],
+            use_schema_registry: false,
+            csv_options: None,
+        },
+        SourceSchema::DebeziumJson => StreamSourceInfo {
+            properties: handle_source_with_properties(stmt.with_properties.0)?,
+            row_format: RowFormatType::DebeziumJson as i32,
+            row_schema_location: "".to_string(),
+            row_id_index: 0,
+            columns: bind_sql_columns(stmt.columns)?,
+            pk_column_ids: vec![0],
+            use_schema_registry: false,
+            csv_options: None,
+        },
+        SourceSchema::Avro(avro_schema) => {
+            let properties = handle_source_with_properties(stmt.with_properties.0)?;
+            let columns = bind_schema_columns(
+                stmt.columns,
+                extract_avro_table_schema(avro_schema, properties.clone()).await?,
+            )?;
+            StreamSourceInfo {
+                properties,
+                row_format: RowFormatType::Avro as i32,
+                row_schema_location: avro_schema.row_schema_location.0.clone(),
+                row_id_index: 0,
+                columns,
+                pk_column_ids: vec![0],
+                use_schema_registry: avro_schema.use_schema_registry,
+                csv_options: None,
+            }
+        }
+        SourceSchema::DebeziumAvro(avro_schema) => {
+            if !avro_schema.use_schema_registry {
+                return Err(RwError::from(InvalidInputSyntax(
+                    "DEBEZIUM_AVRO requires CONFLUENT SCHEMA REGISTRY".to_string(),
+                )));
+            }
+            let properties = handle_source_with_properties(stmt.with_properties.0)?;
+            let columns = bind_schema_columns(
+                stmt.columns,
+                DebeziumAvroParser::map_to_columns(&avro_schema.row_schema_location.0, &properties)
+                    .await?,
+            )?;
+            StreamSourceInfo {
+                properties,
+                row_format: RowFormatType::DebeziumAvro as i32,
+                row_schema_location: avro_schema.row_schema_location.0.clone(),
+                row_id_index: 0,
+                columns,
+                pk_column_ids: vec![0],
+                use_schema_registry: true,
+                csv_options: None,
+            }
+        }
+        SourceSchema::UpsertJson => {
+            if !is_materialized {
+                return Err(RwError::from(InvalidInputSyntax(
+                    "UPSERT_JSON is only supported by tables and materialized sources".to_string(),
+                )));
+            }
+            let columns = bind_sql_columns(stmt.columns.clone())?;
+            let pk_column_ids = bind_pk_column_ids(&columns, &stmt.columns, &stmt.constraints)?;
+            if pk_column_ids.is_empty() {
+                return Err(RwError::from(InvalidInputSyntax(
+                    "UPSERT_JSON requires a primary key".to_string(),
+                )));
+            }
+            StreamSourceInfo {
+                properties: handle_source_with_properties(stmt.with_properties.0)?,
+                row_format: RowFormatType::UpsertJson as i32,
+                row_schema_location: "".to_string(),
+                row_id_index: 0,
+                columns,
+                pk_column_ids,
+                use_schema_registry: false,
+                csv_options: None,
+            }
+        }
+        SourceSchema::Csv(csv_schema) => StreamSourceInfo {
+            properties: handle_source_with_properties(stmt.with_properties.0)?,
+            row_format: RowFormatType::Csv as i32,
+            row_schema_location: "".to_string(),
+            row_id_index: 0,
+            columns: bind_csv_columns(stmt.columns)?,
+            pk_column_ids: vec![0],
+            use_schema_registry: false,
+            csv_options: Some(bind_csv_options(&csv_schema.0)?),
         },
     };
 
@@ -136,7 +396,7 @@ pub mod tests {
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
 
-    use crate::catalog::row_id_column_name;
+    use crate::catalog::{row_id_column_name, ColumnId};
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
     #[tokio::test]
@@ -193,4 +453,155 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_source_with_declared_columns() {
+        let proto_file = create_proto_file(PROTO_FILE_DATA);
+        let create_source = |name: &str, columns: &str| {
+            format!(
+                r#"CREATE SOURCE {} ({})
+    WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001')
+    ROW FORMAT PROTOBUF MESSAGE '.test.TestRecord' ROW SCHEMA LOCATION 'file://{}'"#,
+                name,
+                columns,
+                proto_file.path().to_str().unwrap()
+            )
+        };
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(create_source("t1", "id INT, rate REAL"))
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap()
+            .clone();
+        let columns = source
+            .columns
+            .iter()
+            .map(|col| (col.name(), col.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (row_id_column_name().as_str(), DataType::Int64),
+                ("id", DataType::Int32),
+                ("rate", DataType::Float32),
+            ]
+        );
+
+        let err = frontend
+            .run_sql(create_source("t2", "id BIGINT"))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("column \"id\" is declared as Int64"));
+
+        let err = frontend
+            .run_sql(create_source("t3", "city VARCHAR"))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("column \"city\" is not found in the row schema"));
+    }
+
+    #[tokio::test]
+    async fn test_create_csv_source() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                r#"CREATE SOURCE t (id INT, name VARCHAR)
+    WITH ('s3.region_name' = 'us-east-1', 's3.bucket_name' = 'abc')
+    ROW FORMAT CSV (delimiter = '|', header = true, null_string = 'NULL')"#,
+            )
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        let columns = source
+            .columns
+            .iter()
+            .map(|col| (col.name(), col.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (row_id_column_name().as_str(), DataType::Int64),
+                ("id", DataType::Int32),
+                ("name", DataType::Varchar),
+            ]
+        );
+
+        let err = frontend
+            .run_sql("CREATE SOURCE t2 (id INT) ROW FORMAT CSV (delimiter = ',,')")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid CSV option"));
+
+        let err = frontend
+            .run_sql("CREATE SOURCE t3 ROW FORMAT CSV")
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("columns must be declared for a CSV source"));
+    }
+
+    #[tokio::test]
+    async fn test_create_upsert_table() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                r#"CREATE TABLE t (id INT, name VARCHAR, PRIMARY KEY (id))
+    WITH ('connector' = 'kafka', 'kafka.topic' = 'abc', 'kafka.brokers' = 'localhost:1001')
+    ROW FORMAT UPSERT_JSON"#,
+            )
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        assert_eq!(source.pk_col_ids, vec![ColumnId::new(1)]);
+
+        // The table is keyed by the declared primary key rather than the row id.
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        assert_eq!(table.pks, vec![1]);
+
+        let err = frontend
+            .run_sql("CREATE TABLE t2 (id INT) WITH ('connector' = 'kafka') ROW FORMAT UPSERT_JSON")
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("UPSERT_JSON requires a primary key"));
+
+        let err = frontend
+            .run_sql("CREATE SOURCE t3 (id INT PRIMARY KEY) ROW FORMAT UPSERT_JSON")
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("UPSERT_JSON is only supported by tables and materialized sources"));
+    }
 }
@@ -21,7 +21,7 @@ use risingwave_common::catalog::{ColumnDesc, ColumnId};
 use risingwave_common::error::Result;
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
-use risingwave_pb::plan_common::ColumnCatalog;
+use risingwave_pb::plan_common::{ColumnCatalog, RowFormatType};
 use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName};
 
 use super::create_source::make_prost_source;
@@ -92,7 +92,8 @@ pub(crate) fn gen_create_table_plan(
 }
 
 /// Generate a stream plan with `StreamSource` + `StreamMaterialize`, it ressembles a
-/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`.
+/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`. The table is keyed by the pk of the
+/// source, which is the row id unless a primary key is declared, like for an upsert source.
 pub(crate) fn gen_materialized_source_plan(
     context: OptimizerContextRef,
     source: ProstSource,
@@ -107,14 +108,28 @@ pub(crate) fn gen_materialized_source_plan(
         let mut out_names = source_node.schema().names();
         out_names.remove(0);
 
-        PlanRoot::new(
+        let pk_indices = source_node.pk_indices().to_vec();
+
+        let materialize = PlanRoot::new(
             source_node,
-            Distribution::HashShard(vec![0]),
+            Distribution::HashShard(pk_indices),
             Order::any().clone(),
             required_cols,
             out_names,
         )
-        .gen_create_mv_plan(source.name.clone())?
+        .gen_create_mv_plan(source.name.clone())?;
+
+        // An upsert source inserts the rows with existing keys to overwrite them, and deletes the
+        // rows by key only.
+        let is_upsert = matches!(
+            &source.info,
+            Some(Info::StreamSource(info)) if info.row_format == RowFormatType::UpsertJson as i32
+        );
+        if is_upsert {
+            materialize.with_pk_conflict_handled()
+        } else {
+            materialize
+        }
     };
     let table = materialize
         .table()
@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::PermissionDenied;
+use risingwave_common::error::Result;
+use risingwave_pb::user::UserInfo;
+use risingwave_sqlparser::ast::{CreateUserStatement, UserOption, UserOptions};
+
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::user::user_authentication::encrypted_password;
+
+fn make_prost_user_info(name: String, options: &UserOptions) -> UserInfo {
+    let mut user_info = UserInfo {
+        name,
+        // the LOGIN option is implied if it is not explicitly specified.
+        can_login: true,
+        ..Default::default()
+    };
+    for option in &options.0 {
+        match option {
+            UserOption::SuperUser => user_info.is_supper = true,
+            UserOption::NoSuperUser => user_info.is_supper = false,
+            UserOption::CreateDB => user_info.can_create_db = true,
+            UserOption::NoCreateDB => user_info.can_create_db = false,
+            UserOption::Login => user_info.can_login = true,
+            UserOption::NoLogin => user_info.can_login = false,
+            UserOption::EncryptedPassword(password) => {
+                user_info.auth_info = encrypted_password(&user_info.name, &password.0);
+            }
+            UserOption::Password(opt) => {
+                user_info.auth_info = opt
+                    .as_ref()
+                    .and_then(|password| encrypted_password(&user_info.name, &password.0));
+            }
+        }
+    }
+    user_info
+}
+
+/// Checks that the current user of the session is a super user, which is required to manage other
+/// users.
+pub(super) fn check_super_user(session: &SessionImpl, action: &str) -> Result<()> {
+    let user_reader = session.env().user_info_reader().read_guard();
+    match user_reader.get_user_by_name(session.user_name()) {
+        Some(user) if user.is_supper => Ok(()),
+        _ => Err(PermissionDenied(format!("must be superuser to {}", action)).into()),
+    }
+}
+
+pub async fn handle_create_user(
+    context: OptimizerContext,
+    stmt: CreateUserStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    check_super_user(&session, "create users")?;
+
+    let user_name = Binder::resolve_user_name(stmt.user_name)?;
+    {
+        let user_reader = session.env().user_info_reader().read_guard();
+        if user_reader.get_user_by_name(&user_name).is_some() {
+            return Err(CatalogError::Duplicated("user", user_name).into());
+        }
+    }
+
+    let user_info = make_prost_user_info(user_name, &stmt.with_options);
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer.create_user(user_info).await?;
+    Ok(PgResponse::empty_result(StatementType::CREATE_USER))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::user::auth_info::EncryptionType;
+    use risingwave_pb::user::AuthInfo;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_user() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let user_info_reader = session.env().user_info_reader();
+
+        frontend
+            .run_sql("CREATE USER user1 WITH SUPERUSER CREATEDB PASSWORD 'md5827ccb0eea8a706c4c34a16891f84e7b'")
+            .await
+            .unwrap();
+
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user1")
+            .cloned()
+            .unwrap();
+        assert!(user_info.is_supper);
+        assert!(user_info.can_create_db);
+        assert!(user_info.can_login);
+        assert_eq!(
+            user_info.auth_info,
+            Some(AuthInfo {
+                encryption_type: EncryptionType::Md5 as i32,
+                encrypted_value: b"827ccb0eea8a706c4c34a16891f84e7b".to_vec()
+            })
+        );
+
+        assert!(frontend.run_sql("CREATE USER user1").await.is_err());
+    }
+}
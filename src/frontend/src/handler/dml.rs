@@ -15,6 +15,7 @@
 use futures_async_stream::for_await;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::Statement;
 
 use crate::binder::Binder;
@@ -29,14 +30,22 @@ use crate::session::{OptimizerContext, SessionImpl};
 pub static IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
 
 pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
+    handle_dml_with_params(context, stmt, vec![], vec![]).await
+}
+
+/// Handles a dml statement of a prepared statement, with the parameter `$i` bound to
+/// `params[i - 1]` of type `param_types[i - 1]`.
+pub async fn handle_dml_with_params(
+    context: OptimizerContext,
+    stmt: Statement,
+    param_types: Vec<DataType>,
+    params: Vec<Option<String>>,
+) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
 
     let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
+        let mut binder = Binder::new_with_params(&session, param_types, params);
         binder.bind(stmt)?
     };
 
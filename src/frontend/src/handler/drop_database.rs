@@ -16,6 +16,7 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
+use super::privilege::check_create_db_privilege;
 use crate::binder::Binder;
 use crate::catalog::CatalogError;
 use crate::session::OptimizerContext;
@@ -53,6 +54,7 @@ pub async fn handle_drop_database(
             }
         }
     };
+    check_create_db_privilege(&session, "drop database")?;
     let database_id = {
         // If the mode is `Restrict` or `None`, the `database` need to be empty.
         if !database.is_empty() {
@@ -17,6 +17,7 @@ use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_sqlparser::ast::ObjectName;
 
+use super::privilege::check_schema_create_privilege;
 use crate::binder::Binder;
 use crate::session::OptimizerContext;
 
@@ -26,6 +27,7 @@ pub async fn handle_drop_mv(
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
     let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    check_schema_create_privilege(&session, &schema_name)?;
 
     let catalog_reader = session.env().catalog_reader();
 
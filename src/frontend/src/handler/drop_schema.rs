@@ -16,6 +16,7 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result, TrackingIssue};
 use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
+use super::privilege::check_database_create_privilege;
 use crate::binder::Binder;
 use crate::catalog::CatalogError;
 use crate::session::OptimizerContext;
@@ -48,6 +49,7 @@ pub async fn handle_drop_schema(
             }
         }
     };
+    check_database_create_privilege(&session, &database_name)?;
     let schema_id = {
         // If the mode is `Restrict` or `None`, the `schema` need to be empty.
         if Some(DropMode::Restrict) == mode || None == mode {
@@ -67,6 +69,14 @@ pub async fn handle_drop_schema(
                     source.name.clone(),
                 )
                 .into());
+            } else if let Some(sink) = schema.iter_sink().next() {
+                return Err(CatalogError::NotEmpty(
+                    "schema",
+                    schema_name,
+                    "sink",
+                    sink.name.clone(),
+                )
+                .into());
             }
             schema.id()
         } else {
@@ -17,12 +17,14 @@ use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_sqlparser::ast::ObjectName;
 
+use super::privilege::check_schema_create_privilege;
 use crate::binder::Binder;
 use crate::session::OptimizerContext;
 
 pub async fn handle_drop_source(context: OptimizerContext, name: ObjectName) -> Result<PgResponse> {
     let session = context.session_ctx;
     let (schema_name, source_name) = Binder::resolve_table_name(name)?;
+    check_schema_create_privilege(&session, &schema_name)?;
 
     let catalog_reader = session.env().catalog_reader();
     let source = catalog_reader
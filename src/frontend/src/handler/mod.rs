@@ -17,25 +17,34 @@ use std::sync::Arc;
 use pgwire::pg_response::PgResponse;
 use pgwire::pg_response::StatementType::{ABORT, START_TRANSACTION};
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::{DropStatement, ObjectType, Statement};
+use risingwave_sqlparser::ast::{
+    CreateSourceStatement, DropStatement, ObjectType, Statement, WithProperties,
+};
 
 use crate::session::{OptimizerContext, SessionImpl};
 
+mod alter_user;
 mod create_database;
 pub mod create_index;
 pub mod create_mv;
 mod create_schema;
+pub mod create_sink;
 pub mod create_source;
 pub mod create_table;
+mod create_user;
 mod describe;
 pub mod dml;
 mod drop_database;
 pub mod drop_mv;
 mod drop_schema;
+mod drop_sink;
 pub mod drop_source;
 pub mod drop_table;
+mod drop_user;
 mod explain;
 mod flush;
+pub mod prepared_statement;
+mod privilege;
 #[allow(dead_code)]
 pub mod query;
 mod set;
@@ -52,6 +61,31 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             is_materialized,
             stmt,
         } => create_source::handle_create_source(context, is_materialized, stmt).await,
+        Statement::CreateSink { stmt } => create_sink::handle_create_sink(context, stmt).await,
+        Statement::CreateUser(stmt) => create_user::handle_create_user(context, stmt).await,
+        Statement::AlterUser(stmt) => alter_user::handle_alter_user(context, stmt).await,
+        Statement::Grant { .. } => privilege::handle_grant_privilege(context, stmt).await,
+        Statement::Revoke { .. } => privilege::handle_revoke_privilege(context, stmt).await,
+        // A table fed by a connector is a materialized source.
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            if_not_exists,
+            source_schema: Some(source_schema),
+            ..
+        } => {
+            let stmt = CreateSourceStatement {
+                if_not_exists,
+                columns,
+                constraints,
+                source_name: name,
+                with_properties: WithProperties(with_options),
+                source_schema,
+            };
+            create_source::handle_create_source(context, true, stmt).await
+        }
         Statement::CreateTable { name, columns, .. } => {
             create_table::handle_create_table(context, name, columns).await
         }
@@ -78,6 +112,7 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             ObjectType::Table => drop_table::handle_drop_table(context, object_name).await,
             ObjectType::MaterializedView => drop_mv::handle_drop_mv(context, object_name).await,
             ObjectType::Source => drop_source::handle_drop_source(context, object_name).await,
+            ObjectType::Sink => drop_sink::handle_drop_sink(context, object_name).await,
             ObjectType::Database => {
                 drop_database::handle_drop_database(
                     context,
@@ -91,6 +126,9 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
                 drop_schema::handle_drop_schema(context, object_name, if_exists, drop_mode.into())
                     .await
             }
+            ObjectType::User => {
+                drop_user::handle_drop_user(context, object_name, if_exists, drop_mode.into()).await
+            }
             _ => Err(
                 ErrorCode::InvalidInputSyntax(format!("DROP {} is unsupported", object_type))
                     .into(),
@@ -105,8 +143,9 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             or_replace: false,
             name,
             query,
+            with_options,
             ..
-        } => create_mv::handle_create_mv(context, name, query).await,
+        } => create_mv::handle_create_mv(context, name, query, with_options).await,
         Statement::Flush => flush::handle_flush(context).await,
         Statement::SetVariable {
             local: _,
@@ -0,0 +1,139 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use pgwire::pg_response::PgResponse;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::Statement;
+
+use super::util::to_pg_field;
+use super::{dml, handle, query};
+use crate::binder::{Binder, BoundStatement};
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// Infers the types of the parameters and the result columns of a prepared statement.
+/// `param_types` are the types specified by the client, where `None` means to infer the type from
+/// the context.
+pub fn describe_statement(
+    session: &SessionImpl,
+    stmt: Statement,
+    param_types: Vec<Option<DataType>>,
+) -> Result<(Vec<DataType>, Vec<PgFieldDescriptor>)> {
+    let row_description = match stmt {
+        Statement::Query(_)
+        | Statement::Insert { .. }
+        | Statement::Delete { .. }
+        | Statement::Update { .. } => {
+            let mut binder = Binder::new_with_param_types(session, param_types);
+            let bound = binder.bind(stmt)?;
+            let row_description = match bound {
+                BoundStatement::Query(query) => {
+                    query.schema().fields().iter().map(to_pg_field).collect()
+                }
+                _ => vec![],
+            };
+            return Ok((binder.export_param_types(), row_description));
+        }
+        Statement::Explain { .. } => vec![PgFieldDescriptor::new(
+            "QUERY PLAN".to_owned(),
+            TypeOid::Varchar,
+        )],
+        Statement::ShowObjects(_) => {
+            vec![PgFieldDescriptor::new("Name".to_owned(), TypeOid::Varchar)]
+        }
+        Statement::Describe { .. } | Statement::ShowColumn { .. } => vec![
+            PgFieldDescriptor::new("name".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("type".to_owned(), TypeOid::Varchar),
+        ],
+        _ => vec![],
+    };
+    let param_types = param_types
+        .into_iter()
+        .map(|t| t.unwrap_or(DataType::Varchar))
+        .collect();
+    Ok((param_types, row_description))
+}
+
+/// Handles a prepared statement, with the parameter `$i` bound to `params[i - 1]` of type
+/// `param_types[i - 1]`. Parameters are only supported in queries and DML statements.
+pub async fn handle_with_params(
+    session: Arc<SessionImpl>,
+    stmt: Statement,
+    param_types: Vec<DataType>,
+    params: Vec<Option<String>>,
+) -> Result<PgResponse> {
+    match stmt {
+        Statement::Query(_) => {
+            let context = OptimizerContext::new(session);
+            query::handle_query_with_params(context, stmt, param_types, params).await
+        }
+        Statement::Insert { .. } | Statement::Delete { .. } | Statement::Update { .. } => {
+            let context = OptimizerContext::new(session);
+            dml::handle_dml_with_params(context, stmt, param_types, params).await
+        }
+        _ if params.is_empty() => handle(session, stmt).await,
+        _ => Err(ErrorCode::NotImplemented(
+            format!("parameters in statement: {}", stmt),
+            None.into(),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pgwire::pg_field_descriptor::TypeOid;
+    use risingwave_common::types::DataType;
+    use risingwave_sqlparser::parser::Parser;
+
+    use super::describe_statement;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_describe_statement() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 varchar);")
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+
+        let describe = |sql: &str, param_types: Vec<Option<DataType>>| {
+            let stmt = Parser::parse_sql(sql).unwrap().remove(0);
+            describe_statement(&session, stmt, param_types).unwrap()
+        };
+
+        let (param_types, fields) = describe("select v1, $2 from t where v2 = $1", vec![]);
+        assert_eq!(param_types, vec![DataType::Varchar, DataType::Varchar]);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].get_type_oid(), TypeOid::Int);
+
+        let (param_types, fields) = describe("select v1 + $1 from t", vec![]);
+        assert_eq!(param_types, vec![DataType::Int32]);
+        assert_eq!(fields[0].get_type_oid(), TypeOid::Int);
+
+        let (param_types, fields) = describe(
+            "insert into t values ($1, $2)",
+            vec![None, Some(DataType::Varchar)],
+        );
+        assert_eq!(param_types, vec![DataType::Int32, DataType::Varchar]);
+        assert!(fields.is_empty());
+
+        let (param_types, _) = describe("select $1::bigint", vec![]);
+        assert_eq!(param_types, vec![DataType::Int64]);
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::PermissionDenied;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::grant_privilege::{
+    GrantAllTables, GrantDatabase, GrantSchema, GrantTable, Privilege, Target,
+};
+use risingwave_pb::user::GrantPrivilege;
+use risingwave_sqlparser::ast::{GrantObjects, Ident, Privileges, Statement};
+
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::user::user_privilege::{
+    action_to_privilege, check_grant_option, check_privilege, make_grant_privilege,
+    PrivilegeObject, AVAILABLE_DATABASE_PRIVILEGES, AVAILABLE_SCHEMA_PRIVILEGES,
+    AVAILABLE_TABLE_PRIVILEGES,
+};
+use crate::user::UserId;
+
+/// Checks that the current user has `CREATE` privilege on the schema, which is required to
+/// create or drop relations in it. Note that the owner of relations is not tracked yet, so any
+/// user who can create relations in a schema can also drop them.
+pub(super) fn check_schema_create_privilege(
+    session: &SessionImpl,
+    schema_name: &str,
+) -> Result<()> {
+    let object = {
+        let reader = session.env().catalog_reader().read_guard();
+        PrivilegeObject::Schema {
+            database_id: reader.get_database_by_name(session.database())?.id(),
+            schema_id: reader
+                .get_schema_by_name(session.database(), schema_name)?
+                .id(),
+        }
+    };
+    check_privilege(
+        &session.user_info(),
+        &object,
+        Privilege::Create,
+        schema_name,
+    )
+}
+
+/// Checks that the current user has `CREATE` privilege on the database, which is required to
+/// create or drop schemas in it.
+pub(super) fn check_database_create_privilege(
+    session: &SessionImpl,
+    database_name: &str,
+) -> Result<()> {
+    let object = {
+        let reader = session.env().catalog_reader().read_guard();
+        PrivilegeObject::Database {
+            database_id: reader.get_database_by_name(database_name)?.id(),
+        }
+    };
+    check_privilege(
+        &session.user_info(),
+        &object,
+        Privilege::Create,
+        database_name,
+    )
+}
+
+/// Checks that the current user is a super user or has `CREATEDB` attribute, which is required to
+/// create or drop databases.
+pub(super) fn check_create_db_privilege(session: &SessionImpl, action: &str) -> Result<()> {
+    let user = session.user_info();
+    if user.is_supper || user.can_create_db {
+        Ok(())
+    } else {
+        Err(PermissionDenied(format!("permission denied to {}", action)).into())
+    }
+}
+
+/// Resolves the objects in `GRANT/REVOKE` statement, returning the targets in catalog together
+/// with the objects and names used for checking the grant option.
+fn resolve_grant_objects(
+    session: &SessionImpl,
+    objects: GrantObjects,
+) -> Result<Vec<(Target, Option<PrivilegeObject>, String)>> {
+    let catalog_reader = session.env().catalog_reader().read_guard();
+    let mut resolved = vec![];
+    match objects {
+        GrantObjects::Databases(names) => {
+            for name in names {
+                let database_name = Binder::resolve_database_name(name)?;
+                let database_id = catalog_reader.get_database_by_name(&database_name)?.id();
+                resolved.push((
+                    Target::GrantDatabase(GrantDatabase { database_id }),
+                    Some(PrivilegeObject::Database { database_id }),
+                    database_name,
+                ));
+            }
+        }
+        GrantObjects::Schemas(names) => {
+            for name in names {
+                let (database_name, schema_name) =
+                    Binder::resolve_schema_name(session.database(), name)?;
+                let database_id = catalog_reader.get_database_by_name(&database_name)?.id();
+                let schema_id = catalog_reader
+                    .get_schema_by_name(&database_name, &schema_name)?
+                    .id();
+                resolved.push((
+                    Target::GrantSchema(GrantSchema {
+                        database_id,
+                        schema_id,
+                    }),
+                    Some(PrivilegeObject::Schema {
+                        database_id,
+                        schema_id,
+                    }),
+                    schema_name,
+                ));
+            }
+        }
+        GrantObjects::Tables(names) => {
+            let database_id = catalog_reader
+                .get_database_by_name(session.database())?
+                .id();
+            for name in names {
+                let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+                let schema = catalog_reader.get_schema_by_name(session.database(), &schema_name)?;
+                // Same as the binder, a table takes precedence over its associated source.
+                let table_id = match schema.get_table_by_name(&table_name) {
+                    Some(table) => table.id().table_id,
+                    None => match schema.get_source_by_name(&table_name) {
+                        Some(source) => source.id,
+                        None => {
+                            return Err(CatalogError::NotFound("table", table_name).into());
+                        }
+                    },
+                };
+                let schema_id = schema.id();
+                resolved.push((
+                    Target::GrantTable(GrantTable {
+                        database_id,
+                        schema_id,
+                        table_id,
+                    }),
+                    Some(PrivilegeObject::Table {
+                        database_id,
+                        schema_id,
+                        table_id,
+                    }),
+                    table_name,
+                ));
+            }
+        }
+        GrantObjects::AllTablesInSchema { schemas } => {
+            for name in schemas {
+                let (database_name, schema_name) =
+                    Binder::resolve_schema_name(session.database(), name)?;
+                let database_id = catalog_reader.get_database_by_name(&database_name)?.id();
+                let schema_id = catalog_reader
+                    .get_schema_by_name(&database_name, &schema_name)?
+                    .id();
+                // Only super users can grant privileges on all tables in a schema.
+                resolved.push((
+                    Target::GrantAllTables(GrantAllTables {
+                        database_id,
+                        schema_id,
+                    }),
+                    None,
+                    format!("all tables in schema {}", schema_name),
+                ));
+            }
+        }
+        GrantObjects::Sequences(_) | GrantObjects::AllSequencesInSchema { .. } => {
+            return Err(ErrorCode::NotImplemented(
+                "privileges on sequences".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+    }
+    Ok(resolved)
+}
+
+/// Returns the privileges to grant or revoke on objects with `available` privileges.
+fn resolve_privileges(privileges: Privileges, available: &[Privilege]) -> Result<Vec<Privilege>> {
+    match privileges {
+        Privileges::All { .. } => Ok(available.to_vec()),
+        Privileges::Actions(actions) => actions
+            .iter()
+            .map(|action| {
+                let privilege = action_to_privilege(action)?;
+                if available.contains(&privilege) {
+                    Ok(privilege)
+                } else {
+                    Err(ErrorCode::InvalidInputSyntax(format!(
+                        "invalid privilege type {} for the object",
+                        action
+                    ))
+                    .into())
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Resolves the privileges on objects in `GRANT/REVOKE` statement, checking that the current user
+/// can grant them.
+fn make_grant_privileges(
+    session: &SessionImpl,
+    privileges: Privileges,
+    objects: GrantObjects,
+    with_grant_option: bool,
+) -> Result<Vec<GrantPrivilege>> {
+    let user = session.user_info();
+    let available = match &objects {
+        GrantObjects::Databases(_) => AVAILABLE_DATABASE_PRIVILEGES,
+        GrantObjects::Schemas(_) => AVAILABLE_SCHEMA_PRIVILEGES,
+        _ => AVAILABLE_TABLE_PRIVILEGES,
+    };
+    let privileges = resolve_privileges(privileges, available)?;
+    resolve_grant_objects(session, objects)?
+        .into_iter()
+        .map(|(target, object, name)| {
+            for privilege in &privileges {
+                match &object {
+                    Some(object) => check_grant_option(&user, object, *privilege, &name)?,
+                    None if !user.is_supper => {
+                        return Err(PermissionDenied(format!(
+                            "must be superuser to grant privileges on {}",
+                            name
+                        ))
+                        .into());
+                    }
+                    None => {}
+                }
+            }
+            Ok(make_grant_privilege(target, &privileges, with_grant_option))
+        })
+        .collect()
+}
+
+fn resolve_grantees(session: &SessionImpl, grantees: Vec<Ident>) -> Result<Vec<UserId>> {
+    let user_reader = session.env().user_info_reader().read_guard();
+    grantees
+        .into_iter()
+        .map(|grantee| {
+            let user_name = grantee.value;
+            user_reader
+                .get_user_by_name(&user_name)
+                .map(|user| user.id)
+                .ok_or_else(|| CatalogError::NotFound("user", user_name).into())
+        })
+        .collect()
+}
+
+pub async fn handle_grant_privilege(
+    context: OptimizerContext,
+    stmt: Statement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let Statement::Grant {
+        privileges,
+        objects,
+        grantees,
+        with_grant_option,
+        granted_by,
+    } = stmt else {
+        return Err(ErrorCode::BindError("Invalid grant statement".to_string()).into());
+    };
+    if granted_by.is_some() {
+        return Err(ErrorCode::NotImplemented("GRANTED BY".to_string(), None.into()).into());
+    }
+
+    let privileges = make_grant_privileges(&session, privileges, objects, with_grant_option)?;
+    let users = resolve_grantees(&session, grantees)?;
+
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer.grant_privilege(users, privileges).await?;
+    Ok(PgResponse::empty_result(StatementType::GRANT_PRIVILEGE))
+}
+
+pub async fn handle_revoke_privilege(
+    context: OptimizerContext,
+    stmt: Statement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let Statement::Revoke {
+        revoke_grant_option,
+        privileges,
+        objects,
+        grantees,
+        granted_by,
+        cascade,
+    } = stmt else {
+        return Err(ErrorCode::BindError("Invalid revoke statement".to_string()).into());
+    };
+    if granted_by.is_some() {
+        return Err(ErrorCode::NotImplemented("GRANTED BY".to_string(), None.into()).into());
+    }
+    if cascade {
+        return Err(ErrorCode::NotImplemented("REVOKE CASCADE".to_string(), None.into()).into());
+    }
+
+    let privileges = make_grant_privileges(&session, privileges, objects, false)?;
+    let users = resolve_grantees(&session, grantees)?;
+
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer
+        .revoke_privilege(users, privileges, revoke_grant_option)
+        .await?;
+    Ok(PgResponse::empty_result(StatementType::REVOKE_PRIVILEGE))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use pgwire::pg_server::{Session, UserAuthenticator};
+    use risingwave_common::catalog::DEFAULT_DATABASE_NAME;
+    use risingwave_pb::user::grant_privilege::{Privilege, Target};
+    use risingwave_sqlparser::parser::Parser;
+
+    use crate::binder::Binder;
+    use crate::session::SessionImpl;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_grant_revoke_privilege() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let user_info_reader = session.env().user_info_reader();
+        frontend.run_sql("CREATE TABLE t (v1 int)").await.unwrap();
+        frontend.run_sql("CREATE USER user1").await.unwrap();
+
+        let user_session = Arc::new(SessionImpl::new(
+            session.env().clone(),
+            DEFAULT_DATABASE_NAME.to_string(),
+            "user1".to_string(),
+            UserAuthenticator::None,
+            (0, 0),
+        ));
+        let bind = |sql: &str| {
+            let stmt = Parser::parse_sql(sql).unwrap().remove(0);
+            Binder::new(&user_session).bind(stmt).map(|_| ())
+        };
+        assert!(bind("SELECT * FROM t").is_err());
+        assert!(user_session
+            .clone()
+            .run_statement("CREATE TABLE t2 (v1 int)")
+            .await
+            .is_err());
+
+        frontend
+            .run_sql("GRANT SELECT ON t TO user1 WITH GRANT OPTION")
+            .await
+            .unwrap();
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user1")
+            .cloned()
+            .unwrap();
+        assert_eq!(user_info.privileges.len(), 1);
+        assert!(matches!(
+            user_info.privileges[0].target,
+            Some(Target::GrantTable(_))
+        ));
+        let opts = &user_info.privileges[0].privilege_with_opts;
+        assert_eq!(opts.len(), 1);
+        assert_eq!(opts[0].privilege, Privilege::Select as i32);
+        assert!(opts[0].with_grant_option);
+        assert!(bind("SELECT * FROM t").is_ok());
+        assert!(bind("INSERT INTO t VALUES (1)").is_err());
+
+        frontend
+            .run_sql("REVOKE GRANT OPTION FOR SELECT ON t FROM user1")
+            .await
+            .unwrap();
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user1")
+            .cloned()
+            .unwrap();
+        assert!(!user_info.privileges[0].privilege_with_opts[0].with_grant_option);
+        assert!(user_session
+            .clone()
+            .run_statement("GRANT SELECT ON t TO root")
+            .await
+            .is_err());
+
+        frontend
+            .run_sql("GRANT ALL ON SCHEMA dev TO user1")
+            .await
+            .unwrap();
+        user_session
+            .clone()
+            .run_statement("CREATE TABLE t2 (v1 int)")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("REVOKE ALL ON t FROM user1")
+            .await
+            .unwrap();
+        assert!(bind("SELECT * FROM t").is_err());
+    }
+}
@@ -12,31 +12,70 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use futures_async_stream::for_await;
 use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
-use risingwave_sqlparser::ast::Statement;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::{Query, SetExpr, SetOperator, Statement, With};
 use tracing::info;
 
-use crate::binder::{Binder, BoundStatement};
+use crate::binder::{is_recursive_cte, Binder, BoundStatement};
 use crate::handler::util::{to_pg_field, to_pg_rows};
 use crate::planner::Planner;
 use crate::scheduler::plan_fragmenter::BatchPlanFragmenter;
 use crate::scheduler::{DataChunkStream, ExecutionContext, ExecutionContextRef};
-use crate::session::OptimizerContext;
+use crate::session::{OptimizerContext, SessionImpl};
 
 pub static QUERY_MODE: &str = "query_mode";
 
+/// The maximum number of iterations to evaluate a recursive CTE, beyond which the query fails,
+/// as the recursion may never end.
+pub static MAX_RECURSIVE_ITERATIONS: &str = "RW_MAX_RECURSIVE_ITERATIONS";
+const DEFAULT_MAX_RECURSIVE_ITERATIONS: usize = 100;
+
+/// The maximum number of rows a recursive CTE can produce, beyond which the query fails, as all
+/// the rows are embedded in the plans of the following queries.
+pub static MAX_RECURSIVE_ROWS: &str = "RW_MAX_RECURSIVE_ROWS";
+const DEFAULT_MAX_RECURSIVE_ROWS: usize = 10000;
+
+/// The maximum time a query can run, beyond which it's cancelled. It's in milliseconds unless a
+/// unit like `'5s'` is given, see [`crate::config::parse_statement_timeout`]. Zero disables the
+/// timeout, which is the default.
+pub static STATEMENT_TIMEOUT: &str = "statement_timeout";
+
 pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
+    handle_query_with_params(context, stmt, vec![], vec![]).await
+}
+
+/// Handles a query statement of a prepared statement, with the parameter `$i` bound to
+/// `params[i - 1]` of type `param_types[i - 1]`.
+pub async fn handle_query_with_params(
+    context: OptimizerContext,
+    stmt: Statement,
+    param_types: Vec<DataType>,
+    params: Vec<Option<String>>,
+) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
 
+    let recursive_ctes = match &stmt {
+        Statement::Query(query) => {
+            eval_recursive_ctes(&session, query, &param_types, &params).await?
+        }
+        _ => vec![],
+    };
+
     let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
+        let mut binder = Binder::new_with_params(&session, param_types, params);
+        for (name, schema, rows) in recursive_ctes {
+            binder.add_recursive_cte_rows(name, schema, rows);
+        }
         binder.bind(stmt)?
     };
 
@@ -56,6 +95,183 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
     Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
 }
 
+/// Evaluates the recursive CTEs in the `WITH RECURSIVE` clause of `query` in order, returning the
+/// name, the schema and the rows of each of them.
+///
+/// A recursive CTE has the form `non_recursive_term UNION [ALL] recursive_term`. The recursive
+/// term is evaluated repeatedly, with the CTE referring to the rows produced by the last
+/// iteration, until no more row is produced. `UNION` discards the rows that have been produced.
+///
+/// Each iteration is bound, planned and scheduled as a separate batch query, where the rows of
+/// the evaluated CTEs are embedded as `VALUES` literals, see [`BindingCte::Rows`]. So the number
+/// of rows of a recursive CTE is capped by [`MAX_RECURSIVE_ROWS`] to keep the plans small.
+///
+/// [`BindingCte::Rows`]: crate::binder::BindingCte::Rows
+async fn eval_recursive_ctes(
+    session: &Arc<SessionImpl>,
+    query: &Query,
+    param_types: &[DataType],
+    params: &[Option<String>],
+) -> Result<Vec<(String, Schema, Vec<Row>)>> {
+    let with = match &query.with {
+        Some(with) if with.recursive => with,
+        _ => return Ok(vec![]),
+    };
+    let max_iterations = session
+        .get_config(MAX_RECURSIVE_ITERATIONS)
+        .map(|entry| entry.get_usize(DEFAULT_MAX_RECURSIVE_ITERATIONS))
+        .unwrap_or(DEFAULT_MAX_RECURSIVE_ITERATIONS);
+    let max_rows = session
+        .get_config(MAX_RECURSIVE_ROWS)
+        .map(|entry| entry.get_usize(DEFAULT_MAX_RECURSIVE_ROWS))
+        .unwrap_or(DEFAULT_MAX_RECURSIVE_ROWS);
+
+    let mut evaluated: Vec<(String, Schema, Vec<Row>)> = vec![];
+    for (index, cte) in with.cte_tables.iter().enumerate() {
+        if !is_recursive_cte(cte) {
+            continue;
+        }
+        let name = cte.alias.name.value.clone();
+        let (all, non_recursive_term, recursive_term) = match &cte.query.body {
+            SetExpr::SetOperation {
+                op: SetOperator::Union,
+                all,
+                left,
+                right,
+            } if cte.query.order_by.is_empty()
+                && cte.query.limit.is_none()
+                && cte.query.offset.is_none() =>
+            {
+                (*all, left.as_ref(), right.as_ref())
+            }
+            _ => {
+                return Err(ErrorCode::BindError(format!(
+                    "recursive query \"{}\" does not have the form non-recursive-term UNION [ALL] \
+                     recursive-term",
+                    name
+                ))
+                .into())
+            }
+        };
+        // Both terms can see the CTEs defined before this one.
+        let term_query = |body: &SetExpr| Query {
+            with: Some(With {
+                recursive: true,
+                cte_tables: with.cte_tables[..index].to_vec(),
+            }),
+            body: body.clone(),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        };
+
+        let (mut schema, rows) = execute_for_rows(
+            session,
+            term_query(non_recursive_term),
+            &evaluated,
+            param_types,
+            params,
+        )
+        .await?;
+        if cte.alias.columns.len() > schema.len() {
+            return Err(ErrorCode::BindError(format!(
+                "WITH query \"{}\" has {} columns available but {} columns specified",
+                name,
+                schema.len(),
+                cte.alias.columns.len()
+            ))
+            .into());
+        }
+        for (index, column) in cte.alias.columns.iter().enumerate() {
+            schema.fields[index].name = column.value.clone();
+        }
+
+        let mut seen = HashSet::new();
+        let mut working_rows = rows
+            .into_iter()
+            .filter(|row| all || seen.insert(row.clone()))
+            .collect::<Vec<_>>();
+        let mut result_rows = vec![];
+        let mut iterations = 0;
+        while !working_rows.is_empty() {
+            if iterations == max_iterations {
+                return Err(ErrorCode::InternalError(format!(
+                    "recursive query \"{}\" exceeded {} iterations, which can be raised by \
+                     setting {}",
+                    name, max_iterations, MAX_RECURSIVE_ITERATIONS
+                ))
+                .into());
+            }
+            iterations += 1;
+            if result_rows.len() + working_rows.len() > max_rows {
+                return Err(ErrorCode::InternalError(format!(
+                    "recursive query \"{}\" exceeded {} rows, which can be raised by setting {}",
+                    name, max_rows, MAX_RECURSIVE_ROWS
+                ))
+                .into());
+            }
+            result_rows.extend(working_rows.iter().cloned());
+
+            evaluated.push((name.clone(), schema.clone(), working_rows));
+            let (term_schema, rows) = execute_for_rows(
+                session,
+                term_query(recursive_term),
+                &evaluated,
+                param_types,
+                params,
+            )
+            .await?;
+            evaluated.pop();
+            if term_schema.data_types() != schema.data_types() {
+                return Err(ErrorCode::BindError(format!(
+                    "recursive query \"{}\" has types {:?} in non-recursive term but types {:?} \
+                     in recursive term",
+                    name,
+                    schema.data_types(),
+                    term_schema.data_types()
+                ))
+                .into());
+            }
+            working_rows = rows
+                .into_iter()
+                .filter(|row| all || seen.insert(row.clone()))
+                .collect();
+        }
+        evaluated.push((name, schema, result_rows));
+    }
+    Ok(evaluated)
+}
+
+/// Executes `query` as a batch query, with the given recursive CTEs in scope, and collects its
+/// rows.
+async fn execute_for_rows(
+    session: &Arc<SessionImpl>,
+    query: Query,
+    recursive_ctes: &[(String, Schema, Vec<Row>)],
+    param_types: &[DataType],
+    params: &[Option<String>],
+) -> Result<(Schema, Vec<Row>)> {
+    let (bound, schema) = {
+        let mut binder = Binder::new_with_params(session, param_types.to_vec(), params.to_vec());
+        for (name, schema, rows) in recursive_ctes {
+            binder.add_recursive_cte_rows(name.clone(), schema.clone(), rows.clone());
+        }
+        let query = binder.bind_query(query)?;
+        let schema = query.schema().clone();
+        (BoundStatement::Query(query.into()), schema)
+    };
+
+    let context = OptimizerContext::new(session.clone());
+    let (data_stream, _) = distribute_execute(context, bound).await?;
+    let mut rows = vec![];
+    #[for_await]
+    for chunk in data_stream {
+        rows.extend(chunk?.rows().map(|row| row.to_owned_row()));
+    }
+    Ok((schema, rows))
+}
+
 fn to_statement_type(stmt: &Statement) -> StatementType {
     use StatementType::*;
 
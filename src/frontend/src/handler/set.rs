@@ -16,6 +16,8 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::{Ident, SetVariableValue};
 
+use crate::config::parse_statement_timeout;
+use crate::handler::query::STATEMENT_TIMEOUT;
 use crate::session::OptimizerContext;
 
 pub(super) fn handle_set(
@@ -24,9 +26,17 @@ pub(super) fn handle_set(
     value: Vec<SetVariableValue>,
 ) -> Result<PgResponse> {
     let string_val = to_string(&value[0]);
-    // Currently store the config variable simply as String -> ConfigEntry(String).
-    // In future we can add converter/parser to make the API more robust.
-    context.session_ctx.set_config(&name.value, &string_val);
+    if name.value.eq_ignore_ascii_case(STATEMENT_TIMEOUT) {
+        // Reject invalid values up front and store the timeout in milliseconds.
+        let timeout = parse_statement_timeout(&string_val)?;
+        context
+            .session_ctx
+            .set_config(STATEMENT_TIMEOUT, &timeout.as_millis().to_string());
+    } else {
+        // Currently store the config variable simply as String -> ConfigEntry(String).
+        // In future we can add converter/parser to make the API more robust.
+        context.session_ctx.set_config(&name.value, &string_val);
+    }
 
     Ok(PgResponse::empty_result(StatementType::SET_OPTION))
 }
@@ -83,6 +83,23 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
     }
 }
 
+pub fn type_oid_to_data_type(type_oid: TypeOid) -> DataType {
+    match type_oid {
+        TypeOid::Boolean => DataType::Boolean,
+        TypeOid::BigInt => DataType::Int64,
+        TypeOid::SmallInt => DataType::Int16,
+        TypeOid::Int => DataType::Int32,
+        TypeOid::Float4 => DataType::Float32,
+        TypeOid::Float8 => DataType::Float64,
+        TypeOid::CharArray | TypeOid::Varchar => DataType::Varchar,
+        TypeOid::Date => DataType::Date,
+        TypeOid::Time => DataType::Time,
+        TypeOid::Timestamp => DataType::Timestamp,
+        TypeOid::Timestampz => DataType::Timestampz,
+        TypeOid::Decimal => DataType::Decimal,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use risingwave_common::array::*;
@@ -45,6 +45,7 @@ pub mod planner;
 mod scheduler;
 pub mod session;
 pub mod stream_fragmenter;
+pub mod user;
 pub mod utils;
 extern crate log;
 mod meta_client;
@@ -29,6 +29,7 @@ use tokio::task::JoinHandle;
 use crate::catalog::root_catalog::Catalog;
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
 use crate::scheduler::HummockSnapshotManagerRef;
+use crate::user::user_manager::UserInfoManager;
 
 /// `ObserverManager` is used to update data based on notification from meta.
 /// Call `start` to spawn a new asynchronous task
@@ -41,6 +42,8 @@ pub(crate) struct ObserverManager {
     catalog: Arc<RwLock<Catalog>>,
     catalog_updated_tx: Sender<CatalogVersion>,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    user_info_manager: Arc<RwLock<UserInfoManager>>,
+    user_info_updated_tx: Sender<CatalogVersion>,
 }
 
 const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
@@ -53,6 +56,8 @@ impl ObserverManager {
         catalog: Arc<RwLock<Catalog>>,
         catalog_updated_tx: Sender<CatalogVersion>,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
+        user_info_manager: Arc<RwLock<UserInfoManager>>,
+        user_info_updated_tx: Sender<CatalogVersion>,
     ) -> Self {
         let rx = meta_client
             .subscribe(&addr, WorkerType::Frontend)
@@ -66,12 +71,16 @@ impl ObserverManager {
             catalog,
             catalog_updated_tx,
             hummock_snapshot_manager,
+            user_info_manager,
+            user_info_updated_tx,
         }
     }
 
     pub fn handle_snapshot_notification(&mut self, resp: SubscribeResponse) -> Result<()> {
         let mut catalog_guard = self.catalog.write();
+        let mut user_guard = self.user_info_manager.write();
         catalog_guard.clear();
+        user_guard.clear();
         match resp.info {
             Some(Info::Snapshot(snapshot)) => {
                 for db in snapshot.database {
@@ -86,6 +95,12 @@ impl ObserverManager {
                 for source in snapshot.source {
                     catalog_guard.create_source(source)
                 }
+                for sink in snapshot.sink {
+                    catalog_guard.create_sink(&sink)
+                }
+                for user in snapshot.users {
+                    user_guard.create_user(user)
+                }
                 self.worker_node_manager.refresh_worker_node(snapshot.nodes);
             }
             _ => {
@@ -98,6 +113,7 @@ impl ObserverManager {
         }
         catalog_guard.set_version(resp.version);
         self.catalog_updated_tx.send(resp.version).unwrap();
+        self.user_info_updated_tx.send(resp.version).unwrap();
         Ok(())
     }
 
@@ -132,6 +148,13 @@ impl ObserverManager {
                 }
                 _ => panic!("receive an unsupported notify {:?}", resp),
             },
+            Info::Sink(sink) => match resp.operation() {
+                Operation::Add => catalog_guard.create_sink(sink),
+                Operation::Delete => {
+                    catalog_guard.drop_sink(sink.database_id, sink.schema_id, sink.id)
+                }
+                _ => panic!("receive an unsupported notify {:?}", resp),
+            },
             _ => unreachable!(),
         }
         assert!(
@@ -144,18 +167,40 @@ impl ObserverManager {
         self.catalog_updated_tx.send(resp.version).unwrap();
     }
 
+    fn handle_user_notification(&mut self, resp: SubscribeResponse) {
+        let Some(Info::User(user)) = resp.info.as_ref() else {
+            return;
+        };
+
+        let mut user_guard = self.user_info_manager.write();
+        match resp.operation() {
+            Operation::Add => user_guard.create_user(user.clone()),
+            Operation::Delete => user_guard.drop_user(user.id),
+            Operation::Update => user_guard.update_user(user.clone()),
+            _ => panic!("receive an unsupported notify {:?}", resp),
+        }
+        self.user_info_updated_tx.send(resp.version).unwrap();
+    }
+
     pub async fn handle_notification(&mut self, resp: SubscribeResponse) {
         let Some(info) = resp.info.as_ref() else {
             return;
         };
 
         match info {
-            Info::Database(_) | Info::Schema(_) | Info::Table(_) | Info::Source(_) => {
+            Info::Database(_)
+            | Info::Schema(_)
+            | Info::Table(_)
+            | Info::Source(_)
+            | Info::Sink(_) => {
                 self.handle_catalog_notification(resp);
             }
             Info::Node(node) => {
                 self.update_worker_node_manager(resp.operation(), node.clone());
             }
+            Info::User(_) => {
+                self.handle_user_notification(resp);
+            }
             Info::Snapshot(_) => {
                 panic!(
                     "receiving a snapshot in the middle is unsupported now {:?}",
@@ -22,6 +22,8 @@ mod plan_rewriter;
 mod plan_visitor;
 mod rule;
 
+use std::collections::HashMap;
+
 use fixedbitset::FixedBitSet;
 use itertools::Itertools as _;
 use property::{Distribution, Order};
@@ -29,7 +31,9 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 
 use self::heuristic::{ApplyOrder, HeuristicOptimizer};
-use self::plan_node::{BatchProject, Convention, LogicalProject, StreamMaterialize};
+use self::plan_node::{
+    BatchProject, Convention, LogicalProject, StreamMaterialize, StreamProject, StreamSink,
+};
 use self::rule::*;
 use crate::catalog::TableId;
 use crate::expr::InputRef;
@@ -154,6 +158,14 @@ impl PlanRoot {
         // Predicate Push-down
         plan = plan.predicate_pushdown(Condition::true_cond());
 
+        // Convert the filters of row numbers to group TopN. This should be applied before column
+        // pruning, which removes the window functions not required any more.
+        plan = {
+            let rules = vec![OverAggToTopNRule::create()];
+            let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::TopDown, rules);
+            heuristic_optimizer.optimize(plan)
+        };
+
         // Prune Columns
         //
         // Currently, the expressions in ORDER BY will be merged into the expressions in SELECT and
@@ -231,6 +243,13 @@ impl PlanRoot {
         let plan = match self.plan.convention() {
             Convention::Logical => {
                 let plan = self.gen_optimized_logical_plan();
+                // Convert the inner joins with scalar subqueries to semi joins, which can be
+                // executed as dynamic filters instead of nested-loop joins.
+                let plan = {
+                    let rules = vec![InnerJoinToSemiJoinRule::create()];
+                    let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::TopDown, rules);
+                    heuristic_optimizer.optimize(plan)
+                };
                 let (plan, out_col_change) = plan.logical_rewrite_for_stream()?;
                 self.required_dist = out_col_change
                     .rewrite_required_distribution(&self.required_dist)
@@ -288,6 +307,25 @@ impl PlanRoot {
         )
     }
 
+    /// Optimize and generate a create sink plan. Columns pruned by `out_fields`, e.g. the hidden
+    /// row id, are not delivered to the sink.
+    pub fn gen_create_sink_plan(
+        &mut self,
+        properties: HashMap<String, String>,
+    ) -> Result<StreamSink> {
+        let mut stream_plan = self.gen_stream_plan()?;
+        if self.out_fields.count_ones(..) != self.out_fields.len() {
+            let input_schema = stream_plan.schema();
+            let exprs = self
+                .out_fields
+                .ones()
+                .map(|index| InputRef::new(index, input_schema.fields()[index].data_type()).into())
+                .collect();
+            stream_plan = StreamProject::new(LogicalProject::new(stream_plan, exprs)).into();
+        }
+        Ok(StreamSink::new(stream_plan, properties))
+    }
+
     /// Set the plan root's required dist.
     pub fn set_required_dist(&mut self, required_dist: Distribution) {
         self.required_dist = required_dist;
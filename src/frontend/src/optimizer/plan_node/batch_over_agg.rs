@@ -0,0 +1,107 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::OverWindowNode;
+
+use super::{
+    LogicalOverAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::Distribution;
+
+/// `BatchOverAgg` implements [`super::LogicalOverAgg`] by computing the window functions over the
+/// input sorted by the partition keys and then the order keys.
+#[derive(Debug, Clone)]
+pub struct BatchOverAgg {
+    pub base: PlanBase,
+    logical: LogicalOverAgg,
+}
+
+impl BatchOverAgg {
+    pub fn new(logical: LogicalOverAgg) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let distribution = logical
+            .i2o_col_mapping()
+            .rewrite_provided_distribution(logical.input().distribution());
+        // The rows are output in the order of the input.
+        let base = PlanBase::new_batch(
+            ctx,
+            logical.schema().clone(),
+            distribution,
+            logical.required_input_order(),
+        );
+        BatchOverAgg { base, logical }
+    }
+}
+
+impl fmt::Display for BatchOverAgg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "BatchOverAgg")
+    }
+}
+
+impl PlanTreeNodeUnary for BatchOverAgg {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchOverAgg }
+
+impl ToDistributedBatch for BatchOverAgg {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_distributed_with_required(
+            &self.logical.required_input_order(),
+            &Distribution::Single,
+        )?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
+
+impl ToBatchProst for BatchOverAgg {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::OverWindow(OverWindowNode {
+            calls: self
+                .logical
+                .calls()
+                .iter()
+                .map(|call| call.to_protobuf())
+                .collect(),
+            partition_by: self
+                .logical
+                .partition_by()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            order_by: self.logical.order_by().to_protobuf(self.input().schema()),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchOverAgg {
+    fn to_local(&self) -> Result<PlanRef> {
+        let new_input = self
+            .input()
+            .to_local_with_order_required(&self.logical.required_input_order())?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
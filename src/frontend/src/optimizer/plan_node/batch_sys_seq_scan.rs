@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::SysRowSeqScanNode;
+
+use super::{
+    LogicalSysScan, PlanBase, PlanRef, PlanTreeNodeLeaf, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchSysSeqScan` ships the rows of a system table, which are generated in the frontend, to
+/// the executor that scans them.
+#[derive(Debug, Clone)]
+pub struct BatchSysSeqScan {
+    pub base: PlanBase,
+    logical: LogicalSysScan,
+}
+
+impl PlanTreeNodeLeaf for BatchSysSeqScan {}
+impl_plan_tree_node_for_leaf!(BatchSysSeqScan);
+
+impl BatchSysSeqScan {
+    pub fn new(logical: LogicalSysScan) -> Self {
+        Self::with_dist(logical, Distribution::Any)
+    }
+
+    pub fn with_dist(logical: LogicalSysScan, dist: Distribution) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
+        BatchSysSeqScan { base, logical }
+    }
+
+    #[must_use]
+    pub fn logical(&self) -> &LogicalSysScan {
+        &self.logical
+    }
+}
+
+impl fmt::Display for BatchSysSeqScan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "BatchSysSeqScan")
+    }
+}
+
+impl ToDistributedBatch for BatchSysSeqScan {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        Ok(Self::with_dist(self.logical().clone(), Distribution::Single).into())
+    }
+}
+
+impl ToBatchProst for BatchSysSeqScan {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::SysRowSeqScan(SysRowSeqScanNode {
+            table_name: self.logical.table_name().to_string(),
+            fields: self
+                .logical
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.to_prost())
+                .collect(),
+            rows: self
+                .logical
+                .rows()
+                .iter()
+                .map(|row| row.value_encode().unwrap())
+                .collect(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchSysSeqScan {
+    fn to_local(&self) -> Result<PlanRef> {
+        Ok(Self::with_dist(self.logical().clone(), Distribution::Single).into())
+    }
+}
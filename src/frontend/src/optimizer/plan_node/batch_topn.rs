@@ -36,8 +36,8 @@ impl BatchTopN {
             ctx,
             logical.schema().clone(),
             logical.input().distribution().clone(),
-            // BatchTopN outputs data in the order of specified order
-            logical.topn_order().clone(),
+            // BatchTopN outputs data in the order of specified order, unless grouped
+            logical.output_order(),
         );
         BatchTopN { base, logical }
     }
@@ -45,13 +45,7 @@ impl BatchTopN {
 
 impl fmt::Display for BatchTopN {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "BatchTopN {{ order: {}, limit: {}, offset: {} }}",
-            self.logical.topn_order(),
-            self.logical.limit(),
-            self.logical.offset(),
-        )
+        self.logical.fmt_with_name(f, "BatchTopN")
     }
 }
 
@@ -83,6 +77,12 @@ impl ToBatchProst for BatchTopN {
             limit: self.logical.limit() as u32,
             offset: self.logical.offset() as u32,
             column_orders,
+            group_key: self
+                .logical
+                .group_key()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
         })
     }
 }
@@ -0,0 +1,104 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::UnionNode;
+
+use super::{
+    LogicalUnion, PlanBase, PlanRef, PlanTreeNodeBinary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchUnion` implements [`super::LogicalUnion`] by outputting the rows of the left input and
+/// then those of the right input.
+#[derive(Debug, Clone)]
+pub struct BatchUnion {
+    pub base: PlanBase,
+    logical: LogicalUnion,
+}
+
+impl BatchUnion {
+    pub fn new(logical: LogicalUnion) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let dist = Self::derive_dist(
+            logical.left().distribution(),
+            logical.right().distribution(),
+        );
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
+        BatchUnion { base, logical }
+    }
+
+    fn derive_dist(left: &Distribution, right: &Distribution) -> Distribution {
+        match (left, right) {
+            (Distribution::Single, Distribution::Single) => Distribution::Single,
+            (_, _) => Distribution::Any,
+        }
+    }
+}
+
+impl fmt::Display for BatchUnion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BatchUnion")
+    }
+}
+
+impl PlanTreeNodeBinary for BatchUnion {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { BatchUnion }
+
+impl ToDistributedBatch for BatchUnion {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let left = self
+            .left()
+            .to_distributed_with_required(Order::any(), &Distribution::Single)?;
+        let right = self
+            .right()
+            .to_distributed_with_required(Order::any(), &Distribution::Single)?;
+
+        Ok(self.clone_with_left_right(left, right).into())
+    }
+}
+
+impl ToBatchProst for BatchUnion {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::Union(UnionNode {})
+    }
+}
+
+impl ToLocalBatch for BatchUnion {
+    fn to_local(&self) -> Result<PlanRef> {
+        let left =
+            Distribution::Single.enforce_if_not_satisfies(self.left().to_local()?, Order::any())?;
+        let right = Distribution::Single
+            .enforce_if_not_satisfies(self.right().to_local()?, Order::any())?;
+
+        Ok(self.clone_with_left_right(left, right).into())
+    }
+}
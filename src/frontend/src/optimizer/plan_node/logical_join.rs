@@ -17,12 +17,13 @@ use std::fmt;
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use risingwave_common::catalog::Schema;
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::plan_common::JoinType;
 
 use super::{
     ColPrunable, CollectInputRef, LogicalProject, PlanBase, PlanRef, PlanTreeNodeBinary,
-    PredicatePushdown, StreamHashJoin, ToBatch, ToStream,
+    PlanTreeNodeUnary, PredicatePushdown, StreamDynamicFilter, StreamHashJoin,
+    StreamNestedLoopJoin, ToBatch, ToStream,
 };
 use crate::expr::{ExprImpl, ExprType};
 use crate::optimizer::plan_node::batch_nested_loop_join::BatchNestedLoopJoin;
@@ -238,6 +239,55 @@ impl LogicalJoin {
         matches!(self.join_type(), JoinType::RightSemi | JoinType::RightAnti)
     }
 
+    /// Whether the right input produces at most one row, e.g., the result of a scalar subquery.
+    pub fn is_right_max_one_row(&self) -> bool {
+        fn max_one_row(plan: &PlanRef) -> bool {
+            if let Some(agg) = plan.as_logical_agg() {
+                agg.group_keys().is_empty()
+            } else if let Some(project) = plan.as_logical_project() {
+                max_one_row(&project.input())
+            } else if let Some(filter) = plan.as_logical_filter() {
+                max_one_row(&filter.input())
+            } else {
+                false
+            }
+        }
+        max_one_row(&self.right)
+    }
+
+    /// Returns the indices of the compared left and right columns if the join can be executed as
+    /// a dynamic filter, i.e., a left semi join comparing a left column with a column of a right
+    /// input with at most one row.
+    fn dynamic_filter_keys(&self) -> Option<(usize, usize)> {
+        if self.join_type != JoinType::LeftSemi || !self.is_right_max_one_row() {
+            return None;
+        }
+        let func_call = match &self.on.conjunctions[..] {
+            [ExprImpl::FunctionCall(func_call)] => func_call,
+            _ => return None,
+        };
+        if !matches!(
+            func_call.get_expr_type(),
+            ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ) {
+            return None;
+        }
+        let left_len = self.left.schema().len();
+        match func_call.inputs() {
+            [ExprImpl::InputRef(lhs), ExprImpl::InputRef(rhs)] => {
+                match (lhs.index() < left_len, rhs.index() < left_len) {
+                    (true, false) => Some((lhs.index(), rhs.index() - left_len)),
+                    (false, true) => Some((rhs.index(), lhs.index() - left_len)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Try to split and pushdown `predicate` into a join's left/right child or the on clause.
     /// Returns the pushed predicates. The pushed part will be removed from the original predicate.
     ///
@@ -590,24 +640,37 @@ impl ToStream for LogicalJoin {
             self.right.schema().len(),
             self.on.clone(),
         );
-        let right = self
-            .right()
-            .to_stream_with_dist_required(&Distribution::HashShard(predicate.right_eq_indexes()))?;
 
-        let r2l =
-            predicate.r2l_eq_columns_mapping(self.left().schema().len(), right.schema().len());
+        if predicate.has_eq() {
+            // Delta joins only support inner joins, see `StreamDeltaJoin::new`. The non-equal
+            // conditions of inner joins are pulled up into a filter below.
+            if self.join_type != JoinType::Inner && StreamHashJoin::is_delta_join_forced(self) {
+                return Err(ErrorCode::NotImplemented(
+                    format!("delta join for {:?} join", self.join_type),
+                    None.into(),
+                )
+                .into());
+            }
 
-        let left_dist = r2l
-            .rewrite_required_distribution(right.distribution())
-            .unwrap();
+            let right = self
+                .right()
+                .to_stream_with_dist_required(&Distribution::HashShard(
+                    predicate.right_eq_indexes(),
+                ))?;
 
-        let mut left = self.left().to_stream_with_dist_required(&left_dist)?;
-        if left.distribution() != &left_dist {
-            left = left_dist.enforce(left, Order::any());
-        }
-        let logical_join = self.clone_with_left_right(left, right);
+            let r2l =
+                predicate.r2l_eq_columns_mapping(self.left().schema().len(), right.schema().len());
+
+            let left_dist = r2l
+                .rewrite_required_distribution(right.distribution())
+                .unwrap();
+
+            let mut left = self.left().to_stream_with_dist_required(&left_dist)?;
+            if left.distribution() != &left_dist {
+                left = left_dist.enforce(left, Order::any());
+            }
+            let logical_join = self.clone_with_left_right(left, right);
 
-        if predicate.has_eq() {
             // Convert to Hash Join for equal joins
             // For inner joins, pull non-equal conditions to a filter operator on top of it
             let pull_filter = self.join_type == JoinType::Inner && predicate.has_non_eq();
@@ -624,11 +687,24 @@ impl ToStream for LogicalJoin {
             } else {
                 Ok(StreamHashJoin::new(logical_join, predicate).into())
             }
+        } else if let Some((left_key, right_key)) = self.dynamic_filter_keys() {
+            // Compare the left rows with the only right row broadcast to all the left shards
+            let left = self.left().to_stream()?;
+            let right = self
+                .right()
+                .to_stream_with_dist_required(&Distribution::Broadcast)?;
+            let logical_join = self.clone_with_left_right(left, right);
+            Ok(StreamDynamicFilter::new(logical_join, left_key, right_key).into())
         } else {
-            Err(RwError::from(ErrorCode::NotImplemented(
-                "stream nested-loop join".to_string(),
-                None.into(),
-            )))
+            // Convert to Nested-loop Join for non-equal joins, where all rows must be in one place
+            let left = self
+                .left()
+                .to_stream_with_dist_required(&Distribution::Single)?;
+            let right = self
+                .right()
+                .to_stream_with_dist_required(&Distribution::Single)?;
+            let logical_join = self.clone_with_left_right(left, right);
+            Ok(StreamNestedLoopJoin::new(logical_join).into())
         }
     }
 
@@ -0,0 +1,644 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+use risingwave_expr::expr::AggKind;
+use risingwave_pb::expr::{AggCall as ProstAggCall, WindowFunction as ProstWindowFunction};
+
+use super::{
+    gen_filter_and_pushdown, BatchOverAgg, ColPrunable, LogicalProject, PlanBase, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, StreamOverAgg, ToBatch, ToStream,
+};
+use crate::expr::{
+    AggCall, Expr, ExprImpl, ExprRewriter, ExprType, ExprVisitor, FunctionCall, InputRef,
+    WindowFrame, WindowFunction, WindowFunctionKind,
+};
+use crate::optimizer::property::{Direction, Distribution, FieldOrder, Order};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// Window function call in [`LogicalOverAgg`], whose arguments are columns of the input.
+#[derive(Clone)]
+pub struct PlanWindowFunction {
+    pub kind: WindowFunctionKind,
+    pub return_type: DataType,
+    pub args: Vec<InputRef>,
+    /// The offset of `lag` and `lead`.
+    pub offset: usize,
+    pub frame: WindowFrame,
+}
+
+impl fmt::Debug for PlanWindowFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_tuple(&format!("{}", self.kind));
+        self.args.iter().for_each(|child| {
+            builder.field(child);
+        });
+        builder.finish()
+    }
+}
+
+impl PlanWindowFunction {
+    pub fn to_protobuf(&self) -> ProstWindowFunction {
+        let args = self
+            .args
+            .iter()
+            .map(InputRef::to_agg_arg_proto)
+            .collect_vec();
+        let agg_call = match &self.kind {
+            WindowFunctionKind::Aggregate(agg_kind) => Some(ProstAggCall {
+                r#type: agg_kind.to_prost().into(),
+                return_type: Some(self.return_type.to_protobuf()),
+                args: args.clone(),
+                distinct: false,
+            }),
+            _ => None,
+        };
+        ProstWindowFunction {
+            r#type: self.kind.to_protobuf() as i32,
+            agg_call,
+            args,
+            offset: self.offset as u64,
+            return_type: Some(self.return_type.to_protobuf()),
+            frame: Some(self.frame.to_protobuf()),
+        }
+    }
+
+    fn rewrite_inputs(&self, mapping: &ColIndexMapping) -> Self {
+        let mut call = self.clone();
+        call.args
+            .iter_mut()
+            .for_each(|input| input.index = mapping.map(input.index));
+        call
+    }
+}
+
+/// `LogicalOverAgg` computes window functions over the partitions of its input. All the window
+/// functions share the same `PARTITION BY` and `ORDER BY` of the window.
+///
+/// The output schema is the input columns followed by the results of the window functions.
+#[derive(Debug, Clone)]
+pub struct LogicalOverAgg {
+    pub base: PlanBase,
+    calls: Vec<PlanWindowFunction>,
+    partition_by: Vec<usize>,
+    order_by: Order,
+    input: PlanRef,
+}
+
+impl LogicalOverAgg {
+    pub fn new(
+        calls: Vec<PlanWindowFunction>,
+        partition_by: Vec<usize>,
+        order_by: Order,
+        input: PlanRef,
+    ) -> Self {
+        let ctx = input.ctx();
+        let fields = input
+            .schema()
+            .fields()
+            .iter()
+            .cloned()
+            .chain(
+                calls
+                    .iter()
+                    .map(|call| Field::with_name(call.return_type.clone(), call.kind.to_string())),
+            )
+            .collect();
+        let pk_indices = input.pk_indices().to_vec();
+        let base = PlanBase::new_logical(ctx, Schema { fields }, pk_indices);
+        LogicalOverAgg {
+            base,
+            calls,
+            partition_by,
+            order_by,
+            input,
+        }
+    }
+
+    /// Plans the window functions in `select_exprs` over `input`, returning the new plan and the
+    /// select list rewritten to reference its columns.
+    ///
+    /// Window functions with the same window are computed by one `LogicalOverAgg`, and the
+    /// expressions of the windows and the arguments are computed by a `LogicalProject` below.
+    pub fn create(input: PlanRef, select_exprs: Vec<ExprImpl>) -> Result<(PlanRef, Vec<ExprImpl>)> {
+        let mut collector = CollectWindowFunction::default();
+        select_exprs
+            .iter()
+            .for_each(|expr| collector.visit_expr(expr));
+        for window_function in &collector.window_functions {
+            if window_function.has_subquery() || window_function.has_agg_call() {
+                return Err(ErrorCode::NotImplemented(
+                    "subquery or aggregation in window functions".into(),
+                    None.into(),
+                )
+                .into());
+            }
+        }
+
+        // Project the input columns followed by the expressions used by the windows.
+        let input_len = input.schema().len();
+        let mut project_exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| InputRef::new(idx, field.data_type()).into())
+            .collect_vec();
+        let mut project_index = |expr: &ExprImpl| -> usize {
+            if let ExprImpl::InputRef(input_ref) = expr {
+                return input_ref.index();
+            }
+            match project_exprs.iter().skip(input_len).position(|e| e == expr) {
+                Some(idx) => input_len + idx,
+                None => {
+                    project_exprs.push(expr.clone());
+                    project_exprs.len() - 1
+                }
+            }
+        };
+
+        // Group the window functions by their windows.
+        #[allow(clippy::type_complexity)]
+        let mut windows: Vec<((&[ExprImpl], &[(ExprImpl, Direction)]), Vec<&ExprImpl>)> = vec![];
+        for expr in &collector.window_functions {
+            let function = expr.as_window_function().unwrap();
+            let window = (function.partition_by(), function.order_by());
+            match windows.iter_mut().find(|(w, _)| *w == window) {
+                Some((_, functions)) => functions.push(expr),
+                None => windows.push((window, vec![expr])),
+            }
+        }
+
+        let mut windows_and_calls = vec![];
+        for ((partition_by, order_by), functions) in windows {
+            let partition_by = partition_by.iter().map(&mut project_index).collect_vec();
+            let order_by = Order::new(
+                order_by
+                    .iter()
+                    .map(|(expr, direct)| FieldOrder {
+                        index: project_index(expr),
+                        direct: *direct,
+                    })
+                    .collect(),
+            );
+            let calls = functions
+                .into_iter()
+                .map(|expr| {
+                    let function = expr.as_window_function().unwrap();
+                    let call = PlanWindowFunction {
+                        kind: function.kind().clone(),
+                        return_type: function.return_type(),
+                        args: function
+                            .args()
+                            .iter()
+                            .map(|arg| InputRef::new(project_index(arg), arg.return_type()))
+                            .collect(),
+                        offset: function.offset(),
+                        frame: function.frame(),
+                    };
+                    (expr.clone(), call)
+                })
+                .collect_vec();
+            windows_and_calls.push((partition_by, order_by, calls));
+        }
+
+        let mut root = LogicalProject::create(input, project_exprs);
+        let mut substitute = HashMap::new();
+        for (partition_by, order_by, calls) in windows_and_calls {
+            let mut plan_calls = vec![];
+            let base = root.schema().len();
+            for (window_function, call) in calls {
+                let expr = Self::push_call(call, base, &mut plan_calls);
+                substitute.insert(window_function, expr);
+            }
+            root = LogicalOverAgg::new(plan_calls, partition_by, order_by, root).into();
+        }
+
+        let mut rewriter = SubstituteWindowFunction { substitute };
+        let select_exprs = select_exprs
+            .into_iter()
+            .map(|expr| rewriter.rewrite_expr(expr))
+            .collect();
+        Ok((root, select_exprs))
+    }
+
+    /// Pushes `call` to `plan_calls`, where the calls are output from column `base`, and returns
+    /// the expression of its result. `avg` is rewritten to `cast(sum as avg_return_type) / count`.
+    fn push_call(
+        call: PlanWindowFunction,
+        base: usize,
+        plan_calls: &mut Vec<PlanWindowFunction>,
+    ) -> ExprImpl {
+        if call.kind != WindowFunctionKind::Aggregate(AggKind::Avg) {
+            let return_type = call.return_type.clone();
+            plan_calls.push(call);
+            return InputRef::new(base + plan_calls.len() - 1, return_type).into();
+        }
+
+        let arg_types = call.args.iter().map(|arg| arg.return_type()).collect_vec();
+        let mut sum = call.clone();
+        sum.kind = WindowFunctionKind::Aggregate(AggKind::Sum);
+        sum.return_type = AggCall::infer_return_type(&AggKind::Sum, &arg_types).unwrap();
+        let left = ExprImpl::from(InputRef::new(
+            base + plan_calls.len(),
+            sum.return_type.clone(),
+        ))
+        .cast_implicit(call.return_type.clone())
+        .unwrap();
+        plan_calls.push(sum);
+
+        let mut count = call;
+        count.kind = WindowFunctionKind::Aggregate(AggKind::Count);
+        count.return_type = AggCall::infer_return_type(&AggKind::Count, &arg_types).unwrap();
+        let right = InputRef::new(base + plan_calls.len(), count.return_type.clone());
+        plan_calls.push(count);
+
+        FunctionCall::new(ExprType::Divide, vec![left, right.into()])
+            .unwrap()
+            .into()
+    }
+
+    pub fn calls(&self) -> &[PlanWindowFunction] {
+        &self.calls
+    }
+
+    pub fn partition_by(&self) -> &[usize] {
+        &self.partition_by
+    }
+
+    pub fn order_by(&self) -> &Order {
+        &self.order_by
+    }
+
+    /// The order the input must be sorted in for the batch executor, i.e., the partition keys
+    /// followed by the order keys.
+    pub fn required_input_order(&self) -> Order {
+        Order::new(
+            self.partition_by
+                .iter()
+                .map(|idx| FieldOrder::ascending(*idx))
+                .chain(self.order_by.field_order.iter().cloned())
+                .collect(),
+        )
+    }
+
+    pub fn o2i_col_mapping(&self) -> ColIndexMapping {
+        ColIndexMapping::identity_or_none(self.schema().len(), self.input.schema().len())
+    }
+
+    pub fn i2o_col_mapping(&self) -> ColIndexMapping {
+        ColIndexMapping::identity_or_none(self.input.schema().len(), self.schema().len())
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        f.debug_struct(name)
+            .field("calls", &self.calls)
+            .field("partition_by", &self.partition_by)
+            .field("order_by", &format_args!("{}", self.order_by))
+            .finish()
+    }
+}
+
+/// Collects the distinct window functions in the expressions.
+#[derive(Default)]
+struct CollectWindowFunction {
+    window_functions: Vec<ExprImpl>,
+}
+
+impl ExprVisitor for CollectWindowFunction {
+    fn visit_window_function(&mut self, window_function: &WindowFunction) {
+        let expr = window_function.clone().into();
+        if !self.window_functions.contains(&expr) {
+            self.window_functions.push(expr);
+        }
+    }
+}
+
+/// Substitutes the window functions with the expressions of their results.
+struct SubstituteWindowFunction {
+    substitute: HashMap<ExprImpl, ExprImpl>,
+}
+
+impl ExprRewriter for SubstituteWindowFunction {
+    fn rewrite_window_function(&mut self, window_function: WindowFunction) -> ExprImpl {
+        self.substitute[&window_function.into()].clone()
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalOverAgg {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            self.calls.clone(),
+            self.partition_by.clone(),
+            self.order_by.clone(),
+            input,
+        )
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let calls = self
+            .calls
+            .iter()
+            .map(|call| call.rewrite_inputs(&input_col_change))
+            .collect();
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|idx| input_col_change.map(*idx))
+            .collect();
+        let order_by = input_col_change
+            .rewrite_required_order(&self.order_by)
+            .unwrap();
+        let over_agg = Self::new(calls, partition_by, order_by, input);
+
+        let (mut mapping, new_input_col_num) = input_col_change.into_parts();
+        mapping.extend((0..self.calls.len()).map(|idx| Some(new_input_col_num + idx)));
+        (over_agg, ColIndexMapping::new(mapping))
+    }
+}
+
+impl_plan_tree_node_for_unary! {LogicalOverAgg}
+
+impl fmt::Display for LogicalOverAgg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalOverAgg")
+    }
+}
+
+impl ColPrunable for LogicalOverAgg {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let input_len = self.input.schema().len();
+        let required_calls = required_cols
+            .iter()
+            .filter(|idx| **idx >= input_len)
+            .map(|idx| idx - input_len)
+            .sorted()
+            .collect_vec();
+
+        let input_required_cols = {
+            let mut tmp = FixedBitSet::with_capacity(input_len);
+            tmp.extend(required_cols.iter().copied().filter(|idx| *idx < input_len));
+            if !required_calls.is_empty() {
+                tmp.extend(self.partition_by.iter().copied());
+                tmp.extend(self.order_by.field_order.iter().map(|o| o.index));
+                for idx in &required_calls {
+                    tmp.extend(self.calls[*idx].args.iter().map(|arg| arg.index()));
+                }
+            }
+            tmp.ones().collect_vec()
+        };
+        let input_change = ColIndexMapping::with_remaining_columns(&input_required_cols, input_len);
+        let new_input = self.input.prune_col(&input_required_cols);
+
+        // The calls not required are removed, or the whole node if none is required.
+        let new_plan: PlanRef = if required_calls.is_empty() {
+            new_input
+        } else {
+            let calls = required_calls
+                .iter()
+                .map(|idx| self.calls[*idx].rewrite_inputs(&input_change))
+                .collect();
+            let partition_by = self
+                .partition_by
+                .iter()
+                .map(|idx| input_change.map(*idx))
+                .collect();
+            let order_by = input_change.rewrite_required_order(&self.order_by).unwrap();
+            Self::new(calls, partition_by, order_by, new_input).into()
+        };
+
+        let new_input_len = input_required_cols.len();
+        let output_cols = required_cols
+            .iter()
+            .map(|idx| match idx.checked_sub(input_len) {
+                Some(call_idx) => {
+                    new_input_len + required_calls.iter().position(|i| *i == call_idx).unwrap()
+                }
+                None => input_change.map(*idx),
+            })
+            .collect_vec();
+        if output_cols.iter().copied().eq(0..new_plan.schema().len()) {
+            new_plan
+        } else {
+            let src_size = new_plan.schema().len();
+            LogicalProject::with_mapping(
+                new_plan,
+                ColIndexMapping::with_remaining_columns(&output_cols, src_size),
+            )
+            .into()
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalOverAgg {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // Filtering whole partitions doesn't change the results of the others, so the predicates
+        // only referencing the partition keys can be pushed down.
+        let mut non_partition_columns = FixedBitSet::with_capacity(self.schema().len());
+        non_partition_columns.insert_range(..);
+        self.partition_by
+            .iter()
+            .for_each(|idx| non_partition_columns.set(*idx, false));
+        let (filter_predicate, pushed_predicate) = predicate.split_disjoint(&non_partition_columns);
+        gen_filter_and_pushdown(self, filter_predicate, pushed_predicate)
+    }
+}
+
+impl ToBatch for LogicalOverAgg {
+    fn to_batch(&self) -> Result<PlanRef> {
+        let new_input = self
+            .input()
+            .to_batch_with_order_required(&self.required_input_order())?;
+        let new_logical = self.clone_with_input(new_input);
+        Ok(BatchOverAgg::new(new_logical).into())
+    }
+}
+
+impl ToStream for LogicalOverAgg {
+    fn to_stream(&self) -> Result<PlanRef> {
+        let required_dist = if self.partition_by.is_empty() {
+            Distribution::Single
+        } else {
+            Distribution::HashShard(self.partition_by.clone())
+        };
+        let new_input = self.input().to_stream_with_dist_required(&required_dist)?;
+        Ok(StreamOverAgg::new(self.clone_with_input(new_input)).into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream()?;
+        let (over_agg, out_col_change) = self.rewrite_with_input(input, input_col_change);
+        Ok((over_agg.into(), out_col_change))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::expr::{assert_eq_input_ref, FrameBound};
+    use crate::optimizer::plan_node::LogicalValues;
+    use crate::session::OptimizerContext;
+
+    fn row_number(partition_by: ExprImpl, order_by: ExprImpl) -> ExprImpl {
+        WindowFunction::new(
+            WindowFunctionKind::RowNumber,
+            vec![],
+            0,
+            vec![partition_by],
+            vec![(order_by, Direction::Asc)],
+            WindowFrame::default(),
+        )
+        .unwrap()
+        .into()
+    }
+
+    #[tokio::test]
+    /// Creating
+    /// ```text
+    /// SELECT v1, row_number() OVER (PARTITION BY v1 ORDER BY v2),
+    ///   avg(v3) OVER (PARTITION BY v1 ORDER BY v2 ROWS 1 PRECEDING),
+    ///   row_number() OVER (ORDER BY v1 + v2)
+    /// ```
+    /// will result in
+    /// ```text
+    /// LogicalOverAgg { calls: [row_number], partition_by: [], order_by: [$3 ASC] }
+    ///   LogicalOverAgg { calls: [row_number, sum($2), count($2)], partition_by: [$0], ... }
+    ///     LogicalProject { exprs: [$0, $1, $2, $0 + $1] }
+    ///       LogicalValues(v1, v2, v3)
+    /// ```
+    async fn test_create() {
+        let ctx = OptimizerContext::mock().await;
+        let ty = DataType::Int32;
+        let fields: Vec<Field> = vec![
+            Field::with_name(ty.clone(), "v1"),
+            Field::with_name(ty.clone(), "v2"),
+            Field::with_name(ty.clone(), "v3"),
+        ];
+        let values = LogicalValues::new(vec![], Schema { fields }, ctx);
+        let v1: ExprImpl = InputRef::new(0, ty.clone()).into();
+        let v2: ExprImpl = InputRef::new(1, ty.clone()).into();
+        let v3: ExprImpl = InputRef::new(2, ty.clone()).into();
+        let v1_plus_v2: ExprImpl = FunctionCall::new(ExprType::Add, vec![v1.clone(), v2.clone()])
+            .unwrap()
+            .into();
+        let avg = WindowFunction::new(
+            WindowFunctionKind::Aggregate(AggKind::Avg),
+            vec![v3],
+            0,
+            vec![v1.clone()],
+            vec![(v2.clone(), Direction::Asc)],
+            WindowFrame {
+                rows: true,
+                start: FrameBound::Preceding(1),
+                end: FrameBound::CurrentRow,
+            },
+        )
+        .unwrap()
+        .into();
+        let select_exprs = vec![
+            v1.clone(),
+            row_number(v1.clone(), v2),
+            avg,
+            WindowFunction::new(
+                WindowFunctionKind::RowNumber,
+                vec![],
+                0,
+                vec![],
+                vec![(v1_plus_v2, Direction::Asc)],
+                WindowFrame::default(),
+            )
+            .unwrap()
+            .into(),
+        ];
+
+        let (plan, select_exprs) = LogicalOverAgg::create(values.into(), select_exprs).unwrap();
+        assert_eq_input_ref!(&select_exprs[0], 0);
+        assert_eq_input_ref!(&select_exprs[1], 4);
+        assert!(matches!(&select_exprs[2], ExprImpl::FunctionCall(_)));
+        assert_eq_input_ref!(&select_exprs[3], 7);
+
+        let over_agg = plan.as_logical_over_agg().unwrap();
+        assert!(over_agg.partition_by().is_empty());
+        assert_eq!(over_agg.order_by().field_order[0].index, 3);
+        let over_agg = over_agg.input();
+        let over_agg = over_agg.as_logical_over_agg().unwrap();
+        assert_eq!(over_agg.partition_by(), &[0]);
+        assert_eq!(over_agg.calls().len(), 3);
+        assert_eq!(
+            over_agg.calls()[1].kind,
+            WindowFunctionKind::Aggregate(AggKind::Sum)
+        );
+        assert_eq!(over_agg.calls()[1].args[0].index(), 2);
+        let project = over_agg.input();
+        let project = project.as_logical_project().unwrap();
+        assert_eq!(project.exprs().len(), 4);
+    }
+
+    #[tokio::test]
+    /// Pruning
+    /// ```text
+    /// LogicalOverAgg { calls: [row_number], partition_by: [$0], order_by: [$1 ASC] }
+    ///   LogicalValues(v1, v2, v3)
+    /// ```
+    /// with required columns [2] will remove the `LogicalOverAgg`.
+    async fn test_prune_over_agg() {
+        let ctx = OptimizerContext::mock().await;
+        let ty = DataType::Int32;
+        let fields: Vec<Field> = vec![
+            Field::with_name(ty.clone(), "v1"),
+            Field::with_name(ty.clone(), "v2"),
+            Field::with_name(ty.clone(), "v3"),
+        ];
+        let values = LogicalValues::new(vec![], Schema { fields }, ctx);
+        let v1: ExprImpl = InputRef::new(0, ty.clone()).into();
+        let v2: ExprImpl = InputRef::new(1, ty).into();
+        let (plan, _) = LogicalOverAgg::create(values.into(), vec![row_number(v1, v2)]).unwrap();
+        // Remove the project created for the window.
+        let plan = plan.as_logical_over_agg().unwrap();
+        let plan: PlanRef = plan
+            .clone_with_input(plan.input().as_logical_project().unwrap().input())
+            .into();
+
+        let pruned = plan.prune_col(&[3, 1]);
+        let project = pruned.as_logical_project().unwrap();
+        assert_eq_input_ref!(&project.exprs()[0], 2);
+        assert_eq_input_ref!(&project.exprs()[1], 1);
+        let over_agg = project.input();
+        let over_agg = over_agg.as_logical_over_agg().unwrap();
+        assert_eq!(over_agg.schema().len(), 3);
+
+        let pruned = plan.prune_col(&[2]);
+        let values = pruned.as_logical_values().unwrap();
+        assert_eq!(values.schema().len(), 1);
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::rc::Rc;
+
+use risingwave_common::array::Row;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+
+use super::{ColPrunable, LogicalFilter, PlanBase, PlanRef, PredicatePushdown, ToBatch, ToStream};
+use crate::optimizer::plan_node::BatchSysSeqScan;
+use crate::session::OptimizerContextRef;
+use crate::utils::Condition;
+
+/// `LogicalSysScan` scans a system table, e.g., `pg_catalog.pg_class`, whose rows have been
+/// generated from the catalog during binding.
+#[derive(Debug, Clone)]
+pub struct LogicalSysScan {
+    pub base: PlanBase,
+    table_name: String,
+    rows: Rc<[Row]>,
+}
+
+impl LogicalSysScan {
+    /// Create a [`LogicalSysScan`] node. Used internally by optimizer.
+    pub fn new(
+        table_name: String,
+        rows: Rc<[Row]>,
+        schema: Schema,
+        ctx: OptimizerContextRef,
+    ) -> Self {
+        let base = PlanBase::new_logical(ctx, schema, vec![]);
+        Self {
+            base,
+            table_name,
+            rows,
+        }
+    }
+
+    /// Create a [`LogicalSysScan`] node. Used by planner.
+    pub fn create(
+        table_name: String,
+        rows: Vec<Row>,
+        schema: Schema,
+        ctx: OptimizerContextRef,
+    ) -> PlanRef {
+        Self::new(table_name, rows.into(), schema, ctx).into()
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {{ table: {}, columns: [{}] }}",
+            name,
+            self.table_name,
+            self.schema()
+                .fields()
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+}
+
+impl_plan_tree_node_for_leaf! { LogicalSysScan }
+
+impl fmt::Display for LogicalSysScan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalSysScan")
+    }
+}
+
+impl ColPrunable for LogicalSysScan {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| Row(required_cols.iter().map(|i| row.0[*i].clone()).collect()))
+            .collect();
+        let fields = required_cols
+            .iter()
+            .map(|i| self.schema().fields[*i].clone())
+            .collect();
+        Self::new(
+            self.table_name.clone(),
+            rows,
+            Schema { fields },
+            self.base.ctx.clone(),
+        )
+        .into()
+    }
+}
+
+impl PredicatePushdown for LogicalSysScan {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        LogicalFilter::create(self.clone().into(), predicate)
+    }
+}
+
+impl ToBatch for LogicalSysScan {
+    fn to_batch(&self) -> Result<PlanRef> {
+        Ok(BatchSysSeqScan::new(self.clone()).into())
+    }
+}
+
+impl ToStream for LogicalSysScan {
+    fn to_stream(&self) -> Result<PlanRef> {
+        Err(ErrorCode::NotImplemented(
+            format!("streaming on system table {}", self.table_name),
+            None.into(),
+        )
+        .into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, crate::utils::ColIndexMapping)> {
+        Err(ErrorCode::NotImplemented(
+            format!("streaming on system table {}", self.table_name),
+            None.into(),
+        )
+        .into())
+    }
+}
@@ -27,6 +27,8 @@ use crate::optimizer::property::{Distribution, FieldOrder, Order};
 use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalTopN` sorts the input data and fetches up to `limit` rows from `offset`
+///
+/// If the group key is not empty, the top N rows are fetched in each group instead.
 #[derive(Debug, Clone)]
 pub struct LogicalTopN {
     pub base: PlanBase,
@@ -34,10 +36,21 @@ pub struct LogicalTopN {
     limit: usize,
     offset: usize,
     order: Order,
+    group_key: Vec<usize>,
 }
 
 impl LogicalTopN {
     pub fn new(input: PlanRef, limit: usize, offset: usize, order: Order) -> Self {
+        Self::with_group(input, limit, offset, order, vec![])
+    }
+
+    pub fn with_group(
+        input: PlanRef,
+        limit: usize,
+        offset: usize,
+        order: Order,
+        group_key: Vec<usize>,
+    ) -> Self {
         let ctx = input.ctx();
         let schema = input.schema().clone();
         let pk_indices = input.pk_indices().to_vec();
@@ -48,6 +61,7 @@ impl LogicalTopN {
             limit,
             offset,
             order,
+            group_key,
         }
     }
 
@@ -64,6 +78,31 @@ impl LogicalTopN {
         self.offset
     }
 
+    pub fn group_key(&self) -> &[usize] {
+        &self.group_key
+    }
+
+    /// The order of the output, which is only guaranteed without group key.
+    pub fn output_order(&self) -> Order {
+        if self.group_key.is_empty() {
+            self.order.clone()
+        } else {
+            Order::any().clone()
+        }
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {{ order: {}, limit: {}, offset: {}",
+            name, &self.order, &self.limit, &self.offset,
+        )?;
+        if !self.group_key.is_empty() {
+            write!(f, ", group_key: {:?}", self.group_key)?;
+        }
+        write!(f, " }}")
+    }
+
     /// `topn_order` returns the order of the Top-N operator. This naming is because `order()`
     /// already exists and it was designed to return the operator's physical property order.
     ///
@@ -81,7 +120,13 @@ impl PlanTreeNodeUnary for LogicalTopN {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        Self::new(input, self.limit, self.offset, self.order.clone())
+        Self::with_group(
+            input,
+            self.limit,
+            self.offset,
+            self.order.clone(),
+            self.group_key.clone(),
+        )
     }
 
     #[must_use]
@@ -91,13 +136,17 @@ impl PlanTreeNodeUnary for LogicalTopN {
         input_col_change: ColIndexMapping,
     ) -> (Self, ColIndexMapping) {
         (
-            Self::new(
+            Self::with_group(
                 input,
                 self.limit,
                 self.offset,
                 input_col_change
                     .rewrite_required_order(&self.order)
                     .unwrap(),
+                self.group_key
+                    .iter()
+                    .map(|idx| input_col_change.map(*idx))
+                    .collect(),
             ),
             input_col_change,
         )
@@ -106,11 +155,7 @@ impl PlanTreeNodeUnary for LogicalTopN {
 impl_plan_tree_node_for_unary! {LogicalTopN}
 impl fmt::Display for LogicalTopN {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "LogicalTopN {{ order: {}, limit: {}, offset: {} }}",
-            &self.order, &self.limit, &self.offset,
-        )
+        self.fmt_with_name(f, "LogicalTopN")
     }
 }
 
@@ -123,6 +168,7 @@ impl ColPrunable for LogicalTopN {
                 .field_order
                 .iter()
                 .for_each(|fo| order_required_cols.insert(fo.index));
+            order_required_cols.extend(self.group_key.iter().copied());
             order_required_cols
         };
 
@@ -146,8 +192,10 @@ impl ColPrunable for LogicalTopN {
                 })
                 .collect(),
         };
+        let new_group_key = self.group_key.iter().map(|idx| mapping.map(*idx)).collect();
         let new_input = self.input.prune_col(&input_required_cols);
-        let top_n = Self::new(new_input, self.limit, self.offset, new_order).into();
+        let top_n =
+            Self::with_group(new_input, self.limit, self.offset, new_order, new_group_key).into();
 
         if input_required_cols == required_cols {
             top_n
@@ -178,7 +226,7 @@ impl ToBatch for LogicalTopN {
         let new_logical = self.clone_with_input(new_input);
         let ret = BatchTopN::new(new_logical).into();
 
-        if self.topn_order().satisfies(required_order) {
+        if self.output_order().satisfies(required_order) {
             Ok(ret)
         } else {
             Ok(required_order.enforce(ret))
@@ -189,9 +237,12 @@ impl ToBatch for LogicalTopN {
 impl ToStream for LogicalTopN {
     fn to_stream(&self) -> Result<PlanRef> {
         // Unlike `BatchTopN`, `StreamTopN` cannot guarantee the output order
-        let input = self
-            .input()
-            .to_stream_with_dist_required(&Distribution::Single)?;
+        let required_dist = if self.group_key.is_empty() {
+            Distribution::Single
+        } else {
+            Distribution::HashShard(self.group_key.clone())
+        };
+        let input = self.input().to_stream_with_dist_required(&required_dist)?;
         Ok(StreamTopN::new(self.clone_with_input(input)).into())
     }
 
@@ -0,0 +1,254 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+
+use super::{
+    BatchUnion, ColPrunable, LogicalProject, PlanBase, PlanRef, PlanTreeNodeBinary,
+    PredicatePushdown, StreamUnion, ToBatch, ToStream,
+};
+use crate::expr::{ExprImpl, InputRef, Literal};
+use crate::optimizer::property::{Distribution, Order};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalUnion` outputs all the rows of both inputs, i.e., `UNION ALL`. Both inputs must have
+/// the same data types.
+///
+/// When rewritten for streaming, both inputs are extended with their primary keys and a branch id
+/// column, which together make the primary key of the union.
+#[derive(Debug, Clone)]
+pub struct LogicalUnion {
+    pub base: PlanBase,
+    left: PlanRef,
+    right: PlanRef,
+    /// The column telling which input a row comes from, if any.
+    branch_col: Option<usize>,
+}
+
+impl LogicalUnion {
+    fn new(left: PlanRef, right: PlanRef, branch_col: Option<usize>) -> Self {
+        assert_eq!(left.schema().data_types(), right.schema().data_types());
+        let ctx = left.ctx();
+        let schema = left.schema().clone();
+        let pk_indices = match branch_col {
+            Some(branch_col) => left
+                .pk_indices()
+                .iter()
+                .chain(right.pk_indices())
+                .copied()
+                .chain(std::iter::once(branch_col))
+                .unique()
+                .collect(),
+            None => vec![],
+        };
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalUnion {
+            base,
+            left,
+            right,
+            branch_col,
+        }
+    }
+
+    pub fn create(left: PlanRef, right: PlanRef) -> PlanRef {
+        Self::new(left, right, None).into()
+    }
+
+    /// Projects an input rewritten for streaming to the original columns, then the extra primary
+    /// key columns of the left and the right inputs, filling those of the other input with nulls,
+    /// and the branch id at last.
+    fn project_for_stream(
+        &self,
+        input: PlanRef,
+        col_change: &ColIndexMapping,
+        extra_pk_cols: [&[usize]; 2],
+        extra_pk_types: [Vec<DataType>; 2],
+        branch: i32,
+    ) -> PlanRef {
+        let schema = input.schema();
+        let mut exprs = (0..self.schema().len())
+            .map(|i| {
+                let i = col_change.map(i);
+                InputRef::new(i, schema.fields()[i].data_type()).into()
+            })
+            .collect::<Vec<ExprImpl>>();
+        for (side, (cols, types)) in extra_pk_cols.iter().zip_eq(extra_pk_types).enumerate() {
+            if side as i32 == branch {
+                exprs.extend(
+                    cols.iter()
+                        .map(|&i| InputRef::new(i, schema.fields()[i].data_type()).into()),
+                );
+            } else {
+                exprs.extend(types.into_iter().map(|ty| Literal::new(None, ty).into()));
+            }
+        }
+        exprs.push(ExprImpl::literal_int(branch));
+        LogicalProject::create(input, exprs)
+    }
+}
+
+impl PlanTreeNodeBinary for LogicalUnion {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(left, right, self.branch_col)
+    }
+}
+
+impl_plan_tree_node_for_binary! { LogicalUnion }
+
+impl fmt::Display for LogicalUnion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LogicalUnion")
+    }
+}
+
+impl ColPrunable for LogicalUnion {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let left = self.left.prune_col(required_cols);
+        let right = self.right.prune_col(required_cols);
+        let branch_col = self
+            .branch_col
+            .and_then(|branch_col| required_cols.iter().position(|&i| i == branch_col));
+        Self::new(left, right, branch_col).into()
+    }
+}
+
+impl PredicatePushdown for LogicalUnion {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // Both inputs share the same columns, so the predicate can be pushed to both of them.
+        let left = self.left.predicate_pushdown(predicate.clone());
+        let right = self.right.predicate_pushdown(predicate);
+        self.clone_with_left_right(left, right).into()
+    }
+}
+
+impl ToBatch for LogicalUnion {
+    fn to_batch(&self) -> Result<PlanRef> {
+        let left = self.left.to_batch()?;
+        let right = self.right.to_batch()?;
+        Ok(BatchUnion::new(self.clone_with_left_right(left, right)).into())
+    }
+}
+
+impl ToStream for LogicalUnion {
+    fn to_stream(&self) -> Result<PlanRef> {
+        // The inputs are always shuffled, so that each of them is the input of a fragment.
+        let dist = Distribution::HashShard(self.pk_indices().to_vec());
+        let left = dist.enforce(self.left.to_stream()?, Order::any());
+        let right = dist.enforce(self.right.to_stream()?, Order::any());
+        Ok(StreamUnion::new(self.clone_with_left_right(left, right)).into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
+        let (left, left_col_change) = self.left.logical_rewrite_for_stream()?;
+        let (right, right_col_change) = self.right.logical_rewrite_for_stream()?;
+
+        let column_num = self.schema().len();
+        let extra_pk_cols = |input: &PlanRef, col_change: &ColIndexMapping| {
+            let output_cols = (0..column_num).map(|i| col_change.map(i)).collect_vec();
+            input
+                .pk_indices()
+                .iter()
+                .copied()
+                .filter(|i| !output_cols.contains(i))
+                .collect_vec()
+        };
+        let left_extra_pk_cols = extra_pk_cols(&left, &left_col_change);
+        let right_extra_pk_cols = extra_pk_cols(&right, &right_col_change);
+        let types = |input: &PlanRef, cols: &[usize]| {
+            cols.iter()
+                .map(|&i| input.schema().fields()[i].data_type())
+                .collect_vec()
+        };
+        let left_extra_pk_types = types(&left, &left_extra_pk_cols);
+        let right_extra_pk_types = types(&right, &right_extra_pk_cols);
+
+        let left = self.project_for_stream(
+            left,
+            &left_col_change,
+            [&left_extra_pk_cols, &right_extra_pk_cols],
+            [left_extra_pk_types.clone(), right_extra_pk_types.clone()],
+            0,
+        );
+        let right = self.project_for_stream(
+            right,
+            &right_col_change,
+            [&left_extra_pk_cols, &right_extra_pk_cols],
+            [left_extra_pk_types, right_extra_pk_types],
+            1,
+        );
+        let branch_col = left.schema().len() - 1;
+        let union = Self::new(left, right, Some(branch_col));
+        let out_col_change = ColIndexMapping::identity_or_none(column_num, branch_col + 1);
+        Ok((union.into(), out_col_change))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{Field, Schema};
+
+    use super::*;
+    use crate::optimizer::plan_node::LogicalValues;
+    use crate::session::OptimizerContext;
+
+    /// Pruning
+    /// ```text
+    /// Union
+    ///   Values(v1, v2, v3)
+    ///   Values(v4, v5, v6)
+    /// ```
+    /// with required columns [0, 2] will result in
+    /// ```text
+    /// Union
+    ///   Values(v1, v3)
+    ///   Values(v4, v6)
+    /// ```
+    #[tokio::test]
+    async fn test_prune_union() {
+        let ctx = OptimizerContext::mock().await;
+        let values = |names: [&str; 3]| -> PlanRef {
+            let fields = names
+                .iter()
+                .map(|name| Field::with_name(DataType::Int32, *name))
+                .collect();
+            LogicalValues::new(vec![], Schema { fields }, ctx.clone()).into()
+        };
+        let union = LogicalUnion::create(values(["v1", "v2", "v3"]), values(["v4", "v5", "v6"]));
+
+        let plan = union.prune_col(&[0, 2]);
+        let union = plan.as_logical_union().unwrap();
+        let names = |plan: &PlanRef| {
+            plan.schema()
+                .fields()
+                .iter()
+                .map(|f| f.name.clone())
+                .collect_vec()
+        };
+        assert_eq!(names(&plan), ["v1", "v3"]);
+        assert_eq!(names(&union.left()), ["v1", "v3"]);
+        assert_eq!(names(&union.right()), ["v4", "v6"]);
+    }
+}
@@ -215,11 +215,14 @@ mod batch_hop_window;
 mod batch_insert;
 mod batch_limit;
 mod batch_nested_loop_join;
+mod batch_over_agg;
 mod batch_project;
 mod batch_seq_scan;
 mod batch_simple_agg;
 mod batch_sort;
+mod batch_sys_seq_scan;
 mod batch_topn;
+mod batch_union;
 mod batch_update;
 mod batch_values;
 mod logical_agg;
@@ -232,13 +235,17 @@ mod logical_insert;
 mod logical_join;
 mod logical_limit;
 mod logical_multi_join;
+mod logical_over_agg;
 mod logical_project;
 mod logical_scan;
 mod logical_source;
+mod logical_sys_scan;
 mod logical_topn;
+mod logical_union;
 mod logical_update;
 mod logical_values;
 mod stream_delta_join;
+mod stream_dynamic_filter;
 mod stream_exchange;
 mod stream_filter;
 mod stream_hash_agg;
@@ -246,11 +253,15 @@ mod stream_hash_join;
 mod stream_hop_window;
 mod stream_index_scan;
 mod stream_materialize;
+mod stream_nested_loop_join;
+mod stream_over_agg;
 mod stream_project;
 mod stream_simple_agg;
+mod stream_sink;
 mod stream_source;
 mod stream_table_scan;
 mod stream_topn;
+mod stream_union;
 
 pub use batch_delete::BatchDelete;
 pub use batch_exchange::BatchExchange;
@@ -262,11 +273,14 @@ pub use batch_hop_window::BatchHopWindow;
 pub use batch_insert::BatchInsert;
 pub use batch_limit::BatchLimit;
 pub use batch_nested_loop_join::BatchNestedLoopJoin;
+pub use batch_over_agg::BatchOverAgg;
 pub use batch_project::BatchProject;
 pub use batch_seq_scan::BatchSeqScan;
 pub use batch_simple_agg::BatchSimpleAgg;
 pub use batch_sort::BatchSort;
+pub use batch_sys_seq_scan::BatchSysSeqScan;
 pub use batch_topn::BatchTopN;
+pub use batch_union::BatchUnion;
 pub use batch_update::BatchUpdate;
 pub use batch_values::BatchValues;
 pub use logical_agg::{LogicalAgg, PlanAggCall};
@@ -279,13 +293,17 @@ pub use logical_insert::LogicalInsert;
 pub use logical_join::LogicalJoin;
 pub use logical_limit::LogicalLimit;
 pub use logical_multi_join::LogicalMultiJoin;
+pub use logical_over_agg::{LogicalOverAgg, PlanWindowFunction};
 pub use logical_project::LogicalProject;
 pub use logical_scan::LogicalScan;
 pub use logical_source::LogicalSource;
+pub use logical_sys_scan::LogicalSysScan;
 pub use logical_topn::LogicalTopN;
+pub use logical_union::LogicalUnion;
 pub use logical_update::LogicalUpdate;
 pub use logical_values::LogicalValues;
 pub use stream_delta_join::StreamDeltaJoin;
+pub use stream_dynamic_filter::StreamDynamicFilter;
 pub use stream_exchange::StreamExchange;
 pub use stream_filter::StreamFilter;
 pub use stream_hash_agg::StreamHashAgg;
@@ -293,11 +311,15 @@ pub use stream_hash_join::StreamHashJoin;
 pub use stream_hop_window::StreamHopWindow;
 pub use stream_index_scan::StreamIndexScan;
 pub use stream_materialize::StreamMaterialize;
+pub use stream_nested_loop_join::StreamNestedLoopJoin;
+pub use stream_over_agg::StreamOverAgg;
 pub use stream_project::StreamProject;
 pub use stream_simple_agg::StreamSimpleAgg;
+pub use stream_sink::StreamSink;
 pub use stream_source::StreamSource;
 pub use stream_table_scan::StreamTableScan;
 pub use stream_topn::StreamTopN;
+pub use stream_union::StreamUnion;
 
 use crate::session::OptimizerContextRef;
 
@@ -334,6 +356,9 @@ macro_rules! for_all_plan_nodes {
             , { Logical, HopWindow }
             , { Logical, GenerateSeries }
             , { Logical, MultiJoin }
+            , { Logical, Union }
+            , { Logical, OverAgg }
+            , { Logical, SysScan }
             // , { Logical, Sort } we don't need a LogicalSort, just require the Order
             , { Batch, SimpleAgg }
             , { Batch, HashAgg }
@@ -352,6 +377,9 @@ macro_rules! for_all_plan_nodes {
             , { Batch, TopN }
             , { Batch, HopWindow }
             , { Batch, GenerateSeries }
+            , { Batch, Union }
+            , { Batch, OverAgg }
+            , { Batch, SysSeqScan }
             , { Stream, Project }
             , { Stream, Filter }
             , { Stream, TableScan }
@@ -365,6 +393,11 @@ macro_rules! for_all_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, Sink }
+            , { Stream, Union }
+            , { Stream, OverAgg }
+            , { Stream, NestedLoopJoin }
+            , { Stream, DynamicFilter }
         }
     };
 }
@@ -391,6 +424,9 @@ macro_rules! for_logical_plan_nodes {
             , { Logical, HopWindow }
             , { Logical, GenerateSeries }
             , { Logical, MultiJoin }
+            , { Logical, Union }
+            , { Logical, OverAgg }
+            , { Logical, SysScan }
             // , { Logical, Sort} not sure if we will support Order by clause in subquery/view/MV
             // if we dont support thatk, we don't need LogicalSort, just require the Order at the top of query
         }
@@ -420,6 +456,9 @@ macro_rules! for_batch_plan_nodes {
             , { Batch, Update }
             , { Batch, HopWindow }
             , { Batch, GenerateSeries }
+            , { Batch, Union }
+            , { Batch, OverAgg }
+            , { Batch, SysSeqScan }
         }
     };
 }
@@ -443,6 +482,11 @@ macro_rules! for_stream_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, Sink }
+            , { Stream, Union }
+            , { Stream, OverAgg }
+            , { Stream, NestedLoopJoin }
+            , { Stream, DynamicFilter }
         }
     };
 }
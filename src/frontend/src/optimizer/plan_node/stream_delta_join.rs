@@ -15,6 +15,7 @@
 use std::fmt;
 
 use risingwave_common::catalog::ColumnDesc;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::plan_common::JoinType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{ArrangementInfo, DeltaIndexJoinNode};
@@ -36,21 +37,36 @@ pub struct StreamDeltaJoin {
 }
 
 impl StreamDeltaJoin {
-    pub fn new(logical: LogicalJoin, eq_join_predicate: EqJoinPredicate) -> Self {
-        let ctx = logical.base.ctx.clone();
-        // Inner join won't change the append-only behavior of the stream. The rest might.
-        let append_only = match logical.join_type() {
-            JoinType::Inner => logical.left().append_only() && logical.right().append_only(),
-            _ => todo!("delta join only supports inner join for now"),
-        };
+    /// Creates a delta join, which only supports inner joins with equal conditions. Outer delta
+    /// joins would need null-padding and degree tracking in the lookup arrangements, which are out
+    /// of scope for now: outer joins are executed as hash joins instead.
+    pub fn new(logical: LogicalJoin, eq_join_predicate: EqJoinPredicate) -> Result<Self> {
+        if logical.join_type() != JoinType::Inner {
+            return Err(ErrorCode::NotImplemented(
+                format!("delta join for {:?} join", logical.join_type()),
+                None.into(),
+            )
+            .into());
+        }
         if eq_join_predicate.has_non_eq() {
-            todo!("non-eq condition not supported for delta join");
+            return Err(ErrorCode::NotImplemented(
+                "delta join with non-equal conditions".to_string(),
+                None.into(),
+            )
+            .into());
         }
+        Ok(Self::new_inner(logical, eq_join_predicate))
+    }
+
+    fn new_inner(logical: LogicalJoin, eq_join_predicate: EqJoinPredicate) -> Self {
+        let ctx = logical.base.ctx.clone();
+        // Inner join won't change the append-only behavior of the stream.
+        let append_only = logical.left().append_only() && logical.right().append_only();
         let dist = StreamHashJoin::derive_dist(
             logical.left().distribution(),
             logical.right().distribution(),
             &eq_join_predicate,
-            &logical.l2o_col_mapping(),
+            &logical,
         );
 
         // TODO: derive from input
@@ -96,7 +112,8 @@ impl PlanTreeNodeBinary for StreamDeltaJoin {
     }
 
     fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
-        Self::new(
+        // The join type and the predicate are unchanged, so they have been checked in `new`.
+        Self::new_inner(
             self.logical.clone_with_left_right(left, right),
             self.eq_join_predicate.clone(),
         )
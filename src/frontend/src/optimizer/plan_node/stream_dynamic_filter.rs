@@ -0,0 +1,93 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::DynamicFilterNode;
+
+use super::{LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use crate::expr::{Expr, ExprImpl};
+
+/// [`StreamDynamicFilter`] implements a left semi [`super::LogicalJoin`] comparing a left column
+/// with a column of a right input with at most one row, e.g., `WHERE v > (SELECT avg(v) FROM t)`.
+/// The right row is broadcast to all the shards of the left input, so the left rows are filtered
+/// in place.
+#[derive(Debug, Clone)]
+pub struct StreamDynamicFilter {
+    pub base: PlanBase,
+    logical: LogicalJoin,
+    left_key: usize,
+    right_key: usize,
+}
+
+impl StreamDynamicFilter {
+    pub fn new(logical: LogicalJoin, left_key: usize, right_key: usize) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.base.pk_indices.to_vec(),
+            logical.left().distribution().clone(),
+            false,
+        );
+        Self {
+            base,
+            logical,
+            left_key,
+            right_key,
+        }
+    }
+}
+
+impl fmt::Display for StreamDynamicFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamDynamicFilter {{ predicate: {} }}",
+            self.logical.on()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for StreamDynamicFilter {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(
+            self.logical.clone_with_left_right(left, right),
+            self.left_key,
+            self.right_key,
+        )
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamDynamicFilter }
+
+impl ToStreamProst for StreamDynamicFilter {
+    fn to_stream_prost_body(&self) -> NodeBody {
+        NodeBody::DynamicFilter(DynamicFilterNode {
+            left_key: self.left_key as u32,
+            right_key: self.right_key as u32,
+            condition: Some(ExprImpl::from(self.logical.on().clone()).to_expr_proto()),
+            ..Default::default()
+        })
+    }
+}
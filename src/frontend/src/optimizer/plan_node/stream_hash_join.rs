@@ -15,6 +15,7 @@
 use std::fmt;
 
 use itertools::Itertools;
+use risingwave_common::error::Result;
 use risingwave_pb::plan_common::JoinType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::HashJoinNode;
@@ -23,7 +24,6 @@ use super::{LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, StreamDeltaJoin,
 use crate::expr::Expr;
 use crate::optimizer::plan_node::EqJoinPredicate;
 use crate::optimizer::property::Distribution;
-use crate::utils::ColIndexMapping;
 
 /// [`StreamHashJoin`] implements [`super::LogicalJoin`] with hash table. It builds a hash table
 /// from inner (right-side) relation and probes with data from outer (left-side) relation to
@@ -57,14 +57,10 @@ impl StreamHashJoin {
             logical.left().distribution(),
             logical.right().distribution(),
             &eq_join_predicate,
-            &logical.l2o_col_mapping(),
+            &logical,
         );
 
-        let force_delta = if let Some(config) = ctx.inner().session_ctx.get_config(DELTA_JOIN) {
-            config.is_set(false)
-        } else {
-            false
-        };
+        let force_delta = Self::is_delta_join_forced(&logical);
 
         // TODO: derive from input
         let base = PlanBase::new_stream(
@@ -83,6 +79,15 @@ impl StreamHashJoin {
         }
     }
 
+    /// Whether the join is forced to be a delta join by the session config [`DELTA_JOIN`].
+    pub(super) fn is_delta_join_forced(logical: &LogicalJoin) -> bool {
+        if let Some(config) = logical.base.ctx.inner().session_ctx.get_config(DELTA_JOIN) {
+            config.is_set(false)
+        } else {
+            false
+        }
+    }
+
     /// Get join type
     pub fn join_type(&self) -> JoinType {
         self.logical.join_type()
@@ -97,21 +102,33 @@ impl StreamHashJoin {
         left: &Distribution,
         right: &Distribution,
         predicate: &EqJoinPredicate,
-        l2o_mapping: &ColIndexMapping,
+        logical: &LogicalJoin,
     ) -> Distribution {
         match (left, right) {
             (Distribution::Single, Distribution::Single) => Distribution::Single,
             (Distribution::HashShard(_), Distribution::HashShard(_)) => {
                 assert!(left.satisfies(&Distribution::HashShard(predicate.left_eq_indexes())));
                 assert!(right.satisfies(&Distribution::HashShard(predicate.right_eq_indexes())));
-                l2o_mapping.rewrite_provided_distribution(left)
+                match logical.join_type() {
+                    JoinType::Inner
+                    | JoinType::LeftOuter
+                    | JoinType::LeftSemi
+                    | JoinType::LeftAnti => logical
+                        .l2o_col_mapping()
+                        .rewrite_provided_distribution(left),
+                    JoinType::RightOuter | JoinType::RightSemi | JoinType::RightAnti => logical
+                        .r2o_col_mapping()
+                        .rewrite_provided_distribution(right),
+                    // The join keys of either side may be padded with nulls.
+                    JoinType::FullOuter => Distribution::AnyShard,
+                }
             }
             (_, _) => panic!(),
         }
     }
 
     /// Convert this hash join to a delta join plan
-    pub fn to_delta_join(&self) -> StreamDeltaJoin {
+    pub fn to_delta_join(&self) -> Result<StreamDeltaJoin> {
         StreamDeltaJoin::new(self.logical.clone(), self.eq_join_predicate.clone())
     }
 }
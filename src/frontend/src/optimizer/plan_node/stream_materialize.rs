@@ -39,6 +39,8 @@ pub struct StreamMaterialize {
     /// Child of Materialize plan
     input: PlanRef,
     table: TableCatalog,
+    /// Whether the input may conflict with the materialized rows on pk, like an upsert source.
+    handle_pk_conflict: bool,
 }
 
 impl StreamMaterialize {
@@ -62,7 +64,12 @@ impl StreamMaterialize {
     #[must_use]
     pub fn new(input: PlanRef, table: TableCatalog) -> Self {
         let base = Self::derive_plan_base(&input).unwrap();
-        Self { base, input, table }
+        Self {
+            base,
+            input,
+            table,
+            handle_pk_conflict: false,
+        }
     }
 
     /// Create a materialize node.
@@ -164,7 +171,20 @@ impl StreamMaterialize {
             distribution_keys: base.dist.dist_column_indices().to_vec(),
         };
 
-        Ok(Self { base, input, table })
+        Ok(Self {
+            base,
+            input,
+            table,
+            handle_pk_conflict: false,
+        })
+    }
+
+    /// Makes the materialize executor overwrite the rows with existing pk and ignore the deletes of
+    /// nonexistent pk, instead of expecting the input to be consistent with the materialized rows.
+    #[must_use]
+    pub fn with_pk_conflict_handled(mut self) -> Self {
+        self.handle_pk_conflict = true;
+        self
     }
 
     /// Get a reference to the stream materialize's table.
@@ -228,7 +248,8 @@ impl PlanTreeNodeUnary for StreamMaterialize {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        let new = Self::new(input, self.table().clone());
+        let mut new = Self::new(input, self.table().clone());
+        new.handle_pk_conflict = self.handle_pk_conflict;
         assert_eq!(new.plan_base().schema, self.plan_base().schema);
         assert_eq!(new.plan_base().pk_indices, self.plan_base().pk_indices);
         new
@@ -274,6 +295,7 @@ impl ToStreamProst for StreamMaterialize {
                 .iter()
                 .map(|idx| *idx as i32)
                 .collect_vec(),
+            handle_pk_conflict: self.handle_pk_conflict,
         })
     }
 }
@@ -0,0 +1,93 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan_common::JoinType;
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::NestedLoopJoinNode;
+
+use super::{LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use crate::expr::Expr;
+use crate::optimizer::property::Distribution;
+
+/// [`StreamNestedLoopJoin`] implements [`super::LogicalJoin`] without equal conditions by
+/// checking the join condition against all the rows of the other side, which are kept in one
+/// place.
+#[derive(Debug, Clone)]
+pub struct StreamNestedLoopJoin {
+    pub base: PlanBase,
+    logical: LogicalJoin,
+}
+
+impl StreamNestedLoopJoin {
+    /// Both inputs of `logical` are required to be in [`Distribution::Single`].
+    pub fn new(logical: LogicalJoin) -> Self {
+        let ctx = logical.base.ctx.clone();
+        // Inner join won't change the append-only behavior of the stream. The rest might.
+        let append_only = match logical.join_type() {
+            JoinType::Inner => logical.left().append_only() && logical.right().append_only(),
+            _ => false,
+        };
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.base.pk_indices.to_vec(),
+            Distribution::Single,
+            append_only,
+        );
+        Self { base, logical }
+    }
+}
+
+impl fmt::Display for StreamNestedLoopJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamNestedLoopJoin {{ type: {:?}, predicate: {} }}",
+            self.logical.join_type(),
+            self.logical.on()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for StreamNestedLoopJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamNestedLoopJoin }
+
+impl ToStreamProst for StreamNestedLoopJoin {
+    fn to_stream_prost_body(&self) -> NodeBody {
+        NodeBody::NestedLoopJoin(NestedLoopJoinNode {
+            join_type: self.logical.join_type() as i32,
+            condition: self
+                .logical
+                .on()
+                .as_expr_unless_true()
+                .map(|x| x.to_expr_proto()),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+
+use super::{LogicalOverAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+
+/// `StreamOverAgg` implements [`super::LogicalOverAgg`] by maintaining the rows of each partition
+/// and emitting the changes of the results of the window functions.
+#[derive(Debug, Clone)]
+pub struct StreamOverAgg {
+    pub base: PlanBase,
+    logical: LogicalOverAgg,
+}
+
+impl StreamOverAgg {
+    pub fn new(logical: LogicalOverAgg) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let distribution = logical
+            .i2o_col_mapping()
+            .rewrite_provided_distribution(logical.input().distribution());
+        // A change of a row may change the results of all the rows in its partition.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            pk_indices,
+            distribution,
+            false,
+        );
+        StreamOverAgg { base, logical }
+    }
+}
+
+impl fmt::Display for StreamOverAgg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "StreamOverAgg")
+    }
+}
+
+impl PlanTreeNodeUnary for StreamOverAgg {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamOverAgg }
+
+impl ToStreamProst for StreamOverAgg {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        use risingwave_pb::stream_plan::*;
+        ProstStreamNode::OverWindow(OverWindowNode {
+            calls: self
+                .logical
+                .calls()
+                .iter()
+                .map(|call| call.to_protobuf())
+                .collect(),
+            partition_by: self
+                .logical
+                .partition_by()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            order_by: self.logical.order_by().to_protobuf(self.input().schema()),
+            ..Default::default()
+        })
+    }
+}
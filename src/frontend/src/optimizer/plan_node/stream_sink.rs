@@ -0,0 +1,98 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::SinkNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+
+/// [`StreamSink`] delivers the changes of its input stream to an external system, as specified
+/// by the `properties` of the sink.
+#[derive(Debug, Clone)]
+pub struct StreamSink {
+    pub base: PlanBase,
+    input: PlanRef,
+    properties: HashMap<String, String>,
+}
+
+impl StreamSink {
+    #[must_use]
+    pub fn new(input: PlanRef, properties: HashMap<String, String>) -> Self {
+        let base = PlanBase::new_stream(
+            input.ctx(),
+            input.schema().clone(),
+            input.pk_indices().to_vec(),
+            input.distribution().clone(),
+            input.append_only(),
+        );
+        Self {
+            base,
+            input,
+            properties,
+        }
+    }
+
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+}
+
+impl fmt::Display for StreamSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column_names = self
+            .base
+            .schema
+            .fields()
+            .iter()
+            .map(|field| field.name.as_str())
+            .join(", ");
+        let properties = self
+            .properties
+            .iter()
+            .sorted()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .join(", ");
+        write!(
+            f,
+            "StreamSink {{ columns: [{}], properties: {{ {} }} }}",
+            column_names, properties
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for StreamSink {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.properties.clone())
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamSink }
+
+impl ToStreamProst for StreamSink {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::Sink(SinkNode {
+            // The id will be generated on meta catalog service.
+            sink_id: 0,
+            properties: self.properties.clone(),
+        })
+    }
+}
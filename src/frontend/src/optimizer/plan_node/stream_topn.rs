@@ -34,6 +34,8 @@ impl StreamTopN {
         let dist = match logical.input().distribution() {
             Distribution::Any => Distribution::Any,
             Distribution::Single => Distribution::Single,
+            // The group TopN is distributed by its group key.
+            dist @ Distribution::HashShard(_) if !logical.group_key().is_empty() => dist.clone(),
             _ => panic!(),
         };
 
@@ -46,17 +48,21 @@ impl StreamTopN {
         );
         StreamTopN { base, logical }
     }
+
+    /// An append-only input never retracts rows, so there's no need to keep the rows beyond
+    /// `offset + limit`. The group top-n has no append-only counterpart yet.
+    fn is_append_only_top_n(&self) -> bool {
+        self.input().append_only() && self.logical.group_key().is_empty()
+    }
 }
 
 impl fmt::Display for StreamTopN {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "StreamTopN {{ order: {}, limit: {}, offset: {} }}",
-            self.logical.topn_order(),
-            self.logical.limit(),
-            self.logical.offset(),
-        )
+        if self.is_append_only_top_n() {
+            self.logical.fmt_with_name(f, "StreamAppendOnlyTopN")
+        } else {
+            self.logical.fmt_with_name(f, "StreamTopN")
+        }
     }
 }
 
@@ -88,11 +94,23 @@ impl ToStreamProst for StreamTopN {
                 return_type: Some(self.input().schema()[f.index].data_type().to_protobuf()),
             })
             .collect();
-        ProstStreamNode::TopN(TopNNode {
+        let top_n_node = TopNNode {
             column_orders,
             limit: self.logical.limit() as u64,
             offset: self.logical.offset() as u64,
             distribution_keys: vec![], // TODO: seems unnecessary
-        })
+            group_key: self
+                .logical
+                .group_key()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            ..Default::default()
+        };
+        if self.is_append_only_top_n() {
+            ProstStreamNode::AppendOnlyTopN(top_n_node)
+        } else {
+            ProstStreamNode::TopN(top_n_node)
+        }
     }
 }
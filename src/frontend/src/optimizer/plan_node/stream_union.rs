@@ -0,0 +1,69 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::UnionNode;
+
+use super::{LogicalUnion, PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use crate::optimizer::property::Distribution;
+
+/// `StreamUnion` implements [`super::LogicalUnion`] by merging the streams of both inputs, which
+/// are shuffled by the primary key of the union.
+#[derive(Debug, Clone)]
+pub struct StreamUnion {
+    pub base: PlanBase,
+    logical: LogicalUnion,
+}
+
+impl StreamUnion {
+    pub fn new(logical: LogicalUnion) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let dist = Distribution::HashShard(pk_indices.clone());
+        let append_only = logical.left().append_only() && logical.right().append_only();
+        let base =
+            PlanBase::new_stream(ctx, logical.schema().clone(), pk_indices, dist, append_only);
+        StreamUnion { base, logical }
+    }
+}
+
+impl fmt::Display for StreamUnion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StreamUnion")
+    }
+}
+
+impl PlanTreeNodeBinary for StreamUnion {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamUnion }
+
+impl ToStreamProst for StreamUnion {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::Union(UnionNode {})
+    }
+}
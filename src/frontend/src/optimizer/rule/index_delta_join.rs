@@ -110,11 +110,10 @@ impl Rule for IndexDeltaJoinRule {
                 // We already ensured that index and join use the same distribution, so we directly
                 // replace the children with stream index scan without inserting any exchanges.
 
-                Some(
-                    join.to_delta_join()
-                        .clone_with_left_right(left, right)
-                        .into(),
-                )
+                match join.to_delta_join() {
+                    Ok(delta_join) => Some(delta_join.clone_with_left_right(left, right).into()),
+                    Err(_) => Some(plan),
+                }
             } else {
                 Some(plan)
             }
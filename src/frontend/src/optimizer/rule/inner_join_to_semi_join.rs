@@ -0,0 +1,66 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fixedbitset::FixedBitSet;
+use risingwave_pb::plan_common::JoinType;
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+use crate::expr::{CollectInputRef, ExprVisitor};
+
+/// Transforms an inner [`LogicalJoin`] without equal conditions to a left semi join if the right
+/// input has at most one row and none of its columns is required by the [`LogicalProject`] above,
+/// e.g., `SELECT * FROM t WHERE v > (SELECT avg(v) FROM t)`. The left semi join can then be
+/// executed as a dynamic filter in streaming instead of a nested-loop join.
+pub struct InnerJoinToSemiJoinRule {}
+
+impl Rule for InnerJoinToSemiJoinRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let project = plan.as_logical_project()?;
+        let input = project.input();
+        let join = input.as_logical_join()?;
+        if join.join_type() != JoinType::Inner || !join.is_right_max_one_row() {
+            return None;
+        }
+        let left_len = join.left().schema().len();
+        let right_len = join.right().schema().len();
+        if EqJoinPredicate::create(left_len, right_len, join.on().clone()).has_eq() {
+            return None;
+        }
+
+        let mut input_ref_collector = CollectInputRef::with_capacity(left_len + right_len);
+        project
+            .exprs()
+            .iter()
+            .for_each(|expr| input_ref_collector.visit_expr(expr));
+        let required_cols: FixedBitSet = input_ref_collector.into();
+        if required_cols.ones().any(|idx| idx >= left_len) {
+            return None;
+        }
+
+        let semi_join = LogicalJoin::new(
+            join.left(),
+            join.right(),
+            JoinType::LeftSemi,
+            join.on().clone(),
+        );
+        Some(LogicalProject::new(semi_join.into(), project.exprs().clone()).into())
+    }
+}
+
+impl InnerJoinToSemiJoinRule {
+    pub fn create() -> BoxedRule {
+        Box::new(InnerJoinToSemiJoinRule {})
+    }
+}
@@ -43,3 +43,7 @@ mod multijoin_join;
 pub use multijoin_join::*;
 mod reorder_multijoin;
 pub use reorder_multijoin::*;
+mod over_agg_to_top_n;
+pub use over_agg_to_top_n::*;
+mod inner_join_to_semi_join;
+pub use inner_join_to_semi_join::*;
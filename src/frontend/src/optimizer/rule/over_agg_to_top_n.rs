@@ -0,0 +1,109 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+use crate::expr::{ExprImpl, ExprType, WindowFunctionKind};
+use crate::utils::Condition;
+
+/// Transforms the filter of row numbers like `row_number() OVER (PARTITION BY a ORDER BY b) <= N`
+/// to a [`LogicalTopN`] grouped by the partition keys below the [`LogicalOverAgg`], where only the
+/// row numbers are computed. The [`LogicalOverAgg`] can be pruned later if the row numbers are not
+/// required.
+pub struct OverAggToTopNRule {}
+
+impl Rule for OverAggToTopNRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let filter = plan.as_logical_filter()?;
+        let input = filter.input();
+        let over_agg = input.as_logical_over_agg()?;
+        if over_agg
+            .calls()
+            .iter()
+            .any(|call| call.kind != WindowFunctionKind::RowNumber)
+        {
+            return None;
+        }
+        let input_len = over_agg.input().schema().len();
+
+        let mut limit = None;
+        let mut others = vec![];
+        for conjunction in &filter.predicate().conjunctions {
+            match Self::row_number_limit(conjunction, input_len) {
+                Some(n) if limit.is_none() => limit = Some(n),
+                _ => others.push(conjunction.clone()),
+            }
+        }
+        let limit = limit?;
+
+        let top_n = LogicalTopN::with_group(
+            over_agg.input(),
+            limit,
+            0,
+            over_agg.order_by().clone(),
+            over_agg.partition_by().to_vec(),
+        );
+        let over_agg = over_agg.clone_with_input(top_n.into());
+        Some(LogicalFilter::create(
+            over_agg.into(),
+            Condition {
+                conjunctions: others,
+            },
+        ))
+    }
+}
+
+impl OverAggToTopNRule {
+    pub fn create() -> BoxedRule {
+        Box::new(OverAggToTopNRule {})
+    }
+
+    /// Returns `N` if the expression limits a row number column to the first `N` rows, i.e.,
+    /// `rn <= N`, `rn < N + 1` or `rn = 1`.
+    fn row_number_limit(expr: &ExprImpl, input_len: usize) -> Option<usize> {
+        let func_call = match expr {
+            ExprImpl::FunctionCall(func_call) => func_call,
+            _ => return None,
+        };
+        let (row_number, constant, expr_type) = match func_call.inputs() {
+            [ExprImpl::InputRef(input_ref), constant] => {
+                (input_ref, constant, func_call.get_expr_type())
+            }
+            [constant, ExprImpl::InputRef(input_ref)] => {
+                let expr_type = match func_call.get_expr_type() {
+                    ExprType::GreaterThanOrEqual => ExprType::LessThanOrEqual,
+                    ExprType::GreaterThan => ExprType::LessThan,
+                    ExprType::Equal => ExprType::Equal,
+                    _ => return None,
+                };
+                (input_ref, constant, expr_type)
+            }
+            _ => return None,
+        };
+        if row_number.index() < input_len {
+            return None;
+        }
+        let limit = match (expr_type, constant.as_int_constant()?) {
+            (ExprType::LessThanOrEqual, n) => n,
+            (ExprType::LessThan, n) => n.checked_sub(1)?,
+            (ExprType::Equal, 1) => 1,
+            _ => return None,
+        };
+        if limit > 0 {
+            Some(limit as usize)
+        } else {
+            None
+        }
+    }
+}
@@ -20,13 +20,13 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::ScalarImpl;
 
 use crate::binder::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundWindowTableFunction,
-    Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
 use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::{
     LogicalGenerateSeries, LogicalHopWindow, LogicalJoin, LogicalProject, LogicalScan,
-    LogicalSource, PlanRef,
+    LogicalSource, LogicalSysScan, PlanRef,
 };
 use crate::planner::Planner;
 
@@ -40,6 +40,7 @@ impl Planner {
             Relation::WindowTableFunction(tf) => self.plan_window_table_function(*tf),
             Relation::Source(s) => self.plan_source(*s),
             Relation::GenerateSeriesFunction(gs) => self.plan_generate_series_function(*gs),
+            Relation::SystemTable(st) => self.plan_sys_table(*st),
         }
     }
 
@@ -60,6 +61,15 @@ impl Planner {
         Ok(LogicalSource::new(Rc::new(source.catalog), self.ctx()).into())
     }
 
+    pub(super) fn plan_sys_table(&mut self, sys_table: BoundSystemTable) -> Result<PlanRef> {
+        Ok(LogicalSysScan::create(
+            sys_table.sys_table.name.to_string(),
+            sys_table.rows,
+            Schema::new(sys_table.sys_table.fields()),
+            self.ctx(),
+        ))
+    }
+
     pub(super) fn plan_join(&mut self, join: BoundJoin) -> Result<PlanRef> {
         let left = self.plan_relation(join.left)?;
         let right = self.plan_relation(join.right)?;
@@ -12,11 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::error::Result;
+use itertools::Itertools;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_expr::expr::AggKind;
+use risingwave_sqlparser::ast::SetOperator;
 
 use crate::binder::BoundSetExpr;
-use crate::expr::ExprImpl;
-use crate::optimizer::plan_node::PlanRef;
+use crate::expr::{
+    ExprImpl, ExprType, FunctionCall, InputRef, Literal, WindowFrame, WindowFunctionKind,
+};
+use crate::optimizer::plan_node::{
+    LogicalAgg, LogicalFilter, LogicalOverAgg, LogicalProject, LogicalUnion, PlanAggCall, PlanRef,
+    PlanWindowFunction,
+};
+use crate::optimizer::property::Order;
 use crate::planner::Planner;
 
 impl Planner {
@@ -28,6 +39,178 @@ impl Planner {
         match set_expr {
             BoundSetExpr::Select(s) => self.plan_select(*s, extra_order_exprs),
             BoundSetExpr::Values(v) => self.plan_values(*v),
+            BoundSetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+                schema,
+            } => {
+                if !extra_order_exprs.is_empty() {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "for {}, ORDER BY expressions must appear in select list",
+                        op
+                    ))
+                    .into());
+                }
+                self.plan_set_operation(op, all, *left, *right, &schema)
+            }
         }
     }
+
+    /// Plans a set operation on top of a [`LogicalUnion`]:
+    ///
+    /// - `UNION ALL` is the union itself.
+    /// - `UNION` removes the duplicated rows from the union by an aggregation grouped by all
+    ///   columns.
+    /// - `INTERSECT` and `EXCEPT` mark the rows of both sides, and count the marks of each side in
+    ///   the aggregation, to keep the rows that appear on both sides, or only on the left side.
+    /// - `INTERSECT ALL` and `EXCEPT ALL` do the same after numbering the copies of each row with
+    ///   `row_number`, so that each copy is matched with the copy of the same number on the other
+    ///   side.
+    fn plan_set_operation(
+        &mut self,
+        op: SetOperator,
+        all: bool,
+        left: BoundSetExpr,
+        right: BoundSetExpr,
+        schema: &Schema,
+    ) -> Result<PlanRef> {
+        let left = self.plan_set_expr(left, vec![])?;
+        let left = Self::cast_to_schema(left, schema)?;
+        let right = self.plan_set_expr(right, vec![])?;
+        let right = Self::cast_to_schema(right, schema)?;
+        let column_num = schema.len();
+
+        match (op, all) {
+            (SetOperator::Union, true) => Ok(LogicalUnion::create(left, right)),
+            (SetOperator::Union, false) => {
+                let union = LogicalUnion::create(left, right);
+                Ok(LogicalAgg::new(vec![], (0..column_num).collect(), union).into())
+            }
+            (SetOperator::Intersect | SetOperator::Except, all) => {
+                let mut key_types = schema.data_types();
+                let (left, right) = if all {
+                    // Number the copies of each row on both sides, so that the n-th copy is kept
+                    // if it exists on both sides, or only on the left side. This emits
+                    // `min(l, r)` copies for `INTERSECT ALL` and `max(l - r, 0)` copies for
+                    // `EXCEPT ALL`.
+                    key_types.push(DataType::Int64);
+                    (
+                        Self::number_copies(left, column_num),
+                        Self::number_copies(right, column_num),
+                    )
+                } else {
+                    (left, right)
+                };
+                let filter = Self::plan_intersect_or_except(op, left, right, &key_types)?;
+
+                let exprs = (0..column_num)
+                    .map(|i| InputRef::new(i, schema.fields()[i].data_type()).into())
+                    .collect();
+                Ok(LogicalProject::create(filter, exprs))
+            }
+        }
+    }
+
+    /// Appends the `row_number` of each row among its copies, i.e. partitioned by all columns.
+    fn number_copies(input: PlanRef, column_num: usize) -> PlanRef {
+        let row_number = PlanWindowFunction {
+            kind: WindowFunctionKind::RowNumber,
+            return_type: DataType::Int64,
+            args: vec![],
+            offset: 0,
+            frame: WindowFrame::default(),
+        };
+        LogicalOverAgg::new(
+            vec![row_number],
+            (0..column_num).collect(),
+            Order::any().clone(),
+            input,
+        )
+        .into()
+    }
+
+    /// Keeps the distinct rows of `left` that appear in `right` for `INTERSECT`, or that don't
+    /// appear in `right` for `EXCEPT`, followed by the counts of each side.
+    fn plan_intersect_or_except(
+        op: SetOperator,
+        left: PlanRef,
+        right: PlanRef,
+        key_types: &[DataType],
+    ) -> Result<PlanRef> {
+        let key_num = key_types.len();
+        let mark = |input: PlanRef, is_left: bool| {
+            let mut exprs = key_types
+                .iter()
+                .enumerate()
+                .map(|(i, data_type)| InputRef::new(i, data_type.clone()).into())
+                .collect::<Vec<ExprImpl>>();
+            for side in [true, false] {
+                exprs.push(match side == is_left {
+                    true => ExprImpl::literal_int(1),
+                    false => Literal::new(None, DataType::Int32).into(),
+                });
+            }
+            LogicalProject::create(input, exprs)
+        };
+        let union = LogicalUnion::create(mark(left, true), mark(right, false));
+
+        let count_marks = |mark_idx: usize| PlanAggCall {
+            agg_kind: AggKind::Count,
+            return_type: DataType::Int64,
+            inputs: vec![InputRef::new(mark_idx, DataType::Int32)],
+            distinct: false,
+        };
+        let agg = LogicalAgg::new(
+            vec![count_marks(key_num), count_marks(key_num + 1)],
+            (0..key_num).collect(),
+            union,
+        );
+
+        // The rows must appear on the left side, and on the right side only for `INTERSECT`.
+        let compare_marks = |mark_idx: usize, func_type: ExprType| {
+            FunctionCall::new(
+                func_type,
+                vec![
+                    InputRef::new(mark_idx, DataType::Int64).into(),
+                    Literal::new(Some(ScalarImpl::Int64(0)), DataType::Int64).into(),
+                ],
+            )
+        };
+        let right_cmp = match op {
+            SetOperator::Intersect => ExprType::GreaterThan,
+            _ => ExprType::Equal,
+        };
+        let predicate = FunctionCall::new(
+            ExprType::And,
+            vec![
+                compare_marks(key_num, ExprType::GreaterThan)?.into(),
+                compare_marks(key_num + 1, right_cmp)?.into(),
+            ],
+        )?;
+        Ok(LogicalFilter::create_with_expr(
+            agg.into(),
+            predicate.into(),
+        ))
+    }
+
+    /// Casts the columns of `input` to the types in `schema` if they differ.
+    fn cast_to_schema(input: PlanRef, schema: &Schema) -> Result<PlanRef> {
+        if input.schema().data_types() == schema.data_types() {
+            return Ok(input);
+        }
+        let exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .zip_eq(schema.fields())
+            .map(|((i, field), target)| {
+                ExprImpl::from(InputRef::new(i, field.data_type()))
+                    .cast_implicit(target.data_type())
+            })
+            .collect::<Result<_>>()?;
+        Ok(LogicalProject::create(input, exprs))
+    }
 }
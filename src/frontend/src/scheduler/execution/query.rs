@@ -60,7 +60,7 @@ enum QueryState {
     /// Running
     Running {
         _msg_sender: Sender<QueryMessage>,
-        _task_handle: JoinHandle<Result<()>>,
+        task_handle: JoinHandle<Result<()>>,
     },
 
     /// Failed
@@ -73,7 +73,7 @@ enum QueryState {
 pub struct QueryExecution {
     query: Arc<Query>,
     state: Arc<RwLock<QueryState>>,
-    _stage_executions: Arc<HashMap<StageId, Arc<StageExecution>>>,
+    stage_executions: Arc<HashMap<StageId, Arc<StageExecution>>>,
 }
 
 struct QueryRunner {
@@ -149,59 +149,82 @@ impl QueryExecution {
         Self {
             query,
             state: Arc::new(RwLock::new(state)),
-            _stage_executions: stage_executions,
+            stage_executions,
         }
     }
 
     /// Start execution of this query.
     pub async fn start(&self) -> Result<QueryResultFetcher> {
-        let mut state = self.state.write().await;
-        let mut cur_state = Failed;
-        swap(&mut *state, &mut cur_state);
-
-        match cur_state {
-            QueryState::Pending {
-                runner,
-                root_stage_receiver,
-            } => {
-                let msg_sender = runner.msg_sender.clone();
-                let task_handle = tokio::spawn(async move {
-                    let query_id = runner.query.query_id.clone();
-                    runner.run().await.map_err(|e| {
-                        error!("Query {:?} failed, reason: {:?}", query_id, e);
-                        e
-                    })
-                });
-
-                let root_stage = root_stage_receiver.await.map_err(|e| {
-                    InternalError(format!("Starting query execution failed: {:?}", e))
-                })??;
-
-                info!(
-                    "Received root stage query result fetcher: {:?}, query id: {:?}",
-                    root_stage, self.query.query_id
-                );
-
-                *state = QueryState::Running {
-                    _msg_sender: msg_sender,
-                    _task_handle: task_handle,
-                };
-
-                Ok(root_stage)
+        // The state is released before waiting for the root stage, so that the query can be
+        // aborted while its stages are being scheduled.
+        let root_stage_receiver = {
+            let mut state = self.state.write().await;
+            let mut cur_state = Failed;
+            swap(&mut *state, &mut cur_state);
+
+            match cur_state {
+                QueryState::Pending {
+                    runner,
+                    root_stage_receiver,
+                } => {
+                    let msg_sender = runner.msg_sender.clone();
+                    let task_handle = tokio::spawn(async move {
+                        let query_id = runner.query.query_id.clone();
+                        runner.run().await.map_err(|e| {
+                            error!("Query {:?} failed, reason: {:?}", query_id, e);
+                            e
+                        })
+                    });
+
+                    *state = QueryState::Running {
+                        _msg_sender: msg_sender,
+                        task_handle,
+                    };
+                    root_stage_receiver
+                }
+                s => {
+                    // Restore old state
+                    *state = s;
+                    return Err(ErrorCode::InternalError("Query not pending!".to_string()).into());
+                }
             }
-            s => {
-                // Restore old state
-                *state = s;
-                Err(ErrorCode::InternalError("Query not pending!".to_string()).into())
+        };
+
+        let root_stage = root_stage_receiver
+            .await
+            .map_err(|e| InternalError(format!("Starting query execution failed: {:?}", e)))??;
+
+        info!(
+            "Received root stage query result fetcher: {:?}, query id: {:?}",
+            root_stage, self.query.query_id
+        );
+
+        Ok(root_stage)
+    }
+
+    /// Cancel execution of this query. All its stages are stopped, and the tasks scheduled are
+    /// aborted on the compute nodes.
+    pub async fn abort(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            let mut cur_state = Failed;
+            swap(&mut *state, &mut cur_state);
+            if let QueryState::Running { task_handle, .. } = cur_state {
+                task_handle.abort();
             }
         }
+
+        info!("Aborting query {:?}", self.query.query_id);
+        stop_stages(&self.stage_executions).await
     }
+}
 
-    /// Cancel execution of this query.
-    #[allow(unused)]
-    pub async fn abort(&mut self) -> Result<()> {
-        todo!()
+/// Stops all the stages of a query, including the ones that have not been scheduled.
+async fn stop_stages(stage_executions: &HashMap<StageId, Arc<StageExecution>>) -> Result<()> {
+    for stage_execution in stage_executions.values() {
+        stage_execution.stop().await?;
     }
+    Ok(())
 }
 
 impl QueryRunner {
@@ -280,7 +303,8 @@ impl QueryRunner {
                             );
                         }
                     }
-                    // TODO: We should can cancel all scheduled stages here.
+                    // The query can't go on, so stop the stages to release their tasks.
+                    stop_stages(&self.stage_executions).await?;
                 }
                 _ => {
                     return Err(ErrorCode::NotImplemented(
@@ -388,6 +412,8 @@ mod tests {
         );
 
         assert!(query_execution.start().await.is_err());
+        // No task has been scheduled, so there is nothing to abort on compute nodes.
+        query_execution.abort().await.unwrap();
     }
 
     async fn create_query() -> Query {
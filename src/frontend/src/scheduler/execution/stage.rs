@@ -30,7 +30,7 @@ use tokio::spawn;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 use StageEvent::Failed;
 
@@ -193,8 +193,46 @@ impl StageExecution {
         }
     }
 
+    /// Stops scheduling this stage and aborts its scheduled tasks on the compute nodes. Stopping a
+    /// stage more than once is a no-op.
     pub async fn stop(&self) -> Result<()> {
-        todo!()
+        {
+            let mut s = self.state.write().await;
+            let mut tmp_s = StageState::Failed;
+            swap(&mut *s, &mut tmp_s);
+            match tmp_s {
+                StageState::Started { handle, .. }
+                | StageState::Running {
+                    _handle: handle, ..
+                } => {
+                    handle.abort();
+                }
+                StageState::Pending | StageState::Completed | StageState::Failed => {}
+            }
+        }
+
+        for (task_id, status_holder) in self.tasks.iter() {
+            // Tasks without a location have not been scheduled.
+            let location = match status_holder.get_status().location.clone() {
+                Some(location) => location,
+                None => continue,
+            };
+            let task_id = TaskIdProst {
+                query_id: self.stage.query_id.id.clone(),
+                stage_id: self.stage.id,
+                task_id: *task_id,
+            };
+            // Try our best to abort the other tasks even if some of them fail.
+            if let Err(e) = Self::abort_task(&location, task_id.clone()).await {
+                warn!("Failed to abort task {:?}, reason: {}", task_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn abort_task(location: &HostAddress, task_id: TaskIdProst) -> Result<()> {
+        let compute_client = ComputeClient::new(location.into()).await?;
+        compute_client.abort_task(task_id).await
     }
 
     pub async fn is_scheduled(&self) -> bool {
@@ -240,7 +278,7 @@ impl StageRunner {
                 "Stage {:?}-{:?} failed to schedule tasks, error: {}",
                 self.stage.query_id, self.stage.id, e
             );
-            // TODO: We should cancel all scheduled tasks
+            // The tasks scheduled will be aborted when the query runner stops this stage.
             self.send_event(QueryMessage::Stage(Failed {
                 id: self.stage.id,
                 reason: e,
@@ -261,7 +299,11 @@ impl StageRunner {
                         _handle: handle,
                     };
                 }
-                _ => unreachable!(),
+                // The stage has been stopped while its tasks are being scheduled.
+                tmp_s => {
+                    *s = tmp_s;
+                    return Ok(());
+                }
             }
         }
 
@@ -13,15 +13,18 @@
 // limitations under the License.
 
 use std::fmt::{Debug, Formatter};
+use std::time::Duration;
 
-use futures::Stream;
+use futures::future::pending;
+use futures::{pin_mut, Stream, StreamExt};
 use futures_async_stream::try_stream;
-use log::debug;
+use log::{debug, warn};
 use risingwave_common::array::DataChunk;
-use risingwave_common::error::{Result, RwError};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::batch_plan::{PlanNode as BatchPlanProst, TaskId, TaskOutputId};
 use risingwave_pb::common::HostAddress;
 use risingwave_rpc_client::{ComputeClient, ExchangeSource};
+use tokio::sync::oneshot;
 use uuid::Uuid;
 
 use super::HummockSnapshotManagerRef;
@@ -97,11 +100,16 @@ impl QueryManager {
         Ok(query_result_fetcher.run())
     }
 
+    /// Schedules the query to run in distributed mode. The query is aborted when it's cancelled
+    /// in the session, or when it runs beyond the `statement_timeout` of the session.
     pub async fn schedule(
         &self,
-        _context: ExecutionContextRef,
+        context: ExecutionContextRef,
         query: Query,
     ) -> Result<impl DataChunkStream> {
+        let cancel_rx = context.session().reset_cancel_query_flag();
+        let timeout = context.session().statement_timeout();
+
         // Cheat compiler to resolve type
         let epoch = self.hummock_snapshot_manager.get_epoch().await?;
 
@@ -112,9 +120,78 @@ impl QueryManager {
             self.hummock_snapshot_manager.clone(),
         );
 
-        let query_result_fetcher = query_execution.start().await?;
+        Ok(run_query(query_execution, cancel_rx, timeout))
+    }
+}
+
+/// Runs the query and fetches its results. Once the query fails, is cancelled, or times out, it's
+/// aborted so that its tasks on the compute nodes are released.
+#[try_stream(ok = DataChunk, error = RwError)]
+async fn run_query(
+    query_execution: QueryExecution,
+    cancel_rx: oneshot::Receiver<()>,
+    timeout: Option<Duration>,
+) {
+    let interrupted = wait_for_interrupt(cancel_rx, timeout);
+    pin_mut!(interrupted);
 
-        Ok(query_result_fetcher.run())
+    let query_result_fetcher = tokio::select! {
+        fetcher = query_execution.start() => fetcher,
+        reason = &mut interrupted => Err(reason),
+    };
+    let query_result_fetcher = match query_result_fetcher {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            abort_query(&query_execution).await;
+            return Err(e);
+        }
+    };
+
+    let data_stream = query_result_fetcher.run();
+    pin_mut!(data_stream);
+    loop {
+        let chunk = tokio::select! {
+            chunk = data_stream.next() => chunk,
+            reason = &mut interrupted => Some(Err(reason)),
+        };
+        match chunk {
+            Some(Ok(chunk)) => yield chunk,
+            Some(Err(e)) => {
+                abort_query(&query_execution).await;
+                return Err(e);
+            }
+            None => break,
+        }
+    }
+}
+
+/// Aborts the query, where the failure is only logged since the query has already failed.
+async fn abort_query(query_execution: &QueryExecution) {
+    if let Err(e) = query_execution.abort().await {
+        warn!("Failed to abort query, reason: {}", e);
+    }
+}
+
+/// Waits until the query is cancelled in the session or times out, returning the error to report.
+async fn wait_for_interrupt(
+    cancel_rx: oneshot::Receiver<()>,
+    timeout: Option<Duration>,
+) -> RwError {
+    let cancelled = async {
+        // The sender is dropped without being used if the session is closed.
+        if cancel_rx.await.is_err() {
+            pending::<()>().await;
+        }
+    };
+    let timed_out = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => pending::<()>().await,
+        }
+    };
+    tokio::select! {
+        _ = cancelled => ErrorCode::QueryCancelled("user request".to_string()).into(),
+        _ = timed_out => ErrorCode::QueryCancelled("statement timeout".to_string()).into(),
     }
 }
 
@@ -161,3 +238,41 @@ impl Debug for QueryResultFetcher {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future::pending;
+    use risingwave_common::error::ErrorCode;
+    use tokio::sync::oneshot;
+
+    use super::wait_for_interrupt;
+
+    #[tokio::test]
+    async fn test_query_cancelled_by_timeout() {
+        let (_cancel_tx, cancel_rx) = oneshot::channel();
+        let query = pending::<()>();
+        let reason = tokio::select! {
+            _ = query => unreachable!(),
+            reason = wait_for_interrupt(cancel_rx, Some(Duration::from_millis(10))) => reason,
+        };
+        assert!(
+            matches!(reason.inner(), ErrorCode::QueryCancelled(r) if r == "statement timeout"),
+            "{}",
+            reason
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_cancelled_by_user() {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        cancel_tx.send(()).unwrap();
+        let reason = wait_for_interrupt(cancel_rx, Some(Duration::from_secs(3600))).await;
+        assert!(
+            matches!(reason.inner(), ErrorCode::QueryCancelled(r) if r == "user request"),
+            "{}",
+            reason
+        );
+    }
+}
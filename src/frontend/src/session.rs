@@ -17,31 +17,41 @@ use std::fmt::Formatter;
 use std::marker::Sync;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicI32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
+use pgwire::pg_server::{BoxedError, Session, SessionId, SessionManager, UserAuthenticator};
 use risingwave_common::config::FrontendConfig;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::user::UserInfo;
 use risingwave_rpc_client::MetaClient;
+use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::watch;
+use tokio::sync::{oneshot, watch};
 use tokio::task::JoinHandle;
 
 use crate::catalog::catalog_service::{CatalogReader, CatalogWriter, CatalogWriterImpl};
 use crate::catalog::root_catalog::Catalog;
+use crate::config::parse_statement_timeout;
 use crate::handler::dml::IMPLICIT_FLUSH;
 use crate::handler::handle;
+use crate::handler::prepared_statement::{describe_statement, handle_with_params};
+use crate::handler::query::STATEMENT_TIMEOUT;
+use crate::handler::util::{data_type_to_type_oid, type_oid_to_data_type};
 use crate::meta_client::{FrontendMetaClient, FrontendMetaClientImpl};
 use crate::observer::observer_manager::ObserverManager;
 use crate::optimizer::plan_node::PlanNodeId;
 use crate::scheduler::worker_node_manager::{WorkerNodeManager, WorkerNodeManagerRef};
 use crate::scheduler::{HummockSnapshotManager, QueryManager};
+use crate::user::user_authentication::user_authenticator;
+use crate::user::user_manager::UserInfoManager;
+use crate::user::user_service::{UserInfoReader, UserInfoWriter, UserInfoWriterImpl};
 use crate::FrontendOpts;
 
 pub struct OptimizerContext {
@@ -107,6 +117,17 @@ impl std::fmt::Debug for OptimizerContext {
     }
 }
 
+/// A prepared statement consists of only one statement, same as PG.
+fn check_single_statement(stmts: &[Statement]) -> Result<()> {
+    if stmts.len() > 1 {
+        return Err(ErrorCode::InvalidInputSyntax(
+            "cannot insert multiple commands into a prepared statement".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
 fn load_config(opts: &FrontendOpts) -> FrontendConfig {
     if opts.config_path.is_empty() {
         return FrontendConfig::default();
@@ -124,6 +145,8 @@ pub struct FrontendEnv {
     meta_client: Arc<dyn FrontendMetaClient>,
     catalog_writer: Arc<dyn CatalogWriter>,
     catalog_reader: CatalogReader,
+    user_info_writer: Arc<dyn UserInfoWriter>,
+    user_info_reader: UserInfoReader,
     worker_node_manager: WorkerNodeManagerRef,
     query_manager: QueryManager,
 }
@@ -137,11 +160,14 @@ impl FrontendEnv {
     }
 
     pub fn mock() -> Self {
-        use crate::test_utils::{MockCatalogWriter, MockFrontendMetaClient};
+        use crate::test_utils::{MockCatalogWriter, MockFrontendMetaClient, MockUserInfoWriter};
 
         let catalog = Arc::new(RwLock::new(Catalog::default()));
         let catalog_writer = Arc::new(MockCatalogWriter::new(catalog.clone()));
         let catalog_reader = CatalogReader::new(catalog);
+        let user_info_manager = Arc::new(RwLock::new(UserInfoManager::default()));
+        let user_info_writer = Arc::new(MockUserInfoWriter::new(user_info_manager.clone()));
+        let user_info_reader = UserInfoReader::new(user_info_manager);
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(vec![]));
         let meta_client = Arc::new(MockFrontendMetaClient {});
         let hummock_snapshot_manager = Arc::new(HummockSnapshotManager::new(meta_client.clone()));
@@ -151,6 +177,8 @@ impl FrontendEnv {
             meta_client,
             catalog_writer,
             catalog_reader,
+            user_info_writer,
+            user_info_reader,
             worker_node_manager,
             query_manager,
         }
@@ -187,6 +215,14 @@ impl FrontendEnv {
         ));
         let catalog_reader = CatalogReader::new(catalog.clone());
 
+        let (user_info_updated_tx, user_info_updated_rx) = watch::channel(0);
+        let user_info_manager = Arc::new(RwLock::new(UserInfoManager::default()));
+        let user_info_writer = Arc::new(UserInfoWriterImpl::new(
+            meta_client.clone(),
+            user_info_updated_rx,
+        ));
+        let user_info_reader = UserInfoReader::new(user_info_manager.clone());
+
         let worker_node_manager = Arc::new(WorkerNodeManager::new(meta_client.clone()).await?);
 
         let frontend_meta_client = Arc::new(FrontendMetaClientImpl(meta_client.clone()));
@@ -204,6 +240,8 @@ impl FrontendEnv {
             catalog,
             catalog_updated_tx,
             hummock_snapshot_manager,
+            user_info_manager,
+            user_info_updated_tx,
         )
         .await;
         let observer_join_handle = observer_manager.start().await?;
@@ -214,6 +252,8 @@ impl FrontendEnv {
             Self {
                 catalog_reader,
                 catalog_writer,
+                user_info_reader,
+                user_info_writer,
                 worker_node_manager,
                 meta_client: frontend_meta_client,
                 query_manager,
@@ -234,6 +274,16 @@ impl FrontendEnv {
         &self.catalog_reader
     }
 
+    /// Get a reference to the frontend env's user info writer.
+    pub fn user_info_writer(&self) -> &dyn UserInfoWriter {
+        &*self.user_info_writer
+    }
+
+    /// Get a reference to the frontend env's user info reader.
+    pub fn user_info_reader(&self) -> &UserInfoReader {
+        &self.user_info_reader
+    }
+
     pub fn worker_node_manager(&self) -> &WorkerNodeManager {
         &*self.worker_node_manager
     }
@@ -258,8 +308,14 @@ impl FrontendEnv {
 pub struct SessionImpl {
     env: FrontendEnv,
     database: String,
+    user_name: String,
+    user_authenticator: UserAuthenticator,
+    /// Identifies the session in cancel requests.
+    id: SessionId,
     /// Stores the value of configurations.
     config_map: RwLock<HashMap<String, ConfigEntry>>,
+    /// Notifies the running query to be cancelled. It's replaced each time a query starts.
+    current_query_cancel_tx: Mutex<Option<oneshot::Sender<()>>>,
 }
 
 #[derive(Clone)]
@@ -276,14 +332,29 @@ impl ConfigEntry {
     pub fn is_set(&self, default: bool) -> bool {
         self.str_val.parse().unwrap_or(default)
     }
+
+    /// Only used for integer configurations.
+    pub fn get_usize(&self, default: usize) -> usize {
+        self.str_val.parse().unwrap_or(default)
+    }
 }
 
 impl SessionImpl {
-    pub fn new(env: FrontendEnv, database: String) -> Self {
+    pub fn new(
+        env: FrontendEnv,
+        database: String,
+        user_name: String,
+        user_authenticator: UserAuthenticator,
+        id: SessionId,
+    ) -> Self {
         Self {
             env,
             database,
+            user_name,
+            user_authenticator,
+            id,
             config_map: Self::init_config_map(),
+            current_query_cancel_tx: Mutex::new(None),
         }
     }
 
@@ -292,7 +363,11 @@ impl SessionImpl {
         Self {
             env: FrontendEnv::mock(),
             database: "dev".to_string(),
+            user_name: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
+            user_authenticator: UserAuthenticator::None,
+            id: (0, 0),
             config_map: Self::init_config_map(),
+            current_query_cancel_tx: Mutex::new(None),
         }
     }
 
@@ -304,6 +379,21 @@ impl SessionImpl {
         &self.database
     }
 
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
+    /// Returns the info of the current user, whose privileges are checked on the objects accessed
+    /// by the session. A user dropped after the session is established has no privilege at all.
+    pub fn user_info(&self) -> UserInfo {
+        self.env
+            .user_info_reader()
+            .read_guard()
+            .get_user_by_name(&self.user_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Set configuration values in this session.
     /// For example, `set_config("RW_IMPLICIT_FLUSH", true)` will implicit flush for every inserts.
     pub fn set_config(&self, key: &str, val: &str) {
@@ -318,6 +408,29 @@ impl SessionImpl {
         reader.get(key).cloned()
     }
 
+    /// Returns the maximum time a query can run, or `None` if there is no limit.
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.get_config(STATEMENT_TIMEOUT)
+            .and_then(|entry| parse_statement_timeout(&entry.str_val).ok())
+            .filter(|timeout| !timeout.is_zero())
+    }
+
+    /// Called when a query starts running in this session. The returned receiver is notified
+    /// when the query is cancelled by [`SessionImpl::cancel_current_query`].
+    pub fn reset_cancel_query_flag(&self) -> oneshot::Receiver<()> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        *self.current_query_cancel_tx.lock() = Some(cancel_tx);
+        cancel_rx
+    }
+
+    /// Cancels the running query of this session, if any.
+    pub fn cancel_current_query(&self) {
+        if let Some(cancel_tx) = self.current_query_cancel_tx.lock().take() {
+            // The query may have finished, which drops the receiver.
+            let _ = cancel_tx.send(());
+        }
+    }
+
     fn init_config_map() -> RwLock<HashMap<String, ConfigEntry>> {
         let mut map = HashMap::new();
         // FIXME: May need better init way + default config.
@@ -325,12 +438,21 @@ impl SessionImpl {
             IMPLICIT_FLUSH.to_string(),
             ConfigEntry::new("false".to_string()),
         );
+        map.insert(
+            STATEMENT_TIMEOUT.to_string(),
+            ConfigEntry::new("0".to_string()),
+        );
         RwLock::new(map)
     }
 }
 
 pub struct SessionManagerImpl {
     env: FrontendEnv,
+    /// The sessions connected, which are looked up by cancel requests. Closed sessions are
+    /// removed lazily when new sessions connect.
+    sessions: Mutex<HashMap<SessionId, Weak<SessionImpl>>>,
+    /// Used to assign a unique process id to each session.
+    next_process_id: AtomicI32,
     observer_join_handle: JoinHandle<()>,
     heartbeat_join_handle: JoinHandle<()>,
     _heartbeat_shutdown_sender: UnboundedSender<()>,
@@ -339,8 +461,57 @@ pub struct SessionManagerImpl {
 impl SessionManager for SessionManagerImpl {
     type Session = SessionImpl;
 
-    fn connect(&self, database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
-        Ok(SessionImpl::new(self.env.clone(), database.to_string()).into())
+    fn connect(
+        &self,
+        database: &str,
+        user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+        let user_authenticator = {
+            let reader = self.env.user_info_reader().read_guard();
+            let user = reader.get_user_by_name(user_name).ok_or_else(|| {
+                ErrorCode::PermissionDenied(format!("User {} does not exist", user_name))
+            })?;
+            if !user.can_login {
+                return Err(ErrorCode::PermissionDenied(format!(
+                    "User {} is not allowed to login",
+                    user_name
+                ))
+                .into());
+            }
+            user_authenticator(user)?
+        };
+
+        // The secret key prevents other clients from cancelling queries of the session by
+        // guessing its process id.
+        let id = (
+            self.next_process_id.fetch_add(1, Ordering::Relaxed),
+            rand::random::<i32>(),
+        );
+        let session: Arc<SessionImpl> = SessionImpl::new(
+            self.env.clone(),
+            database.to_string(),
+            user_name.to_string(),
+            user_authenticator,
+            id,
+        )
+        .into();
+
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|_, session| session.strong_count() > 0);
+        sessions.insert(id, Arc::downgrade(&session));
+        Ok(session)
+    }
+
+    fn cancel_queries_in_session(&self, session_id: SessionId) {
+        let session = self
+            .sessions
+            .lock()
+            .get(&session_id)
+            .and_then(Weak::upgrade);
+        match session {
+            Some(session) => session.cancel_current_query(),
+            None => tracing::info!("Session {:?} to cancel does not exist", session_id),
+        }
     }
 }
 
@@ -350,6 +521,8 @@ impl SessionManagerImpl {
             FrontendEnv::init(opts).await?;
         Ok(Self {
             env,
+            sessions: Mutex::new(HashMap::new()),
+            next_process_id: AtomicI32::new(1),
             observer_join_handle: join_handle,
             heartbeat_join_handle,
             _heartbeat_shutdown_sender: heartbeat_shutdown_sender,
@@ -365,6 +538,14 @@ impl SessionManagerImpl {
 
 #[async_trait::async_trait]
 impl Session for SessionImpl {
+    fn user_authenticator(&self) -> &UserAuthenticator {
+        &self.user_authenticator
+    }
+
+    fn id(&self) -> SessionId {
+        self.id
+    }
+
     async fn run_statement(
         self: Arc<Self>,
         sql: &str,
@@ -374,8 +555,13 @@ impl Session for SessionImpl {
             tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
             e
         })?;
-        // With pgwire, there would be at most 1 statement in the vec.
-        assert!(stmts.len() <= 1);
+        if stmts.len() > 1 {
+            return Err(ErrorCode::NotImplemented(
+                "multiple statements in a single query".to_string(),
+                None.into(),
+            )
+            .into());
+        }
         if stmts.is_empty() {
             return Ok(PgResponse::new(
                 pgwire::pg_response::StatementType::EMPTY,
@@ -391,17 +577,92 @@ impl Session for SessionImpl {
         })?;
         Ok(rsp)
     }
+
+    fn describe_statement(
+        &self,
+        sql: &str,
+        param_types: &[Option<TypeOid>],
+    ) -> std::result::Result<(Vec<TypeOid>, Vec<PgFieldDescriptor>), BoxedError> {
+        let mut stmts = Parser::parse_sql(sql)?;
+        if stmts.is_empty() {
+            return Ok((
+                param_types
+                    .iter()
+                    .map(|t| t.unwrap_or(TypeOid::Varchar))
+                    .collect(),
+                vec![],
+            ));
+        }
+        check_single_statement(&stmts)?;
+        let stmt = stmts.swap_remove(0);
+        let param_types = param_types
+            .iter()
+            .map(|t| t.map(type_oid_to_data_type))
+            .collect();
+        let (param_types, row_description) =
+            describe_statement(self, stmt, param_types).map_err(|e| {
+                tracing::error!("failed to describe sql:\n{}:\n{}", sql, e);
+                e
+            })?;
+        Ok((
+            param_types.into_iter().map(data_type_to_type_oid).collect(),
+            row_description,
+        ))
+    }
+
+    async fn run_statement_with_params(
+        self: Arc<Self>,
+        sql: &str,
+        param_types: &[TypeOid],
+        params: &[Option<String>],
+    ) -> std::result::Result<PgResponse, BoxedError> {
+        let mut stmts = Parser::parse_sql(sql)?;
+        if stmts.is_empty() {
+            return Ok(PgResponse::new(
+                pgwire::pg_response::StatementType::EMPTY,
+                0,
+                vec![],
+                vec![],
+            ));
+        }
+        check_single_statement(&stmts)?;
+        let stmt = stmts.swap_remove(0);
+        let param_types = param_types
+            .iter()
+            .copied()
+            .map(type_oid_to_data_type)
+            .collect();
+        let rsp = handle_with_params(self, stmt, param_types, params.to_vec())
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
+                e
+            })?;
+        Ok(rsp)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use assert_impl::assert_impl;
+    use risingwave_sqlparser::parser::Parser;
 
-    use crate::session::OptimizerContextRef;
+    use crate::session::{check_single_statement, OptimizerContextRef};
 
     #[test]
     fn check_query_context_ref() {
         assert_impl!(Send: OptimizerContextRef);
         assert_impl!(!Sync: OptimizerContextRef);
     }
+
+    #[test]
+    fn test_check_single_statement() {
+        let stmts = Parser::parse_sql("select 1").unwrap();
+        assert!(check_single_statement(&stmts).is_ok());
+        let stmts = Parser::parse_sql("select 1; select 2").unwrap();
+        let err = check_single_statement(&stmts).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("cannot insert multiple commands into a prepared statement"));
+    }
 }
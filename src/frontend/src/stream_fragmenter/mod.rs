@@ -182,10 +182,15 @@ impl StreamFragmenter {
         match stream_node.get_node_body()? {
             NodeBody::Source(_) => current_fragment.fragment_type = FragmentType::Source,
 
-            NodeBody::Materialize(_) => current_fragment.fragment_type = FragmentType::Sink,
+            NodeBody::Materialize(_) | NodeBody::Sink(_) => {
+                current_fragment.fragment_type = FragmentType::Sink
+            }
 
             // TODO: Force singleton for TopN as a workaround. We should implement two phase TopN.
-            NodeBody::TopN(_) => current_fragment.is_singleton = true,
+            // The group TopN is distributed by its group key instead.
+            NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) if node.group_key.is_empty() => {
+                current_fragment.is_singleton = true
+            }
 
             NodeBody::Chain(ref node) => {
                 // memorize table id for later use
@@ -241,6 +246,18 @@ impl StreamFragmenter {
                     node.table_ids.push(state.gen_table_id());
                 }
             }
+            NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) => {
+                node.table_id = state.gen_table_id();
+            }
+            NodeBody::OverWindow(node) => {
+                node.table_id = state.gen_table_id();
+            }
+            NodeBody::NestedLoopJoin(node) => {
+                node.table_id = state.gen_table_id();
+            }
+            NodeBody::DynamicFilter(node) => {
+                node.table_id = state.gen_table_id();
+            }
             _ => {}
         }
 
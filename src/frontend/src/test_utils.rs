@@ -19,14 +19,20 @@ use std::sync::Arc;
 
 use parking_lot::RwLock;
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
-use risingwave_common::catalog::{TableId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+use pgwire::pg_server::{BoxedError, Session, SessionId, SessionManager, UserAuthenticator};
+use risingwave_common::catalog::{
+    TableId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, DEFAULT_SUPPER_USER,
+    DEFAULT_SUPPER_USER_FOR_PG,
+};
 use risingwave_common::error::Result;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
 use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
 use tempfile::{Builder, NamedTempFile};
@@ -39,6 +45,9 @@ use crate::meta_client::FrontendMetaClient;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
 use crate::session::{FrontendEnv, OptimizerContext, SessionImpl};
+use crate::user::user_manager::UserInfoManager;
+use crate::user::user_service::UserInfoWriter;
+use crate::user::UserId;
 use crate::FrontendOpts;
 
 /// An embedded frontend without starting meta and without starting frontend as a tcp server.
@@ -50,9 +59,16 @@ pub struct LocalFrontend {
 impl SessionManager for LocalFrontend {
     type Session = SessionImpl;
 
-    fn connect(&self, _database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+    fn connect(
+        &self,
+        _database: &str,
+        _user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
         Ok(self.session_ref())
     }
+
+    /// Queries can't be cancelled, as each statement runs in a new session.
+    fn cancel_queries_in_session(&self, _session_id: SessionId) {}
 }
 
 impl LocalFrontend {
@@ -86,10 +102,7 @@ impl LocalFrontend {
             let session = self.session_ref();
 
             let bound = {
-                let mut binder = Binder::new(
-                    session.env().catalog_reader().read_guard(),
-                    session.database().to_string(),
-                );
+                let mut binder = Binder::new(&session);
                 binder.bind(Statement::Query(query.clone()))?
             };
             Planner::new(OptimizerContext::new(session).into())
@@ -105,6 +118,9 @@ impl LocalFrontend {
         Arc::new(SessionImpl::new(
             self.env.clone(),
             DEFAULT_DATABASE_NAME.to_string(),
+            DEFAULT_SUPPER_USER.to_string(),
+            UserAuthenticator::None,
+            (0, 0),
         ))
     }
 }
@@ -165,6 +181,13 @@ impl CatalogWriter for MockCatalogWriter {
         self.create_source_inner(source).map(|_| ())
     }
 
+    async fn create_sink(&self, mut sink: ProstSink, _graph: StreamFragmentGraph) -> Result<()> {
+        sink.id = self.gen_id();
+        self.catalog.write().create_sink(&sink);
+        self.add_table_or_source_id(sink.id, sink.schema_id, sink.database_id);
+        Ok(())
+    }
+
     async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()> {
         let (database_id, schema_id) = self.drop_table_or_source_id(source_id);
         self.drop_table_or_source_id(table_id.table_id);
@@ -185,6 +208,14 @@ impl CatalogWriter for MockCatalogWriter {
         Ok(())
     }
 
+    async fn drop_sink(&self, sink_id: u32) -> Result<()> {
+        let (database_id, schema_id) = self.drop_table_or_source_id(sink_id);
+        self.catalog
+            .write()
+            .drop_sink(database_id, schema_id, sink_id);
+        Ok(())
+    }
+
     async fn drop_database(&self, database_id: u32) -> Result<()> {
         self.catalog.write().drop_database(database_id);
         Ok(())
@@ -276,6 +307,148 @@ impl MockCatalogWriter {
     }
 }
 
+pub struct MockUserInfoWriter {
+    id: AtomicU32,
+    user_info: Arc<RwLock<UserInfoManager>>,
+}
+
+#[async_trait::async_trait]
+impl UserInfoWriter for MockUserInfoWriter {
+    async fn create_user(&self, mut user: UserInfo) -> Result<()> {
+        user.id = self.gen_id();
+        self.user_info.write().create_user(user);
+        Ok(())
+    }
+
+    async fn drop_user(&self, id: UserId) -> Result<()> {
+        self.user_info.write().drop_user(id);
+        Ok(())
+    }
+
+    async fn update_user(
+        &self,
+        update_user: UserInfo,
+        update_fields: Vec<UpdateField>,
+    ) -> Result<()> {
+        let mut user = {
+            let reader = self.user_info.read();
+            let user_name = reader.get_user_name_by_id(update_user.id).unwrap();
+            reader.get_user_by_name(&user_name).unwrap().clone()
+        };
+        for field in update_fields {
+            match field {
+                UpdateField::Unknown => {}
+                UpdateField::Super => user.is_supper = update_user.is_supper,
+                UpdateField::Login => user.can_login = update_user.can_login,
+                UpdateField::CreateDb => user.can_create_db = update_user.can_create_db,
+                UpdateField::AuthInfo => user.auth_info = update_user.auth_info.clone(),
+                UpdateField::Rename => user.name = update_user.name.clone(),
+            }
+        }
+        self.user_info.write().update_user(user);
+        Ok(())
+    }
+
+    async fn grant_privilege(
+        &self,
+        users: Vec<UserId>,
+        privileges: Vec<GrantPrivilege>,
+    ) -> Result<()> {
+        let mut user_info = self.user_info.write();
+        for user_id in users {
+            let Some(user) = user_info.get_user_mut(user_id) else {
+                continue;
+            };
+            for new_privilege in &privileges {
+                match user
+                    .privileges
+                    .iter_mut()
+                    .find(|privilege| privilege.target == new_privilege.target)
+                {
+                    Some(privilege) => {
+                        for new_opt in &new_privilege.privilege_with_opts {
+                            match privilege
+                                .privilege_with_opts
+                                .iter_mut()
+                                .find(|opt| opt.privilege == new_opt.privilege)
+                            {
+                                Some(opt) => opt.with_grant_option |= new_opt.with_grant_option,
+                                None => privilege.privilege_with_opts.push(new_opt.clone()),
+                            }
+                        }
+                    }
+                    None => user.privileges.push(new_privilege.clone()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn revoke_privilege(
+        &self,
+        users: Vec<UserId>,
+        privileges: Vec<GrantPrivilege>,
+        revoke_grant_option: bool,
+    ) -> Result<()> {
+        let mut user_info = self.user_info.write();
+        for user_id in users {
+            let Some(user) = user_info.get_user_mut(user_id) else {
+                continue;
+            };
+            for revoke_privilege in &privileges {
+                for privilege in user
+                    .privileges
+                    .iter_mut()
+                    .filter(|privilege| privilege.target == revoke_privilege.target)
+                {
+                    for revoke_opt in &revoke_privilege.privilege_with_opts {
+                        if revoke_grant_option {
+                            privilege
+                                .privilege_with_opts
+                                .iter_mut()
+                                .filter(|opt| opt.privilege == revoke_opt.privilege)
+                                .for_each(|opt| opt.with_grant_option = false);
+                        } else {
+                            privilege
+                                .privilege_with_opts
+                                .retain(|opt| opt.privilege != revoke_opt.privilege);
+                        }
+                    }
+                }
+            }
+            user.privileges
+                .retain(|privilege| !privilege.privilege_with_opts.is_empty());
+        }
+        Ok(())
+    }
+}
+
+impl MockUserInfoWriter {
+    pub fn new(user_info: Arc<RwLock<UserInfoManager>>) -> Self {
+        for (id, name) in [DEFAULT_SUPPER_USER, DEFAULT_SUPPER_USER_FOR_PG]
+            .into_iter()
+            .enumerate()
+        {
+            user_info.write().create_user(UserInfo {
+                id: id as u32,
+                name: name.to_string(),
+                is_supper: true,
+                can_create_db: true,
+                can_login: true,
+                ..Default::default()
+            });
+        }
+        Self {
+            user_info,
+            id: AtomicU32::new(2),
+        }
+    }
+
+    fn gen_id(&self) -> u32 {
+        self.id.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
 pub struct MockFrontendMetaClient {}
 
 #[async_trait::async_trait]
@@ -0,0 +1,111 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_server::UserAuthenticator;
+use rand::Rng;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::auth_info::EncryptionType;
+use risingwave_pb::user::{AuthInfo, UserInfo};
+
+const MD5_ENCRYPTED_PREFIX: &str = "md5";
+const MD5_HEX_LEN: usize = 32;
+
+/// Builds the `AuthInfo` stored in the catalog for `password` of user `user_name`. Same as
+/// PostgreSQL, the password is stored as the hex of `md5(password + user_name)`, and a password
+/// that is already in the form of `"md5" + 32 hex digits` is regarded as encrypted. An empty
+/// password means no password.
+pub fn encrypted_password(user_name: &str, password: &str) -> Option<AuthInfo> {
+    if password.is_empty() {
+        return None;
+    }
+    let encrypted_value = if valid_md5_password(password) {
+        password[MD5_ENCRYPTED_PREFIX.len()..].as_bytes().to_vec()
+    } else {
+        let digest = md5::compute(format!("{}{}", password, user_name));
+        format!("{:x}", digest).into_bytes()
+    };
+    Some(AuthInfo {
+        encryption_type: EncryptionType::Md5 as i32,
+        encrypted_value,
+    })
+}
+
+fn valid_md5_password(password: &str) -> bool {
+    password.len() == MD5_ENCRYPTED_PREFIX.len() + MD5_HEX_LEN
+        && password.starts_with(MD5_ENCRYPTED_PREFIX)
+        && password[MD5_ENCRYPTED_PREFIX.len()..]
+            .chars()
+            .all(|c| c.is_ascii_hexdigit())
+}
+
+/// Builds the authenticator used by pgwire to check the password of `user` on connection.
+pub fn user_authenticator(user: &UserInfo) -> Result<UserAuthenticator> {
+    let Some(auth_info) = &user.auth_info else {
+        return Ok(UserAuthenticator::None);
+    };
+    match auth_info.encryption_type() {
+        EncryptionType::Plaintext => Ok(UserAuthenticator::ClearText(
+            auth_info.encrypted_value.clone(),
+        )),
+        EncryptionType::Md5 => Ok(UserAuthenticator::Md5WithSalt {
+            encrypted_password: auth_info.encrypted_value.clone(),
+            salt: rand::thread_rng().gen(),
+        }),
+        encryption_type => Err(ErrorCode::NotImplemented(
+            format!("password encryption type {:?}", encryption_type),
+            None.into(),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_password() {
+        let auth_info = encrypted_password("user", "password").unwrap();
+        assert_eq!(auth_info.encryption_type(), EncryptionType::Md5);
+        assert_eq!(
+            auth_info.encrypted_value,
+            format!("{:x}", md5::compute("passworduser")).into_bytes()
+        );
+
+        let encrypted = format!("md5{:x}", md5::compute("passworduser"));
+        assert_eq!(
+            encrypted_password("another_user", &encrypted).unwrap(),
+            auth_info
+        );
+        assert!(encrypted_password("user", "").is_none());
+    }
+
+    #[test]
+    fn test_md5_authenticator() {
+        let user = UserInfo {
+            name: "user".to_string(),
+            auth_info: encrypted_password("user", "password"),
+            ..Default::default()
+        };
+        let authenticator = user_authenticator(&user).unwrap();
+        let UserAuthenticator::Md5WithSalt { encrypted_password, salt } = &authenticator else {
+            panic!("expect md5 authenticator");
+        };
+        let mut salted = encrypted_password.clone();
+        salted.extend_from_slice(salt);
+        let response = format!("md5{:x}", md5::compute(salted));
+        assert!(authenticator.authenticate(response.as_bytes()).is_ok());
+        assert!(authenticator.authenticate(b"md5wrong").is_err());
+    }
+}
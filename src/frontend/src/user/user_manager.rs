@@ -0,0 +1,73 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use risingwave_pb::user::UserInfo;
+
+use crate::user::UserId;
+
+/// `UserInfoManager` is responsible for managing users.
+#[derive(Default)]
+pub struct UserInfoManager {
+    user_by_name: HashMap<String, UserInfo>,
+    user_name_by_id: HashMap<UserId, String>,
+}
+
+impl UserInfoManager {
+    pub fn get_user_mut(&mut self, id: UserId) -> Option<&mut UserInfo> {
+        let name = self.user_name_by_id.get(&id)?;
+        self.user_by_name.get_mut(name)
+    }
+
+    pub fn get_all_users(&self) -> Vec<UserInfo> {
+        self.user_by_name.values().cloned().collect()
+    }
+
+    pub fn get_user_by_name(&self, user_name: &str) -> Option<&UserInfo> {
+        self.user_by_name.get(user_name)
+    }
+
+    pub fn get_user_name_by_id(&self, id: UserId) -> Option<String> {
+        self.user_name_by_id.get(&id).cloned()
+    }
+
+    pub fn create_user(&mut self, user_info: UserInfo) {
+        let id = user_info.id;
+        let name = user_info.name.clone();
+        self.user_by_name
+            .try_insert(name.clone(), user_info)
+            .unwrap();
+        self.user_name_by_id.try_insert(id, name).unwrap();
+    }
+
+    pub fn drop_user(&mut self, id: UserId) {
+        let name = self.user_name_by_id.remove(&id).unwrap();
+        self.user_by_name.remove(&name).unwrap();
+    }
+
+    /// Replaces the user with the same id, the user may be renamed.
+    pub fn update_user(&mut self, user_info: UserInfo) {
+        let id = user_info.id;
+        let name = user_info.name.clone();
+        let old_name = self.user_name_by_id.insert(id, name.clone()).unwrap();
+        self.user_by_name.remove(&old_name).unwrap();
+        self.user_by_name.insert(name, user_info);
+    }
+
+    pub fn clear(&mut self) {
+        self.user_by_name.clear();
+        self.user_name_by_id.clear();
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::ErrorCode::PermissionDenied;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::grant_privilege::{
+    GrantAllTables, GrantDatabase, GrantSchema, GrantTable, Privilege, PrivilegeWithGrantOption,
+    Target,
+};
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
+use risingwave_sqlparser::ast::Action;
+
+use crate::catalog::{DatabaseId, SchemaId};
+
+/// The privileges that can be granted on each kind of objects, which is also what `ALL` expands
+/// to.
+pub const AVAILABLE_DATABASE_PRIVILEGES: &[Privilege] = &[Privilege::Connect, Privilege::Create];
+pub const AVAILABLE_SCHEMA_PRIVILEGES: &[Privilege] = &[Privilege::Create];
+pub const AVAILABLE_TABLE_PRIVILEGES: &[Privilege] = &[
+    Privilege::Select,
+    Privilege::Insert,
+    Privilege::Update,
+    Privilege::Delete,
+];
+
+/// An object on which the privileges of a user are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivilegeObject {
+    Database {
+        database_id: DatabaseId,
+    },
+    Schema {
+        database_id: DatabaseId,
+        schema_id: SchemaId,
+    },
+    /// A table, materialized view or source.
+    Table {
+        database_id: DatabaseId,
+        schema_id: SchemaId,
+        table_id: u32,
+    },
+}
+
+impl PrivilegeObject {
+    fn kind(&self) -> &'static str {
+        match self {
+            PrivilegeObject::Database { .. } => "database",
+            PrivilegeObject::Schema { .. } => "schema",
+            PrivilegeObject::Table { .. } => "table",
+        }
+    }
+
+    /// Whether the privileges granted on `target` apply to this object.
+    fn covered_by(&self, target: &Target) -> bool {
+        match (self, target) {
+            (
+                PrivilegeObject::Database { database_id },
+                Target::GrantDatabase(GrantDatabase {
+                    database_id: target_database_id,
+                }),
+            ) => database_id == target_database_id,
+            (
+                PrivilegeObject::Schema { schema_id, .. },
+                Target::GrantSchema(GrantSchema {
+                    schema_id: target_schema_id,
+                    ..
+                }),
+            ) => schema_id == target_schema_id,
+            (
+                PrivilegeObject::Table { table_id, .. },
+                Target::GrantTable(GrantTable {
+                    table_id: target_table_id,
+                    ..
+                }),
+            ) => table_id == target_table_id,
+            (
+                PrivilegeObject::Table { schema_id, .. },
+                Target::GrantAllTables(GrantAllTables {
+                    schema_id: target_schema_id,
+                    ..
+                }),
+            ) => schema_id == target_schema_id,
+            _ => false,
+        }
+    }
+
+    /// The privileges that can be granted on this object.
+    pub fn available_privileges(&self) -> &'static [Privilege] {
+        match self {
+            PrivilegeObject::Database { .. } => AVAILABLE_DATABASE_PRIVILEGES,
+            PrivilegeObject::Schema { .. } => AVAILABLE_SCHEMA_PRIVILEGES,
+            PrivilegeObject::Table { .. } => AVAILABLE_TABLE_PRIVILEGES,
+        }
+    }
+}
+
+/// Returns the `PrivilegeWithGrantOption` of `user` on `object` for `privilege`, if any.
+fn find_privilege<'a>(
+    user: &'a UserInfo,
+    object: &PrivilegeObject,
+    privilege: Privilege,
+) -> impl Iterator<Item = &'a PrivilegeWithGrantOption> + 'a {
+    let object = *object;
+    user.privileges
+        .iter()
+        .filter(move |grant| {
+            grant
+                .target
+                .as_ref()
+                .map_or(false, |target| object.covered_by(target))
+        })
+        .flat_map(|grant| grant.privilege_with_opts.iter())
+        .filter(move |opt| opt.privilege == privilege as i32)
+}
+
+/// Checks that `user` has `privilege` on `object` named `object_name`. Super users have all the
+/// privileges.
+pub fn check_privilege(
+    user: &UserInfo,
+    object: &PrivilegeObject,
+    privilege: Privilege,
+    object_name: &str,
+) -> Result<()> {
+    if user.is_supper || find_privilege(user, object, privilege).next().is_some() {
+        Ok(())
+    } else {
+        Err(PermissionDenied(format!(
+            "permission denied for {} {}",
+            object.kind(),
+            object_name
+        ))
+        .into())
+    }
+}
+
+/// Checks that `user` can grant `privilege` on `object` to others, i.e. `user` is a super user or
+/// has the privilege with grant option.
+pub fn check_grant_option(
+    user: &UserInfo,
+    object: &PrivilegeObject,
+    privilege: Privilege,
+    object_name: &str,
+) -> Result<()> {
+    if user.is_supper || find_privilege(user, object, privilege).any(|opt| opt.with_grant_option) {
+        Ok(())
+    } else {
+        Err(PermissionDenied(format!(
+            "no grant option of {:?} for {} {}",
+            privilege,
+            object.kind(),
+            object_name
+        ))
+        .into())
+    }
+}
+
+/// Converts an action in `GRANT/REVOKE` statement to the privilege in catalog.
+pub fn action_to_privilege(action: &Action) -> Result<Privilege> {
+    match action {
+        Action::Connect => Ok(Privilege::Connect),
+        Action::Create => Ok(Privilege::Create),
+        Action::Delete => Ok(Privilege::Delete),
+        Action::Insert { columns: None } => Ok(Privilege::Insert),
+        Action::Select { columns: None } => Ok(Privilege::Select),
+        Action::Update { columns: None } => Ok(Privilege::Update),
+        _ => Err(ErrorCode::NotImplemented(format!("privilege {}", action), None.into()).into()),
+    }
+}
+
+/// Builds the `GrantPrivilege` of `privileges` on `target`.
+pub fn make_grant_privilege(
+    target: Target,
+    privileges: &[Privilege],
+    with_grant_option: bool,
+) -> GrantPrivilege {
+    GrantPrivilege {
+        target: Some(target),
+        privilege_with_opts: privileges
+            .iter()
+            .map(|privilege| PrivilegeWithGrantOption {
+                privilege: *privilege as i32,
+                with_grant_option,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_privilege() {
+        let table = PrivilegeObject::Table {
+            database_id: 1,
+            schema_id: 2,
+            table_id: 3,
+        };
+        let mut user = UserInfo {
+            name: "user".to_string(),
+            ..Default::default()
+        };
+        assert!(check_privilege(&user, &table, Privilege::Select, "t").is_err());
+
+        user.privileges.push(make_grant_privilege(
+            Target::GrantTable(GrantTable {
+                database_id: 1,
+                schema_id: 2,
+                table_id: 3,
+            }),
+            &[Privilege::Select],
+            false,
+        ));
+        assert!(check_privilege(&user, &table, Privilege::Select, "t").is_ok());
+        assert!(check_privilege(&user, &table, Privilege::Insert, "t").is_err());
+        assert!(check_grant_option(&user, &table, Privilege::Select, "t").is_err());
+
+        user.privileges.push(make_grant_privilege(
+            Target::GrantAllTables(GrantAllTables {
+                database_id: 1,
+                schema_id: 2,
+            }),
+            &[Privilege::Insert],
+            true,
+        ));
+        assert!(check_privilege(&user, &table, Privilege::Insert, "t").is_ok());
+        assert!(check_grant_option(&user, &table, Privilege::Insert, "t").is_ok());
+
+        let schema = PrivilegeObject::Schema {
+            database_id: 1,
+            schema_id: 2,
+        };
+        assert!(check_privilege(&user, &schema, Privilege::Create, "s").is_err());
+
+        user.is_supper = true;
+        assert!(check_privilege(&user, &schema, Privilege::Create, "s").is_ok());
+    }
+}
@@ -348,6 +348,9 @@ where
     ) -> Result<Vec<InjectBarrierResponse>> {
         let mutation = command_context.to_mutation().await?;
         let info = command_context.info;
+        // Sinks delivering exactly once rely on this to make the changes of committed epochs
+        // visible.
+        let committed_epoch = self.hummock_manager.get_max_committed_epoch().await;
 
         let collect_futures = info.node_map.iter().filter_map(|(node_id, node)| {
             let actor_ids_to_send = info.actor_ids_to_send(node_id).collect_vec();
@@ -368,6 +371,7 @@ where
                     mutation: Some(mutation),
                     // TODO(chi): add distributed tracing
                     span: vec![],
+                    committed_epoch,
                 };
 
                 async move {
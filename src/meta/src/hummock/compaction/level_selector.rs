@@ -298,6 +298,7 @@ pub mod tests {
             level0_max_file_number: 0,
             level0_trigger_number: 2,
             compaction_mode: RangeMode,
+            compression_algorithm: vec![],
         };
         let selector =
             DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
@@ -375,6 +376,7 @@ pub mod tests {
             level0_max_file_number: 0,
             level0_trigger_number: 2,
             compaction_mode: RangeMode,
+            compression_algorithm: vec![],
         };
         let selector =
             DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
@@ -17,18 +17,18 @@ mod level_selector;
 mod overlap_strategy;
 mod tier_compaction_picker;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::io::Cursor;
 use std::sync::Arc;
 
 use itertools::Itertools;
 use prost::Message;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::hummock::{
-    CompactMetrics, CompactTask, HummockVersion, Level, TableSetStatistics,
+    CompactMetrics, CompactTask, CompressionAlgorithm, HummockVersion, Level, TableSetStatistics,
 };
 
 use crate::hummock::compaction::level_selector::{DynamicLevelSelector, LevelSelector};
@@ -58,6 +58,7 @@ pub struct CompactStatus {
     pub(crate) level_handlers: Vec<LevelHandler>,
     pub(crate) next_compact_task_id: u64,
     compaction_selector: Box<dyn LevelSelector>,
+    compaction_config: Arc<CompactionConfig>,
 }
 
 impl Debug for CompactStatus {
@@ -82,8 +83,8 @@ impl Clone for CompactStatus {
     fn clone(&self) -> Self {
         Self {
             level_handlers: self.level_handlers.clone(),
-            compaction_selector: Box::new(DynamicLevelSelector::default()),
             next_compact_task_id: self.next_compact_task_id,
+            ..Self::new(self.compaction_config.clone())
         }
     }
 }
@@ -109,6 +110,10 @@ pub struct CompactionConfig {
     pub level0_max_file_number: usize,
     pub level0_trigger_number: usize,
     pub compaction_mode: CompactionMode,
+    /// Compression algorithm of the SSTs written to each level, indexed by the target level of
+    /// compaction. Levels beyond the end use the last one, and SSTs are not compressed if it's
+    /// empty.
+    pub compression_algorithm: Vec<CompressionAlgorithm>,
 }
 
 impl Default for CompactionConfig {
@@ -121,10 +126,39 @@ impl Default for CompactionConfig {
             level0_max_file_number: DEFAULT_LEVEL0_MAX_FILE_NUMBER,
             level0_trigger_number: DEFAULT_LEVEL0_TRIGGER_NUMBER,
             compaction_mode: ConsistentHashMode,
+            compression_algorithm: vec![],
         }
     }
 }
 
+/// Parses the compression algorithm of each level, given by `compression_algorithm_per_level`
+/// in the storage config.
+pub fn parse_compression_algorithm(names: &[String]) -> Result<Vec<CompressionAlgorithm>> {
+    names
+        .iter()
+        .map(|name| match name.to_ascii_lowercase().as_str() {
+            "none" => Ok(CompressionAlgorithm::None),
+            "lz4" => Ok(CompressionAlgorithm::Lz4),
+            "zstd" => Ok(CompressionAlgorithm::Zstd),
+            _ => Err(ErrorCode::InvalidConfigValue {
+                config_entry: "compression_algorithm_per_level".to_string(),
+                config_value: name.clone(),
+            }
+            .into()),
+        })
+        .collect()
+}
+
+impl CompactionConfig {
+    pub fn compression_algorithm_of_level(&self, level: usize) -> CompressionAlgorithm {
+        self.compression_algorithm
+            .get(level)
+            .or_else(|| self.compression_algorithm.last())
+            .copied()
+            .unwrap_or(CompressionAlgorithm::None)
+    }
+}
+
 impl CompactStatus {
     pub fn new(config: Arc<CompactionConfig>) -> CompactStatus {
         let mut level_handlers = vec![];
@@ -139,7 +173,11 @@ impl CompactStatus {
             level_handlers,
             next_compact_task_id: 1,
             // TODO: create selector and overlap strategy by configure.
-            compaction_selector: Box::new(DynamicLevelSelector::new(config, overlap_strategy)),
+            compaction_selector: Box::new(DynamicLevelSelector::new(
+                config.clone(),
+                overlap_strategy,
+            )),
+            compaction_config: config,
         }
     }
 
@@ -151,12 +189,16 @@ impl CompactStatus {
         HUMMOCK_COMPACT_STATUS_KEY
     }
 
-    pub async fn get<S: MetaStore>(meta_store: &S) -> Result<Option<CompactStatus>> {
+    /// Restores the status persisted in the meta store, with the `config` of the manager.
+    pub async fn get<S: MetaStore>(
+        meta_store: &S,
+        config: Arc<CompactionConfig>,
+    ) -> Result<Option<CompactStatus>> {
         match meta_store
             .get_cf(CompactStatus::cf_name(), CompactStatus::key().as_bytes())
             .await
             .map(|v| risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(v)).unwrap())
-            .map(|s| (&s).into())
+            .map(|s| CompactStatus::from_protobuf(&s, config))
         {
             Ok(compact_status) => Ok(Some(compact_status)),
             Err(err) => {
@@ -216,6 +258,13 @@ impl CompactStatus {
             // TODO: fill with compaction group info
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            deleted_table_ids: vec![],
+            table_ttls: HashMap::default(),
+            compression_algorithm: self
+                .compaction_config
+                .compression_algorithm_of_level(target_level_id as usize)
+                as i32,
+            table_prefix_bloom_lens: HashMap::default(),
         };
         self.next_compact_task_id += 1;
         Some(compact_task)
@@ -330,12 +379,16 @@ impl From<&CompactStatus> for risingwave_pb::hummock::CompactStatus {
     }
 }
 
-impl From<&risingwave_pb::hummock::CompactStatus> for CompactStatus {
-    fn from(status: &risingwave_pb::hummock::CompactStatus) -> Self {
+impl CompactStatus {
+    /// The compaction config is not persisted, so it's given by the manager instead.
+    pub fn from_protobuf(
+        status: &risingwave_pb::hummock::CompactStatus,
+        config: Arc<CompactionConfig>,
+    ) -> Self {
         CompactStatus {
             level_handlers: status.level_handlers.iter().map_into().collect(),
             next_compact_task_id: status.next_compact_task_id,
-            compaction_selector: Box::new(DynamicLevelSelector::default()),
+            ..Self::new(config)
         }
     }
 }
@@ -346,15 +399,70 @@ mod tests {
 
     #[tokio::test]
     async fn test_serde() -> Result<()> {
+        let config = Arc::new(CompactionConfig {
+            compression_algorithm: vec![CompressionAlgorithm::Lz4],
+            ..Default::default()
+        });
         let origin = CompactStatus {
             next_compact_task_id: 4,
-            ..Default::default()
+            ..CompactStatus::new(config.clone())
         };
         let ser = risingwave_pb::hummock::CompactStatus::from(&origin).encode_to_vec();
         let de = risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(ser));
-        let de = (&de.unwrap()).into();
+        let de = CompactStatus::from_protobuf(&de.unwrap(), config.clone());
         assert_eq!(origin, de);
+        assert_eq!(de.compaction_config, config);
+        assert_eq!(origin.clone().compaction_config, config);
 
         Ok(())
     }
+
+    #[test]
+    fn test_compression_algorithm_of_level() {
+        let config = CompactionConfig::default();
+        assert_eq!(
+            config.compression_algorithm_of_level(0),
+            CompressionAlgorithm::None
+        );
+        assert_eq!(
+            config.compression_algorithm_of_level(MAX_LEVEL),
+            CompressionAlgorithm::None
+        );
+
+        let config = CompactionConfig {
+            compression_algorithm: vec![
+                CompressionAlgorithm::None,
+                CompressionAlgorithm::Lz4,
+                CompressionAlgorithm::Zstd,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.compression_algorithm_of_level(0),
+            CompressionAlgorithm::None
+        );
+        assert_eq!(
+            config.compression_algorithm_of_level(1),
+            CompressionAlgorithm::Lz4
+        );
+        assert_eq!(
+            config.compression_algorithm_of_level(MAX_LEVEL),
+            CompressionAlgorithm::Zstd
+        );
+    }
+
+    #[test]
+    fn test_parse_compression_algorithm() {
+        let names = ["none", "LZ4", "zstd"].map(String::from);
+        assert_eq!(
+            parse_compression_algorithm(&names).unwrap(),
+            vec![
+                CompressionAlgorithm::None,
+                CompressionAlgorithm::Lz4,
+                CompressionAlgorithm::Zstd
+            ]
+        );
+        assert!(parse_compression_algorithm(&[]).unwrap().is_empty());
+        assert!(parse_compression_algorithm(&["snappy".to_string()]).is_err());
+    }
 }
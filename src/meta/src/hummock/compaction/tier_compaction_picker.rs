@@ -343,6 +343,7 @@ pub mod tests {
             }),
             file_size: 1,
             vnode_bitmaps: vec![],
+            has_range_tombstones: false,
         }
     }
 
@@ -131,7 +131,11 @@ impl CompactorManager {
 
 #[cfg(test)]
 mod tests {
-    use risingwave_pb::hummock::{CompactMetrics, CompactTask, TableSetStatistics};
+    use std::collections::HashMap;
+
+    use risingwave_pb::hummock::{
+        CompactMetrics, CompactTask, CompressionAlgorithm, TableSetStatistics,
+    };
     use tokio::sync::mpsc::error::TryRecvError;
 
     use crate::hummock::test_utils::{generate_test_tables, setup_compute_env};
@@ -173,6 +177,10 @@ mod tests {
             task_status: false,
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            deleted_table_ids: vec![],
+            table_ttls: HashMap::default(),
+            compression_algorithm: CompressionAlgorithm::None as i32,
+            table_prefix_bloom_lens: HashMap::default(),
         }
     }
 
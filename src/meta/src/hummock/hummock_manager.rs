@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::future::Future;
 use std::ops::DerefMut;
 use std::sync::Arc;
@@ -28,11 +28,12 @@ use risingwave_hummock_sdk::{
     get_remote_sst_id, HummockContextId, HummockEpoch, HummockRefCount, HummockSSTableId,
     HummockVersionId,
 };
+use risingwave_pb::catalog::Table;
 use risingwave_pb::common::ParallelUnitMapping;
 use risingwave_pb::hummock::{
-    CompactTask, CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion,
-    HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstableIdInfo,
-    SstableInfo, UncommittedEpoch,
+    CompactTask, CompactTaskAssignment, HummockDeletedTable, HummockPinnedSnapshot,
+    HummockPinnedVersion, HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType,
+    SstableIdInfo, SstableInfo, UncommittedEpoch,
 };
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use tokio::sync::RwLock;
@@ -47,7 +48,7 @@ use crate::hummock::model::{
     INVALID_TIMESTAMP,
 };
 use crate::manager::{IdCategory, MetaSrvEnv};
-use crate::model::{MetadataModel, ValTransaction, VarTransaction, Worker};
+use crate::model::{MetadataModel, TableFragments, ValTransaction, VarTransaction, Worker};
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::{MetaStore, Transaction};
 
@@ -120,6 +121,14 @@ struct Versioning {
     pinned_snapshots: BTreeMap<HummockContextId, HummockPinnedSnapshot>,
     stale_sstables: BTreeMap<HummockVersionId, HummockStaleSstables>,
     sstable_id_infos: BTreeMap<HummockSSTableId, SstableIdInfo>,
+    /// The dropped tables, whose keys are removed by compactions.
+    deleted_tables: BTreeMap<u32, HummockDeletedTable>,
+    /// `table_id` -> TTL of the state tables with TTL, which is not persisted but cached from the
+    /// catalog.
+    table_ttls: HashMap<u32, u32>,
+    /// `table_id` -> Length of the key prefixes to build prefix bloom filters with, which is not
+    /// persisted but cached from the table fragments.
+    table_prefix_bloom_lens: HashMap<u32, u32>,
 }
 
 impl Versioning {
@@ -132,6 +141,19 @@ impl Versioning {
     pub fn current_version(&self) -> HummockVersion {
         self.current_version_ref().clone()
     }
+
+    fn cache_state_tables(
+        &mut self,
+        state_table_ids: impl IntoIterator<Item = u32>,
+        ttl: u32,
+        prefix_bloom_lens: HashMap<u32, u32>,
+    ) {
+        if ttl > 0 {
+            self.table_ttls
+                .extend(state_table_ids.into_iter().map(|table_id| (table_id, ttl)));
+        }
+        self.table_prefix_bloom_lens.extend(prefix_bloom_lens);
+    }
 }
 
 impl<S> HummockManager<S>
@@ -143,6 +165,11 @@ where
         cluster_manager: ClusterManagerRef<S>,
         metrics: Arc<MetaMetrics>,
     ) -> Result<HummockManager<S>> {
+        // TODO: load the rest of the config from etcd or configuration file.
+        let config = Arc::new(CompactionConfig {
+            compression_algorithm: env.opts.compression_algorithm.clone(),
+            ..Default::default()
+        });
         let instance = HummockManager {
             env,
             versioning: RwLock::new(Versioning {
@@ -152,6 +179,9 @@ where
                 pinned_snapshots: Default::default(),
                 stale_sstables: Default::default(),
                 sstable_id_infos: Default::default(),
+                deleted_tables: Default::default(),
+                table_ttls: Default::default(),
+                table_prefix_bloom_lens: Default::default(),
             }),
             compaction: RwLock::new(Compaction {
                 compact_status: CompactStatus::default(),
@@ -160,8 +190,7 @@ where
             metrics,
             cluster_manager,
             compaction_scheduler: parking_lot::RwLock::new(None),
-            // TODO: load it from etcd or configuration file.
-            config: Arc::new(CompactionConfig::default()),
+            config,
         };
 
         instance.load_meta_store_state().await?;
@@ -178,7 +207,7 @@ where
         let config = self.config.clone();
         let mut compaction_guard = self.compaction.write().await;
 
-        compaction_guard.compact_status = CompactStatus::get(self.env.meta_store())
+        compaction_guard.compact_status = CompactStatus::get(self.env.meta_store(), config.clone())
             .await?
             .unwrap_or_else(|| CompactStatus::new(config));
 
@@ -249,6 +278,35 @@ where
             .map(|s| (s.id, s))
             .collect();
 
+        versioning_guard.deleted_tables = HummockDeletedTable::list(self.env.meta_store())
+            .await?
+            .into_iter()
+            .map(|t| (t.table_id, t))
+            .collect();
+
+        // The TTL of a materialized view applies to all its state tables.
+        let ttls: HashMap<u32, u32> = Table::list(self.env.meta_store())
+            .await?
+            .into_iter()
+            .map(|table| (table.id, table.ttl))
+            .collect();
+        versioning_guard.table_ttls.clear();
+        versioning_guard.table_prefix_bloom_lens.clear();
+        for table_fragments in TableFragments::list(self.env.meta_store()).await? {
+            let ttl = ttls
+                .get(&table_fragments.table_id().table_id)
+                .copied()
+                .unwrap_or_default();
+            versioning_guard.cache_state_tables(
+                table_fragments
+                    .state_table_ids()
+                    .iter()
+                    .map(|table_id| table_id.table_id),
+                ttl,
+                table_fragments.state_table_prefix_bloom_lens(),
+            );
+        }
+
         Ok(())
     }
 
@@ -589,6 +647,23 @@ where
                             .collect_vec()
                     })
                     .collect::<HashSet<u32>>();
+                {
+                    let versioning_guard = self.versioning.read().await;
+                    compact_task.deleted_table_ids =
+                        versioning_guard.deleted_tables.keys().copied().collect();
+                    compact_task.table_ttls = versioning_guard
+                        .table_ttls
+                        .iter()
+                        .filter(|(table_id, _)| table_ids.contains(table_id))
+                        .map(|(table_id, ttl)| (*table_id, *ttl))
+                        .collect();
+                    compact_task.table_prefix_bloom_lens = versioning_guard
+                        .table_prefix_bloom_lens
+                        .iter()
+                        .filter(|(table_id, _)| table_ids.contains(table_id))
+                        .map(|(table_id, len)| (*table_id, *len))
+                        .collect();
+                }
                 compact_task.vnode_mappings.reserve_exact(table_ids.len());
                 for table_id in table_ids {
                     if let Some(vnode_mapping) = self
@@ -629,6 +704,52 @@ where
         ret
     }
 
+    /// Marks the tables as dropped, so that their keys will be removed by the following
+    /// compactions.
+    pub async fn mark_tables_deleted(
+        &self,
+        table_ids: impl IntoIterator<Item = u32>,
+    ) -> Result<()> {
+        let table_ids = table_ids.into_iter().collect_vec();
+        let mut versioning_guard = self.versioning.write().await;
+        let versioning = versioning_guard.deref_mut();
+        let mut deleted_tables = VarTransaction::new(&mut versioning.deleted_tables);
+        for &table_id in &table_ids {
+            deleted_tables.insert(table_id, HummockDeletedTable { table_id });
+        }
+        commit_multi_var!(self, None, deleted_tables)?;
+        for table_id in &table_ids {
+            versioning.table_ttls.remove(table_id);
+            versioning.table_prefix_bloom_lens.remove(table_id);
+        }
+        Ok(())
+    }
+
+    /// Caches the TTL and the prefix bloom filter lengths of the state tables of a created
+    /// materialized view, which are passed to the following compactions.
+    pub async fn register_state_tables(
+        &self,
+        state_table_ids: impl IntoIterator<Item = u32>,
+        ttl: u32,
+        prefix_bloom_lens: HashMap<u32, u32>,
+    ) {
+        self.versioning
+            .write()
+            .await
+            .cache_state_tables(state_table_ids, ttl, prefix_bloom_lens);
+    }
+
+    /// Returns the ids of the dropped tables whose keys are still to be removed.
+    pub async fn list_deleted_table_ids(&self) -> Vec<u32> {
+        self.versioning
+            .read()
+            .await
+            .deleted_tables
+            .keys()
+            .copied()
+            .collect()
+    }
+
     /// Assigns a compaction task to a compactor
     pub async fn assign_compaction_task<T: Future<Output = bool>>(
         &self,
@@ -710,6 +831,26 @@ where
             let mut new_version = CompactStatus::apply_compact_result(compact_task, old_version);
             current_version_id.increase();
             new_version.id = current_version_id.id();
+
+            // Forget the dropped tables once no SST contains their keys anymore.
+            let mut deleted_tables = VarTransaction::new(&mut versioning.deleted_tables);
+            if !deleted_tables.is_empty() {
+                let remaining_table_ids = new_version
+                    .levels
+                    .iter()
+                    .flat_map(|level| level.table_infos.iter())
+                    .chain(
+                        new_version
+                            .uncommitted_epochs
+                            .iter()
+                            .flat_map(|uncommitted_epoch| uncommitted_epoch.tables.iter()),
+                    )
+                    .flat_map(|sst_info| {
+                        sst_info.vnode_bitmaps.iter().map(|bitmap| bitmap.table_id)
+                    })
+                    .collect::<HashSet<u32>>();
+                deleted_tables.retain(|table_id, _| remaining_table_ids.contains(table_id));
+            }
             hummock_versions.insert(new_version.id, new_version);
 
             for SstableInfo { id: ref sst_id, .. } in &compact_task.sorted_output_ssts {
@@ -734,7 +875,8 @@ where
                 current_version_id,
                 hummock_versions,
                 version_stale_sstables,
-                sstable_id_infos
+                sstable_id_infos,
+                deleted_tables
             )?;
         } else {
             // The compaction task is cancelled.
@@ -1124,6 +1266,7 @@ where
             let pinned_snapshots_copy = versioning_guard.pinned_snapshots.clone();
             let stale_sstables_copy = versioning_guard.stale_sstables.clone();
             let sst_id_infos_copy = versioning_guard.sstable_id_infos.clone();
+            let deleted_tables_copy = versioning_guard.deleted_tables.clone();
             (
                 compact_status_copy,
                 compact_task_assignment_copy,
@@ -1133,6 +1276,7 @@ where
                 pinned_snapshots_copy,
                 stale_sstables_copy,
                 sst_id_infos_copy,
+                deleted_tables_copy,
             )
         };
         let mem_state = get_state().await;
@@ -1289,6 +1433,15 @@ where
         self.versioning.read().await.current_version()
     }
 
+    /// Gets the max committed epoch of current version.
+    pub async fn get_max_committed_epoch(&self) -> HummockEpoch {
+        self.versioning
+            .read()
+            .await
+            .current_version_ref()
+            .max_committed_epoch
+    }
+
     pub fn set_compaction_scheduler(&self, sender: CompactionRequestChannelRef) {
         *self.compaction_scheduler.write() = Some(sender);
     }
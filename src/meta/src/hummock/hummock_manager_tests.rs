@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::time::Duration;
 
 use itertools::Itertools;
@@ -1029,3 +1030,74 @@ async fn test_mark_orphan_ssts() {
         .unwrap_err();
     assert!(matches!(error, Error::InternalError(_)));
 }
+
+#[tokio::test]
+async fn test_prune_deleted_tables() {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let epoch: u64 = 1;
+    // The SST contains the keys of table 1 and 2.
+    let ssts = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+    hummock_manager
+        .add_tables(context_id, ssts, epoch)
+        .await
+        .unwrap();
+    hummock_manager.commit_epoch(epoch).await.unwrap();
+
+    hummock_manager
+        .mark_tables_deleted([1, 2, 100])
+        .await
+        .unwrap();
+    // The deleted tables are persisted.
+    hummock_manager.check_state_consistency().await;
+    assert_eq!(
+        hummock_manager.list_deleted_table_ids().await,
+        vec![1, 2, 100]
+    );
+
+    let mut compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    assert_eq!(compact_task.deleted_table_ids, vec![1, 2, 100]);
+    hummock_manager
+        .assign_compaction_task(&compact_task, context_id, async { true })
+        .await
+        .unwrap();
+    // Only the keys of table 1 are left after the compaction.
+    let mut output_ssts = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+    output_ssts[0]
+        .vnode_bitmaps
+        .retain(|bitmap| bitmap.table_id == 1);
+    compact_task.sorted_output_ssts = output_ssts;
+    compact_task.task_status = true;
+    hummock_manager
+        .report_compact_task(&compact_task)
+        .await
+        .unwrap();
+
+    assert_eq!(hummock_manager.list_deleted_table_ids().await, vec![1]);
+}
+
+#[tokio::test]
+async fn test_register_state_tables() {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let epoch: u64 = 1;
+    // The SST contains the keys of table 1 and 2.
+    let ssts = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+    hummock_manager
+        .add_tables(context_id, ssts, epoch)
+        .await
+        .unwrap();
+    hummock_manager.commit_epoch(epoch).await.unwrap();
+
+    hummock_manager
+        .register_state_tables([1, 3], 10, HashMap::from([(1, 4), (3, 8)]))
+        .await;
+
+    // Only the tables in the compaction are passed to it.
+    let compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    assert_eq!(compact_task.table_ttls, HashMap::from([(1, 10)]));
+    assert_eq!(
+        compact_task.table_prefix_bloom_lens,
+        HashMap::from([(1, 4)])
+    );
+}
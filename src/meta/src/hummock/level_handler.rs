@@ -26,6 +26,7 @@ pub struct SSTableInfo {
     pub table_id: HummockSSTableId,
     pub file_size: u64,
     pub vnode_bitmaps: Vec<VNodeBitmap>,
+    pub has_range_tombstones: bool,
 }
 
 impl From<&SstableInfo> for SSTableInfo {
@@ -35,6 +36,7 @@ impl From<&SstableInfo> for SSTableInfo {
             table_id: sst.id,
             file_size: sst.file_size,
             vnode_bitmaps: sst.vnode_bitmaps.clone(),
+            has_range_tombstones: sst.has_range_tombstones,
         }
     }
 }
@@ -46,6 +48,7 @@ impl From<SSTableInfo> for SstableInfo {
             id: info.table_id,
             file_size: info.file_size,
             vnode_bitmaps: info.vnode_bitmaps,
+            has_range_tombstones: info.has_range_tombstones,
         }
     }
 }
@@ -32,6 +32,7 @@ mod vacuum;
 use std::sync::Arc;
 use std::time::Duration;
 
+pub use compaction::parse_compression_algorithm;
 pub use compaction_scheduler::CompactionScheduler;
 pub use compactor_manager::*;
 pub use hummock_manager::*;
@@ -14,6 +14,7 @@
 
 mod compact_task_assignment;
 mod current_version_id;
+mod deleted_table;
 mod pinned_snapshot;
 mod pinned_version;
 pub mod sstable_id_info;
@@ -21,6 +22,7 @@ mod stale_sstables;
 mod version;
 
 pub use current_version_id::*;
+pub use deleted_table::*;
 pub use pinned_snapshot::*;
 pub use pinned_version::*;
 pub use sstable_id_info::*;
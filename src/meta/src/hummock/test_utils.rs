@@ -106,6 +106,7 @@ pub fn generate_test_tables(epoch: u64, sst_ids: Vec<HummockSSTableId>) -> Vec<S
                     bitmap: vec![],
                 },
             ],
+            has_range_tombstones: false,
         });
     }
     sst_info
@@ -117,6 +117,10 @@ pub async fn start(opts: MetaNodeOpts) {
     let max_heartbeat_interval = Duration::from_millis(opts.max_heartbeat_interval as u64);
     let checkpoint_interval =
         Duration::from_millis(compute_config.streaming.checkpoint_interval_ms as u64);
+    let compression_algorithm = hummock::parse_compression_algorithm(
+        &compute_config.storage.compression_algorithm_per_level,
+    )
+    .unwrap();
 
     tracing::info!("Meta server listening at {}", addr);
     let (join_handle, _shutdown_send) = rpc_serve(
@@ -129,6 +133,7 @@ pub async fn start(opts: MetaNodeOpts) {
         MetaOpts {
             enable_recovery: !opts.disable_recovery,
             checkpoint_interval,
+            compression_algorithm,
         },
     )
     .await
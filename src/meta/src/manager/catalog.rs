@@ -23,7 +23,7 @@ use risingwave_common::ensure;
 use risingwave_common::error::ErrorCode::{CatalogError, InternalError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
-use risingwave_pb::catalog::{Database, Schema, Source, Table};
+use risingwave_pb::catalog::{Database, Schema, Sink, Source, Table};
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use tokio::sync::{Mutex, MutexGuard};
 
@@ -36,9 +36,16 @@ pub type DatabaseId = u32;
 pub type SchemaId = u32;
 pub type TableId = u32;
 pub type SourceId = u32;
+pub type SinkId = u32;
 pub type RelationId = u32;
 
-pub type Catalog = (Vec<Database>, Vec<Schema>, Vec<Table>, Vec<Source>);
+pub type Catalog = (
+    Vec<Database>,
+    Vec<Schema>,
+    Vec<Table>,
+    Vec<Source>,
+    Vec<Sink>,
+);
 
 pub struct CatalogManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
@@ -406,6 +413,78 @@ where
         }
     }
 
+    pub async fn start_create_sink_procedure(&self, sink: &Sink) -> Result<()> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && !core.has_in_progress_creation(&key) {
+            core.mark_creating(&key);
+            core.increase_ref_count(sink.associated_table_id);
+            Ok(())
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exists or in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn finish_create_sink_procedure(&self, sink: &Sink) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && core.has_in_progress_creation(&key) {
+            core.unmark_creating(&key);
+            sink.insert(self.env.meta_store()).await?;
+            core.add_sink(sink);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Add, Info::Sink(sink.to_owned()))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exist or not in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn cancel_create_sink_procedure(&self, sink: &Sink) -> Result<()> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && core.has_in_progress_creation(&key) {
+            core.unmark_creating(&key);
+            core.decrease_ref_count(sink.associated_table_id);
+            Ok(())
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exist or not in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn drop_sink(&self, sink_id: SinkId) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let sink = Sink::select(self.env.meta_store(), &sink_id).await?;
+        if let Some(sink) = sink {
+            Sink::delete(self.env.meta_store(), &sink_id).await?;
+            core.drop_sink(&sink);
+            core.decrease_ref_count(sink.associated_table_id);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Delete, Info::Sink(sink))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(InternalError(
+                "sink doesn't exist".to_string(),
+            )))
+        }
+    }
+
     pub async fn start_create_materialized_source_procedure(
         &self,
         source: &Source,
@@ -576,6 +655,7 @@ type DatabaseKey = String;
 type SchemaKey = (DatabaseId, String);
 type TableKey = (DatabaseId, SchemaId, String);
 type SourceKey = (DatabaseId, SchemaId, String);
+type SinkKey = (DatabaseId, SchemaId, String);
 type RelationKey = (DatabaseId, SchemaId, String);
 
 /// [`CatalogManagerCore`] caches meta catalog information and maintains dependent relationship
@@ -590,6 +670,8 @@ pub struct CatalogManagerCore<S: MetaStore> {
     sources: HashSet<SourceKey>,
     /// Cached table key information.
     tables: HashSet<TableKey>,
+    /// Cached sink key information.
+    sinks: HashSet<SinkKey>,
     /// Relation refer count mapping.
     relation_ref_count: HashMap<RelationId, usize>,
 
@@ -606,6 +688,7 @@ where
         let schemas = Schema::list(env.meta_store()).await?;
         let sources = Source::list(env.meta_store()).await?;
         let tables = Table::list(env.meta_store()).await?;
+        let sinks = Sink::list(env.meta_store()).await?;
 
         let mut relation_ref_count = HashMap::new();
 
@@ -626,6 +709,12 @@ where
             }
             (table.database_id, table.schema_id, table.name)
         }));
+        let sinks = HashSet::from_iter(sinks.into_iter().map(|sink| {
+            *relation_ref_count
+                .entry(sink.associated_table_id)
+                .or_insert(0) += 1;
+            (sink.database_id, sink.schema_id, sink.name)
+        }));
 
         let in_progress_creation_tracker = HashSet::new();
 
@@ -635,6 +724,7 @@ where
             schemas,
             sources,
             tables,
+            sinks,
             relation_ref_count,
             in_progress_creation_tracker,
         })
@@ -646,6 +736,7 @@ where
             Schema::list(self.env.meta_store()).await?,
             Table::list(self.env.meta_store()).await?,
             Source::list(self.env.meta_store()).await?,
+            Sink::list(self.env.meta_store()).await?,
         ))
     }
 
@@ -710,6 +801,21 @@ where
             .remove(&(source.database_id, source.schema_id, source.name.clone()))
     }
 
+    fn has_sink(&self, sink: &Sink) -> bool {
+        self.sinks
+            .contains(&(sink.database_id, sink.schema_id, sink.name.clone()))
+    }
+
+    fn add_sink(&mut self, sink: &Sink) {
+        self.sinks
+            .insert((sink.database_id, sink.schema_id, sink.name.clone()));
+    }
+
+    fn drop_sink(&mut self, sink: &Sink) -> bool {
+        self.sinks
+            .remove(&(sink.database_id, sink.schema_id, sink.name.clone()))
+    }
+
     pub async fn get_source(&self, id: SourceId) -> Result<Option<Source>> {
         Source::select(self.env.meta_store(), &id).await
     }
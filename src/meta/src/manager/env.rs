@@ -16,6 +16,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
+use risingwave_pb::hummock::CompressionAlgorithm;
 use risingwave_rpc_client::{StreamClientPool, StreamClientPoolRef};
 
 use super::{HashMappingManager, HashMappingManagerRef};
@@ -56,6 +57,8 @@ where
 pub struct MetaOpts {
     pub enable_recovery: bool,
     pub checkpoint_interval: Duration,
+    /// Compression algorithm of the SSTs written to each level by compactions.
+    pub compression_algorithm: Vec<CompressionAlgorithm>,
 }
 
 impl Default for MetaOpts {
@@ -63,6 +66,7 @@ impl Default for MetaOpts {
         Self {
             enable_recovery: false,
             checkpoint_interval: Duration::from_millis(100),
+            compression_algorithm: vec![],
         }
     }
 }
@@ -132,6 +132,7 @@ pub mod IdCategory {
     pub const HummockSSTableId: IdCategoryType = 8;
     pub const ParallelUnit: IdCategoryType = 9;
     pub const Source: IdCategoryType = 10;
+    pub const User: IdCategoryType = 11;
 }
 
 pub type IdGeneratorManagerRef<S> = Arc<IdGeneratorManager<S>>;
@@ -150,6 +151,7 @@ pub struct IdGeneratorManager<S> {
     hummock_snapshot: Arc<StoredIdGenerator<S>>,
     hummock_ss_table_id: Arc<StoredIdGenerator<S>>,
     parallel_unit: Arc<StoredIdGenerator<S>>,
+    user: Arc<StoredIdGenerator<S>>,
 }
 
 impl<S> IdGeneratorManager<S>
@@ -180,6 +182,7 @@ where
             parallel_unit: Arc::new(
                 StoredIdGenerator::new(meta_store.clone(), "parallel_unit", None).await,
             ),
+            user: Arc::new(StoredIdGenerator::new(meta_store.clone(), "user", None).await),
         }
     }
 
@@ -196,6 +199,7 @@ where
             IdCategory::Worker => &self.worker,
             IdCategory::HummockSSTableId => &self.hummock_ss_table_id,
             IdCategory::ParallelUnit => &self.parallel_unit,
+            IdCategory::User => &self.user,
             _ => unreachable!(),
         }
     }
@@ -17,9 +17,11 @@ mod env;
 mod hash_mapping;
 mod id;
 mod notification;
+mod user;
 
 pub use catalog::*;
 pub use env::*;
 pub use hash_mapping::*;
 pub use id::*;
 pub use notification::*;
+pub use user::*;
@@ -0,0 +1,473 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use risingwave_common::catalog::{CatalogVersion, DEFAULT_SUPPER_USER, DEFAULT_SUPPER_USER_FOR_PG};
+use risingwave_common::error::ErrorCode::{InternalError, PermissionDenied};
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::IdCategory;
+use crate::manager::MetaSrvEnv;
+use crate::model::{MetadataModel, Transactional};
+use crate::storage::{MetaStore, Transaction};
+
+pub type UserId = u32;
+
+/// `UserManager` manages the user info, including authentication and privileges. It only
+/// responds to manager of users and privileges, the prerequisite check of the privilege should be
+/// done in the frontend.
+pub struct UserManager<S: MetaStore> {
+    env: MetaSrvEnv<S>,
+    core: Mutex<UserManagerCore>,
+}
+
+pub type UserManagerRef<S> = Arc<UserManager<S>>;
+
+impl<S: MetaStore> UserManager<S> {
+    pub async fn new(env: MetaSrvEnv<S>) -> Result<Self> {
+        let users = UserInfo::list(env.meta_store()).await?;
+        let user_manager = Self {
+            env,
+            core: Mutex::new(UserManagerCore::new(users)),
+        };
+        user_manager.init().await?;
+        Ok(user_manager)
+    }
+
+    // Create default super users.
+    async fn init(&self) -> Result<()> {
+        for user in [DEFAULT_SUPPER_USER, DEFAULT_SUPPER_USER_FOR_PG] {
+            if !self.core.lock().await.has_user_name(user) {
+                let id = self
+                    .env
+                    .id_gen_manager()
+                    .generate::<{ IdCategory::User }>()
+                    .await? as u32;
+                let default_user = UserInfo {
+                    id,
+                    name: user.to_string(),
+                    is_supper: true,
+                    can_create_db: true,
+                    can_login: true,
+                    ..Default::default()
+                };
+                self.create_user(&default_user).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Used in `NotificationService::subscribe`.
+    /// Need to pay attention to the order of acquiring locks to prevent deadlock problems.
+    pub async fn get_user_core_guard(&self) -> MutexGuard<'_, UserManagerCore> {
+        self.core.lock().await
+    }
+
+    pub async fn list_users(&self) -> Vec<UserInfo> {
+        self.core.lock().await.list_users()
+    }
+
+    pub async fn get_user(&self, id: UserId) -> Result<UserInfo> {
+        self.core
+            .lock()
+            .await
+            .user_info
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| InternalError(format!("User {} not found", id)).into())
+    }
+
+    pub async fn create_user(&self, user: &UserInfo) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        if core.has_user_name(&user.name) {
+            return Err(RwError::from(PermissionDenied(format!(
+                "User {} already exists",
+                user.name
+            ))));
+        }
+        user.insert(self.env.meta_store()).await?;
+        core.user_info.insert(user.id, user.clone());
+
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Add, Info::User(user.to_owned()))
+            .await;
+        Ok(version)
+    }
+
+    /// Updates the fields of the user listed in `update_fields`, other fields are kept unchanged.
+    pub async fn update_user(
+        &self,
+        update_user: &UserInfo,
+        update_fields: &[UpdateField],
+    ) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let mut user = core
+            .user_info
+            .get(&update_user.id)
+            .cloned()
+            .ok_or_else(|| InternalError(format!("User {} not found", update_user.id)))?;
+
+        for field in update_fields {
+            match field {
+                UpdateField::Unknown => {}
+                UpdateField::Super => user.is_supper = update_user.is_supper,
+                UpdateField::Login => user.can_login = update_user.can_login,
+                UpdateField::CreateDb => user.can_create_db = update_user.can_create_db,
+                UpdateField::AuthInfo => user.auth_info = update_user.auth_info.clone(),
+                UpdateField::Rename => {
+                    if core.has_user_name(&update_user.name) {
+                        return Err(RwError::from(PermissionDenied(format!(
+                            "User {} already exists",
+                            update_user.name
+                        ))));
+                    }
+                    if is_default_user(&user.name) {
+                        return Err(RwError::from(PermissionDenied(format!(
+                            "Cannot rename default super user {}",
+                            user.name
+                        ))));
+                    }
+                    user.name = update_user.name.clone();
+                }
+            }
+        }
+
+        user.insert(self.env.meta_store()).await?;
+        core.user_info.insert(user.id, user.clone());
+
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::User(user))
+            .await;
+        Ok(version)
+    }
+
+    pub async fn drop_user(&self, id: UserId) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let user = core
+            .user_info
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| InternalError(format!("User {} not found", id)))?;
+        if is_default_user(&user.name) {
+            return Err(RwError::from(PermissionDenied(format!(
+                "Cannot drop default super user {}",
+                user.name
+            ))));
+        }
+        UserInfo::delete(self.env.meta_store(), &id).await?;
+        core.user_info.remove(&id);
+
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Delete, Info::User(user))
+            .await;
+        Ok(version)
+    }
+
+    /// Grants `new_privileges` to the users. A privilege that the user already has is only
+    /// updated with the grant option.
+    pub async fn grant_privilege(
+        &self,
+        user_ids: &[UserId],
+        new_privileges: &[GrantPrivilege],
+    ) -> Result<CatalogVersion> {
+        self.update_privileges(user_ids, |user| {
+            for new_privilege in new_privileges {
+                grant_privilege_to_user(user, new_privilege);
+            }
+        })
+        .await
+    }
+
+    /// Revokes `revoke_privileges` from the users. If `revoke_grant_option` is true, only the
+    /// grant option of the privileges are revoked.
+    pub async fn revoke_privilege(
+        &self,
+        user_ids: &[UserId],
+        revoke_privileges: &[GrantPrivilege],
+        revoke_grant_option: bool,
+    ) -> Result<CatalogVersion> {
+        self.update_privileges(user_ids, |user| {
+            for revoke_privilege in revoke_privileges {
+                revoke_privilege_from_user(user, revoke_privilege, revoke_grant_option);
+            }
+        })
+        .await
+    }
+
+    /// Applies `update` to the privileges of the users in a single transaction.
+    async fn update_privileges(
+        &self,
+        user_ids: &[UserId],
+        update: impl Fn(&mut UserInfo),
+    ) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let mut users = Vec::with_capacity(user_ids.len());
+        for user_id in user_ids {
+            let mut user = core
+                .user_info
+                .get(user_id)
+                .cloned()
+                .ok_or_else(|| InternalError(format!("User {} not found", user_id)))?;
+            update(&mut user);
+            users.push(user);
+        }
+
+        let mut transaction = Transaction::default();
+        for user in &users {
+            user.upsert_in_transaction(&mut transaction)?;
+        }
+        self.env.meta_store().txn(transaction).await?;
+
+        let mut version = 0;
+        for user in users {
+            core.user_info.insert(user.id, user.clone());
+            version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Update, Info::User(user))
+                .await;
+        }
+        Ok(version)
+    }
+}
+
+/// Merges `new_privilege` into the privileges of `user` on the same target.
+fn grant_privilege_to_user(user: &mut UserInfo, new_privilege: &GrantPrivilege) {
+    let Some(privilege) = user
+        .privileges
+        .iter_mut()
+        .find(|privilege| privilege.target == new_privilege.target)
+    else {
+        user.privileges.push(new_privilege.clone());
+        return;
+    };
+    for new_opt in &new_privilege.privilege_with_opts {
+        match privilege
+            .privilege_with_opts
+            .iter_mut()
+            .find(|opt| opt.privilege == new_opt.privilege)
+        {
+            Some(opt) => opt.with_grant_option |= new_opt.with_grant_option,
+            None => privilege.privilege_with_opts.push(new_opt.clone()),
+        }
+    }
+}
+
+/// Removes the privileges in `revoke_privilege` (or only their grant options) from `user`.
+fn revoke_privilege_from_user(
+    user: &mut UserInfo,
+    revoke_privilege: &GrantPrivilege,
+    revoke_grant_option: bool,
+) {
+    let Some(privilege) = user
+        .privileges
+        .iter_mut()
+        .find(|privilege| privilege.target == revoke_privilege.target)
+    else {
+        return;
+    };
+    for revoke_opt in &revoke_privilege.privilege_with_opts {
+        if revoke_grant_option {
+            privilege
+                .privilege_with_opts
+                .iter_mut()
+                .filter(|opt| opt.privilege == revoke_opt.privilege)
+                .for_each(|opt| opt.with_grant_option = false);
+        } else {
+            privilege
+                .privilege_with_opts
+                .retain(|opt| opt.privilege != revoke_opt.privilege);
+        }
+    }
+    user.privileges
+        .retain(|privilege| !privilege.privilege_with_opts.is_empty());
+}
+
+fn is_default_user(user_name: &str) -> bool {
+    user_name == DEFAULT_SUPPER_USER || user_name == DEFAULT_SUPPER_USER_FOR_PG
+}
+
+/// `UserManagerCore` caches all user infos in memory, keyed by user id.
+pub struct UserManagerCore {
+    user_info: HashMap<UserId, UserInfo>,
+}
+
+impl UserManagerCore {
+    fn new(users: Vec<UserInfo>) -> Self {
+        Self {
+            user_info: users.into_iter().map(|user| (user.id, user)).collect(),
+        }
+    }
+
+    fn has_user_name(&self, user_name: &str) -> bool {
+        self.user_info.values().any(|user| user.name == user_name)
+    }
+
+    pub fn list_users(&self) -> Vec<UserInfo> {
+        self.user_info.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::user::grant_privilege::{
+        GrantTable, Privilege, PrivilegeWithGrantOption, Target,
+    };
+
+    use super::*;
+
+    fn make_test_user(id: UserId, name: &str) -> UserInfo {
+        UserInfo {
+            id,
+            name: name.to_string(),
+            can_login: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_manager() -> Result<()> {
+        let user_manager = UserManager::new(MetaSrvEnv::for_test().await).await?;
+        let users = user_manager.list_users().await;
+        assert_eq!(users.len(), 2);
+        assert!(users.iter().all(|user| user.is_supper));
+
+        let test_user = make_test_user(100, "test");
+        user_manager.create_user(&test_user).await?;
+        assert!(user_manager
+            .create_user(&make_test_user(101, "test"))
+            .await
+            .is_err());
+
+        let mut update_user = test_user.clone();
+        update_user.name = "test_renamed".to_string();
+        update_user.can_login = false;
+        user_manager
+            .update_user(&update_user, &[UpdateField::Rename])
+            .await?;
+        let user = user_manager.get_user(100).await?;
+        assert_eq!(user.name, "test_renamed");
+        assert!(user.can_login);
+
+        let root = users
+            .iter()
+            .find(|user| user.name == DEFAULT_SUPPER_USER)
+            .unwrap();
+        assert!(user_manager.drop_user(root.id).await.is_err());
+
+        user_manager.drop_user(100).await?;
+        assert!(user_manager.get_user(100).await.is_err());
+        assert_eq!(user_manager.list_users().await.len(), 2);
+
+        Ok(())
+    }
+
+    fn make_table_privilege(
+        table_id: u32,
+        privileges: &[Privilege],
+        with_grant_option: bool,
+    ) -> GrantPrivilege {
+        GrantPrivilege {
+            target: Some(Target::GrantTable(GrantTable {
+                database_id: 0,
+                schema_id: 0,
+                table_id,
+            })),
+            privilege_with_opts: privileges
+                .iter()
+                .map(|privilege| PrivilegeWithGrantOption {
+                    privilege: *privilege as i32,
+                    with_grant_option,
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grant_revoke_privilege() -> Result<()> {
+        let user_manager = UserManager::new(MetaSrvEnv::for_test().await).await?;
+        user_manager
+            .create_user(&make_test_user(100, "test"))
+            .await?;
+
+        user_manager
+            .grant_privilege(
+                &[100],
+                &[make_table_privilege(1, &[Privilege::Select], false)],
+            )
+            .await?;
+        user_manager
+            .grant_privilege(
+                &[100],
+                &[make_table_privilege(
+                    1,
+                    &[Privilege::Select, Privilege::Insert],
+                    true,
+                )],
+            )
+            .await?;
+        let user = user_manager.get_user(100).await?;
+        assert_eq!(
+            user.privileges,
+            vec![make_table_privilege(
+                1,
+                &[Privilege::Select, Privilege::Insert],
+                true
+            )]
+        );
+
+        user_manager
+            .revoke_privilege(
+                &[100],
+                &[make_table_privilege(1, &[Privilege::Select], false)],
+                true,
+            )
+            .await?;
+        user_manager
+            .revoke_privilege(
+                &[100],
+                &[make_table_privilege(1, &[Privilege::Insert], false)],
+                false,
+            )
+            .await?;
+        let user = user_manager.get_user(100).await?;
+        assert_eq!(
+            user.privileges,
+            vec![make_table_privilege(1, &[Privilege::Select], false)]
+        );
+
+        user_manager
+            .revoke_privilege(
+                &[100],
+                &[make_table_privilege(1, &[Privilege::Select], false)],
+                false,
+            )
+            .await?;
+        assert!(user_manager.get_user(100).await?.privileges.is_empty());
+
+        Ok(())
+    }
+}
@@ -17,8 +17,11 @@ use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::Result;
+use risingwave_hummock_sdk::key::TABLE_PREFIX_LEN;
+use risingwave_pb::data::data_type::TypeName;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
+use risingwave_pb::plan_common::Field;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{FragmentType, StreamActor, StreamNode};
@@ -207,6 +210,135 @@ impl TableFragments {
         table_ids
     }
 
+    /// Resolve the state tables written by the actors.
+    fn resolve_state_table(stream_node: &StreamNode, table_ids: &mut HashSet<TableId>) {
+        match stream_node.node_body.as_ref() {
+            Some(NodeBody::Materialize(node)) => {
+                table_ids.insert(TableId::from(&node.table_ref_id));
+            }
+            Some(NodeBody::Arrange(node)) => {
+                table_ids.insert(TableId::new(node.table_id));
+            }
+            Some(NodeBody::HashAgg(node)) => {
+                table_ids.extend(node.table_ids.iter().copied().map(TableId::new));
+            }
+            Some(NodeBody::GlobalSimpleAgg(node) | NodeBody::LocalSimpleAgg(node)) => {
+                table_ids.extend(node.table_ids.iter().copied().map(TableId::new));
+            }
+            Some(NodeBody::HashJoin(node)) => {
+                table_ids.insert(TableId::new(node.left_table_id));
+                table_ids.insert(TableId::new(node.right_table_id));
+            }
+            Some(NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node)) => {
+                table_ids.insert(TableId::new(node.table_id));
+            }
+            Some(NodeBody::OverWindow(node)) => {
+                table_ids.insert(TableId::new(node.table_id));
+            }
+            Some(NodeBody::NestedLoopJoin(node)) => {
+                table_ids.insert(TableId::new(node.table_id));
+            }
+            Some(NodeBody::DynamicFilter(node)) => {
+                table_ids.insert(TableId::new(node.table_id));
+            }
+            _ => {}
+        }
+
+        for child in &stream_node.input {
+            Self::resolve_state_table(child, table_ids);
+        }
+    }
+
+    /// Returns the ids of the tables whose states are owned by the materialized view, including
+    /// the materialized view itself.
+    pub fn state_table_ids(&self) -> HashSet<TableId> {
+        let mut table_ids = HashSet::new();
+        self.fragments.values().for_each(|fragment| {
+            let actor = &fragment.actors[0];
+            Self::resolve_state_table(actor.nodes.as_ref().unwrap(), &mut table_ids);
+        });
+
+        table_ids
+    }
+
+    /// Returns the length of the longest key prefix of a state table that is fixed for each value
+    /// of the leading `key_indices` columns, or `None` if it only covers the table prefix.
+    ///
+    /// Keys are encoded in memcomparable format, where each datum takes a null tag byte and its
+    /// encoded value. So the prefix stops at the first column without a fixed encoded size.
+    fn prefix_bloom_len(fields: &[Field], key_indices: &[i32]) -> Option<u32> {
+        let mut prefix_len = TABLE_PREFIX_LEN;
+        for &idx in key_indices {
+            let type_name = fields[idx as usize]
+                .get_data_type()
+                .ok()?
+                .get_type_name()
+                .ok()?;
+            prefix_len += 1 + match type_name {
+                TypeName::Boolean => 1,
+                TypeName::Int16 => 2,
+                TypeName::Int32 | TypeName::Float => 4,
+                TypeName::Int64 | TypeName::Double => 8,
+                _ => break,
+            };
+        }
+        if prefix_len > TABLE_PREFIX_LEN {
+            Some(prefix_len as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Resolve the prefix lengths of the state tables which are scanned by key prefixes.
+    fn resolve_state_table_prefix_bloom_len(
+        stream_node: &StreamNode,
+        prefix_bloom_lens: &mut HashMap<u32, u32>,
+    ) {
+        match stream_node.node_body.as_ref() {
+            Some(NodeBody::HashAgg(node)) => {
+                if let Some(prefix_len) =
+                    Self::prefix_bloom_len(&stream_node.input[0].fields, &node.distribution_keys)
+                {
+                    for &table_id in &node.table_ids {
+                        prefix_bloom_lens.insert(table_id, prefix_len);
+                    }
+                }
+            }
+            Some(NodeBody::HashJoin(node)) => {
+                if let Some(prefix_len) =
+                    Self::prefix_bloom_len(&stream_node.input[0].fields, &node.left_key)
+                {
+                    prefix_bloom_lens.insert(node.left_table_id, prefix_len);
+                }
+                if let Some(prefix_len) =
+                    Self::prefix_bloom_len(&stream_node.input[1].fields, &node.right_key)
+                {
+                    prefix_bloom_lens.insert(node.right_table_id, prefix_len);
+                }
+            }
+            _ => {}
+        }
+
+        for child in &stream_node.input {
+            Self::resolve_state_table_prefix_bloom_len(child, prefix_bloom_lens);
+        }
+    }
+
+    /// Returns `table_id` -> Length of the key prefixes to build prefix bloom filters with, for the
+    /// state tables owned by the materialized view.
+    pub fn state_table_prefix_bloom_lens(&self) -> HashMap<u32, u32> {
+        let mut prefix_bloom_lens = HashMap::new();
+        self.fragments.values().for_each(|fragment| {
+            let actor = &fragment.actors[0];
+            Self::resolve_state_table_prefix_bloom_len(
+                actor.nodes.as_ref().unwrap(),
+                &mut prefix_bloom_lens,
+            );
+        });
+
+        prefix_bloom_lens
+    }
+
     /// Returns states of actors group by node id.
     pub fn node_actor_states(&self) -> BTreeMap<WorkerId, Vec<(ActorId, ActorState)>> {
         let mut map = BTreeMap::default();
@@ -27,7 +27,8 @@ use risingwave_pb::stream_plan::{StreamFragmentGraph, StreamNode};
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
-use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SourceId, TableId};
+use crate::hummock::HummockManagerRef;
+use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SinkId, SourceId, TableId};
 use crate::model::TableFragments;
 use crate::storage::MetaStore;
 use crate::stream::{
@@ -43,6 +44,7 @@ pub struct DdlServiceImpl<S: MetaStore> {
     source_manager: SourceManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
     fragment_manager: FragmentManagerRef<S>,
+    hummock_manager: HummockManagerRef<S>,
 }
 
 impl<S> DdlServiceImpl<S>
@@ -56,6 +58,7 @@ where
         source_manager: SourceManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         fragment_manager: FragmentManagerRef<S>,
+        hummock_manager: HummockManagerRef<S>,
     ) -> Self {
         Self {
             env,
@@ -64,6 +67,7 @@ where
             source_manager,
             cluster_manager,
             fragment_manager,
+            hummock_manager,
         }
     }
 }
@@ -295,6 +299,9 @@ where
                 .map_err(tonic_err)?;
             return Err(e.to_grpc_status());
         }
+        self.register_state_tables(id, mview.ttl)
+            .await
+            .map_err(tonic_err)?;
 
         // 4. Finally, update the catalog.
         let version = self
@@ -314,8 +321,6 @@ where
         &self,
         request: Request<DropMaterializedViewRequest>,
     ) -> Result<Response<DropMaterializedViewResponse>, Status> {
-        use risingwave_common::catalog::TableId;
-
         let table_id = request.into_inner().table_id;
         // 1. Drop table in catalog. Ref count will be checked.
         let version = self
@@ -325,8 +330,7 @@ where
             .map_err(tonic_err)?;
 
         // 2. drop mv in stream manager
-        self.stream_manager
-            .drop_materialized_view(&TableId::new(table_id))
+        self.drop_materialized_view_inner(table_id)
             .await
             .map_err(tonic_err)?;
 
@@ -376,6 +380,52 @@ where
             version,
         }))
     }
+
+    async fn create_sink(
+        &self,
+        request: Request<CreateSinkRequest>,
+    ) -> Result<Response<CreateSinkResponse>, Status> {
+        let req = request.into_inner();
+        let sink = req.get_sink().map_err(tonic_err)?.clone();
+        let fragment_graph = req.get_fragment_graph().map_err(tonic_err)?.clone();
+
+        let (sink_id, version) = self
+            .create_sink_inner(sink, fragment_graph)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(CreateSinkResponse {
+            status: None,
+            sink_id,
+            version,
+        }))
+    }
+
+    async fn drop_sink(
+        &self,
+        request: Request<DropSinkRequest>,
+    ) -> Result<Response<DropSinkResponse>, Status> {
+        use risingwave_common::catalog::TableId;
+
+        let sink_id = request.into_inner().sink_id;
+        // 1. Drop sink in catalog and release the reference to the associated table.
+        let version = self
+            .catalog_manager
+            .drop_sink(sink_id)
+            .await
+            .map_err(tonic_err)?;
+
+        // 2. Drop the sink's actors in stream manager.
+        self.stream_manager
+            .drop_materialized_view(&TableId::new(sink_id))
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(DropSinkResponse {
+            status: None,
+            version,
+        }))
+    }
 }
 
 impl<S> DdlServiceImpl<S>
@@ -390,8 +440,6 @@ where
     ) -> RwResult<()> {
         use risingwave_common::catalog::TableId;
 
-        use crate::stream::CreateMaterializedViewContext;
-
         // Fill in the correct mview id for stream node.
         fn fill_mview_id(stream_node: &mut StreamNode, mview_id: TableId) -> usize {
             let mut mview_count = 0;
@@ -417,6 +465,20 @@ where
             "require exactly 1 materialize node when creating materialized view"
         );
 
+        self.create_stream_job_on_compute_node(fragment_graph, mview_id, affiliated_source)
+            .await
+    }
+
+    /// Builds the actor graph of a streaming job, i.e. a materialized view or a sink, and creates
+    /// it on compute nodes. The ids in stream nodes should have been filled.
+    async fn create_stream_job_on_compute_node(
+        &self,
+        fragment_graph: StreamFragmentGraph,
+        job_id: risingwave_common::catalog::TableId,
+        affiliated_source: Option<Source>,
+    ) -> RwResult<()> {
+        use crate::stream::CreateMaterializedViewContext;
+
         // Resolve fragments.
         let parallel_degree = self
             .cluster_manager
@@ -434,7 +496,7 @@ where
             &mut ctx,
         )
         .await?;
-        let table_fragments = TableFragments::new(mview_id, graph);
+        let table_fragments = TableFragments::new(job_id, graph);
 
         // Create on compute node.
         self.stream_manager
@@ -519,6 +581,7 @@ where
             self.source_manager.drop_source(source_id).await?;
             return Err(e);
         }
+        self.register_state_tables(mview_id, mview.ttl).await?;
 
         // Finally, update the catalog.
         let version = self
@@ -529,13 +592,74 @@ where
         Ok((source_id, mview_id, version))
     }
 
+    async fn create_sink_inner(
+        &self,
+        mut sink: Sink,
+        mut fragment_graph: StreamFragmentGraph,
+    ) -> RwResult<(SinkId, CatalogVersion)> {
+        // Generate sink id. Sinks share the id space with tables, since the actors of a sink are
+        // managed as table fragments.
+        let sink_id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Table }>() // TODO: use sink category
+            .await? as u32;
+        sink.id = sink_id;
+
+        // Fill in the correct sink id for stream node.
+        fn fill_sink_id(stream_node: &mut StreamNode, sink_id: u32) -> usize {
+            let mut sink_count = 0;
+            if let NodeBody::Sink(sink_node) = stream_node.node_body.as_mut().unwrap() {
+                sink_node.sink_id = sink_id;
+                sink_count += 1;
+            }
+            for input in &mut stream_node.input {
+                sink_count += fill_sink_id(input, sink_id);
+            }
+            sink_count
+        }
+
+        let mut sink_count = 0;
+        for fragment in fragment_graph.fragments.values_mut() {
+            sink_count += fill_sink_id(fragment.node.as_mut().unwrap(), sink_id);
+        }
+        assert_eq!(
+            sink_count, 1,
+            "require exactly 1 sink node when creating sink"
+        );
+
+        // Mark current sink as "creating" and add reference count to the associated table.
+        self.catalog_manager
+            .start_create_sink_procedure(&sink)
+            .await?;
+
+        if let Err(e) = self
+            .create_stream_job_on_compute_node(
+                fragment_graph,
+                risingwave_common::catalog::TableId::new(sink_id),
+                None,
+            )
+            .await
+        {
+            self.catalog_manager
+                .cancel_create_sink_procedure(&sink)
+                .await?;
+            return Err(e);
+        }
+
+        let version = self
+            .catalog_manager
+            .finish_create_sink_procedure(&sink)
+            .await?;
+
+        Ok((sink_id, version))
+    }
+
     async fn drop_materialized_source_inner(
         &self,
         source_id: SourceId,
         table_id: TableId,
     ) -> RwResult<CatalogVersion> {
-        use risingwave_common::catalog::TableId;
-
         // 1. Drop materialized source in catalog, source_id will be checked if it is
         // associated_source_id in mview.
         let version = self
@@ -545,10 +669,53 @@ where
 
         // 2. Drop source and mv separately.
         self.source_manager.drop_source(source_id).await?;
+        self.drop_materialized_view_inner(table_id).await?;
+
+        Ok(version)
+    }
+
+    /// Caches the TTL and the prefix bloom filter lengths of the state tables of a created
+    /// materialized view in hummock manager for compactions.
+    async fn register_state_tables(&self, table_id: TableId, ttl: u32) -> RwResult<()> {
+        use risingwave_common::catalog::TableId;
+
+        let table_id = TableId::new(table_id);
+        let state_table_ids = self
+            .fragment_manager
+            .table_state_table_ids(&table_id)
+            .await?;
+        let prefix_bloom_lens = self
+            .fragment_manager
+            .table_state_table_prefix_bloom_lens(&table_id)
+            .await?;
+        self.hummock_manager
+            .register_state_tables(
+                state_table_ids.iter().map(TableId::table_id),
+                ttl,
+                prefix_bloom_lens,
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// Drops the materialized view in stream manager, and marks its state tables as deleted so
+    /// that their keys will be removed by compactions.
+    async fn drop_materialized_view_inner(&self, table_id: TableId) -> RwResult<()> {
+        use risingwave_common::catalog::TableId;
+
+        let table_id = TableId::new(table_id);
+        let state_table_ids = self
+            .fragment_manager
+            .table_state_table_ids(&table_id)
+            .await?;
         self.stream_manager
-            .drop_materialized_view(&TableId::new(table_id))
+            .drop_materialized_view(&table_id)
+            .await?;
+        self.hummock_manager
+            .mark_tables_deleted(state_table_ids.iter().map(TableId::table_id))
             .await?;
 
-        Ok(version)
+        Ok(())
     }
 }
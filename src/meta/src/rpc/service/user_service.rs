@@ -0,0 +1,150 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::tonic_err;
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_pb::user::user_service_server::UserService;
+use risingwave_pb::user::{
+    CreateUserRequest, CreateUserResponse, DropUserRequest, DropUserResponse,
+    GrantPrivilegeRequest, GrantPrivilegeResponse, RevokePrivilegeRequest, RevokePrivilegeResponse,
+    UpdateUserRequest, UpdateUserResponse,
+};
+use tonic::{Request, Response, Status};
+
+use crate::manager::{IdCategory, MetaSrvEnv, UserManagerRef};
+use crate::storage::MetaStore;
+
+pub struct UserServiceImpl<S: MetaStore> {
+    env: MetaSrvEnv<S>,
+
+    user_manager: UserManagerRef<S>,
+}
+
+impl<S> UserServiceImpl<S>
+where
+    S: MetaStore,
+{
+    pub fn new(env: MetaSrvEnv<S>, user_manager: UserManagerRef<S>) -> Self {
+        Self { env, user_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> UserService for UserServiceImpl<S>
+where
+    S: MetaStore,
+{
+    async fn create_user(
+        &self,
+        request: Request<CreateUserRequest>,
+    ) -> Result<Response<CreateUserResponse>, Status> {
+        let req = request.into_inner();
+        let id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::User }>()
+            .await
+            .map_err(tonic_err)? as u32;
+        let mut user = req.get_user().map_err(tonic_err)?.clone();
+        user.id = id;
+        let version = self
+            .user_manager
+            .create_user(&user)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(CreateUserResponse {
+            status: None,
+            user_id: id,
+            version,
+        }))
+    }
+
+    async fn drop_user(
+        &self,
+        request: Request<DropUserRequest>,
+    ) -> Result<Response<DropUserResponse>, Status> {
+        let req = request.into_inner();
+        let version = self
+            .user_manager
+            .drop_user(req.user_id)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(DropUserResponse {
+            status: None,
+            version,
+        }))
+    }
+
+    async fn update_user(
+        &self,
+        request: Request<UpdateUserRequest>,
+    ) -> Result<Response<UpdateUserResponse>, Status> {
+        let req = request.into_inner();
+        let update_fields = req
+            .update_fields
+            .iter()
+            .map(|i| {
+                UpdateField::from_i32(*i)
+                    .ok_or_else(|| Status::invalid_argument(format!("invalid update field {}", i)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let user = req.get_user().map_err(tonic_err)?.clone();
+        let version = self
+            .user_manager
+            .update_user(&user, &update_fields)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(UpdateUserResponse {
+            status: None,
+            version,
+        }))
+    }
+
+    async fn grant_privilege(
+        &self,
+        request: Request<GrantPrivilegeRequest>,
+    ) -> Result<Response<GrantPrivilegeResponse>, Status> {
+        let req = request.into_inner();
+        let version = self
+            .user_manager
+            .grant_privilege(&req.user_ids, &req.privileges)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(GrantPrivilegeResponse {
+            status: None,
+            version,
+        }))
+    }
+
+    async fn revoke_privilege(
+        &self,
+        request: Request<RevokePrivilegeRequest>,
+    ) -> Result<Response<RevokePrivilegeResponse>, Status> {
+        let req = request.into_inner();
+        let version = self
+            .user_manager
+            .revoke_privilege(&req.user_ids, &req.privileges, req.revoke_grant_option)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(RevokePrivilegeResponse {
+            status: None,
+            version,
+        }))
+    }
+}
@@ -330,6 +330,31 @@ where
         }
     }
 
+    pub async fn table_state_table_ids(&self, table_id: &TableId) -> Result<HashSet<TableId>> {
+        let map = &self.core.read().await.table_fragments;
+        match map.get(table_id) {
+            Some(table_fragment) => Ok(table_fragment.state_table_ids()),
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
+    pub async fn table_state_table_prefix_bloom_lens(
+        &self,
+        table_id: &TableId,
+    ) -> Result<HashMap<u32, u32>> {
+        let map = &self.core.read().await.table_fragments;
+        match map.get(table_id) {
+            Some(table_fragment) => Ok(table_fragment.state_table_prefix_bloom_lens()),
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
     pub async fn get_table_actor_ids(&self, table_id: &TableId) -> Result<Vec<ActorId>> {
         let map = &self.core.read().await.table_fragments;
         match map.get(table_id) {
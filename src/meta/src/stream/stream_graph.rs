@@ -576,6 +576,18 @@ impl StreamGraphBuilder {
                             *table_id += table_id_offset;
                         }
                     }
+                    NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) => {
+                        node.table_id += table_id_offset;
+                    }
+                    NodeBody::OverWindow(node) => {
+                        node.table_id += table_id_offset;
+                    }
+                    NodeBody::NestedLoopJoin(node) => {
+                        node.table_id += table_id_offset;
+                    }
+                    NodeBody::DynamicFilter(node) => {
+                        node.table_id += table_id_offset;
+                    }
                     _ => {}
                 }
 
@@ -26,8 +26,8 @@ use risingwave_pb::batch_plan::{ExchangeInfo, PlanFragment, PlanNode, TaskId, Ta
 use risingwave_pb::task_service::exchange_service_client::ExchangeServiceClient;
 use risingwave_pb::task_service::task_service_client::TaskServiceClient;
 use risingwave_pb::task_service::{
-    CreateTaskRequest, CreateTaskResponse, GetDataRequest, GetDataResponse, GetStreamRequest,
-    GetStreamResponse,
+    AbortTaskRequest, CreateTaskRequest, CreateTaskResponse, GetDataRequest, GetDataResponse,
+    GetStreamRequest, GetStreamResponse,
 };
 use tonic::transport::{Channel, Endpoint};
 use tonic::Streaming;
@@ -134,6 +134,20 @@ impl ComputeClient {
         Ok(())
     }
 
+    /// Aborts a running task, whose outputs will then end with an error.
+    pub async fn abort_task(&self, task_id: TaskId) -> Result<()> {
+        let _ = self
+            .task_client
+            .to_owned()
+            .abort_task(AbortTaskRequest {
+                task_id: Some(task_id),
+                force: false,
+            })
+            .await
+            .to_rw_result()?;
+        Ok(())
+    }
+
     async fn create_task_inner(&self, req: CreateTaskRequest) -> Result<CreateTaskResponse> {
         Ok(self
             .task_client
@@ -23,17 +23,19 @@ use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
 use risingwave_pb::common::{WorkerNode, WorkerType};
 use risingwave_pb::ddl_service::ddl_service_client::DdlServiceClient;
 use risingwave_pb::ddl_service::{
     CreateDatabaseRequest, CreateDatabaseResponse, CreateMaterializedSourceRequest,
     CreateMaterializedSourceResponse, CreateMaterializedViewRequest,
-    CreateMaterializedViewResponse, CreateSchemaRequest, CreateSchemaResponse, CreateSourceRequest,
-    CreateSourceResponse, DropDatabaseRequest, DropDatabaseResponse, DropMaterializedSourceRequest,
-    DropMaterializedSourceResponse, DropMaterializedViewRequest, DropMaterializedViewResponse,
-    DropSchemaRequest, DropSchemaResponse, DropSourceRequest, DropSourceResponse,
+    CreateMaterializedViewResponse, CreateSchemaRequest, CreateSchemaResponse, CreateSinkRequest,
+    CreateSinkResponse, CreateSourceRequest, CreateSourceResponse, DropDatabaseRequest,
+    DropDatabaseResponse, DropMaterializedSourceRequest, DropMaterializedSourceResponse,
+    DropMaterializedViewRequest, DropMaterializedViewResponse, DropSchemaRequest,
+    DropSchemaResponse, DropSinkRequest, DropSinkResponse, DropSourceRequest, DropSourceResponse,
 };
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
 use risingwave_pb::hummock::{
@@ -56,6 +58,13 @@ use risingwave_pb::meta::{
     SubscribeRequest, SubscribeResponse,
 };
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_pb::user::user_service_client::UserServiceClient;
+use risingwave_pb::user::{
+    CreateUserRequest, CreateUserResponse, DropUserRequest, DropUserResponse, GrantPrivilege,
+    GrantPrivilegeRequest, GrantPrivilegeResponse, RevokePrivilegeRequest, RevokePrivilegeResponse,
+    UpdateUserRequest, UpdateUserResponse, UserInfo,
+};
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 use tokio::task::JoinHandle;
 use tonic::transport::{Channel, Endpoint};
@@ -213,6 +222,26 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn create_sink(
+        &self,
+        sink: ProstSink,
+        graph: StreamFragmentGraph,
+    ) -> Result<(u32, CatalogVersion)> {
+        let request = CreateSinkRequest {
+            sink: Some(sink),
+            fragment_graph: Some(graph),
+        };
+        let resp = self.inner.create_sink(request).await?;
+        // TODO: handle error in `resp.status` here
+        Ok((resp.sink_id, resp.version))
+    }
+
+    pub async fn drop_sink(&self, sink_id: u32) -> Result<CatalogVersion> {
+        let request = DropSinkRequest { sink_id };
+        let resp = self.inner.drop_sink(request).await?;
+        Ok(resp.version)
+    }
+
     pub async fn drop_source(&self, source_id: u32) -> Result<CatalogVersion> {
         let request = DropSourceRequest { source_id };
         let resp = self.inner.drop_source(request).await?;
@@ -231,6 +260,62 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn create_user(&self, user: UserInfo) -> Result<CatalogVersion> {
+        let request = CreateUserRequest { user: Some(user) };
+        let resp = self.inner.create_user(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn drop_user(&self, user_id: u32) -> Result<CatalogVersion> {
+        let request = DropUserRequest { user_id };
+        let resp = self.inner.drop_user(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn update_user(
+        &self,
+        user: UserInfo,
+        update_fields: Vec<UpdateField>,
+    ) -> Result<CatalogVersion> {
+        let request = UpdateUserRequest {
+            user: Some(user),
+            update_fields: update_fields
+                .into_iter()
+                .map(|field| field as i32)
+                .collect::<Vec<_>>(),
+        };
+        let resp = self.inner.update_user(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn grant_privilege(
+        &self,
+        user_ids: Vec<u32>,
+        privileges: Vec<GrantPrivilege>,
+    ) -> Result<CatalogVersion> {
+        let request = GrantPrivilegeRequest {
+            user_ids,
+            privileges,
+        };
+        let resp = self.inner.grant_privilege(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn revoke_privilege(
+        &self,
+        user_ids: Vec<u32>,
+        privileges: Vec<GrantPrivilege>,
+        revoke_grant_option: bool,
+    ) -> Result<CatalogVersion> {
+        let request = RevokePrivilegeRequest {
+            user_ids,
+            privileges,
+            revoke_grant_option,
+        };
+        let resp = self.inner.revoke_privilege(request).await?;
+        Ok(resp.version)
+    }
+
     /// Unregister the current node to the cluster.
     pub async fn unregister(&self, addr: HostAddr) -> Result<()> {
         let request = DeleteWorkerNodeRequest {
@@ -415,6 +500,7 @@ pub struct GrpcMetaClient {
     pub hummock_client: HummockManagerServiceClient<Channel>,
     pub notification_client: NotificationServiceClient<Channel>,
     pub stream_client: StreamManagerServiceClient<Channel>,
+    pub user_client: UserServiceClient<Channel>,
 }
 
 impl GrpcMetaClient {
@@ -431,7 +517,8 @@ impl GrpcMetaClient {
         let ddl_client = DdlServiceClient::new(channel.clone());
         let hummock_client = HummockManagerServiceClient::new(channel.clone());
         let notification_client = NotificationServiceClient::new(channel.clone());
-        let stream_client = StreamManagerServiceClient::new(channel);
+        let stream_client = StreamManagerServiceClient::new(channel.clone());
+        let user_client = UserServiceClient::new(channel);
         Ok(Self {
             cluster_client,
             heartbeat_client,
@@ -439,6 +526,7 @@ impl GrpcMetaClient {
             hummock_client,
             notification_client,
             stream_client,
+            user_client,
         })
     }
 }
@@ -474,11 +562,13 @@ macro_rules! for_all_meta_rpc {
             ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
             ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
             ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
+            ,{ ddl_client, create_sink, CreateSinkRequest, CreateSinkResponse }
             ,{ ddl_client, create_schema, CreateSchemaRequest, CreateSchemaResponse }
             ,{ ddl_client, create_database, CreateDatabaseRequest, CreateDatabaseResponse }
             ,{ ddl_client, drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse }
             ,{ ddl_client, drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse }
             ,{ ddl_client, drop_source, DropSourceRequest, DropSourceResponse }
+            ,{ ddl_client, drop_sink, DropSinkRequest, DropSinkResponse }
             ,{ ddl_client, drop_database, DropDatabaseRequest, DropDatabaseResponse }
             ,{ ddl_client, drop_schema, DropSchemaRequest, DropSchemaResponse }
             ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse }
@@ -492,6 +582,11 @@ macro_rules! for_all_meta_rpc {
             ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse }
             ,{ hummock_client, commit_epoch, CommitEpochRequest, CommitEpochResponse }
             ,{ hummock_client, abort_epoch, AbortEpochRequest, AbortEpochResponse }
+            ,{ user_client, create_user, CreateUserRequest, CreateUserResponse }
+            ,{ user_client, drop_user, DropUserRequest, DropUserResponse }
+            ,{ user_client, update_user, UpdateUserRequest, UpdateUserResponse }
+            ,{ user_client, grant_privilege, GrantPrivilegeRequest, GrantPrivilegeResponse }
+            ,{ user_client, revoke_privilege, RevokePrivilegeRequest, RevokePrivilegeResponse }
         }
     };
 }
@@ -142,16 +142,22 @@ impl StreamSourceReader for ConnectorSourceReader {
             Either::Right(e) => return Err(e),
         };
 
+        self.parser
+            .resolve_schemas(batch.iter().filter_map(|msg| msg.payload.as_deref()))
+            .await?;
+
         let mut events = Vec::with_capacity(batch.len());
         let mut split_offset_mapping: HashMap<String, String> = HashMap::new();
 
         for msg in batch {
-            if let Some(content) = msg.payload {
-                *split_offset_mapping
-                    .entry(msg.split_id.clone())
-                    .or_insert_with(|| "".to_string()) = msg.offset.to_string();
-                events.push(self.parser.parse(content.as_ref(), &self.columns)?);
-            }
+            *split_offset_mapping
+                .entry(msg.split_id.clone())
+                .or_insert_with(|| "".to_string()) = msg.offset.to_string();
+            events.push(self.parser.parse_with_key(
+                msg.key.as_deref(),
+                msg.payload.as_deref(),
+                &self.columns,
+            )?);
         }
         let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
         let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
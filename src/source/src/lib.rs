@@ -60,6 +60,9 @@ pub enum SourceFormat {
     Protobuf,
     DebeziumJson,
     Avro,
+    Csv,
+    DebeziumAvro,
+    UpsertJson,
 }
 
 #[derive(Debug, EnumAsInner)]
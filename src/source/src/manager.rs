@@ -113,6 +113,9 @@ impl SourceManager for MemSourceManager {
             RowFormatType::Protobuf => SourceFormat::Protobuf,
             RowFormatType::DebeziumJson => SourceFormat::DebeziumJson,
             RowFormatType::Avro => SourceFormat::Avro,
+            RowFormatType::Csv => SourceFormat::Csv,
+            RowFormatType::DebeziumAvro => SourceFormat::DebeziumAvro,
+            RowFormatType::UpsertJson => SourceFormat::UpsertJson,
         };
 
         if format == SourceFormat::Protobuf && info.row_schema_location.is_empty() {
@@ -120,15 +123,13 @@ impl SourceManager for MemSourceManager {
                 "protobuf file location not provided".to_string(),
             )));
         }
-        let source_parser_rs =
-            SourceParserImpl::create(&format, &info.properties, info.row_schema_location.as_str())
-                .await;
-        let parser = if let Ok(source_parser) = source_parser_rs {
-            source_parser
-        } else {
-            return Err(source_parser_rs.err().unwrap());
-        };
-
+        if (format == SourceFormat::Avro || format == SourceFormat::DebeziumAvro)
+            && info.row_schema_location.is_empty()
+        {
+            return Err(RwError::from(ProtocolError(
+                "avro schema location not provided".to_string(),
+            )));
+        }
         let columns = info
             .columns
             .iter()
@@ -144,6 +145,8 @@ impl SourceManager for MemSourceManager {
             })
             .collect::<Vec<SourceColumnDesc>>();
 
+        let parser = SourceParserImpl::create(&format, &info, &columns).await?;
+
         assert!(
             info.row_id_index >= 0,
             "expected row_id_index >= 0, got {}",
@@ -151,8 +154,15 @@ impl SourceManager for MemSourceManager {
         );
         let row_id_index = info.row_id_index as usize;
 
-        let config = ConnectorProperties::extract(info.properties)
+        let mut config = ConnectorProperties::extract(info.properties)
             .map_err(|e| RwError::from(ConnectorError(e.to_string())))?;
+        // A CSV record in the files on S3 may span lines inside quoted fields, so the reader needs
+        // the quote to split the records.
+        if let (ConnectorProperties::S3(props), SourceParserImpl::Csv(csv_parser)) =
+            (&mut config, parser.as_ref())
+        {
+            props.csv_quote = Some(csv_parser.quote());
+        }
 
         let source = SourceImpl::Connector(ConnectorSource {
             config,
@@ -282,6 +292,8 @@ mod tests {
             row_id_index: 0,
             pk_column_ids: vec![0],
             columns,
+            use_schema_registry: false,
+            csv_options: None,
         };
         let source_id = TableId::default();
 
@@ -16,9 +16,10 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::future::Future;
 use std::path::Path;
+use std::sync::Arc;
 
 use apache_avro::types::Value;
-use apache_avro::{Reader, Schema};
+use apache_avro::{from_avro_datum, Reader, Schema};
 use chrono::{Datelike, NaiveDate};
 use num_traits::FromPrimitive;
 use risingwave_common::array::Op;
@@ -28,11 +29,14 @@ use risingwave_common::types::{
     DataType, Datum, Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, ScalarImpl,
 };
 use risingwave_connector::aws_utils::{default_conn_config, s3_client, AwsConfigV2};
+use risingwave_pb::plan_common::ColumnDesc;
 use url::Url;
 
+use super::schema_registry::{extract_schema_id, SchemaRegistryClient};
 use crate::{Event, SourceColumnDesc, SourceParser};
 
 const AVRO_SCHEMA_LOCATION_S3_REGION: &str = "region";
+pub(crate) const KAFKA_TOPIC_KEY: &str = "kafka.topic";
 
 pub fn unix_epoch_days() -> i32 {
     NaiveDate::from_ymd(1970, 1, 1).num_days_from_ce()
@@ -40,11 +44,21 @@ pub fn unix_epoch_days() -> i32 {
 
 #[derive(Debug)]
 pub struct AvroParser {
-    schema: Schema,
+    schema: Arc<Schema>,
+    /// The schema registry to resolve the writer schemas of the messages in the Confluent wire
+    /// format. If not set, the messages are Avro object container files.
+    schema_registry: Option<SchemaRegistryClient>,
 }
 
 impl AvroParser {
-    pub async fn new(schema_location: &str, props: HashMap<String, String>) -> Result<Self> {
+    pub async fn new(
+        schema_location: &str,
+        use_schema_registry: bool,
+        props: HashMap<String, String>,
+    ) -> Result<Self> {
+        if use_schema_registry {
+            return Self::new_with_schema_registry(schema_location, props).await;
+        }
         let url = Url::parse(schema_location)
             .map_err(|e| InternalError(format!("failed to parse url ({}): {}", schema_location, e)))
             .unwrap();
@@ -72,11 +86,165 @@ impl AvroParser {
                 )))),
             };
         if let Ok(schema) = arvo_schema {
-            Ok(Self { schema })
+            Ok(Self {
+                schema: Arc::new(schema),
+                schema_registry: None,
+            })
         } else {
             Err(arvo_schema.err().unwrap())
         }
     }
+
+    /// Creates a parser of the messages whose schemas are registered in the registry at `url`,
+    /// under the subject `<topic>-value`. The latest schema of the subject is used as the reader
+    /// schema.
+    async fn new_with_schema_registry(url: &str, props: HashMap<String, String>) -> Result<Self> {
+        let topic = props.get(KAFKA_TOPIC_KEY).ok_or_else(|| {
+            RwError::from(ProtocolError(format!(
+                "Must specify '{}' in WITH clause",
+                KAFKA_TOPIC_KEY
+            )))
+        })?;
+        let schema_registry = SchemaRegistryClient::new(url)?;
+        let schema = schema_registry
+            .get_latest_schema(&format!("{}-value", topic))
+            .await?;
+        Ok(Self {
+            schema,
+            schema_registry: Some(schema_registry),
+        })
+    }
+
+    /// Maps the fields of the Avro record schema to columns.
+    pub fn map_to_columns(&self) -> Result<Vec<ColumnDesc>> {
+        if let Schema::Record { fields, .. } = self.schema.as_ref() {
+            let mut index = 0;
+            fields
+                .iter()
+                .map(|field| avro_field_to_column_desc(&field.name, &field.schema, &mut index))
+                .collect()
+        } else {
+            Err(RwError::from(InternalError(
+                "Avro schema must be a record".to_string(),
+            )))
+        }
+    }
+
+    /// Fetches the writer schemas of the messages in the Confluent wire format from the registry,
+    /// so that they can be decoded by [`SourceParser::parse`] without blocking. Messages not in the
+    /// wire format are skipped here and rejected when parsed.
+    pub async fn resolve_schemas<'a>(
+        &self,
+        payloads: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<()> {
+        if let Some(schema_registry) = &self.schema_registry {
+            for payload in payloads {
+                if let Ok((schema_id, _)) = extract_schema_id(payload) {
+                    schema_registry.get_schema(schema_id).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes a message in the Confluent wire format into a record of the reader schema. The
+    /// writer schema must have been fetched by [`AvroParser::resolve_schemas`].
+    fn decode_with_schema_registry(
+        &self,
+        schema_registry: &SchemaRegistryClient,
+        payload: &[u8],
+    ) -> Result<Value> {
+        let (schema_id, mut datum) = extract_schema_id(payload)?;
+        let writer_schema = schema_registry.get_cached_schema(schema_id)?;
+        from_avro_datum(&writer_schema, &mut datum, Some(&self.schema))
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))
+    }
+}
+
+/// Unwraps a union of `null` and another type, which is how Avro represents a nullable field.
+pub(crate) fn unwrap_nullable(schema: &Schema) -> &Schema {
+    if let Schema::Union(union_schema) = schema {
+        let mut variants = union_schema
+            .variants()
+            .iter()
+            .filter(|s| !matches!(s, Schema::Null));
+        if let (Some(variant), None) = (variants.next(), variants.next()) {
+            return variant;
+        }
+    }
+    schema
+}
+
+// Use avro field to create column_desc, use index to create increment column_id
+pub(crate) fn avro_field_to_column_desc(
+    name: &str,
+    schema: &Schema,
+    index: &mut i32,
+) -> Result<ColumnDesc> {
+    let schema = unwrap_nullable(schema);
+    let data_type = avro_type_mapping(schema)?;
+    if let Schema::Record {
+        name: schema_name,
+        fields,
+        ..
+    } = schema
+    {
+        let field_descs = fields
+            .iter()
+            .map(|f| avro_field_to_column_desc(&f.name, &f.schema, index))
+            .collect::<Result<Vec<_>>>()?;
+        *index += 1;
+        Ok(ColumnDesc {
+            column_id: *index,
+            name: name.to_string(),
+            column_type: Some(data_type.to_protobuf()),
+            field_descs,
+            type_name: schema_name.name.clone(),
+        })
+    } else {
+        *index += 1;
+        Ok(ColumnDesc {
+            column_id: *index,
+            name: name.to_string(),
+            column_type: Some(data_type.to_protobuf()),
+            ..Default::default()
+        })
+    }
+}
+
+/// Maps an Avro type to a DB column type. Nested records are mapped to structs and arrays are
+/// mapped to lists.
+pub(crate) fn avro_type_mapping(schema: &Schema) -> Result<DataType> {
+    let data_type = match unwrap_nullable(schema) {
+        Schema::Boolean => DataType::Boolean,
+        Schema::Int => DataType::Int32,
+        Schema::Long => DataType::Int64,
+        Schema::Float => DataType::Float32,
+        Schema::Double => DataType::Float64,
+        Schema::String => DataType::Varchar,
+        Schema::Date => DataType::Date,
+        Schema::TimestampMillis => DataType::Timestamp,
+        Schema::Record { fields, .. } => {
+            let fields = fields
+                .iter()
+                .map(|f| avro_type_mapping(&f.schema))
+                .collect::<Result<Vec<_>>>()?;
+            DataType::Struct {
+                fields: fields.into(),
+            }
+        }
+        Schema::Array(item) => DataType::List {
+            datatype: Box::new(avro_type_mapping(item)?),
+        },
+        actual_type => {
+            return Err(ErrorCode::NotImplemented(
+                format!("unsupported Avro type: {:?}", actual_type),
+                None.into(),
+            )
+            .into());
+        }
+    };
+    Ok(data_type)
 }
 
 macro_rules! from_avro_datetime {
@@ -178,26 +346,40 @@ pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) ->
     }
 }
 
+/// Extracts the values of `columns` from the fields of an Avro record.
+fn record_to_row(fields: &[(String, Value)], columns: &[SourceColumnDesc]) -> Vec<Datum> {
+    columns
+        .iter()
+        .map(|column| {
+            if column.skip_parse {
+                None
+            } else {
+                let tuple = fields.iter().find(|val| column.name.eq(&val.0)).unwrap();
+                from_avro_value(column, tuple.clone().1).ok()
+            }
+        })
+        .collect::<Vec<Datum>>()
+}
+
 impl SourceParser for AvroParser {
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        if let Some(schema_registry) = &self.schema_registry {
+            return match self.decode_with_schema_registry(schema_registry, payload)? {
+                Value::Record(fields) => Ok(Event {
+                    ops: vec![Op::Insert],
+                    rows: vec![record_to_row(&fields, columns)],
+                }),
+                _ => Err(RwError::from(ProtocolError(
+                    "avro parse error.not a record".to_string(),
+                ))),
+            };
+        }
         let reader_rs = Reader::with_schema(&self.schema, payload);
         if let Ok(reader) = reader_rs {
             let mut rows = Vec::new();
             for record in reader {
                 if let Ok(Value::Record(fields)) = record {
-                    let vals = columns
-                        .iter()
-                        .map(|column| {
-                            if column.skip_parse {
-                                None
-                            } else {
-                                let tuple =
-                                    fields.iter().find(|val| column.name.eq(&val.0)).unwrap();
-                                from_avro_value(column, tuple.clone().1).ok()
-                            }
-                        })
-                        .collect::<Vec<Datum>>();
-                    rows.push(vals);
+                    rows.push(record_to_row(&fields, columns));
                 } else {
                     return Err(RwError::from(ProtocolError(
                         record.err().unwrap().to_string(),
@@ -343,15 +525,20 @@ mod test {
     use std::collections::HashMap;
     use std::env;
     use std::ops::Sub;
+    use std::sync::Arc;
 
     use apache_avro::types::{Record, Value};
-    use apache_avro::{Codec, Schema, Writer};
+    use apache_avro::{to_avro_datum, Codec, Schema, Writer};
     use chrono::NaiveDate;
     use risingwave_common::catalog::ColumnId;
     use risingwave_common::error;
     use risingwave_common::error::ErrorCode::InternalError;
     use risingwave_common::error::RwError;
+    use risingwave_common::test_prelude::*;
     use risingwave_common::types::{DataType, NaiveDateTimeWrapper, NaiveDateWrapper, ScalarImpl};
+    use risingwave_pb::plan_common::ColumnDesc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     use crate::parser::avro_parser::{
         load_schema_async, read_schema_from_local, read_schema_from_s3, unix_epoch_days, AvroParser,
@@ -401,7 +588,7 @@ mod test {
 
     async fn new_avro_parser_from_local(file_name: &str) -> error::Result<AvroParser> {
         let schema_path = "file://".to_owned() + &test_data_path(file_name);
-        AvroParser::new(schema_path.as_str(), HashMap::new()).await
+        AvroParser::new(schema_path.as_str(), false, HashMap::new()).await
     }
 
     #[tokio::test]
@@ -571,6 +758,99 @@ mod test {
         record
     }
 
+    #[tokio::test]
+    async fn test_avro_parser_with_schema_registry() {
+        let schema_content = std::fs::read_to_string(test_data_path("simple-schema.avsc")).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/test-value/versions/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "id": 1, "version": 1, "schema": schema_content }),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "schema": schema_content })),
+            )
+            .mount(&server)
+            .await;
+
+        let props = HashMap::from([("kafka.topic".to_string(), "test".to_string())]);
+        let avro_parser = AvroParser::new(&server.uri(), true, props).await.unwrap();
+
+        // The message is written with the schema of id 1, which is cached when creating parser.
+        let schema = Schema::parse_str(&schema_content).unwrap();
+        let record = build_avro_data(&schema);
+        let mut payload = vec![0, 0, 0, 0, 1];
+        payload.extend(to_avro_datum(&schema, record).unwrap());
+        let columns = build_rw_columns();
+        let event = avro_parser.parse(&payload, &columns).unwrap();
+        assert_eq!(event.rows.len(), 1);
+        assert_eq!(event.rows[0][0], Some(ScalarImpl::Int32(32)));
+        assert_eq!(
+            event.rows[0][2],
+            Some(ScalarImpl::Utf8("str_value".to_string()))
+        );
+
+        // The message written with the schema of id 2 can only be parsed after resolving it.
+        payload[4] = 2;
+        assert!(avro_parser.parse(&payload, &columns).is_err());
+        avro_parser.resolve_schemas([&payload[..]]).await.unwrap();
+        assert_eq!(avro_parser.parse(&payload, &columns).unwrap().rows.len(), 1);
+
+        // Messages not in the confluent wire format are rejected.
+        avro_parser.resolve_schemas([&payload[5..]]).await.unwrap();
+        assert!(avro_parser.parse(&payload[5..], &columns).is_err());
+    }
+
+    #[test]
+    fn test_map_to_columns() {
+        let schema = Schema::parse_str(
+            r#"{
+                "name": "test",
+                "type": "record",
+                "fields": [
+                    {"name": "id", "type": "int"},
+                    {"name": "name", "type": ["null", "string"]},
+                    {"name": "scores", "type": {"type": "array", "items": "double"}},
+                    {"name": "city", "type": {"name": "city", "type": "record", "fields": [
+                        {"name": "address", "type": "string"},
+                        {"name": "zipcode", "type": "long"}
+                    ]}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let avro_parser = AvroParser {
+            schema: Arc::new(schema),
+            schema_registry: None,
+        };
+        let columns = avro_parser.map_to_columns().unwrap();
+        let city = vec![
+            ColumnDesc::new_atomic(DataType::Varchar.to_protobuf(), "address", 4),
+            ColumnDesc::new_atomic(DataType::Int64.to_protobuf(), "zipcode", 5),
+        ];
+        assert_eq!(
+            columns,
+            vec![
+                ColumnDesc::new_atomic(DataType::Int32.to_protobuf(), "id", 1),
+                ColumnDesc::new_atomic(DataType::Varchar.to_protobuf(), "name", 2),
+                ColumnDesc::new_atomic(
+                    DataType::List {
+                        datatype: Box::new(DataType::Float64)
+                    }
+                    .to_protobuf(),
+                    "scores",
+                    3
+                ),
+                ColumnDesc::new_struct("city", 6, "city", city),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_new_avro_parser() {
         let avro_parser_rs = new_avro_parser_from_local("simple-schema.avsc").await;
@@ -0,0 +1,440 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::array::Op;
+use risingwave_common::catalog::ColumnId;
+use risingwave_common::error::ErrorCode::{self, ProtocolError};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_expr::vector_op::cast::{
+    str_parse, str_to_bool, str_to_date, str_to_time, str_to_timestamp, str_to_timestampz,
+};
+use risingwave_pb::catalog::CsvOptions;
+
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+const DEFAULT_DELIMITER: char = ',';
+const DEFAULT_QUOTE: char = '"';
+
+/// A field of a CSV record. A quoted field is never null, even if it equals the null string.
+#[derive(Debug, PartialEq)]
+struct Field {
+    value: String,
+    quoted: bool,
+}
+
+/// Parser for CSV format. A message may contain any number of records separated by newlines, and
+/// the fields of a record are mapped to the columns of the source in order.
+///
+/// Since messages are parsed independently, the header can not be told by its position in the
+/// file. Instead, when `header` is set, the records whose fields are exactly the column names are
+/// skipped.
+///
+/// Malformed records are skipped with a warning naming the record and the column, but not the
+/// payload which may be large or sensitive, so that a bad line does not stop the whole source.
+#[derive(Debug)]
+pub struct CsvParser {
+    delimiter: char,
+    quote: char,
+    header: bool,
+    null_string: String,
+    /// The ids and names of the columns to which the fields of a record are mapped, in order.
+    fields: Vec<(ColumnId, String)>,
+}
+
+impl CsvParser {
+    pub fn new(options: &CsvOptions, columns: &[SourceColumnDesc]) -> Result<Self> {
+        let delimiter = Self::parse_char(&options.delimiter, DEFAULT_DELIMITER, "delimiter")?;
+        let quote = Self::parse_char(&options.quote, DEFAULT_QUOTE, "quote")?;
+        if delimiter == quote || delimiter == '\n' || quote == '\n' {
+            return Err(RwError::from(ProtocolError(format!(
+                "invalid CSV delimiter {:?} and quote {:?}",
+                delimiter, quote
+            ))));
+        }
+        Ok(Self {
+            delimiter,
+            quote,
+            header: options.header,
+            null_string: options.null_string.clone(),
+            fields: columns
+                .iter()
+                .filter(|c| !c.skip_parse)
+                .map(|c| (c.column_id, c.name.clone()))
+                .collect(),
+        })
+    }
+
+    pub fn quote(&self) -> char {
+        self.quote
+    }
+
+    fn parse_char(value: &str, default: char, name: &str) -> Result<char> {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => Ok(default),
+            (Some(c), None) => Ok(c),
+            _ => Err(RwError::from(ProtocolError(format!(
+                "CSV {} must be a single character, got {:?}",
+                name, value
+            )))),
+        }
+    }
+
+    /// Splits the payload into records. Quoted fields may contain delimiters, newlines and quotes
+    /// escaped by doubling them. Blank lines are ignored.
+    fn split_records(&self, payload: &str) -> Result<Vec<Vec<Field>>> {
+        let mut records = vec![];
+        let mut record = vec![];
+        let mut value = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+
+        let mut chars = payload.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c != self.quote {
+                    value.push(c);
+                } else if chars.peek() == Some(&self.quote) {
+                    value.push(c);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else if c == self.quote {
+                in_quotes = true;
+                quoted = true;
+            } else if c == self.delimiter {
+                record.push(Field {
+                    value: std::mem::take(&mut value),
+                    quoted: std::mem::take(&mut quoted),
+                });
+            } else if c == '\n' {
+                if record.is_empty() && value.is_empty() && !quoted {
+                    continue;
+                }
+                record.push(Field {
+                    value: std::mem::take(&mut value),
+                    quoted: std::mem::take(&mut quoted),
+                });
+                records.push(std::mem::take(&mut record));
+            } else if c == '\r' && chars.peek() == Some(&'\n') {
+                continue;
+            } else {
+                value.push(c);
+            }
+        }
+
+        if in_quotes {
+            return Err(RwError::from(ProtocolError(format!(
+                "unterminated quoted field in CSV record {}",
+                records.len() + 1
+            ))));
+        }
+        if !record.is_empty() || !value.is_empty() || quoted {
+            record.push(Field { value, quoted });
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn is_header(&self, record: &[Field]) -> bool {
+        self.header
+            && record.len() == self.fields.len()
+            && record
+                .iter()
+                .zip_eq(self.fields.iter())
+                .all(|(field, (_, name))| field.value.trim() == name)
+    }
+
+    fn parse_record(&self, record: &[Field], columns: &[SourceColumnDesc]) -> Result<Vec<Datum>> {
+        if record.len() != self.fields.len() {
+            return Err(RwError::from(ProtocolError(format!(
+                "expected {} fields, got {}",
+                self.fields.len(),
+                record.len()
+            ))));
+        }
+        columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return Ok(None);
+                }
+                let idx = self
+                    .fields
+                    .iter()
+                    .position(|(id, _)| *id == column.column_id)
+                    .ok_or_else(|| {
+                        RwError::from(ProtocolError(format!(
+                            "column \"{}\" is not in the CSV records",
+                            column.name
+                        )))
+                    })?;
+                let field = &record[idx];
+                if !field.quoted && field.value == self.null_string {
+                    return Ok(None);
+                }
+                parse_field(&field.value, &column.data_type)
+                    .map(Some)
+                    .map_err(|e| {
+                        RwError::from(ProtocolError(format!("column \"{}\": {}", column.name, e)))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Coerces a CSV field to `data_type`, using the same rules as casting from varchar.
+fn parse_field(value: &str, data_type: &DataType) -> Result<ScalarImpl> {
+    if *data_type == DataType::Varchar {
+        return Ok(ScalarImpl::Utf8(value.to_string()));
+    }
+    let value = value.trim();
+    let scalar = match data_type {
+        DataType::Boolean => ScalarImpl::Bool(str_to_bool(value)?),
+        DataType::Int16 => ScalarImpl::Int16(str_parse(value)?),
+        DataType::Int32 => ScalarImpl::Int32(str_parse(value)?),
+        DataType::Int64 => ScalarImpl::Int64(str_parse(value)?),
+        DataType::Float32 => ScalarImpl::Float32(str_parse(value)?),
+        DataType::Float64 => ScalarImpl::Float64(str_parse(value)?),
+        DataType::Decimal => ScalarImpl::Decimal(str_parse(value)?),
+        DataType::Date => ScalarImpl::NaiveDate(str_to_date(value)?),
+        DataType::Time => ScalarImpl::NaiveTime(str_to_time(value)?),
+        DataType::Timestamp => ScalarImpl::NaiveDateTime(str_to_timestamp(value)?),
+        DataType::Timestampz => ScalarImpl::Int64(str_to_timestampz(value)?),
+        DataType::Varchar => unreachable!(),
+        DataType::Interval | DataType::Struct { .. } | DataType::List { .. } => {
+            return Err(ErrorCode::NotImplemented(
+                format!("CSV field of type {:?}", data_type),
+                None.into(),
+            )
+            .into())
+        }
+    };
+    Ok(scalar)
+}
+
+impl SourceParser for CsvParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        let payload = std::str::from_utf8(payload)
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+
+        let records = match self.split_records(payload) {
+            Ok(records) => records,
+            Err(e) => {
+                log::warn!("skip malformed CSV message: {}", e);
+                return Ok(Event::default());
+            }
+        };
+
+        let mut event = Event::default();
+        for (i, record) in records.iter().enumerate() {
+            if self.is_header(record) {
+                continue;
+            }
+            match self.parse_record(record, columns) {
+                Ok(row) => {
+                    event.ops.push(Op::Insert);
+                    event.rows.push(row);
+                }
+                Err(e) => log::warn!("skip malformed CSV record {} of message: {}", i + 1, e),
+            }
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::Decimal;
+    use risingwave_storage::object::parse_object_store;
+
+    use super::*;
+
+    fn columns() -> Vec<SourceColumnDesc> {
+        [
+            ("_row_id", DataType::Int64),
+            ("id", DataType::Int32),
+            ("name", DataType::Varchar),
+            ("price", DataType::Decimal),
+            ("on_sale", DataType::Boolean),
+            ("created_at", DataType::Timestamp),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, data_type))| SourceColumnDesc {
+            name: name.to_string(),
+            data_type,
+            column_id: ColumnId::from(i as i32),
+            skip_parse: i == 0,
+        })
+        .collect()
+    }
+
+    fn options(delimiter: &str, header: bool, null_string: &str) -> CsvOptions {
+        CsvOptions {
+            delimiter: delimiter.to_string(),
+            quote: "".to_string(),
+            header,
+            null_string: null_string.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_split_records() {
+        let parser = CsvParser::new(&options("", false, ""), &columns()).unwrap();
+        let records = parser
+            .split_records("a,\"b,\"\"c\"\"\",\r\n\n\"d\ne\",\"\",f")
+            .unwrap();
+        let values = records
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .map(|f| (f.value.as_str(), f.quoted))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                vec![("a", false), ("b,\"c\"", true), ("", false)],
+                vec![("d\ne", true), ("", true), ("f", false)],
+            ]
+        );
+
+        assert!(parser.split_records("a,\"b").is_err());
+        assert!(CsvParser::new(&options("||", false, ""), &columns()).is_err());
+    }
+
+    #[test]
+    fn test_csv_parser() {
+        let columns = columns();
+        let parser = CsvParser::new(&options("|", true, "NULL"), &columns).unwrap();
+        let payload = b"id|name|price|on_sale|created_at\n\
+            1|apple|1.5|true|2022-06-01 12:00:00\n\
+            2|\"NULL\"|NULL|false|2022-06-02 12:00:00\n\
+            x|banana|2|true|2022-06-03 12:00:00\n\
+            4|orange|3\n";
+        let event = parser.parse(payload, &columns).unwrap();
+
+        // The header and the malformed records are skipped.
+        assert_eq!(event.ops, vec![Op::Insert, Op::Insert]);
+        assert_eq!(
+            event.rows[0],
+            vec![
+                None,
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("apple".to_string())),
+                Some(ScalarImpl::Decimal("1.5".parse::<Decimal>().unwrap())),
+                Some(ScalarImpl::Bool(true)),
+                Some(ScalarImpl::NaiveDateTime(
+                    str_to_timestamp("2022-06-01 12:00:00").unwrap()
+                )),
+            ]
+        );
+        assert_eq!(event.rows[1][2], Some(ScalarImpl::Utf8("NULL".to_string())));
+        assert_eq!(event.rows[1][3], None);
+
+        let records = parser
+            .split_records("x|banana|2|true|2022-06-03 12:00:00\n4|orange|3")
+            .unwrap();
+        let err = parser.parse_record(&records[0], &columns).unwrap_err();
+        assert!(err.to_string().contains("column \"id\""), "{}", err);
+        let err = parser.parse_record(&records[1], &columns).unwrap_err();
+        assert!(
+            err.to_string().contains("expected 5 fields, got 3"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_field() {
+        let cases = [
+            ("t", DataType::Boolean, ScalarImpl::Bool(true)),
+            ("-3", DataType::Int16, ScalarImpl::Int16(-3)),
+            ("42", DataType::Int32, ScalarImpl::Int32(42)),
+            (" 42 ", DataType::Int64, ScalarImpl::Int64(42)),
+            ("1.5", DataType::Float32, ScalarImpl::Float32(1.5f32.into())),
+            ("1.5", DataType::Float64, ScalarImpl::Float64(1.5f64.into())),
+            (
+                "2022-06-01",
+                DataType::Date,
+                ScalarImpl::NaiveDate(str_to_date("2022-06-01").unwrap()),
+            ),
+            (
+                "12:30:00",
+                DataType::Time,
+                ScalarImpl::NaiveTime(str_to_time("12:30:00").unwrap()),
+            ),
+            (
+                "2022-06-01 12:30:00 +00:00",
+                DataType::Timestampz,
+                ScalarImpl::Int64(str_to_timestampz("2022-06-01 12:30:00 +00:00").unwrap()),
+            ),
+            (
+                " a ",
+                DataType::Varchar,
+                ScalarImpl::Utf8(" a ".to_string()),
+            ),
+        ];
+        for (value, data_type, expected) in cases {
+            assert_eq!(parse_field(value, &data_type).unwrap(), expected);
+        }
+        assert!(parse_field("1", &DataType::Interval).is_err());
+        assert!(parse_field("abc", &DataType::Float64).is_err());
+    }
+
+    /// Reads a CSV file back from the disk object store record by record, as the S3 reader
+    /// does.
+    #[tokio::test]
+    async fn test_csv_file_from_object_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = parse_object_store(&format!("disk://{}", dir.path().display()), true).await;
+        store
+            .upload(
+                "data/products.csv",
+                "id,name,price,on_sale,created_at\n\
+                1,apple,1.5,true,2022-06-01 12:00:00\n\
+                2,\"pear,\ngreen\",,false,2022-06-02 12:00:00\n"
+                    .into(),
+            )
+            .await
+            .unwrap();
+        let object = store.read("data/products.csv", None).await.unwrap();
+
+        let columns = columns();
+        let parser = CsvParser::new(&options("", true, ""), &columns).unwrap();
+        let mut rows = vec![];
+        let mut record = vec![];
+        for line in object.split_inclusive(|b| *b == b'\n') {
+            record.extend_from_slice(line);
+            // A newline inside a quoted field belongs to the record.
+            if record.iter().filter(|b| **b == b'"').count() % 2 == 1 {
+                continue;
+            }
+            rows.extend(parser.parse(&record, &columns).unwrap().rows);
+            record.clear();
+        }
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][1], Some(ScalarImpl::Int32(1)));
+        assert_eq!(
+            rows[1][2],
+            Some(ScalarImpl::Utf8("pear,\ngreen".to_string()))
+        );
+        assert_eq!(rows[1][3], None);
+    }
+}
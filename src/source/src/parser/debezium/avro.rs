@@ -0,0 +1,558 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use apache_avro::types::Value;
+use apache_avro::{from_avro_datum, Schema};
+use parking_lot::RwLock;
+use risingwave_common::array::Op;
+use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum};
+use risingwave_pb::plan_common::ColumnDesc;
+
+use super::{DEBEZIUM_CREATE_OP, DEBEZIUM_DELETE_OP, DEBEZIUM_READ_OP, DEBEZIUM_UPDATE_OP};
+use crate::parser::avro_parser::{
+    avro_field_to_column_desc, avro_type_mapping, from_avro_value, unwrap_nullable, KAFKA_TOPIC_KEY,
+};
+use crate::parser::schema_registry::{extract_schema_id, SchemaRegistryClient};
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+const BEFORE: &str = "before";
+const AFTER: &str = "after";
+const OP: &str = "op";
+/// Only in the records of the transaction metadata topic.
+const TRANSACTION_STATUS: &str = "status";
+/// Only in the records of the schema change topic.
+const DDL: &str = "ddl";
+
+/// Parser for Debezium change events in Avro, whose schemas are resolved from the Confluent
+/// schema registry.
+///
+/// - Snapshot reads (`r`) and creates (`c`) are inserts, updates (`u`) are pairs of `UpdateDelete`
+///   and `UpdateInsert`, and deletes (`d`) are deletes.
+/// - Tombstones, which follow the deletes for log compaction, and transaction metadata are skipped.
+/// - The first time a writer schema is seen, it is checked against the columns of the source, so
+///   that a schema change in the upstream table that drops or retypes a column fails the source
+///   with a clear error rather than producing nulls.
+#[derive(Debug)]
+pub struct DebeziumAvroParser {
+    schema_registry: SchemaRegistryClient,
+    /// The writer schemas that have been checked against the columns, by schema id.
+    schemas: RwLock<HashMap<i32, Arc<Schema>>>,
+    columns: Vec<(String, DataType)>,
+}
+
+impl DebeziumAvroParser {
+    pub fn new(schema_registry_url: &str, columns: &[SourceColumnDesc]) -> Result<Self> {
+        Ok(Self {
+            schema_registry: SchemaRegistryClient::new(schema_registry_url)?,
+            schemas: RwLock::new(HashMap::new()),
+            columns: columns
+                .iter()
+                .filter(|c| !c.skip_parse)
+                .map(|c| (c.name.clone(), c.data_type.clone()))
+                .collect(),
+        })
+    }
+
+    /// Maps the fields of the `after` record in the latest envelope schema of the topic, which are
+    /// the columns of the upstream table, to columns.
+    pub async fn map_to_columns(
+        schema_registry_url: &str,
+        props: &HashMap<String, String>,
+    ) -> Result<Vec<ColumnDesc>> {
+        let topic = props.get(KAFKA_TOPIC_KEY).ok_or_else(|| {
+            RwError::from(ProtocolError(format!(
+                "Must specify '{}' in WITH clause",
+                KAFKA_TOPIC_KEY
+            )))
+        })?;
+        let schema = SchemaRegistryClient::new(schema_registry_url)?
+            .get_latest_schema(&format!("{}-value", topic))
+            .await?;
+        let fields = match row_schema(&schema) {
+            Some(Schema::Record { fields, .. }) => fields,
+            _ => {
+                return Err(RwError::from(InternalError(
+                    "Debezium Avro schema must be an envelope of records".to_string(),
+                )))
+            }
+        };
+        let mut index = 0;
+        fields
+            .iter()
+            .map(|field| avro_field_to_column_desc(&field.name, &field.schema, &mut index))
+            .collect()
+    }
+
+    /// Fetches the writer schemas of the messages from the registry, and checks each of them
+    /// against the columns the first time it is seen, so that the messages can be parsed by
+    /// [`SourceParser::parse`] without blocking. Tombstones and messages not in the Confluent wire
+    /// format are skipped here.
+    pub async fn resolve_schemas<'a>(
+        &self,
+        payloads: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<()> {
+        for payload in payloads {
+            let schema_id = match extract_schema_id(payload) {
+                Ok((schema_id, _)) => schema_id,
+                Err(_) => continue,
+            };
+            if self.schemas.read().contains_key(&schema_id) {
+                continue;
+            }
+            let schema = self.schema_registry.get_schema(schema_id).await?;
+            self.check_schema(schema_id, &schema)?;
+            self.schemas.write().insert(schema_id, schema);
+        }
+        Ok(())
+    }
+
+    /// Gets the writer schema of `schema_id`, which must have been resolved by
+    /// [`DebeziumAvroParser::resolve_schemas`].
+    fn get_schema(&self, schema_id: i32) -> Result<Arc<Schema>> {
+        self.schemas.read().get(&schema_id).cloned().ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "writer schema {} is not resolved",
+                schema_id
+            )))
+        })
+    }
+
+    /// Checks that every column of the source is still in the row schema with the same type.
+    /// Schemas without the row schema, like the transaction metadata, are not checked.
+    fn check_schema(&self, schema_id: i32, schema: &Schema) -> Result<()> {
+        let fields = match row_schema(schema) {
+            Some(Schema::Record { fields, .. }) => fields,
+            _ => return Ok(()),
+        };
+        for (name, data_type) in &self.columns {
+            let field = fields.iter().find(|f| &f.name == name).ok_or_else(|| {
+                RwError::from(ProtocolError(format!(
+                    "schema change detected in writer schema {}: column \"{}\" is dropped, \
+                     the source needs to be recreated",
+                    schema_id, name
+                )))
+            })?;
+            let field_type = avro_type_mapping(&field.schema).ok();
+            if field_type.as_ref() != Some(data_type) {
+                return Err(RwError::from(ProtocolError(format!(
+                    "schema change detected in writer schema {}: column \"{}\" is {:?} in the \
+                     source, but {:?} in the schema, the source needs to be recreated",
+                    schema_id, name, data_type, field.schema
+                ))));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the row schema of an envelope, which is the record schema of `before` and `after`.
+/// Debezium defines it in `before` and refers to it by name in `after`.
+fn row_schema(schema: &Schema) -> Option<&Schema> {
+    if let Schema::Record { fields, .. } = schema {
+        fields
+            .iter()
+            .filter(|f| f.name == BEFORE || f.name == AFTER)
+            .map(|f| unwrap_nullable(&f.schema))
+            .find(|s| matches!(s, Schema::Record { .. }))
+    } else {
+        None
+    }
+}
+
+/// Unwraps the value of a nullable field, returning `None` for null.
+fn unwrap_union(value: Value) -> Option<Value> {
+    match value {
+        Value::Union(_, value) => unwrap_union(*value),
+        Value::Null => None,
+        value => Some(value),
+    }
+}
+
+fn take_field(fields: &mut [(String, Value)], name: &str) -> Option<Value> {
+    fields
+        .iter_mut()
+        .find(|(field_name, _)| field_name == name)
+        .and_then(|(_, value)| unwrap_union(std::mem::replace(value, Value::Null)))
+}
+
+fn record_to_row(
+    record: Option<Value>,
+    columns: &[SourceColumnDesc],
+    name: &str,
+) -> Result<Vec<Datum>> {
+    let mut fields = match record {
+        Some(Value::Record(fields)) => fields,
+        _ => {
+            return Err(RwError::from(ProtocolError(format!(
+                "{} is missing or not a record",
+                name
+            ))))
+        }
+    };
+    columns
+        .iter()
+        .map(|column| {
+            if column.skip_parse {
+                return Ok(None);
+            }
+            take_field(&mut fields, &column.name)
+                .map(|value| {
+                    from_avro_value(column, value).map_err(|e| {
+                        RwError::from(ProtocolError(format!(
+                            "failed to convert column \"{}\" of {}: {}",
+                            column.name, name, e
+                        )))
+                    })
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+impl SourceParser for DebeziumAvroParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        // Tombstone.
+        if payload.is_empty() {
+            return Ok(Event::default());
+        }
+
+        let (schema_id, mut datum) = extract_schema_id(payload)?;
+        let schema = self.get_schema(schema_id)?;
+        let mut fields = match from_avro_datum(&schema, &mut datum, None) {
+            Ok(Value::Record(fields)) => fields,
+            Ok(_) => {
+                return Err(RwError::from(ProtocolError(
+                    "Debezium Avro message is not a record".to_string(),
+                )))
+            }
+            Err(e) => return Err(RwError::from(ProtocolError(e.to_string()))),
+        };
+
+        let op = match take_field(&mut fields, OP) {
+            Some(Value::String(op)) => op,
+            _ if fields.iter().any(|(name, _)| name == TRANSACTION_STATUS) => {
+                return Ok(Event::default());
+            }
+            _ if fields.iter().any(|(name, _)| name == DDL) => {
+                return Err(RwError::from(ProtocolError(
+                    "schema change event is not supported, the source needs to be recreated"
+                        .to_string(),
+                )));
+            }
+            _ => {
+                return Err(RwError::from(ProtocolError(
+                    "op is missing in Debezium event".to_string(),
+                )))
+            }
+        };
+
+        match op.as_str() {
+            DEBEZIUM_CREATE_OP | DEBEZIUM_READ_OP => Ok(Event {
+                ops: vec![Op::Insert],
+                rows: vec![record_to_row(
+                    take_field(&mut fields, AFTER),
+                    columns,
+                    AFTER,
+                )?],
+            }),
+            DEBEZIUM_UPDATE_OP => {
+                let before = record_to_row(take_field(&mut fields, BEFORE), columns, BEFORE)?;
+                let after = record_to_row(take_field(&mut fields, AFTER), columns, AFTER)?;
+                // The update does not change any column of the source.
+                if before == after {
+                    return Ok(Event::default());
+                }
+                Ok(Event {
+                    ops: vec![Op::UpdateDelete, Op::UpdateInsert],
+                    rows: vec![before, after],
+                })
+            }
+            DEBEZIUM_DELETE_OP => Ok(Event {
+                ops: vec![Op::Delete],
+                rows: vec![record_to_row(
+                    take_field(&mut fields, BEFORE),
+                    columns,
+                    BEFORE,
+                )?],
+            }),
+            _ => Err(RwError::from(ProtocolError(format!(
+                "unknown debezium op: {}",
+                op
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::to_avro_datum;
+    use apache_avro::types::Record;
+    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::types::ScalarImpl;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const ROW_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Value",
+        "fields": [
+            {"name": "id", "type": "int"},
+            {"name": "name", "type": ["null", "string"]}
+        ]
+    }"#;
+
+    /// The row schema after the upstream table drops the column `name`.
+    const CHANGED_ROW_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Value",
+        "fields": [{"name": "id", "type": "int"}]
+    }"#;
+
+    const TRANSACTION_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "TransactionMetadataValue",
+        "fields": [
+            {"name": "status", "type": "string"},
+            {"name": "id", "type": "string"}
+        ]
+    }"#;
+
+    fn envelope_schema(row_schema: &str) -> String {
+        format!(
+            r#"{{
+                "type": "record",
+                "name": "Envelope",
+                "fields": [
+                    {{"name": "before", "type": ["null", {}], "default": null}},
+                    {{"name": "after", "type": ["null", "Value"], "default": null}},
+                    {{"name": "op", "type": "string"}},
+                    {{"name": "ts_ms", "type": ["null", "long"], "default": null}}
+                ]
+            }}"#,
+            row_schema
+        )
+    }
+
+    fn columns() -> Vec<SourceColumnDesc> {
+        vec![
+            SourceColumnDesc {
+                name: "_row_id".to_string(),
+                data_type: DataType::Int64,
+                column_id: ColumnId::from(0),
+                skip_parse: true,
+            },
+            SourceColumnDesc {
+                name: "id".to_string(),
+                data_type: DataType::Int32,
+                column_id: ColumnId::from(1),
+                skip_parse: false,
+            },
+            SourceColumnDesc {
+                name: "name".to_string(),
+                data_type: DataType::Varchar,
+                column_id: ColumnId::from(2),
+                skip_parse: false,
+            },
+        ]
+    }
+
+    async fn mock_schema(server: &MockServer, id: i32, schema: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/schemas/ids/{}", id)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "schema": schema })),
+            )
+            .mount(server)
+            .await;
+    }
+
+    fn encode(schema_id: i32, schema: &Schema, value: Value) -> Vec<u8> {
+        let mut payload = vec![0];
+        payload.extend(schema_id.to_be_bytes());
+        payload.extend(to_avro_datum(schema, value).unwrap());
+        payload
+    }
+
+    fn row_value(schema: &Schema, id: i32, name: &str) -> Value {
+        let row_schema = row_schema(schema).unwrap();
+        let mut row = Record::new(row_schema).unwrap();
+        row.put("id", id);
+        row.put("name", Some(name.to_string()));
+        row.into()
+    }
+
+    fn event(schema: &Schema, op: &str, before: Option<Value>, after: Option<Value>) -> Value {
+        let mut record = Record::new(schema).unwrap();
+        record.put("before", before);
+        record.put("after", after);
+        record.put("op", op);
+        record.put("ts_ms", Some(1_i64));
+        record.into()
+    }
+
+    /// Resolves the writer schema of the message and parses it, as the source reader does.
+    async fn resolve_and_parse(
+        parser: &DebeziumAvroParser,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+    ) -> Result<Event> {
+        parser.resolve_schemas([payload]).await?;
+        parser.parse(payload, columns)
+    }
+
+    #[tokio::test]
+    async fn test_debezium_avro_parser() {
+        let server = MockServer::start().await;
+        let envelope = Schema::parse_str(&envelope_schema(ROW_SCHEMA)).unwrap();
+        let transaction = Schema::parse_str(TRANSACTION_SCHEMA).unwrap();
+        mock_schema(&server, 1, &envelope_schema(ROW_SCHEMA)).await;
+        mock_schema(&server, 2, TRANSACTION_SCHEMA).await;
+
+        let columns = columns();
+        let parser = DebeziumAvroParser::new(&server.uri(), &columns).unwrap();
+
+        let payload = encode(
+            1,
+            &envelope,
+            event(&envelope, "r", None, Some(row_value(&envelope, 1, "a"))),
+        );
+        // The writer schema is not resolved yet.
+        assert!(parser.parse(&payload, &columns).is_err());
+        let result = resolve_and_parse(&parser, &payload, &columns)
+            .await
+            .unwrap();
+        assert_eq!(result.ops, vec![Op::Insert]);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                None,
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("a".to_string()))
+            ]]
+        );
+
+        let payload = encode(
+            1,
+            &envelope,
+            event(
+                &envelope,
+                "u",
+                Some(row_value(&envelope, 1, "a")),
+                Some(row_value(&envelope, 1, "b")),
+            ),
+        );
+        let result = parser.parse(&payload, &columns).unwrap();
+        assert_eq!(result.ops, vec![Op::UpdateDelete, Op::UpdateInsert]);
+        assert_eq!(result.rows[0][2], Some(ScalarImpl::Utf8("a".to_string())));
+        assert_eq!(result.rows[1][2], Some(ScalarImpl::Utf8("b".to_string())));
+
+        let payload = encode(
+            1,
+            &envelope,
+            event(&envelope, "d", Some(row_value(&envelope, 1, "b")), None),
+        );
+        let result = parser.parse(&payload, &columns).unwrap();
+        assert_eq!(result.ops, vec![Op::Delete]);
+        assert_eq!(result.rows[0][1], Some(ScalarImpl::Int32(1)));
+
+        // Tombstone.
+        let result = parser.parse(&[], &columns).unwrap();
+        assert!(result.ops.is_empty());
+
+        // Transaction metadata.
+        let mut record = Record::new(&transaction).unwrap();
+        record.put("status", "BEGIN");
+        record.put("id", "571");
+        let payload = encode(2, &transaction, record.into());
+        let result = resolve_and_parse(&parser, &payload, &columns)
+            .await
+            .unwrap();
+        assert!(result.ops.is_empty());
+    }
+
+    #[test]
+    fn test_record_to_row_conversion_error() {
+        let columns = columns();
+        let record = Value::Record(vec![
+            ("id".to_string(), Value::String("not an int".to_string())),
+            ("name".to_string(), Value::String("a".to_string())),
+        ]);
+        let err = record_to_row(Some(record), &columns, AFTER).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("failed to convert column \"id\" of after"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debezium_avro_parser_schema_change() {
+        let server = MockServer::start().await;
+        let envelope = Schema::parse_str(&envelope_schema(CHANGED_ROW_SCHEMA)).unwrap();
+        mock_schema(&server, 3, &envelope_schema(CHANGED_ROW_SCHEMA)).await;
+
+        let columns = columns();
+        let parser = DebeziumAvroParser::new(&server.uri(), &columns).unwrap();
+        let mut row = Record::new(row_schema(&envelope).unwrap()).unwrap();
+        row.put("id", 1);
+        let payload = encode(3, &envelope, event(&envelope, "c", None, Some(row.into())));
+        let err = resolve_and_parse(&parser, &payload, &columns)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("schema change detected in writer schema 3: column \"name\" is dropped"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debezium_avro_map_to_columns() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/test-value/versions/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "id": 1, "schema": envelope_schema(ROW_SCHEMA) }),
+            ))
+            .mount(&server)
+            .await;
+
+        let props = HashMap::from([(KAFKA_TOPIC_KEY.to_string(), "test".to_string())]);
+        let columns = DebeziumAvroParser::map_to_columns(&server.uri(), &props)
+            .await
+            .unwrap();
+        let columns = columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    DataType::from(c.column_type.as_ref().unwrap()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![("id", DataType::Int32), ("name", DataType::Varchar)]
+        );
+    }
+}
@@ -24,14 +24,10 @@ use risingwave_common::types::Datum;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::{DEBEZIUM_CREATE_OP, DEBEZIUM_DELETE_OP, DEBEZIUM_READ_OP, DEBEZIUM_UPDATE_OP};
 use crate::parser::common::json_parse_value;
 use crate::{Event, SourceColumnDesc, SourceParser};
 
-const DEBEZIUM_READ_OP: &str = "r";
-const DEBEZIUM_CREATE_OP: &str = "c";
-const DEBEZIUM_UPDATE_OP: &str = "u";
-const DEBEZIUM_DELETE_OP: &str = "d";
-
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebeziumEvent {
@@ -12,6 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use avro::*;
 pub use json::*;
 
+mod avro;
 mod json;
+
+const DEBEZIUM_READ_OP: &str = "r";
+const DEBEZIUM_CREATE_OP: &str = "c";
+const DEBEZIUM_UPDATE_OP: &str = "u";
+const DEBEZIUM_DELETE_OP: &str = "d";
@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+pub use avro_parser::AvroParser;
+pub use csv_parser::CsvParser;
 pub use debezium::*;
 pub use json_parser::*;
 pub use protobuf_parser::*;
@@ -23,16 +24,20 @@ use risingwave_common::array::Op;
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::Datum;
+use risingwave_pb::catalog::StreamSourceInfo;
+pub use upsert_json_parser::UpsertJsonParser;
 
-use crate::parser::avro_parser::AvroParser;
 use crate::{SourceColumnDesc, SourceFormat};
 
 #[allow(dead_code)]
 mod avro_parser;
 mod common;
+mod csv_parser;
 mod debezium;
 mod json_parser;
 mod protobuf_parser;
+mod schema_registry;
+mod upsert_json_parser;
 
 #[derive(Debug, Default)]
 pub struct Event {
@@ -47,6 +52,20 @@ pub struct Event {
 pub trait SourceParser: Send + Sync + Debug + 'static {
     /// parse needs to be a member method because some format like Protobuf needs to be pre-compiled
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event>;
+
+    /// Parses a message along with its key. Parsers of keyed formats, like upsert, override this,
+    /// while the others ignore the key and skip the messages without payload.
+    fn parse_with_key(
+        &self,
+        _key: Option<&[u8]>,
+        payload: Option<&[u8]>,
+        columns: &[SourceColumnDesc],
+    ) -> Result<Event> {
+        match payload {
+            Some(payload) => self.parse(payload, columns),
+            None => Ok(Event::default()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -55,6 +74,9 @@ pub enum SourceParserImpl {
     Protobuf(ProtobufParser),
     DebeziumJson(DebeziumJsonParser),
     Avro(AvroParser),
+    Csv(CsvParser),
+    DebeziumAvro(DebeziumAvroParser),
+    UpsertJson(UpsertJsonParser),
 }
 
 impl SourceParserImpl {
@@ -64,15 +86,50 @@ impl SourceParserImpl {
             Self::Protobuf(parser) => parser.parse(payload, columns),
             Self::DebeziumJson(parser) => parser.parse(payload, columns),
             Self::Avro(avro_parser) => avro_parser.parse(payload, columns),
+            Self::Csv(parser) => parser.parse(payload, columns),
+            Self::DebeziumAvro(parser) => parser.parse(payload, columns),
+            Self::UpsertJson(parser) => parser.parse(payload, columns),
+        }
+    }
+
+    pub fn parse_with_key(
+        &self,
+        key: Option<&[u8]>,
+        payload: Option<&[u8]>,
+        columns: &[SourceColumnDesc],
+    ) -> Result<Event> {
+        match self {
+            Self::Json(parser) => parser.parse_with_key(key, payload, columns),
+            Self::Protobuf(parser) => parser.parse_with_key(key, payload, columns),
+            Self::DebeziumJson(parser) => parser.parse_with_key(key, payload, columns),
+            Self::Avro(avro_parser) => avro_parser.parse_with_key(key, payload, columns),
+            Self::Csv(parser) => parser.parse_with_key(key, payload, columns),
+            Self::DebeziumAvro(parser) => parser.parse_with_key(key, payload, columns),
+            Self::UpsertJson(parser) => parser.parse_with_key(key, payload, columns),
+        }
+    }
+
+    /// Resolves what the parser needs to parse the messages asynchronously, e.g., the writer
+    /// schemas from the schema registry, so that parsing itself never blocks.
+    pub async fn resolve_schemas<'a>(
+        &self,
+        payloads: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<()> {
+        match self {
+            Self::Avro(parser) => parser.resolve_schemas(payloads).await,
+            Self::DebeziumAvro(parser) => parser.resolve_schemas(payloads).await,
+            _ => Ok(()),
         }
     }
 
     pub async fn create(
         format: &SourceFormat,
-        properties: &HashMap<String, String>,
-        schema_location: &str,
+        info: &StreamSourceInfo,
+        columns: &[SourceColumnDesc],
     ) -> Result<Arc<Self>> {
         const PROTOBUF_MESSAGE_KEY: &str = "proto.message";
+        let properties = &info.properties;
+        let schema_location = info.row_schema_location.as_str();
         let parser = match format {
             SourceFormat::Json => SourceParserImpl::Json(JSONParser {}),
             SourceFormat::Protobuf => {
@@ -85,8 +142,23 @@ impl SourceParserImpl {
                 SourceParserImpl::Protobuf(ProtobufParser::new(schema_location, message_name)?)
             }
             SourceFormat::DebeziumJson => SourceParserImpl::DebeziumJson(DebeziumJsonParser {}),
-            SourceFormat::Avro => {
-                SourceParserImpl::Avro(AvroParser::new(schema_location, properties.clone()).await?)
+            SourceFormat::Avro => SourceParserImpl::Avro(
+                AvroParser::new(
+                    schema_location,
+                    info.use_schema_registry,
+                    properties.clone(),
+                )
+                .await?,
+            ),
+            SourceFormat::Csv => SourceParserImpl::Csv(CsvParser::new(
+                &info.csv_options.clone().unwrap_or_default(),
+                columns,
+            )?),
+            SourceFormat::DebeziumAvro => {
+                SourceParserImpl::DebeziumAvro(DebeziumAvroParser::new(schema_location, columns)?)
+            }
+            SourceFormat::UpsertJson => {
+                SourceParserImpl::UpsertJson(UpsertJsonParser::new(&info.pk_column_ids, columns)?)
             }
             _ => {
                 return Err(RwError::from(ProtocolError(
@@ -140,7 +140,7 @@ impl ProtobufParser {
     ) -> Result<ColumnDesc> {
         let field_type = field_descriptor.field_type(descriptors);
         let data_type = protobuf_type_mapping(field_descriptor, descriptors)?;
-        if let FieldType::Message(m) = field_type {
+        if let (FieldType::Message(m), false) = (field_type, field_descriptor.is_repeated()) {
             let column_vec = m
                 .fields()
                 .iter()
@@ -179,17 +179,10 @@ macro_rules! protobuf_match_type {
     };
 }
 
-/// Maps a protobuf field type to a DB column type.
+/// Maps a protobuf field type to a DB column type. Nested messages are mapped to structs and
+/// repeated fields are mapped to lists.
 fn protobuf_type_mapping(f: &FieldDescriptor, descriptors: &Descriptors) -> Result<DataType> {
-    let is_repeated = f.is_repeated();
     let field_type = &f.field_type(descriptors);
-    if is_repeated {
-        return Err(ErrorCode::NotImplemented(
-            "repeated field is not supported".to_string(),
-            None.into(),
-        )
-        .into());
-    }
     let t = match field_type {
         FieldType::Double => DataType::Float64,
         FieldType::Float => DataType::Float32,
@@ -213,7 +206,13 @@ fn protobuf_type_mapping(f: &FieldDescriptor, descriptors: &Descriptors) -> Resu
             .into());
         }
     };
-    Ok(t)
+    if f.is_repeated() {
+        Ok(DataType::List {
+            datatype: Box::new(t),
+        })
+    } else {
+        Ok(t)
+    }
 }
 
 impl SourceParser for ProtobufParser {
@@ -359,6 +358,19 @@ mod tests {
       string zipcode = 2;
     }"#;
 
+    static PROTO_REPEATED_FILE_DATA: &str = r#"
+    syntax = "proto3";
+    package test;
+    message TestRecord {
+      int32 id = 1;
+      repeated string tags = 2;
+      repeated City cities = 3;
+    }
+    message City {
+      string address = 1;
+      string zipcode = 2;
+    }"#;
+
     #[test]
     fn test_proto_message_name() {
         assert_eq!(ProtobufParser::normalize_message_name(""), "".to_string());
@@ -504,4 +516,35 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_map_repeated_to_columns() {
+        let parser = create_parser(PROTO_REPEATED_FILE_DATA).unwrap();
+        let columns = parser.map_to_columns().unwrap();
+        let city = DataType::Struct {
+            fields: vec![DataType::Varchar, DataType::Varchar].into(),
+        };
+        assert_eq!(
+            columns,
+            vec![
+                ColumnDesc::new_atomic(DataType::Int32.to_protobuf(), "id", 1),
+                ColumnDesc::new_atomic(
+                    DataType::List {
+                        datatype: Box::new(DataType::Varchar)
+                    }
+                    .to_protobuf(),
+                    "tags",
+                    2
+                ),
+                ColumnDesc::new_atomic(
+                    DataType::List {
+                        datatype: Box::new(city)
+                    }
+                    .to_protobuf(),
+                    "cities",
+                    3
+                ),
+            ]
+        );
+    }
 }
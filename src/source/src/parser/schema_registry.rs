@@ -0,0 +1,183 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client of the Confluent Schema Registry, which resolves the schemas of the Avro messages in the
+//! Confluent wire format: a magic byte `0`, a 4-byte big-endian schema id and the Avro datum.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use apache_avro::Schema;
+use hyper::body::Buf;
+use hyper::{Client, Uri};
+use parking_lot::RwLock;
+use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
+use risingwave_common::error::{Result, RwError};
+use serde_derive::Deserialize;
+
+/// The magic byte leading the messages in the Confluent wire format.
+const MAGIC_BYTE: u8 = 0;
+
+/// Splits a message in the Confluent wire format into the schema id and the Avro datum.
+pub(crate) fn extract_schema_id(payload: &[u8]) -> Result<(i32, &[u8])> {
+    if payload.len() < 5 || payload[0] != MAGIC_BYTE {
+        return Err(RwError::from(ProtocolError(
+            "message is not in the confluent wire format".to_string(),
+        )));
+    }
+    let schema_id = i32::from_be_bytes(payload[1..5].try_into().unwrap());
+    Ok((schema_id, &payload[5..]))
+}
+
+#[derive(Debug, Deserialize)]
+struct GetSchemaResponse {
+    schema: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetSubjectVersionResponse {
+    id: i32,
+    schema: String,
+}
+
+/// `SchemaRegistryClient` fetches the schemas from the registry and caches them by id, since the
+/// schema of an id never changes.
+#[derive(Debug)]
+pub(crate) struct SchemaRegistryClient {
+    url: String,
+    cache: RwLock<HashMap<i32, Arc<Schema>>>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(url: &str) -> Result<Self> {
+        url.parse::<Uri>().map_err(|e| {
+            RwError::from(ProtocolError(format!(
+                "invalid schema registry url ({}): {}",
+                url, e
+            )))
+        })?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Gets the schema of `id`, fetching it from the registry if it is not cached.
+    pub async fn get_schema(&self, id: i32) -> Result<Arc<Schema>> {
+        if let Some(schema) = self.cache.read().get(&id) {
+            return Ok(schema.clone());
+        }
+        let response: GetSchemaResponse = self.get(&format!("schemas/ids/{}", id)).await?;
+        self.insert(id, &response.schema)
+    }
+
+    /// Gets the schema of `id` from the cache, which must have been fetched by
+    /// [`SchemaRegistryClient::get_schema`] before.
+    pub fn get_cached_schema(&self, id: i32) -> Result<Arc<Schema>> {
+        self.cache
+            .read()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RwError::from(InternalError(format!("schema {} is not resolved", id))))
+    }
+
+    /// Gets the latest schema registered under `subject`.
+    pub async fn get_latest_schema(&self, subject: &str) -> Result<Arc<Schema>> {
+        let response: GetSubjectVersionResponse = self
+            .get(&format!("subjects/{}/versions/latest", subject))
+            .await?;
+        self.insert(response.id, &response.schema)
+    }
+
+    fn insert(&self, id: i32, schema: &str) -> Result<Arc<Schema>> {
+        let schema = Arc::new(Schema::parse_str(schema).map_err(|e| {
+            RwError::from(InternalError(format!(
+                "Avro schema parse error {}",
+                anyhow::Error::from(e)
+            )))
+        })?);
+        self.cache.write().insert(id, schema.clone());
+        Ok(schema)
+    }
+
+    async fn get<T>(&self, path: &str) -> Result<T>
+    where
+        T: for<'a> serde::Deserialize<'a>,
+    {
+        let url: Uri = format!("{}/{}", self.url, path)
+            .parse()
+            .map_err(|e| RwError::from(InternalError(format!("{}", e))))?;
+        let response = Client::new().get(url.clone()).await.map_err(|e| {
+            RwError::from(ProtocolError(format!(
+                "failed to request schema registry {}: {}",
+                url, e
+            )))
+        })?;
+        if !response.status().is_success() {
+            return Err(RwError::from(ProtocolError(format!(
+                "failed to request schema registry {}: {}",
+                url,
+                response.status()
+            ))));
+        }
+        let body = hyper::body::aggregate(response)
+            .await
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+        serde_json::from_reader(body.reader())
+            .map_err(|e| RwError::from(ProtocolError(format!("invalid registry response {}", e))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const SCHEMA: &str =
+        r#"{"type": "record", "name": "test", "fields": [{"name": "id", "type": "int"}]}"#;
+
+    #[test]
+    fn test_extract_schema_id() {
+        let (id, datum) = extract_schema_id(&[0, 0, 0, 1, 2, 42]).unwrap();
+        assert_eq!(id, 258);
+        assert_eq!(datum, &[42]);
+
+        assert!(extract_schema_id(&[1, 0, 0, 1, 2, 42]).is_err());
+        assert!(extract_schema_id(&[0, 0, 0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schema_registry_client() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "schema": SCHEMA })),
+            )
+            // The schema is fetched only once.
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = SchemaRegistryClient::new(&server.uri()).unwrap();
+        let schema = client.get_schema(1).await.unwrap();
+        assert!(matches!(schema.as_ref(), Schema::Record { .. }));
+        client.get_schema(1).await.unwrap();
+        assert!(client.get_cached_schema(1).is_ok());
+        assert!(client.get_schema(2).await.is_err());
+        assert!(client.get_cached_schema(2).is_err());
+    }
+}
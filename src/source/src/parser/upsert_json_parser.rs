@@ -0,0 +1,232 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use risingwave_common::array::Op;
+use risingwave_common::catalog::ColumnId;
+use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::Datum;
+use serde_json::Value;
+
+use crate::parser::common::json_parse_value;
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+/// Parser for the upsert JSON format, where the key of a message holds the primary key of a row in
+/// JSON, and the value holds the whole row, or is empty to delete the row, as in the Kafka
+/// compacted topics.
+///
+/// The parser only emits inserts and deletes keyed by the primary key. It's up to the materialize
+/// executor to turn them into updates or to drop them against the existing rows.
+#[derive(Debug)]
+pub struct UpsertJsonParser {
+    pk_column_ids: HashSet<ColumnId>,
+}
+
+impl UpsertJsonParser {
+    pub fn new(pk_column_ids: &[i32], columns: &[SourceColumnDesc]) -> Result<Self> {
+        let pk_column_ids: HashSet<ColumnId> =
+            pk_column_ids.iter().copied().map(ColumnId::from).collect();
+        if pk_column_ids.is_empty()
+            || columns
+                .iter()
+                .any(|c| c.skip_parse && pk_column_ids.contains(&c.column_id))
+        {
+            return Err(RwError::from(ProtocolError(
+                "upsert json source requires a primary key on the user columns".to_string(),
+            )));
+        }
+        Ok(Self { pk_column_ids })
+    }
+
+    fn parse_json(bytes: &[u8]) -> Result<Value> {
+        serde_json::from_slice(bytes).map_err(|e| RwError::from(ProtocolError(e.to_string())))
+    }
+
+    /// Gets the value of a primary key column from the key. A key with a single primary key column
+    /// may be the bare value rather than an object.
+    fn key_field<'a>(&self, key: &'a Value, column: &SourceColumnDesc) -> Option<&'a Value> {
+        match key {
+            Value::Object(map) => map.get(&column.name),
+            value if self.pk_column_ids.len() == 1 => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Builds a row from the key and the value. Primary key columns are taken from the key if it
+    /// exists, while the other columns are taken from the value, or left null if there's no value.
+    fn build_row(
+        &self,
+        key: Option<&Value>,
+        value: Option<&Value>,
+        columns: &[SourceColumnDesc],
+    ) -> Result<Vec<Datum>> {
+        columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return Ok(None);
+                }
+                if self.pk_column_ids.contains(&column.column_id) {
+                    let field = match key {
+                        Some(key) => self.key_field(key, column),
+                        None => value.and_then(|v| v.get(&column.name)),
+                    };
+                    return json_parse_value(column, field).map(Some).map_err(|_| {
+                        RwError::from(ProtocolError(format!(
+                            "primary key column \"{}\" is missing or invalid in the message",
+                            column.name
+                        )))
+                    });
+                }
+                Ok(value.and_then(|v| json_parse_value(column, v.get(&column.name)).ok()))
+            })
+            .collect()
+    }
+}
+
+impl SourceParser for UpsertJsonParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        self.parse_with_key(None, Some(payload), columns)
+    }
+
+    fn parse_with_key(
+        &self,
+        key: Option<&[u8]>,
+        payload: Option<&[u8]>,
+        columns: &[SourceColumnDesc],
+    ) -> Result<Event> {
+        let key = key.map(Self::parse_json).transpose()?;
+        match payload.filter(|p| !p.is_empty()) {
+            Some(payload) => {
+                let value = Self::parse_json(payload)?;
+                Ok(Event {
+                    ops: vec![Op::Insert],
+                    rows: vec![self.build_row(key.as_ref(), Some(&value), columns)?],
+                })
+            }
+            // A tombstone without key deletes nothing.
+            None if key.is_none() => Ok(Event::default()),
+            None => Ok(Event {
+                ops: vec![Op::Delete],
+                rows: vec![self.build_row(key.as_ref(), None, columns)?],
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::Op;
+    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use crate::{SourceColumnDesc, SourceParser, UpsertJsonParser};
+
+    fn columns() -> Vec<SourceColumnDesc> {
+        vec![
+            SourceColumnDesc {
+                name: "_row_id".to_string(),
+                data_type: DataType::Int64,
+                column_id: ColumnId::from(0),
+                skip_parse: true,
+            },
+            SourceColumnDesc {
+                name: "id".to_string(),
+                data_type: DataType::Int32,
+                column_id: ColumnId::from(1),
+                skip_parse: false,
+            },
+            SourceColumnDesc {
+                name: "name".to_string(),
+                data_type: DataType::Varchar,
+                column_id: ColumnId::from(2),
+                skip_parse: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_upsert_json_parser() {
+        let columns = columns();
+        let parser = UpsertJsonParser::new(&[1], &columns).unwrap();
+
+        let event = parser
+            .parse_with_key(
+                Some(br#"{"id": 1}"#.as_ref()),
+                Some(br#"{"id": 1, "name": "foo"}"#.as_ref()),
+                &columns,
+            )
+            .unwrap();
+        assert_eq!(event.ops, vec![Op::Insert]);
+        assert_eq!(
+            event.rows,
+            vec![vec![
+                None,
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("foo".to_string()))
+            ]]
+        );
+
+        // The primary key is taken from the key, which may be a bare value.
+        let event = parser
+            .parse_with_key(
+                Some(b"2".as_ref()),
+                Some(br#"{"name": "bar"}"#.as_ref()),
+                &columns,
+            )
+            .unwrap();
+        assert_eq!(
+            event.rows,
+            vec![vec![
+                None,
+                Some(ScalarImpl::Int32(2)),
+                Some(ScalarImpl::Utf8("bar".to_string()))
+            ]]
+        );
+
+        // Tombstones delete the row of the key.
+        for payload in [None, Some(b"".as_ref())] {
+            let event = parser
+                .parse_with_key(Some(br#"{"id": 1}"#.as_ref()), payload, &columns)
+                .unwrap();
+            assert_eq!(event.ops, vec![Op::Delete]);
+            assert_eq!(
+                event.rows,
+                vec![vec![None, Some(ScalarImpl::Int32(1)), None]]
+            );
+        }
+        let event = parser.parse_with_key(None, None, &columns).unwrap();
+        assert!(event.ops.is_empty());
+
+        // Without key, the primary key must be in the value.
+        let event = parser
+            .parse(br#"{"id": 3, "name": "baz"}"#, &columns)
+            .unwrap();
+        assert_eq!(event.rows[0][1], Some(ScalarImpl::Int32(3)));
+        assert!(parser.parse(br#"{"name": "baz"}"#, &columns).is_err());
+        assert!(parser
+            .parse_with_key(Some(br#"{"id": 1"#.as_ref()), None, &columns)
+            .is_err());
+    }
+
+    #[test]
+    fn test_upsert_json_parser_requires_pk() {
+        let columns = columns();
+        assert!(UpsertJsonParser::new(&[], &columns).is_err());
+        // The row id column can't be the primary key of an upsert source.
+        assert!(UpsertJsonParser::new(&[0], &columns).is_err());
+    }
+}
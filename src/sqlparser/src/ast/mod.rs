@@ -296,6 +296,8 @@ pub enum Expr {
     Array(Vec<Expr>),
     /// An array index expression e.g. `(ARRAY[1, 2])[1]` or `(current_schemas(FALSE))[1]`
     ArrayIndex { obj: Box<Expr>, indexs: Vec<Expr> },
+    /// A positional parameter of a prepared statement, e.g. `$1`
+    Parameter { index: u64 },
 }
 
 impl fmt::Display for Expr {
@@ -477,6 +479,7 @@ impl fmt::Display for Expr {
                     .as_slice()
                     .join(", ")
             ),
+            Expr::Parameter { index } => write!(f, "${}", index),
         }
     }
 }
@@ -734,6 +737,9 @@ pub enum Statement {
         with_options: Vec<SqlOption>,
         query: Option<Box<Query>>,
         like: Option<ObjectName>,
+        /// The row format of a table fed by a connector, i.e., `ROW FORMAT ...` after
+        /// `WITH (connector = ...)`
+        source_schema: Option<SourceSchema>,
     },
     /// CREATE INDEX
     CreateIndex {
@@ -749,12 +755,18 @@ pub enum Statement {
         is_materialized: bool,
         stmt: CreateSourceStatement,
     },
+    /// CREATE SINK
+    CreateSink { stmt: CreateSinkStatement },
+    /// CREATE USER
+    CreateUser(CreateUserStatement),
     /// ALTER TABLE
     AlterTable {
         /// Table name
         name: ObjectName,
         operation: AlterTableOperation,
     },
+    /// ALTER USER
+    AlterUser(AlterUserStatement),
     /// DESCRIBE TABLE OR SOURCE
     Describe {
         /// Table or Source name
@@ -827,6 +839,7 @@ pub enum Statement {
     },
     /// REVOKE privileges ON objects FROM grantees
     Revoke {
+        revoke_grant_option: bool,
         privileges: Privileges,
         objects: GrantObjects,
         grantees: Vec<Ident>,
@@ -1029,6 +1042,7 @@ impl fmt::Display for Statement {
                 temporary,
                 query,
                 like,
+                source_schema,
             } => {
                 // We want to allow the following options
                 // Empty column list, allowed by PostgreSQL:
@@ -1061,6 +1075,9 @@ impl fmt::Display for Statement {
                 if !with_options.is_empty() {
                     write!(f, " WITH ({})", display_comma_separated(with_options))?;
                 }
+                if let Some(source_schema) = source_schema {
+                    write!(f, " ROW FORMAT {}", source_schema)?;
+                }
                 if let Some(query) = query {
                     write!(f, " AS {}", query)?;
                 }
@@ -1094,9 +1111,12 @@ impl fmt::Display for Statement {
                     ""
                 }
             ),
+            Statement::CreateSink { stmt } => write!(f, "CREATE SINK {}", stmt),
+            Statement::CreateUser(statement) => write!(f, "CREATE USER {}", statement),
             Statement::AlterTable { name, operation } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
+            Statement::AlterUser(statement) => write!(f, "ALTER USER {}", statement),
             Statement::Drop(stmt) => write!(f, "DROP {}", stmt),
             Statement::SetVariable {
                 local,
@@ -1184,13 +1204,18 @@ impl fmt::Display for Statement {
                 Ok(())
             }
             Statement::Revoke {
+                revoke_grant_option,
                 privileges,
                 objects,
                 grantees,
                 granted_by,
                 cascade,
             } => {
-                write!(f, "REVOKE {} ", privileges)?;
+                write!(f, "REVOKE ")?;
+                if *revoke_grant_option {
+                    write!(f, "GRANT OPTION FOR ")?;
+                }
+                write!(f, "{} ", privileges)?;
                 write!(f, "ON {} ", objects)?;
                 write!(f, "FROM {}", display_comma_separated(grantees))?;
                 if let Some(grantor) = granted_by {
@@ -1355,6 +1380,8 @@ pub enum GrantObjects {
     AllSequencesInSchema { schemas: Vec<ObjectName> },
     /// Grant privileges on `ALL TABLES IN SCHEMA <schema_name> [, ...]`
     AllTablesInSchema { schemas: Vec<ObjectName> },
+    /// Grant privileges on specific databases
+    Databases(Vec<ObjectName>),
     /// Grant privileges on specific schemas
     Schemas(Vec<ObjectName>),
     /// Grant privileges on specific sequences
@@ -1369,6 +1396,9 @@ impl fmt::Display for GrantObjects {
             GrantObjects::Sequences(sequences) => {
                 write!(f, "SEQUENCE {}", display_comma_separated(sequences))
             }
+            GrantObjects::Databases(databases) => {
+                write!(f, "DATABASE {}", display_comma_separated(databases))
+            }
             GrantObjects::Schemas(schemas) => {
                 write!(f, "SCHEMA {}", display_comma_separated(schemas))
             }
@@ -1486,7 +1516,9 @@ pub enum ObjectType {
     Schema,
     Source,
     MaterializedSource,
+    Sink,
     Database,
+    User,
 }
 
 impl fmt::Display for ObjectType {
@@ -1499,7 +1531,9 @@ impl fmt::Display for ObjectType {
             ObjectType::Schema => "SCHEMA",
             ObjectType::Source => "SOURCE",
             ObjectType::MaterializedSource => "MATERIALIZED SOURCE",
+            ObjectType::Sink => "SINK",
             ObjectType::Database => "DATABASE",
+            ObjectType::User => "USER",
         })
     }
 }
@@ -1516,15 +1550,19 @@ impl ParseTo for ObjectType {
             ObjectType::MaterializedSource
         } else if parser.parse_keyword(Keyword::SOURCE) {
             ObjectType::Source
+        } else if parser.parse_keyword(Keyword::SINK) {
+            ObjectType::Sink
         } else if parser.parse_keyword(Keyword::INDEX) {
             ObjectType::Index
         } else if parser.parse_keyword(Keyword::SCHEMA) {
             ObjectType::Schema
         } else if parser.parse_keyword(Keyword::DATABASE) {
             ObjectType::Database
+        } else if parser.parse_keyword(Keyword::USER) {
+            ObjectType::User
         } else {
             return parser.expected(
-                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, or SCHEMA after DROP",
+                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SINK, SCHEMA, DATABASE or USER after DROP",
                 parser.peek_token(),
             );
         };
@@ -20,9 +20,12 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use super::ObjectType;
-use crate::ast::{display_comma_separated, ColumnDef, ObjectName, SqlOption, TableConstraint};
+use crate::ast::{
+    display_comma_separated, display_separated, ColumnDef, ObjectName, SqlOption, TableConstraint,
+};
 use crate::keywords::Keyword;
 use crate::parser::{Parser, ParserError};
+use crate::tokenizer::Token;
 
 /// Consumes token from the parser into an AST node.
 pub trait ParseTo: Sized {
@@ -83,7 +86,15 @@ pub struct CreateSourceStatement {
 pub enum SourceSchema {
     Protobuf(ProtobufSchema),
     // Keyword::PROTOBUF ProtobufSchema
-    Json, // Keyword::JSON
+    Json,         // Keyword::JSON
+    DebeziumJson, // Keyword::DEBEZIUM_JSON
+    Avro(AvroSchema),
+    // Keyword::AVRO AvroSchema
+    DebeziumAvro(AvroSchema),
+    // Keyword::DEBEZIUM_AVRO AvroSchema
+    Csv(CsvSchema),
+    // Keyword::CSV CsvSchema
+    UpsertJson, // Keyword::UPSERT_JSON
 }
 
 impl ParseTo for SourceSchema {
@@ -93,9 +104,23 @@ impl ParseTo for SourceSchema {
         } else if p.parse_keywords(&[Keyword::PROTOBUF]) {
             impl_parse_to!(protobuf_schema: ProtobufSchema, p);
             SourceSchema::Protobuf(protobuf_schema)
+        } else if p.parse_keywords(&[Keyword::DEBEZIUM_JSON]) {
+            SourceSchema::DebeziumJson
+        } else if p.parse_keywords(&[Keyword::AVRO]) {
+            impl_parse_to!(avro_schema: AvroSchema, p);
+            SourceSchema::Avro(avro_schema)
+        } else if p.parse_keywords(&[Keyword::DEBEZIUM_AVRO]) {
+            impl_parse_to!(avro_schema: AvroSchema, p);
+            SourceSchema::DebeziumAvro(avro_schema)
+        } else if p.parse_keywords(&[Keyword::CSV]) {
+            impl_parse_to!(csv_schema: CsvSchema, p);
+            SourceSchema::Csv(csv_schema)
+        } else if p.parse_keywords(&[Keyword::UPSERT_JSON]) {
+            SourceSchema::UpsertJson
         } else {
             return Err(ParserError::ParserError(
-                "expected JSON | PROTOBUF after ROW FORMAT".to_string(),
+                "expected JSON | PROTOBUF | DEBEZIUM_JSON | AVRO | DEBEZIUM_AVRO | CSV | UPSERT_JSON after ROW FORMAT"
+                    .to_string(),
             ));
         };
         Ok(schema)
@@ -107,6 +132,11 @@ impl fmt::Display for SourceSchema {
         match self {
             SourceSchema::Protobuf(protobuf_schema) => write!(f, "PROTOBUF {}", protobuf_schema),
             SourceSchema::Json => write!(f, "JSON"),
+            SourceSchema::DebeziumJson => write!(f, "DEBEZIUM_JSON"),
+            SourceSchema::Avro(avro_schema) => write!(f, "AVRO {}", avro_schema),
+            SourceSchema::DebeziumAvro(avro_schema) => write!(f, "DEBEZIUM_AVRO {}", avro_schema),
+            SourceSchema::Csv(csv_schema) => write!(f, "CSV{}", csv_schema),
+            SourceSchema::UpsertJson => write!(f, "UPSERT_JSON"),
         }
     }
 }
@@ -148,6 +178,75 @@ impl fmt::Display for ProtobufSchema {
     }
 }
 
+// sql_grammar!(AvroSchema {
+//     [Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION],
+//     use_schema_registry => [Keyword::CONFLUENT, Keyword::SCHEMA, Keyword::REGISTRY],
+//     row_schema_location: AstString,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AvroSchema {
+    /// The location of the schema file, or the url of the schema registry if
+    /// `use_schema_registry` is set.
+    pub row_schema_location: AstString,
+    pub use_schema_registry: bool,
+}
+
+impl ParseTo for AvroSchema {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!([Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION], p);
+        impl_parse_to!(
+            use_schema_registry => [Keyword::CONFLUENT, Keyword::SCHEMA, Keyword::REGISTRY],
+            p
+        );
+        impl_parse_to!(row_schema_location: AstString, p);
+        Ok(Self {
+            row_schema_location,
+            use_schema_registry,
+        })
+    }
+}
+
+impl fmt::Display for AvroSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!([Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION], v);
+        impl_fmt_display!(
+            use_schema_registry => [Keyword::CONFLUENT, Keyword::SCHEMA, Keyword::REGISTRY],
+            v,
+            self
+        );
+        impl_fmt_display!(row_schema_location, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
+/// The options of the CSV row format, e.g. `CSV (delimiter = '|', header = true)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CsvSchema(pub Vec<SqlOption>);
+
+impl ParseTo for CsvSchema {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        if !p.consume_token(&Token::LParen) {
+            return Ok(Self(vec![]));
+        }
+        let options = p.parse_comma_separated(Parser::parse_sql_option)?;
+        p.expect_token(&Token::RParen)?;
+        Ok(Self(options))
+    }
+}
+
+impl fmt::Display for CsvSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, " ({})", display_comma_separated(self.0.as_slice()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 impl ParseTo for CreateSourceStatement {
     fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
         impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
@@ -182,6 +281,50 @@ impl fmt::Display for CreateSourceStatement {
     }
 }
 
+// sql_grammar!(CreateSinkStatement {
+//     if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS],
+//     sink_name: Ident,
+//     [Keyword::FROM],
+//     materialized_view: Ident,
+//     with_properties: AstOption<WithProperties>,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateSinkStatement {
+    pub if_not_exists: bool,
+    pub sink_name: ObjectName,
+    pub materialized_view: ObjectName,
+    pub with_properties: WithProperties,
+}
+
+impl ParseTo for CreateSinkStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
+        impl_parse_to!(sink_name: ObjectName, p);
+        impl_parse_to!([Keyword::FROM], p);
+        impl_parse_to!(materialized_view: ObjectName, p);
+        impl_parse_to!(with_properties: WithProperties, p);
+        Ok(Self {
+            if_not_exists,
+            sink_name,
+            materialized_view,
+            with_properties,
+        })
+    }
+}
+
+impl fmt::Display for CreateSinkStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
+        impl_fmt_display!(sink_name, v, self);
+        impl_fmt_display!([Keyword::FROM], v);
+        impl_fmt_display!(materialized_view, v, self);
+        impl_fmt_display!(with_properties, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AstVec<T>(pub Vec<T>);
@@ -347,6 +490,190 @@ impl fmt::Display for DropStatement {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UserOption {
+    SuperUser,
+    NoSuperUser,
+    CreateDB,
+    NoCreateDB,
+    Login,
+    NoLogin,
+    EncryptedPassword(AstString),
+    Password(Option<AstString>),
+}
+
+impl fmt::Display for UserOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserOption::SuperUser => write!(f, "SUPERUSER"),
+            UserOption::NoSuperUser => write!(f, "NOSUPERUSER"),
+            UserOption::CreateDB => write!(f, "CREATEDB"),
+            UserOption::NoCreateDB => write!(f, "NOCREATEDB"),
+            UserOption::Login => write!(f, "LOGIN"),
+            UserOption::NoLogin => write!(f, "NOLOGIN"),
+            UserOption::EncryptedPassword(p) => write!(f, "ENCRYPTED PASSWORD {}", p),
+            UserOption::Password(None) => write!(f, "PASSWORD NULL"),
+            UserOption::Password(Some(p)) => write!(f, "PASSWORD {}", p),
+        }
+    }
+}
+
+/// Options of `CREATE USER` and `ALTER USER`, see
+/// <https://www.postgresql.org/docs/current/sql-createuser.html>.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UserOptions(pub Vec<UserOption>);
+
+impl ParseTo for UserOptions {
+    fn parse_to(parser: &mut Parser) -> Result<Self, ParserError> {
+        let _ = parser.parse_keyword(Keyword::WITH);
+        let mut options = vec![];
+        // Each kind of option can be specified at most once.
+        let (mut super_user, mut create_db, mut login, mut password) = (false, false, false, false);
+        loop {
+            let keyword = match parser.parse_one_of_keywords(&[
+                Keyword::SUPERUSER,
+                Keyword::NOSUPERUSER,
+                Keyword::CREATEDB,
+                Keyword::NOCREATEDB,
+                Keyword::LOGIN,
+                Keyword::NOLOGIN,
+                Keyword::PASSWORD,
+                Keyword::ENCRYPTED,
+            ]) {
+                Some(keyword) => keyword,
+                None => break,
+            };
+            let (option, specified) = match keyword {
+                Keyword::SUPERUSER => (UserOption::SuperUser, &mut super_user),
+                Keyword::NOSUPERUSER => (UserOption::NoSuperUser, &mut super_user),
+                Keyword::CREATEDB => (UserOption::CreateDB, &mut create_db),
+                Keyword::NOCREATEDB => (UserOption::NoCreateDB, &mut create_db),
+                Keyword::LOGIN => (UserOption::Login, &mut login),
+                Keyword::NOLOGIN => (UserOption::NoLogin, &mut login),
+                Keyword::ENCRYPTED => {
+                    parser.expect_keyword(Keyword::PASSWORD)?;
+                    let password_str = AstString::parse_to(parser)?;
+                    (UserOption::EncryptedPassword(password_str), &mut password)
+                }
+                Keyword::PASSWORD => {
+                    let option = if parser.parse_keyword(Keyword::NULL) {
+                        UserOption::Password(None)
+                    } else {
+                        UserOption::Password(Some(AstString::parse_to(parser)?))
+                    };
+                    (option, &mut password)
+                }
+                _ => unreachable!(),
+            };
+            if *specified {
+                return Err(ParserError::ParserError(
+                    "conflicting or redundant options".to_string(),
+                ));
+            }
+            *specified = true;
+            options.push(option);
+        }
+        Ok(Self(options))
+    }
+}
+
+impl fmt::Display for UserOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, "WITH {}", display_separated(self.0.as_slice(), " "))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// sql_grammar!(CreateUserStatement {
+//     user_name: ObjectName,
+//     with_options: UserOptions,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateUserStatement {
+    pub user_name: ObjectName,
+    pub with_options: UserOptions,
+}
+
+impl ParseTo for CreateUserStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(user_name: ObjectName, p);
+        impl_parse_to!(with_options: UserOptions, p);
+        Ok(Self {
+            user_name,
+            with_options,
+        })
+    }
+}
+
+impl fmt::Display for CreateUserStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(user_name, v, self);
+        impl_fmt_display!(with_options, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterUserMode {
+    Options(UserOptions),
+    Rename(ObjectName),
+}
+
+impl fmt::Display for AlterUserMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterUserMode::Options(options) => options.fmt(f),
+            AlterUserMode::Rename(new_name) => write!(f, "RENAME TO {}", new_name),
+        }
+    }
+}
+
+// sql_grammar!(AlterUserStatement {
+//     user_name: ObjectName,
+//     mode: AlterUserMode,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlterUserStatement {
+    pub user_name: ObjectName,
+    pub mode: AlterUserMode,
+}
+
+impl ParseTo for AlterUserStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(user_name: ObjectName, p);
+        let mode = if p.parse_keyword(Keyword::RENAME) {
+            p.expect_keyword(Keyword::TO)?;
+            impl_parse_to!(new_name: ObjectName, p);
+            AlterUserMode::Rename(new_name)
+        } else {
+            impl_parse_to!(with_options: UserOptions, p);
+            if with_options.0.is_empty() {
+                return p.expected("user options or RENAME TO", p.peek_token());
+            }
+            AlterUserMode::Options(with_options)
+        };
+        Ok(Self { user_name, mode })
+    }
+}
+
+impl fmt::Display for AlterUserStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(user_name, v, self);
+        impl_fmt_display!(mode, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DropMode {
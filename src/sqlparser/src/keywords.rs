@@ -130,6 +130,7 @@ define_keywords!(
     COMMIT,
     COMMITTED,
     CONDITION,
+    CONFLUENT,
     CONNECT,
     CONSTRAINT,
     CONTAINS,
@@ -141,6 +142,7 @@ define_keywords!(
     COVAR_POP,
     COVAR_SAMP,
     CREATE,
+    CREATEDB,
     CROSS,
     CSV,
     CUBE,
@@ -165,6 +167,8 @@ define_keywords!(
     DATE,
     DAY,
     DEALLOCATE,
+    DEBEZIUM_AVRO,
+    DEBEZIUM_JSON,
     DEC,
     DECIMAL,
     DECLARE,
@@ -184,6 +188,7 @@ define_keywords!(
     EACH,
     ELEMENT,
     ELSE,
+    ENCRYPTED,
     END,
     END_EXEC = "END-EXEC",
     END_FRAME,
@@ -266,6 +271,7 @@ define_keywords!(
     LOCALTIME,
     LOCALTIMESTAMP,
     LOCATION,
+    LOGIN,
     LOWER,
     MATCH,
     MATERIALIZED,
@@ -288,9 +294,12 @@ define_keywords!(
     NEW,
     NEXT,
     NO,
+    NOCREATEDB,
+    NOLOGIN,
     NONE,
     NORMALIZE,
     NOSCAN,
+    NOSUPERUSER,
     NOT,
     NTH_VALUE,
     NTILE,
@@ -321,6 +330,7 @@ define_keywords!(
     PARTITION,
     PARTITIONED,
     PARTITIONS,
+    PASSWORD,
     PERCENT,
     PERCENTILE_CONT,
     PERCENTILE_DISC,
@@ -350,6 +360,7 @@ define_keywords!(
     REFERENCES,
     REFERENCING,
     REGCLASS,
+    REGISTRY,
     REGR_AVGX,
     REGR_AVGY,
     REGR_COUNT,
@@ -396,6 +407,7 @@ define_keywords!(
     SETS,
     SHOW,
     SIMILAR,
+    SINK,
     SMALLINT,
     SNAPSHOT,
     SOME,
@@ -423,6 +435,7 @@ define_keywords!(
     SUBSTRING_REGEX,
     SUCCEEDS,
     SUM,
+    SUPERUSER,
     SYMMETRIC,
     SYNC,
     SYSTEM,
@@ -467,6 +480,7 @@ define_keywords!(
     UNNEST,
     UPDATE,
     UPPER,
+    UPSERT_JSON,
     USAGE,
     USER,
     USING,
@@ -466,6 +466,10 @@ impl Parser {
                 self.prev_token();
                 Ok(Expr::Value(self.parse_value()?))
             }
+            Token::Parameter(s) => match s.parse::<u64>() {
+                Ok(index) if index > 0 => Ok(Expr::Parameter { index }),
+                _ => parser_err!(format!("Invalid parameter ${}", s)),
+            },
 
             Token::LParen => {
                 let expr =
@@ -1378,6 +1382,8 @@ impl Parser {
             self.parse_create_source(false, or_replace)
         } else if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::SOURCE]) {
             self.parse_create_source(true, or_replace)
+        } else if self.parse_keyword(Keyword::SINK) {
+            self.parse_create_sink(or_replace)
         } else if or_replace {
             self.expected(
                 "[EXTERNAL] TABLE or [MATERIALIZED] VIEW after CREATE OR REPLACE",
@@ -1391,11 +1397,17 @@ impl Parser {
             self.parse_create_schema()
         } else if self.parse_keyword(Keyword::DATABASE) {
             self.parse_create_database()
+        } else if self.parse_keyword(Keyword::USER) {
+            self.parse_create_user()
         } else {
             self.expected("an object type after CREATE", self.peek_token())
         }
     }
 
+    fn parse_create_user(&mut self) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateUser(CreateUserStatement::parse_to(self)?))
+    }
+
     pub fn parse_create_schema(&mut self) -> Result<Statement, ParserError> {
         let if_not_exists = self.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
         let schema_name = self.parse_object_name()?;
@@ -1458,6 +1470,19 @@ impl Parser {
         })
     }
 
+    // CREATE [OR REPLACE]?
+    // SINK
+    // [IF NOT EXISTS]?
+    // <sink_name: Ident>
+    // FROM
+    // <materialized_view: Ident>
+    // [WITH (properties)]?
+    pub fn parse_create_sink(&mut self, _or_replace: bool) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateSink {
+            stmt: CreateSinkStatement::parse_to(self)?,
+        })
+    }
+
     fn parse_with_properties(&mut self) -> Result<Vec<SqlOption>, ParserError> {
         Ok(self.parse_options(Keyword::WITH)?.to_vec())
     }
@@ -1501,6 +1526,12 @@ impl Parser {
         // PostgreSQL supports `WITH ( options )`, before `AS`
         let with_options = self.parse_with_properties()?;
         let table_properties = self.parse_options(Keyword::TBLPROPERTIES)?;
+        // Parse optional `ROW FORMAT ...` of a table fed by a connector
+        let source_schema = if self.parse_keywords(&[Keyword::ROW, Keyword::FORMAT]) {
+            Some(SourceSchema::parse_to(self)?)
+        } else {
+            None
+        };
         // Parse optional `AS ( query )`
         let query = if self.parse_keyword(Keyword::AS) {
             Some(Box::new(self.parse_query()?))
@@ -1519,6 +1550,7 @@ impl Parser {
             if_not_exists,
             query,
             like,
+            source_schema,
         })
     }
 
@@ -1733,8 +1765,17 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword(Keyword::TABLE)?;
-        self.parse_alter_table()
+        if self.parse_keyword(Keyword::TABLE) {
+            self.parse_alter_table()
+        } else if self.parse_keyword(Keyword::USER) {
+            self.parse_alter_user()
+        } else {
+            self.expected("TABLE or USER after ALTER", self.peek_token())
+        }
+    }
+
+    pub fn parse_alter_user(&mut self) -> Result<Statement, ParserError> {
+        Ok(Statement::AlterUser(AlterUserStatement::parse_to(self)?))
     }
 
     pub fn parse_alter_table(&mut self) -> Result<Statement, ParserError> {
@@ -2846,10 +2887,15 @@ impl Parser {
                 schemas: self.parse_comma_separated(Parser::parse_object_name)?,
             }
         } else {
-            let object_type =
-                self.parse_one_of_keywords(&[Keyword::SEQUENCE, Keyword::SCHEMA, Keyword::TABLE]);
+            let object_type = self.parse_one_of_keywords(&[
+                Keyword::SEQUENCE,
+                Keyword::DATABASE,
+                Keyword::SCHEMA,
+                Keyword::TABLE,
+            ]);
             let objects = self.parse_comma_separated(Parser::parse_object_name);
             match object_type {
+                Some(Keyword::DATABASE) => GrantObjects::Databases(objects?),
                 Some(Keyword::SCHEMA) => GrantObjects::Schemas(objects?),
                 Some(Keyword::SEQUENCE) => GrantObjects::Sequences(objects?),
                 Some(Keyword::TABLE) | None => GrantObjects::Tables(objects?),
@@ -2894,6 +2940,8 @@ impl Parser {
 
     /// Parse a REVOKE statement
     pub fn parse_revoke(&mut self) -> Result<Statement, ParserError> {
+        let revoke_grant_option =
+            self.parse_keywords(&[Keyword::GRANT, Keyword::OPTION, Keyword::FOR]);
         let (privileges, objects) = self.parse_grant_revoke_privileges_objects()?;
 
         self.expect_keyword(Keyword::FROM)?;
@@ -2910,6 +2958,7 @@ impl Parser {
         }
 
         Ok(Statement::Revoke {
+            revoke_grant_option,
             privileges,
             objects,
             grantees,
@@ -3528,12 +3528,14 @@ fn test_revoke() {
     let sql = "REVOKE ALL PRIVILEGES ON users, auth FROM analyst CASCADE";
     match verified_stmt(sql) {
         Statement::Revoke {
+            revoke_grant_option,
             privileges,
             objects: GrantObjects::Tables(tables),
             grantees,
             cascade,
             granted_by,
         } => {
+            assert!(!revoke_grant_option);
             assert_eq!(
                 Privileges::All {
                     with_privileges_keyword: true
@@ -70,6 +70,9 @@ pub fn user_key(full_key: &[u8]) -> &[u8] {
     split_key_epoch(full_key).0
 }
 
+/// Length of the table prefix of keys, i.e. `'t'` followed by the table id.
+pub const TABLE_PREFIX_LEN: usize = 5;
+
 /// Extract table id in key prefix
 pub fn get_table_id(full_key: &[u8]) -> Option<u32> {
     if full_key[0] == b't' {
@@ -0,0 +1,176 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use risingwave_common::util::epoch::Epoch;
+use risingwave_hummock_sdk::key::{get_epoch, get_table_id};
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::hummock::CompactTask;
+
+/// A [`CompactionFilter`] decides whether a key should be dropped by the compaction.
+///
+/// Note that once a version of a user key is dropped, all the older versions of the same user key
+/// are dropped as well. So a filter must also drop the older versions of any key it drops.
+pub trait CompactionFilter: Send {
+    /// Returns whether the full key `key` should be dropped.
+    fn should_delete(&mut self, key: &[u8]) -> bool;
+}
+
+/// Drops the keys of the tables which have been dropped.
+pub struct StateCleanUpCompactionFilter {
+    deleted_table_ids: HashSet<u32>,
+}
+
+impl StateCleanUpCompactionFilter {
+    pub fn new(deleted_table_ids: HashSet<u32>) -> Self {
+        Self { deleted_table_ids }
+    }
+}
+
+impl CompactionFilter for StateCleanUpCompactionFilter {
+    fn should_delete(&mut self, key: &[u8]) -> bool {
+        get_table_id(key).map_or(false, |table_id| self.deleted_table_ids.contains(&table_id))
+    }
+}
+
+/// Drops the keys of the tables with TTL, which are written more than TTL before `now_epoch`.
+///
+/// It's only applied to the compactions into the bottom level.
+pub struct TtlCompactionFilter {
+    /// Time-to-live in seconds of each table.
+    table_ttls: HashMap<u32, u32>,
+    now_epoch: Epoch,
+}
+
+impl TtlCompactionFilter {
+    pub fn new(table_ttls: HashMap<u32, u32>, now_epoch: HummockEpoch) -> Self {
+        Self {
+            table_ttls,
+            now_epoch: Epoch::from(now_epoch),
+        }
+    }
+}
+
+impl CompactionFilter for TtlCompactionFilter {
+    fn should_delete(&mut self, key: &[u8]) -> bool {
+        let ttl = match get_table_id(key).and_then(|table_id| self.table_ttls.get(&table_id)) {
+            Some(ttl) if *ttl > 0 => *ttl as u64,
+            _ => return false,
+        };
+        let write_time = Epoch::from(get_epoch(key)).physical_time();
+        write_time.saturating_add(ttl * 1000) <= self.now_epoch.physical_time()
+    }
+}
+
+/// Drops the keys which any of the filters drops.
+#[derive(Default)]
+pub struct MultiCompactionFilter {
+    filters: Vec<Box<dyn CompactionFilter>>,
+}
+
+impl MultiCompactionFilter {
+    /// Builds the filters required by `compact_task`.
+    pub fn from_compact_task(compact_task: &CompactTask) -> Self {
+        let mut multi_filter = Self::default();
+        if !compact_task.deleted_table_ids.is_empty() {
+            multi_filter.register(Box::new(StateCleanUpCompactionFilter::new(
+                compact_task.deleted_table_ids.iter().copied().collect(),
+            )));
+        }
+        // Older versions of an expired key may live in the lower levels, which would become
+        // visible again if the expired one is dropped. So the keys only expire when compacted into
+        // the bottom level, where no older versions could exist outside the compaction.
+        if !compact_task.table_ttls.is_empty() && compact_task.is_target_ultimate_and_leveling {
+            multi_filter.register(Box::new(TtlCompactionFilter::new(
+                compact_task.table_ttls.clone(),
+                compact_task.watermark,
+            )));
+        }
+        multi_filter
+    }
+
+    pub fn register(&mut self, filter: Box<dyn CompactionFilter>) {
+        self.filters.push(filter);
+    }
+}
+
+impl CompactionFilter for MultiCompactionFilter {
+    fn should_delete(&mut self, key: &[u8]) -> bool {
+        self.filters
+            .iter_mut()
+            .any(|filter| filter.should_delete(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+
+    fn table_key(table_id: u32, epoch: HummockEpoch) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b't');
+        buf.put_u32(table_id);
+        buf.put_slice(b"key");
+        key_with_epoch(buf.to_vec(), epoch)
+    }
+
+    #[test]
+    fn test_compaction_filter() {
+        let now_epoch = Epoch::now();
+        let hour_ago = Epoch::from(now_epoch.0 - ((3600 * 1000) << 16));
+
+        let mut filter = MultiCompactionFilter::default();
+        filter.register(Box::new(StateCleanUpCompactionFilter::new(
+            [1].into_iter().collect(),
+        )));
+        filter.register(Box::new(TtlCompactionFilter::new(
+            [(2, 60), (3, 7200)].into_iter().collect(),
+            now_epoch.0,
+        )));
+
+        // Dropped table.
+        assert!(filter.should_delete(&table_key(1, now_epoch.0)));
+        // Expired.
+        assert!(filter.should_delete(&table_key(2, hour_ago.0)));
+        assert!(!filter.should_delete(&table_key(2, now_epoch.0)));
+        // Not expired yet.
+        assert!(!filter.should_delete(&table_key(3, hour_ago.0)));
+        // Table without TTL.
+        assert!(!filter.should_delete(&table_key(4, hour_ago.0)));
+        // Keys not belonging to any table.
+        assert!(!filter.should_delete(&key_with_epoch(b"e-key".to_vec(), hour_ago.0)));
+    }
+
+    #[test]
+    fn test_ttl_only_in_bottom_level() {
+        let now_epoch = Epoch::now();
+        let hour_ago = Epoch::from(now_epoch.0 - ((3600 * 1000) << 16));
+        let mut compact_task = CompactTask {
+            table_ttls: [(2, 60)].into_iter().collect(),
+            watermark: now_epoch.0,
+            ..Default::default()
+        };
+
+        let mut filter = MultiCompactionFilter::from_compact_task(&compact_task);
+        assert!(!filter.should_delete(&table_key(2, hour_ago.0)));
+
+        compact_task.is_target_ultimate_and_leveling = true;
+        let mut filter = MultiCompactionFilter::from_compact_task(&compact_task);
+        assert!(filter.should_delete(&table_key(2, hour_ago.0)));
+    }
+}
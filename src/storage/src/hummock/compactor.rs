@@ -24,7 +24,7 @@ use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
 use risingwave_common::util::compress::decompress_data;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
-use risingwave_hummock_sdk::key::{get_epoch, Epoch, FullKey};
+use risingwave_hummock_sdk::key::{get_epoch, user_key, Epoch, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::{HummockSSTableId, VersionedComparator};
 use risingwave_pb::hummock::{
@@ -34,13 +34,17 @@ use risingwave_rpc_client::HummockMetaClient;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
 
+use super::compaction_filter::{CompactionFilter, MultiCompactionFilter};
 use super::group_builder::KeyValueGroupingImpl::VirtualNode;
 use super::group_builder::{GroupedSstableBuilder, VirtualNodeGrouping};
 use super::iterator::{
     BoxedForwardHummockIterator, ConcatIterator, ForwardHummockIterator, MergeIterator,
 };
 use super::shared_buffer::shared_buffer_batch::SharedBufferBatch;
-use super::{HummockResult, SSTableBuilder, SSTableIterator, Sstable};
+use super::{
+    HummockResult, RangeTombstone, RangeTombstonesAggregator, SSTableBuilder,
+    SSTableBuilderOptions, SSTableIterator, Sstable,
+};
 use crate::hummock::iterator::ReadOptions;
 use crate::hummock::sstable_store::SstableStoreRef;
 use crate::hummock::utils::can_concat;
@@ -155,6 +159,13 @@ impl Compactor {
             prefix_pairs: vec![],
             // VNode mappings are not required when compacting shared buffer to L0
             vnode_mappings: vec![],
+            // Keys are filtered only when compacting SSTs
+            deleted_table_ids: vec![],
+            table_ttls: HashMap::default(),
+            // L0 SSTs are soon compacted again, so it's not worth compressing them.
+            compression_algorithm: risingwave_pb::hummock::CompressionAlgorithm::None as i32,
+            // Prefix lengths are defined by the stream plans, which are unknown to compute nodes.
+            table_prefix_bloom_lens: HashMap::default(),
         };
 
         let parallelism = compact_task.splits.len();
@@ -164,6 +175,10 @@ impl Compactor {
         let compactor = Compactor::new(context, compact_task.clone());
 
         let vnode2unit: Arc<HashMap<u32, Vec<u32>>> = Arc::new(HashMap::new());
+        let range_tombstones = buffers
+            .iter()
+            .flat_map(|m| m.range_tombstones().iter().cloned())
+            .collect_vec();
 
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
@@ -174,9 +189,10 @@ impl Compactor {
                 MergeIterator::new(iters, stats.clone())
             };
             let vnode2unit = vnode2unit.clone();
+            let range_tombstones = range_tombstones.clone();
             compaction_futures.push(tokio::spawn(async move {
                 compactor
-                    .compact_key_range(split_index, iter, vnode2unit)
+                    .compact_key_range(split_index, iter, range_tombstones, vnode2unit)
                     .await
             }));
         }
@@ -285,8 +301,9 @@ impl Compactor {
             let vnode2unit = vnode2unit.clone();
             compaction_futures.push(tokio::spawn(async move {
                 let merge_iter = compactor.build_sst_iter().await?;
+                let range_tombstones = compactor.collect_range_tombstones().await?;
                 compactor
-                    .compact_key_range(split_index, merge_iter, vnode2unit)
+                    .compact_key_range(split_index, merge_iter, range_tombstones, vnode2unit)
                     .await
             }));
         }
@@ -340,6 +357,7 @@ impl Compactor {
                     }),
                     file_size: sst.meta.estimated_size as u64,
                     vnode_bitmaps,
+                    has_range_tombstones: !sst.meta.range_tombstones.is_empty(),
                 };
                 self.context
                     .stats
@@ -369,6 +387,7 @@ impl Compactor {
         &self,
         split_index: usize,
         iter: MergeIterator,
+        range_tombstones: Vec<RangeTombstone>,
         vnode2unit: Arc<HashMap<u32, Vec<u32>>>,
     ) -> HummockResult<CompactOutput> {
         let split = self.compact_task.splits[split_index].clone();
@@ -378,11 +397,20 @@ impl Compactor {
             inf: split.get_inf(),
         };
 
+        let mut builder_options = SSTableBuilderOptions::from(self.context.options.as_ref());
+        builder_options.compression_algorithm = self.compact_task.compression_algorithm().into();
+        builder_options.prefix_bloom_lens = self
+            .compact_task
+            .table_prefix_bloom_lens
+            .iter()
+            .map(|(table_id, len)| (*table_id, *len as usize))
+            .collect();
+
         // NOTICE: should be user_key overlap, NOT full_key overlap!
         let mut builder = GroupedSstableBuilder::new(
             || async {
                 let table_id = (self.context.sstable_id_generator)().await?;
-                let builder = SSTableBuilder::new(self.context.options.as_ref().into());
+                let builder = SSTableBuilder::new(builder_options.clone());
                 Ok((table_id, builder))
             },
             VirtualNode(VirtualNodeGrouping::new(vnode2unit)),
@@ -398,6 +426,8 @@ impl Compactor {
             &mut builder,
             kr,
             iter,
+            range_tombstones,
+            MultiCompactionFilter::from_compact_task(&self.compact_task),
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
         )
@@ -412,7 +442,7 @@ impl Compactor {
         // compaction tasks.
         let mut pending_requests = vec![];
         for (table_id, data, meta, vnode_bitmaps) in builder.finish() {
-            let sst = Sstable { id: table_id, meta };
+            let sst = Sstable::new(table_id, meta);
             let len = data.len();
             ssts.push((sst.clone(), vnode_bitmaps));
             let sstable_store = self.context.sstable_store.clone();
@@ -479,6 +509,21 @@ impl Compactor {
         Ok(MergeIterator::new(table_iters, self.context.stats.clone()))
     }
 
+    /// Collects the range tombstones of all the input ssts.
+    async fn collect_range_tombstones(&self) -> HummockResult<Vec<RangeTombstone>> {
+        let mut range_tombstones = vec![];
+        for table_info in self
+            .compact_task
+            .input_ssts
+            .iter()
+            .flat_map(|level| level.table_infos.iter())
+        {
+            let table = self.context.sstable_store.sstable(table_info.id).await?;
+            range_tombstones.extend(table.value().meta.range_tombstones.iter().cloned());
+        }
+        Ok(range_tombstones)
+    }
+
     pub async fn try_vacuum(
         vacuum_task: Option<VacuumTask>,
         sstable_store: SstableStoreRef,
@@ -610,6 +655,8 @@ impl Compactor {
         sst_builder: &mut GroupedSstableBuilder<B>,
         kr: KeyRange,
         mut iter: MergeIterator,
+        range_tombstones: Vec<RangeTombstone>,
+        mut compaction_filter: impl CompactionFilter,
         has_user_key_overlap: bool,
         watermark: Epoch,
     ) -> HummockResult<()>
@@ -623,6 +670,11 @@ impl Compactor {
             iter.rewind().await?;
         }
 
+        // Keys deleted by range tombstones are invisible to all the readers, if the tombstones are
+        // written no later than `watermark`.
+        let mut range_tombstones_aggregator = RangeTombstonesAggregator::new(watermark);
+        range_tombstones_aggregator.add_tombstones(&range_tombstones);
+
         let mut skip_key = BytesMut::new();
         let mut last_key = BytesMut::new();
 
@@ -655,6 +707,16 @@ impl Compactor {
 
             let epoch = get_epoch(iter_key);
 
+            // The key and all its previous versions are deleted by a range tombstone, or dropped by
+            // the compaction filter.
+            if range_tombstones_aggregator.should_delete(user_key(iter_key), epoch)
+                || compaction_filter.should_delete(iter_key)
+            {
+                skip_key = BytesMut::from(iter_key);
+                iter.next().await?;
+                continue;
+            }
+
             // Among keys with same user key, only retain keys which satisfy `epoch` >= `watermark`,
             // and the latest key which satisfies `epoch` < `watermark`
             if epoch < watermark {
@@ -672,6 +734,29 @@ impl Compactor {
 
             iter.next().await?;
         }
+
+        // Retain the range tombstones within the key range, unless no data could be deleted by them
+        // anymore.
+        let left_user_key = (!kr.left.is_empty()).then(|| user_key(&kr.left));
+        let right_user_key = (!kr.right.is_empty()).then(|| user_key(&kr.right));
+        for mut range_tombstone in range_tombstones {
+            if !has_user_key_overlap && range_tombstone.epoch <= watermark {
+                continue;
+            }
+            if let Some(left_user_key) = left_user_key
+                && range_tombstone.start_user_key.as_slice() < left_user_key
+            {
+                range_tombstone.start_user_key = left_user_key.to_vec();
+            }
+            if let Some(right_user_key) = right_user_key
+                && range_tombstone.end_user_key.as_slice() > right_user_key
+            {
+                range_tombstone.end_user_key = right_user_key.to_vec();
+            }
+            if range_tombstone.start_user_key < range_tombstone.end_user_key {
+                sst_builder.add_range_tombstone(range_tombstone).await?;
+            }
+        }
         Ok(())
     }
 }
@@ -18,6 +18,7 @@ mod tests {
     use std::sync::Arc;
 
     use bytes::Bytes;
+    use itertools::Itertools;
     use risingwave_common::config::StorageConfig;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
@@ -25,6 +26,7 @@ mod tests {
 
     use crate::hummock::compactor::{get_remote_sstable_id_generator, Compactor, CompactorContext};
     use crate::hummock::iterator::test_utils::mock_sstable_store;
+    use crate::hummock::utils::can_concat;
     use crate::hummock::HummockStorage;
     use crate::monitor::StateStoreMetrics;
     use crate::storage_value::StorageValue;
@@ -151,4 +153,89 @@ mod tests {
 
         assert!(compact_task.is_none());
     }
+
+    #[tokio::test]
+    async fn test_compaction_range_tombstone_not_overlap() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            sstable_id_generator: get_remote_sstable_id_generator(hummock_meta_client.clone()),
+        };
+
+        // 1. add sstables, which are split into several tables by compaction
+        let val = Bytes::from(b"0"[..].repeat(64 << 10)); // 64KB value
+        let kv_count = 64;
+        let epoch = 1;
+        storage
+            .ingest_batch(
+                (0..kv_count)
+                    .map(|i| {
+                        (
+                            Bytes::from(format!("key_{:04}", i)),
+                            StorageValue::new_default_put(val.clone()),
+                        )
+                    })
+                    .collect(),
+                epoch,
+            )
+            .await
+            .unwrap();
+        storage.sync(Some(epoch)).await.unwrap();
+        hummock_meta_client.commit_epoch(epoch).await.unwrap();
+
+        // Pin the snapshot, so that the range tombstone is retained by compaction.
+        hummock_meta_client.pin_snapshot(0).await.unwrap();
+
+        // 2. add a range tombstone across the tables
+        let epoch = epoch + 1;
+        storage
+            .ingest_delete_ranges(
+                vec![(Bytes::from("key_0005"), Bytes::from("key_0060"))],
+                epoch,
+            )
+            .await
+            .unwrap();
+        storage.sync(Some(epoch)).await.unwrap();
+        hummock_meta_client.commit_epoch(epoch).await.unwrap();
+
+        // 3. compact
+        let compact_task = hummock_manager_ref
+            .get_compact_task()
+            .await
+            .unwrap()
+            .unwrap();
+        hummock_manager_ref
+            .assign_compaction_task(&compact_task, worker_node.id, async { true })
+            .await
+            .unwrap();
+        Compactor::compact(Arc::new(compact_ctx), compact_task.clone()).await;
+
+        // 4. the output tables should not overlap
+        let version = hummock_manager_ref.get_current_version().await;
+        let output_level = version
+            .get_levels()
+            .iter()
+            .find(|level| level.level_idx == compact_task.target_level)
+            .unwrap();
+        assert!(output_level.table_infos.len() > 1);
+        assert!(can_concat(&output_level.table_infos.iter().collect_vec()));
+
+        // 5. the range tombstone still takes effect
+        storage
+            .local_version_manager()
+            .try_update_pinned_version(version);
+        assert!(storage.get(b"key_0004", epoch).await.unwrap().is_some());
+        assert!(storage.get(b"key_0030", epoch).await.unwrap().is_none());
+        assert!(storage.get(b"key_0060", epoch).await.unwrap().is_some());
+    }
 }
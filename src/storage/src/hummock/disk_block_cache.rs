@@ -0,0 +1,436 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use risingwave_hummock_sdk::HummockSSTableId;
+
+use super::{HummockError, HummockResult};
+use crate::monitor::StateStoreMetrics;
+
+const BLOCK_FILE_SUFFIX: &str = "blk";
+const TMP_FILE_SUFFIX: &str = "tmp";
+/// Subdirectory of [`DiskBlockCacheOptions::dir`] holding the cache files. Only the block files
+/// and temporary files in it are ever removed by the cache.
+const CACHE_SUBDIR: &str = "hummock_block_cache";
+/// Maximum number of blocks remembered by [`DiskCacheAdmission::SecondRead`]. The candidates are
+/// forgotten all at once when exceeded.
+const MAX_ADMISSION_CANDIDATES: usize = 1 << 16;
+
+/// Decides which blocks read from the object store are written into the [`DiskBlockCache`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskCacheAdmission {
+    /// Admit every block read from the object store.
+    All,
+    /// Admit a block only when it's read from the object store for the second time, e.g. after
+    /// being evicted from the memory cache, so that one-off scans don't flush the disk cache.
+    SecondRead,
+}
+
+#[derive(Clone, Debug)]
+pub struct DiskBlockCacheOptions {
+    /// Directory of the cache. The cache files are kept in a subdirectory of it, so it may be
+    /// shared with other files.
+    pub dir: PathBuf,
+    /// Maximum total size in bytes of the cached blocks.
+    pub capacity: usize,
+    pub admission: DiskCacheAdmission,
+}
+
+type BlockKey = (HummockSSTableId, u64);
+
+#[derive(Default)]
+struct DiskCacheIndex {
+    /// Block -> (size, LRU tick).
+    entries: HashMap<BlockKey, (usize, u64)>,
+    /// LRU tick -> block. The first one is the least recently used.
+    lru: BTreeMap<u64, BlockKey>,
+    next_tick: u64,
+    size: usize,
+    /// Blocks that have been read from the object store once but not admitted yet.
+    admission_candidates: HashSet<BlockKey>,
+}
+
+impl DiskCacheIndex {
+    fn touch(&mut self, key: BlockKey) -> bool {
+        let tick = self.next_tick;
+        match self.entries.get_mut(&key) {
+            Some((_, old_tick)) => {
+                self.lru.remove(old_tick);
+                *old_tick = tick;
+                self.lru.insert(tick, key);
+                self.next_tick += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, key: BlockKey, size: usize) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.entries.insert(key, (size, tick));
+        self.lru.insert(tick, key);
+        self.size += size;
+    }
+
+    fn remove(&mut self, key: &BlockKey) -> bool {
+        match self.entries.remove(key) {
+            Some((size, tick)) => {
+                self.lru.remove(&tick);
+                self.size -= size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Evicts the least recently used blocks until `size` more bytes fit in `capacity`.
+    fn evict(&mut self, size: usize, capacity: usize) -> Vec<BlockKey> {
+        let mut evicted = vec![];
+        while self.size + size > capacity {
+            let (_, key) = match self.lru.pop_first() {
+                Some(entry) => entry,
+                None => break,
+            };
+            let (size, _) = self.entries.remove(&key).unwrap();
+            self.size -= size;
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+/// [`DiskBlockCache`] is an optional cache tier on the local disk between [`super::BlockCache`]
+/// and the object store, which keeps the encoded blocks read from the object store, one file per
+/// block.
+///
+/// A block file is written to a temporary file and then renamed, so a block file with a valid
+/// name always has complete content unless the machine crashes before the data is flushed, which
+/// is caught by the checksum of the block. The index is kept in memory only, and is rebuilt from
+/// the block files in the cache subdirectory on [`DiskBlockCache::open`].
+///
+/// Blocks of deleted SSTs are not removed actively, but evicted eventually since no one reads
+/// them.
+#[derive(Clone)]
+pub struct DiskBlockCache {
+    inner: Arc<DiskBlockCacheInner>,
+}
+
+struct DiskBlockCacheInner {
+    options: DiskBlockCacheOptions,
+    /// The cache subdirectory in `options.dir`.
+    cache_dir: PathBuf,
+    index: Mutex<DiskCacheIndex>,
+    next_tmp_file_id: AtomicU64,
+    stats: Arc<StateStoreMetrics>,
+}
+
+impl DiskBlockCache {
+    /// Opens the cache in the cache subdirectory of `options.dir`, and rebuilds the index from the
+    /// block files in it. Blocks beyond the capacity are evicted, in the order of their modified
+    /// time.
+    pub async fn open(
+        options: DiskBlockCacheOptions,
+        stats: Arc<StateStoreMetrics>,
+    ) -> HummockResult<Self> {
+        let cache_dir = options.dir.join(CACHE_SUBDIR);
+        tokio::fs::create_dir_all(&cache_dir)
+            .await
+            .map_err(HummockError::other)?;
+
+        let mut files = vec![];
+        let mut read_dir = tokio::fs::read_dir(&cache_dir)
+            .await
+            .map_err(HummockError::other)?;
+        while let Some(entry) = read_dir.next_entry().await.map_err(HummockError::other)? {
+            let path = entry.path();
+            let metadata = entry.metadata().await.map_err(HummockError::other)?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(name) => name,
+                None => continue,
+            };
+            if let Some(key) = parse_block_file_name(name) {
+                files.push((
+                    metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    key,
+                    metadata.len() as usize,
+                ));
+            } else if is_cache_file_name(name) {
+                // Remove the temporary files left by the last run, as well as the malformed block
+                // files. Other files are not touched.
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+        }
+        files.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut index = DiskCacheIndex::default();
+        for (_, key, size) in files {
+            index.insert(key, size);
+        }
+        let evicted = index.evict(0, options.capacity);
+        stats.disk_cache_size.set(index.size as i64);
+
+        let cache = Self {
+            inner: Arc::new(DiskBlockCacheInner {
+                options,
+                cache_dir,
+                index: Mutex::new(index),
+                next_tmp_file_id: AtomicU64::new(0),
+                stats,
+            }),
+        };
+        cache.remove_files(evicted).await;
+        Ok(cache)
+    }
+
+    /// Returns the encoded block, or `None` if the block is not cached or fails to be read.
+    pub async fn get(&self, sst_id: HummockSSTableId, block_idx: u64) -> Option<Bytes> {
+        let key = (sst_id, block_idx);
+        let cached = self.inner.index.lock().touch(key);
+        if !cached {
+            self.inner.stats.disk_cache_miss_counts.inc();
+            return None;
+        }
+        match tokio::fs::read(self.block_file_path(&key)).await {
+            Ok(data) => {
+                self.inner.stats.disk_cache_hit_counts.inc();
+                Some(Bytes::from(data))
+            }
+            Err(e) => {
+                tracing::warn!("failed to read block {:?} from disk cache: {}", key, e);
+                self.remove(sst_id, block_idx).await;
+                self.inner.stats.disk_cache_miss_counts.inc();
+                None
+            }
+        }
+    }
+
+    /// Offers the encoded block read from the object store to the cache, which is written only
+    /// if admitted by the admission policy. Errors are logged and ignored.
+    pub async fn insert(&self, sst_id: HummockSSTableId, block_idx: u64, data: Bytes) {
+        let key = (sst_id, block_idx);
+        let evicted = {
+            let mut index = self.inner.index.lock();
+            if index.entries.contains_key(&key) || data.len() > self.inner.options.capacity {
+                return;
+            }
+            if self.inner.options.admission == DiskCacheAdmission::SecondRead
+                && !index.admission_candidates.remove(&key)
+            {
+                if index.admission_candidates.len() >= MAX_ADMISSION_CANDIDATES {
+                    index.admission_candidates.clear();
+                }
+                index.admission_candidates.insert(key);
+                return;
+            }
+            index.evict(data.len(), self.inner.options.capacity)
+        };
+        self.remove_files(evicted).await;
+
+        if let Err(e) = self.write_block_file(&key, &data).await {
+            tracing::warn!("failed to write block {:?} to disk cache: {}", key, e);
+            return;
+        }
+        let mut index = self.inner.index.lock();
+        if index.entries.contains_key(&key) {
+            // Written by a concurrent insertion of the same block.
+            return;
+        }
+        index.insert(key, data.len());
+        self.inner.stats.disk_cache_insert_counts.inc();
+        self.inner.stats.disk_cache_size.set(index.size as i64);
+    }
+
+    /// Removes the block from the cache, e.g. when it turns out to be corrupted.
+    pub async fn remove(&self, sst_id: HummockSSTableId, block_idx: u64) {
+        let key = (sst_id, block_idx);
+        let removed = {
+            let mut index = self.inner.index.lock();
+            let removed = index.remove(&key);
+            self.inner.stats.disk_cache_size.set(index.size as i64);
+            removed
+        };
+        if removed {
+            let _ = tokio::fs::remove_file(self.block_file_path(&key)).await;
+        }
+    }
+
+    /// Total size in bytes of the cached blocks.
+    pub fn size(&self) -> usize {
+        self.inner.index.lock().size
+    }
+
+    async fn write_block_file(&self, key: &BlockKey, data: &[u8]) -> std::io::Result<()> {
+        let tmp_file_id = self.inner.next_tmp_file_id.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = self.inner.cache_dir.join(format!(
+            "{}_{}.{}.{}",
+            key.0, key.1, tmp_file_id, TMP_FILE_SUFFIX
+        ));
+        let result = async {
+            tokio::fs::write(&tmp_path, data).await?;
+            tokio::fs::rename(&tmp_path, self.block_file_path(key)).await
+        }
+        .await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+        result
+    }
+
+    async fn remove_files(&self, keys: Vec<BlockKey>) {
+        if keys.is_empty() {
+            return;
+        }
+        self.inner
+            .stats
+            .disk_cache_evict_counts
+            .inc_by(keys.len() as u64);
+        self.inner
+            .stats
+            .disk_cache_size
+            .set(self.inner.index.lock().size as i64);
+        for key in keys {
+            let _ = tokio::fs::remove_file(self.block_file_path(&key)).await;
+        }
+    }
+
+    fn block_file_path(&self, key: &BlockKey) -> PathBuf {
+        self.inner
+            .cache_dir
+            .join(format!("{}_{}.{}", key.0, key.1, BLOCK_FILE_SUFFIX))
+    }
+}
+
+/// Parses `{sst_id}_{block_idx}.blk`.
+fn parse_block_file_name(name: &str) -> Option<BlockKey> {
+    let (sst_id, block_idx) = name
+        .strip_suffix(BLOCK_FILE_SUFFIX)?
+        .strip_suffix('.')?
+        .split_once('_')?;
+    Some((sst_id.parse().ok()?, block_idx.parse().ok()?))
+}
+
+/// Whether the file is a block file or a temporary file, which may be removed by the cache.
+fn is_cache_file_name(name: &str) -> bool {
+    name.rsplit_once('.').map_or(false, |(_, suffix)| {
+        suffix == BLOCK_FILE_SUFFIX || suffix == TMP_FILE_SUFFIX
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn open_cache(
+        dir: &tempfile::TempDir,
+        capacity: usize,
+        admission: DiskCacheAdmission,
+    ) -> DiskBlockCache {
+        DiskBlockCache::open(
+            DiskBlockCacheOptions {
+                dir: dir.path().to_path_buf(),
+                capacity,
+                admission,
+            },
+            Arc::new(StateStoreMetrics::unused()),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn block_of(len: usize, byte: u8) -> Bytes {
+        Bytes::from(vec![byte; len])
+    }
+
+    #[tokio::test]
+    async fn test_disk_block_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open_cache(&dir, 300, DiskCacheAdmission::All).await;
+
+        assert_eq!(cache.get(1, 0).await, None);
+        cache.insert(1, 0, block_of(100, 1)).await;
+        cache.insert(1, 1, block_of(100, 2)).await;
+        cache.insert(2, 0, block_of(100, 3)).await;
+        assert_eq!(cache.size(), 300);
+        assert_eq!(cache.get(1, 0).await, Some(block_of(100, 1)));
+
+        // Evicts the least recently used block (1, 1).
+        cache.insert(2, 1, block_of(100, 4)).await;
+        assert_eq!(cache.size(), 300);
+        assert_eq!(cache.get(1, 1).await, None);
+        assert_eq!(cache.get(1, 0).await, Some(block_of(100, 1)));
+        assert_eq!(cache.get(2, 1).await, Some(block_of(100, 4)));
+
+        // Blocks larger than the capacity are not admitted.
+        cache.insert(3, 0, block_of(400, 5)).await;
+        assert_eq!(cache.get(3, 0).await, None);
+
+        cache.remove(1, 0).await;
+        assert_eq!(cache.get(1, 0).await, None);
+        assert_eq!(cache.size(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_disk_block_cache_admission() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open_cache(&dir, 300, DiskCacheAdmission::SecondRead).await;
+
+        cache.insert(1, 0, block_of(100, 1)).await;
+        assert_eq!(cache.get(1, 0).await, None);
+        cache.insert(1, 0, block_of(100, 1)).await;
+        assert_eq!(cache.get(1, 0).await, Some(block_of(100, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_disk_block_cache_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let cache = open_cache(&dir, 300, DiskCacheAdmission::All).await;
+            cache.insert(1, 0, block_of(100, 1)).await;
+            cache.insert(1, 1, block_of(100, 2)).await;
+            cache.insert(2, 0, block_of(100, 3)).await;
+        }
+        let cache_dir = dir.path().join(CACHE_SUBDIR);
+        // Left by an interrupted write.
+        std::fs::write(cache_dir.join("3_0.0.tmp"), b"partial").unwrap();
+        // Files unknown to the cache are kept, both in the cache subdirectory and beside it.
+        std::fs::write(cache_dir.join("README"), b"keep").unwrap();
+        std::fs::write(dir.path().join("4_0.blk"), b"keep").unwrap();
+
+        let cache = open_cache(&dir, 300, DiskCacheAdmission::All).await;
+        assert_eq!(cache.size(), 300);
+        assert_eq!(cache.get(1, 1).await, Some(block_of(100, 2)));
+        assert_eq!(cache.get(4, 0).await, None);
+        assert!(!cache_dir.join("3_0.0.tmp").exists());
+        assert!(cache_dir.join("README").exists());
+        assert!(dir.path().join("4_0.blk").exists());
+
+        // Rebuilt with a smaller capacity.
+        drop(cache);
+        let cache = open_cache(&dir, 200, DiskCacheAdmission::All).await;
+        assert_eq!(cache.size(), 200);
+        // Two block files and the unknown file.
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 3);
+    }
+}
@@ -20,7 +20,7 @@ use risingwave_hummock_sdk::key::{get_epoch, key_with_epoch, user_key as to_user
 use crate::hummock::iterator::{BackwardMergeIterator, HummockIterator};
 use crate::hummock::local_version::PinnedVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{HummockResult, RangeTombstonesAggregator};
 
 /// [`BackwardUserIterator`] can be used by user directly.
 pub struct BackwardUserIterator {
@@ -48,6 +48,9 @@ pub struct BackwardUserIterator {
     /// Only reads values if `epoch <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Range tombstones of all the sources of `iterator`.
+    range_tombstones: RangeTombstonesAggregator,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<PinnedVersion>>,
 }
@@ -78,10 +81,20 @@ impl BackwardUserIterator {
             last_val: Vec::new(),
             last_delete: true,
             read_epoch,
+            range_tombstones: RangeTombstonesAggregator::new(read_epoch),
             _version: version,
         }
     }
 
+    /// Hides the keys deleted by the given range tombstones.
+    pub(crate) fn with_range_tombstones(
+        mut self,
+        range_tombstones: RangeTombstonesAggregator,
+    ) -> Self {
+        self.range_tombstones = range_tombstones;
+        self
+    }
+
     fn out_of_range(&self, key: &[u8]) -> bool {
         match &self.key_range.0 {
             Included(begin_key) => key < begin_key.as_slice(),
@@ -169,6 +182,10 @@ impl BackwardUserIterator {
 
                 // 1 and 2(a)
                 match self.iterator.value() {
+                    // Deleted by a range tombstone.
+                    HummockValue::Put(..) if self.range_tombstones.should_delete(key, epoch) => {
+                        self.last_delete = true;
+                    }
                     HummockValue::Put(_, val) => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
@@ -1064,9 +1081,6 @@ mod tests {
     }
 
     fn clone_sst(sst: &Sstable) -> Sstable {
-        Sstable {
-            id: sst.id,
-            meta: sst.meta.clone(),
-        }
+        Sstable::new(sst.id, sst.meta.clone())
     }
 }
@@ -59,10 +59,11 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
     }
 
     /// Seeks to a table, and then seeks to the key if `seek_key` is given.
-    async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
-        if idx >= self.tables.len() {
-            self.sstable_iter = None;
-        } else {
+    ///
+    /// Tables without any keys, e.g., those with only range tombstones, are skipped.
+    async fn seek_idx(&mut self, mut idx: usize, mut seek_key: Option<&[u8]>) -> HummockResult<()> {
+        self.sstable_iter = None;
+        while idx < self.tables.len() {
             let table = self.sstable_store.sstable(self.tables[idx].id).await?;
             let mut sstable_iter =
                 TI::new(table, self.sstable_store.clone(), self.read_options.clone());
@@ -72,8 +73,13 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
                 sstable_iter.rewind().await?;
             }
 
-            self.sstable_iter = Some(sstable_iter);
             self.cur_idx = idx;
+            if sstable_iter.is_valid() {
+                self.sstable_iter = Some(sstable_iter);
+                break;
+            }
+            idx += 1;
+            seek_key = None;
         }
         Ok(())
     }
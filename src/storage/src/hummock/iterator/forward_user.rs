@@ -21,7 +21,7 @@ use super::{ForwardHummockIterator, MergeIterator};
 use crate::hummock::iterator::BackwardUserIterator;
 use crate::hummock::local_version::PinnedVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{HummockResult, RangeTombstonesAggregator};
 
 pub enum DirectedUserIterator {
     Forward(UserIterator),
@@ -96,6 +96,9 @@ pub struct UserIterator {
     /// Only reads values if `ts <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Range tombstones of all the sources of `iterator`.
+    range_tombstones: RangeTombstonesAggregator,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<PinnedVersion>>,
 }
@@ -125,10 +128,20 @@ impl UserIterator {
             last_key: Vec::new(),
             last_val: Vec::new(),
             read_epoch,
+            range_tombstones: RangeTombstonesAggregator::new(read_epoch),
             _version: version,
         }
     }
 
+    /// Hides the keys deleted by the given range tombstones.
+    pub(crate) fn with_range_tombstones(
+        mut self,
+        range_tombstones: RangeTombstonesAggregator,
+    ) -> Self {
+        self.range_tombstones = range_tombstones;
+        self
+    }
+
     /// Gets the iterator move to the next step.
     ///
     /// Returned result:
@@ -148,6 +161,8 @@ impl UserIterator {
 
                 // handle delete operation
                 match self.iterator.value() {
+                    // The key and its previous versions (if any) are deleted by a range tombstone.
+                    HummockValue::Put(..) if self.range_tombstones.should_delete(key, epoch) => {}
                     HummockValue::Put(_, val) => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
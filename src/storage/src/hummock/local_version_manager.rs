@@ -41,7 +41,7 @@ use crate::hummock::shared_buffer::shared_buffer_uploader::UploadTask;
 use crate::hummock::shared_buffer::SharedBuffer;
 use crate::hummock::utils::validate_table_key_range;
 use crate::hummock::{
-    HummockEpoch, HummockError, HummockResult, HummockVersionId, INVALID_VERSION_ID,
+    HummockEpoch, HummockError, HummockResult, HummockVersionId, RangeTombstone, INVALID_VERSION_ID,
 };
 use crate::monitor::StateStoreMetrics;
 use crate::storage_value::StorageValue;
@@ -260,7 +260,50 @@ impl LocalVersionManager {
                 self.buffer_tracker.upload_size.clone()
             },
         );
+        self.write_shared_buffer_batch(epoch, batch, is_remote_batch);
 
+        Ok(batch_size)
+    }
+
+    /// Writes range tombstones which delete the keys in the given `[start, end)` ranges into the
+    /// shared buffer.
+    pub async fn write_shared_buffer_range_tombstones(
+        &self,
+        epoch: HummockEpoch,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+    ) -> HummockResult<usize> {
+        let range_tombstones = delete_ranges
+            .into_iter()
+            .filter(|(start, end)| start < end)
+            .map(|(start, end)| RangeTombstone::new(start.to_vec(), end.to_vec(), epoch))
+            .collect_vec();
+        if range_tombstones.is_empty() {
+            return Ok(0);
+        }
+
+        let batch_size = SharedBufferBatch::measure_range_tombstones_size(&range_tombstones);
+        while !self.buffer_tracker.can_write() {
+            self.sync_shared_buffer(None).await?;
+        }
+
+        let batch = SharedBufferBatch::new_with_range_tombstones(
+            vec![],
+            range_tombstones,
+            epoch,
+            batch_size,
+            self.buffer_tracker.upload_size.clone(),
+        );
+        self.write_shared_buffer_batch(epoch, batch, false);
+
+        Ok(batch_size)
+    }
+
+    fn write_shared_buffer_batch(
+        &self,
+        epoch: HummockEpoch,
+        batch: SharedBufferBatch,
+        is_remote_batch: bool,
+    ) {
         // Try get shared buffer with version read lock
         let shared_buffer = self.local_version.read().get_shared_buffer(epoch).cloned();
 
@@ -277,8 +320,6 @@ impl LocalVersionManager {
             // The batch will be synced to S3 asynchronously if it is a local batch
             shared_buffer.write().write_batch(batch);
         }
-
-        Ok(batch_size)
     }
 
     pub async fn sync_shared_buffer(&self, epoch: Option<HummockEpoch>) -> HummockResult<()> {
@@ -599,6 +640,7 @@ mod tests {
             }),
             file_size: batches.len() as u64,
             vnode_bitmaps: vec![],
+            has_range_tombstones: false,
         }
     }
 
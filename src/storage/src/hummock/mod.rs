@@ -24,9 +24,12 @@ use risingwave_rpc_client::HummockMetaClient;
 
 mod block_cache;
 pub use block_cache::*;
+mod disk_block_cache;
+pub use disk_block_cache::*;
 mod sstable;
 pub use sstable::*;
 mod cache;
+pub mod compaction_filter;
 pub mod compactor;
 #[cfg(test)]
 mod compactor_tests;
@@ -54,7 +57,7 @@ pub use error::*;
 use value::*;
 
 use self::iterator::HummockIterator;
-use self::key::user_key;
+use self::key::{get_epoch, user_key};
 pub use self::sstable_store::*;
 pub use self::state_store::HummockStateStoreIter;
 use super::monitor::StateStoreMetrics;
@@ -126,7 +129,10 @@ impl HummockStorage {
         internal_key: &[u8],
         key: &[u8],
         read_options: Arc<ReadOptions>,
+        range_tombstones: &mut RangeTombstonesAggregator,
     ) -> HummockResult<Option<Bytes>> {
+        // Range tombstones in the table may delete the key in this table or the older ones.
+        range_tombstones.add_tombstones(&table.value().meta.range_tombstones);
         if table.value().surely_not_have_user_key(key) {
             self.stats.bloom_filter_true_negative_counts.inc();
             return Ok(None);
@@ -143,8 +149,10 @@ impl HummockStorage {
         // Iterator gets us the key, we tell if it's the key we want
         // or key next to it.
         let value = match user_key(iter.key()) == key {
-            true => iter.value().into_user_value().map(Bytes::copy_from_slice),
-            false => None,
+            true if !range_tombstones.should_delete(key, get_epoch(iter.key())) => {
+                iter.value().into_user_value().map(Bytes::copy_from_slice)
+            }
+            _ => None,
         };
         Ok(value)
     }
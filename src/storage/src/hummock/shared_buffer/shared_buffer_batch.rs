@@ -27,13 +27,14 @@ use crate::hummock::iterator::{
     Backward, DirectionEnum, Forward, HummockIterator, HummockIteratorDirection,
 };
 use crate::hummock::value::HummockValue;
-use crate::hummock::{key, HummockEpoch, HummockResult};
+use crate::hummock::{key, HummockEpoch, HummockResult, RangeTombstone};
 use crate::storage_value::VALUE_META_SIZE;
 
 pub(crate) type SharedBufferItem = (Bytes, HummockValue<Bytes>);
 
 pub(crate) struct SharedBufferBatchInner {
     payload: Vec<SharedBufferItem>,
+    range_tombstones: Vec<RangeTombstone>,
     size: usize,
     buffer_size_tracker: Arc<AtomicUsize>,
 }
@@ -56,15 +57,15 @@ impl Debug for SharedBufferBatchInner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "SharedBufferBatchInner {{ payload: {:?}, size: {} }}",
-            self.payload, self.size
+            "SharedBufferBatchInner {{ payload: {:?}, range_tombstones: {:?}, size: {} }}",
+            self.payload, self.range_tombstones, self.size
         )
     }
 }
 
 impl PartialEq for SharedBufferBatchInner {
     fn eq(&self, other: &Self) -> bool {
-        self.payload == other.payload
+        self.payload == other.payload && self.range_tombstones == other.range_tombstones
     }
 }
 
@@ -94,11 +95,25 @@ impl SharedBufferBatch {
         size: usize,
         buffer_size_tracker: Arc<AtomicUsize>,
     ) -> Self {
+        Self::new_with_range_tombstones(sorted_items, vec![], epoch, size, buffer_size_tracker)
+    }
+
+    /// Creates a batch with range tombstones. Either `sorted_items` or `range_tombstones` should
+    /// not be empty.
+    pub fn new_with_range_tombstones(
+        sorted_items: Vec<SharedBufferItem>,
+        range_tombstones: Vec<RangeTombstone>,
+        epoch: HummockEpoch,
+        size: usize,
+        buffer_size_tracker: Arc<AtomicUsize>,
+    ) -> Self {
+        debug_assert!(!sorted_items.is_empty() || !range_tombstones.is_empty());
         buffer_size_tracker.fetch_add(size, Relaxed);
 
         Self {
             inner: Arc::new(SharedBufferBatchInner {
                 payload: sorted_items,
+                range_tombstones,
                 size,
                 buffer_size_tracker,
             }),
@@ -121,6 +136,18 @@ impl SharedBufferBatch {
             .sum()
     }
 
+    pub fn measure_range_tombstones_size(range_tombstones: &[RangeTombstone]) -> usize {
+        // size = Sum(length of start key + length of end key + length of epoch)
+        range_tombstones
+            .iter()
+            .map(|tombstone| {
+                tombstone.start_user_key.len()
+                    + tombstone.end_user_key.len()
+                    + std::mem::size_of::<HummockEpoch>()
+            })
+            .sum()
+    }
+
     pub fn get(&self, user_key: &[u8]) -> Option<HummockValue<Vec<u8>>> {
         // Perform binary search on user key because the items in SharedBufferBatch is ordered by
         // user key.
@@ -145,6 +172,10 @@ impl SharedBufferBatch {
         &self.inner
     }
 
+    pub fn range_tombstones(&self) -> &[RangeTombstone] {
+        &self.inner.range_tombstones
+    }
+
     #[allow(dead_code)]
     pub fn start_key(&self) -> &[u8] {
         &self.inner.first().unwrap().0
@@ -155,12 +186,36 @@ impl SharedBufferBatch {
         &self.inner.last().unwrap().0
     }
 
+    /// Returns the smallest user key of the batch, including the start keys of range tombstones.
     pub fn start_user_key(&self) -> &[u8] {
-        key::user_key(&self.inner.first().unwrap().0)
+        self.inner
+            .first()
+            .map(|(k, _)| key::user_key(k))
+            .into_iter()
+            .chain(
+                self.inner
+                    .range_tombstones
+                    .iter()
+                    .map(|tombstone| tombstone.start_user_key.as_slice()),
+            )
+            .min()
+            .unwrap()
     }
 
+    /// Returns the largest user key of the batch, including the end keys of range tombstones.
     pub fn end_user_key(&self) -> &[u8] {
-        key::user_key(&self.inner.last().unwrap().0)
+        self.inner
+            .last()
+            .map(|(k, _)| key::user_key(k))
+            .into_iter()
+            .chain(
+                self.inner
+                    .range_tombstones
+                    .iter()
+                    .map(|tombstone| tombstone.end_user_key.as_slice()),
+            )
+            .max()
+            .unwrap()
     }
 
     pub fn epoch(&self) -> u64 {
@@ -191,6 +191,7 @@ impl SharedBufferUploader {
                 }),
                 file_size: sst.meta.estimated_size as u64,
                 vnode_bitmaps,
+                has_range_tombstones: !sst.meta.range_tombstones.is_empty(),
             })
             .collect();
 
@@ -19,6 +19,7 @@ use std::ops::Range;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use lz4::Decoder;
 use risingwave_hummock_sdk::VersionedComparator;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
 
 use super::utils::{
     bytes_diff, var_u32_len, xxhash64_verify, BufExt, BufMutExt, CompressionAlgorithm,
@@ -29,6 +30,7 @@ use crate::hummock::{HummockError, HummockResult};
 pub const DEFAULT_BLOCK_SIZE: usize = 4 * 1024;
 pub const DEFAULT_RESTART_INTERVAL: usize = 16;
 pub const DEFAULT_ENTRY_SIZE: usize = 16;
+pub const ZSTD_COMPRESSION_LEVEL: i32 = 3;
 
 pub struct Block {
     /// Uncompressed entries data.
@@ -39,6 +41,15 @@ pub struct Block {
 
 impl Block {
     pub fn decode(buf: Bytes) -> HummockResult<Self> {
+        Self::decode_with_dictionary(buf, None)
+    }
+
+    /// Decodes a block compressed with the zstd `dictionary` of the sstable, which is prepared
+    /// once per sstable. The dictionary is ignored if the block is not compressed by zstd.
+    pub fn decode_with_dictionary(
+        buf: Bytes,
+        dictionary: Option<&DecoderDictionary<'_>>,
+    ) -> HummockResult<Self> {
         // Verify checksum.
         let xxhash64_checksum = (&buf[buf.len() - 8..]).get_u64_le();
         xxhash64_verify(&buf[..buf.len() - 8], xxhash64_checksum)?;
@@ -58,6 +69,20 @@ impl Block {
                     .unwrap();
                 Bytes::from(decoded)
             }
+            CompressionAlgorithm::Zstd => {
+                let reader = buf.slice(..buf.len() - 9).reader();
+                let mut decoded = Vec::with_capacity(DEFAULT_BLOCK_SIZE);
+                match dictionary {
+                    Some(dictionary) => {
+                        zstd::stream::read::Decoder::with_prepared_dictionary(reader, dictionary)
+                            .and_then(|mut decoder| decoder.read_to_end(&mut decoded))
+                    }
+                    None => zstd::stream::read::Decoder::new(reader)
+                        .and_then(|mut decoder| decoder.read_to_end(&mut decoded)),
+                }
+                .map_err(HummockError::decode_error)?;
+                Bytes::from(decoded)
+            }
         };
 
         // Decode restart points.
@@ -276,33 +301,20 @@ impl BlockBuilder {
     /// # Panics
     ///
     /// Panic if there is compression error.
-    pub fn build(mut self) -> Bytes {
+    pub fn build(self) -> Bytes {
+        let compression_algorithm = self.compression_algorithm;
+        compress_block(self.build_uncompressed(), compression_algorithm, None)
+    }
+
+    /// Finishes building block without compression, which is to be compressed later by
+    /// [`compress_block`].
+    pub fn build_uncompressed(mut self) -> BytesMut {
         assert!(self.entry_count > 0);
         for restart_point in &self.restart_points {
             self.buf.put_u32_le(*restart_point);
         }
         self.buf.put_u32_le(self.restart_points.len() as u32);
-        let mut buf = match self.compression_algorithm {
-            CompressionAlgorithm::None => self.buf,
-            CompressionAlgorithm::Lz4 => {
-                let mut encoder = lz4::EncoderBuilder::new()
-                    .level(4)
-                    .build(BytesMut::with_capacity(self.buf.len()).writer())
-                    .map_err(HummockError::encode_error)
-                    .unwrap();
-                encoder
-                    .write(&self.buf[..])
-                    .map_err(HummockError::encode_error)
-                    .unwrap();
-                let (writer, result) = encoder.finish();
-                result.map_err(HummockError::encode_error).unwrap();
-                writer.into_inner()
-            }
-        };
-        self.compression_algorithm.encode(&mut buf);
-        let checksum = xxhash64_checksum(&buf);
-        buf.put_u64_le(checksum);
-        buf.freeze()
+        self.buf
     }
 
     /// Approximate block len (uncompressed).
@@ -311,6 +323,60 @@ impl BlockBuilder {
     }
 }
 
+/// Compresses the block built by [`BlockBuilder::build_uncompressed`], and appends the compression
+/// method and the checksum. `dictionary` is only used by zstd, and is prepared once per sstable.
+///
+/// # Panics
+///
+/// Panic if there is compression error.
+pub fn compress_block(
+    buf: BytesMut,
+    compression_algorithm: CompressionAlgorithm,
+    dictionary: Option<&EncoderDictionary<'_>>,
+) -> Bytes {
+    let mut buf = match compression_algorithm {
+        CompressionAlgorithm::None => buf,
+        CompressionAlgorithm::Lz4 => {
+            let mut encoder = lz4::EncoderBuilder::new()
+                .level(4)
+                .build(BytesMut::with_capacity(buf.len()).writer())
+                .map_err(HummockError::encode_error)
+                .unwrap();
+            encoder
+                .write(&buf[..])
+                .map_err(HummockError::encode_error)
+                .unwrap();
+            let (writer, result) = encoder.finish();
+            result.map_err(HummockError::encode_error).unwrap();
+            writer.into_inner()
+        }
+        CompressionAlgorithm::Zstd => {
+            let writer = BytesMut::with_capacity(buf.len()).writer();
+            let mut encoder = match dictionary {
+                Some(dictionary) => {
+                    zstd::stream::write::Encoder::with_prepared_dictionary(writer, dictionary)
+                }
+                None => zstd::stream::write::Encoder::new(writer, ZSTD_COMPRESSION_LEVEL),
+            }
+            .map_err(HummockError::encode_error)
+            .unwrap();
+            encoder
+                .write_all(&buf[..])
+                .map_err(HummockError::encode_error)
+                .unwrap();
+            encoder
+                .finish()
+                .map_err(HummockError::encode_error)
+                .unwrap()
+                .into_inner()
+        }
+    };
+    compression_algorithm.encode(&mut buf);
+    let checksum = xxhash64_checksum(&buf);
+    buf.put_u64_le(checksum);
+    buf.freeze()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,6 +457,42 @@ mod tests {
         assert!(!bi.is_valid());
     }
 
+    #[test]
+    fn test_zstd_compressed_block_enc_dec() {
+        let build = || {
+            let mut builder = BlockBuilder::new(BlockBuilderOptions::default());
+            builder.add(&full_key(b"k1", 1), b"v01");
+            builder.add(&full_key(b"k2", 2), b"v02");
+            builder.add(&full_key(b"k3", 3), b"v03");
+            builder.build_uncompressed()
+        };
+        // Any bytes could be used as a raw content dictionary.
+        let dictionary = b"k1v01k2v02k3v03";
+        let encoder_dictionary = EncoderDictionary::copy(dictionary, ZSTD_COMPRESSION_LEVEL);
+        let decoder_dictionary = DecoderDictionary::copy(dictionary);
+        for (encoder_dictionary, decoder_dictionary) in [
+            (None, None),
+            (Some(&encoder_dictionary), Some(&decoder_dictionary)),
+        ] {
+            let buf = compress_block(build(), CompressionAlgorithm::Zstd, encoder_dictionary);
+            let block = Box::new(Block::decode_with_dictionary(buf, decoder_dictionary).unwrap());
+            let mut bi = BlockIterator::new(BlockHolder::from_owned_block(block));
+
+            bi.seek_to_first();
+            for (key, value) in [
+                (full_key(b"k1", 1), b"v01"),
+                (full_key(b"k2", 2), b"v02"),
+                (full_key(b"k3", 3), b"v03"),
+            ] {
+                assert!(bi.is_valid());
+                assert_eq!(&key[..], bi.key());
+                assert_eq!(value, bi.value());
+                bi.next();
+            }
+            assert!(!bi.is_valid());
+        }
+    }
+
     pub fn full_key(user_key: &[u8], epoch: u64) -> Bytes {
         let mut buf = BytesMut::with_capacity(user_key.len() + 8);
         buf.put_slice(user_key);
@@ -12,18 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use bytes::{BufMut, Bytes, BytesMut};
+use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
-use risingwave_hummock_sdk::key::{get_table_id, user_key};
+use risingwave_hummock_sdk::key::{get_table_id, key_with_epoch, user_key, Epoch};
+use risingwave_hummock_sdk::VersionedComparator;
 use risingwave_pb::hummock::VNodeBitmap;
+use zstd::dict::EncoderDictionary;
 
 use super::bloom::Bloom;
 use super::utils::CompressionAlgorithm;
 use super::{
-    BlockBuilder, BlockBuilderOptions, BlockMeta, SstableMeta, DEFAULT_BLOCK_SIZE,
-    DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    compress_block, BlockBuilder, BlockBuilderOptions, BlockMeta, RangeTombstone, SstableMeta,
+    DEFAULT_BLOCK_SIZE, DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    ZSTD_COMPRESSION_LEVEL,
 };
 use crate::hummock::value::HummockValue;
 
@@ -42,6 +46,12 @@ pub struct SSTableBuilderOptions {
     pub bloom_false_positive: f64,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Maximum size of the zstd dictionary trained from the blocks of each sstable. 0 disables
+    /// the dictionary. Only used when `compression_algorithm` is zstd.
+    pub compression_dictionary_size: usize,
+    /// `table_id` -> Length of the user key prefixes, including the table prefix, to build the
+    /// prefix bloom filter with. Keys of the other tables are not added to the filter.
+    pub prefix_bloom_lens: HashMap<u32, usize>,
 }
 
 impl From<&StorageConfig> for SSTableBuilderOptions {
@@ -51,8 +61,11 @@ impl From<&StorageConfig> for SSTableBuilderOptions {
             block_capacity: (options.block_size_kb as usize) * (1 << 10),
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: options.bloom_false_positive,
-            // TODO: Make this configurable.
+            // Overridden by the compaction task according to the target level.
             compression_algorithm: CompressionAlgorithm::None,
+            compression_dictionary_size: (options.zstd_dictionary_size_kb as usize) * (1 << 10),
+            // Overridden by the compaction task according to the stream plans.
+            prefix_bloom_lens: HashMap::new(),
         }
     }
 }
@@ -65,6 +78,8 @@ impl Default for SSTableBuilderOptions {
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: DEFAULT_BLOOM_FALSE_POSITIVE,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_dictionary_size: 0,
+            prefix_bloom_lens: HashMap::new(),
         }
     }
 }
@@ -87,6 +102,16 @@ pub struct SSTableBuilder {
     /// Last added full key.
     last_full_key: Bytes,
     key_count: usize,
+    /// Range tombstones to be written into the meta.
+    range_tombstones: Vec<RangeTombstone>,
+    /// Uncompressed blocks, which are kept until the zstd dictionary is trained from them on
+    /// `finish`. Always empty if the dictionary is disabled.
+    uncompressed_blocks: Vec<BytesMut>,
+    uncompressed_blocks_len: usize,
+    /// Hashes of user key prefixes, without consecutive duplicates.
+    prefix_hashes: Vec<u32>,
+    /// `table_id` -> Length of the prefixes added to `prefix_hashes`.
+    prefix_bloom_lens: BTreeMap<u32, u32>,
 }
 
 impl SSTableBuilder {
@@ -100,9 +125,25 @@ impl SSTableBuilder {
             user_key_hashes: Vec::with_capacity(options.capacity / DEFAULT_ENTRY_SIZE + 1),
             last_full_key: Bytes::default(),
             key_count: 0,
+            range_tombstones: vec![],
+            uncompressed_blocks: vec![],
+            uncompressed_blocks_len: 0,
+            prefix_hashes: vec![],
+            prefix_bloom_lens: BTreeMap::new(),
         }
     }
 
+    fn use_compression_dictionary(&self) -> bool {
+        self.options.compression_algorithm == CompressionAlgorithm::Zstd
+            && self.options.compression_dictionary_size > 0
+    }
+
+    /// Add a range tombstone to sstable. The key range of the sstable will be extended to cover
+    /// the tombstone.
+    pub fn add_range_tombstone(&mut self, range_tombstone: RangeTombstone) {
+        self.range_tombstones.push(range_tombstone);
+    }
+
     /// Add kv pair to sstable.
     pub fn add(&mut self, full_key: &[u8], value: HummockValue<&[u8]>) {
         // Rotate block builder if the previous one has been built.
@@ -125,7 +166,8 @@ impl SSTableBuilder {
         // TODO: refine me
         let mut raw_value = BytesMut::default();
         let value_meta = value.encode(&mut raw_value) & ((1 << VNODE_BITS) - 1);
-        if let Some(table_id) = get_table_id(full_key) {
+        let table_id = get_table_id(full_key);
+        if let Some(table_id) = table_id {
             // We use 8 bit of bitmap[x] to indicate existence of virtual node x*8..(x+1)*8,
             // respectively
             self.vnode_bitmaps
@@ -139,6 +181,16 @@ impl SSTableBuilder {
 
         let user_key = user_key(full_key);
         self.user_key_hashes.push(farmhash::fingerprint32(user_key));
+        if let Some(table_id) = table_id
+            && let Some(&prefix_len) = self.options.prefix_bloom_lens.get(&table_id)
+            && user_key.len() >= prefix_len
+        {
+            self.prefix_bloom_lens.insert(table_id, prefix_len as u32);
+            let prefix_hash = farmhash::fingerprint32(&user_key[..prefix_len]);
+            if self.prefix_hashes.last() != Some(&prefix_hash) {
+                self.prefix_hashes.push(prefix_hash);
+            }
+        }
 
         if self.last_full_key.is_empty() {
             self.block_metas.last_mut().unwrap().smallest_key = full_key.to_vec();
@@ -164,11 +216,57 @@ impl SSTableBuilder {
     /// | Block 0 | ... | Block N-1 | N (4B) |
     /// ```
     pub fn finish(mut self) -> (Bytes, SstableMeta, Vec<VNodeBitmap>) {
-        let smallest_key = self.block_metas[0].smallest_key.clone();
-        let largest_key = self.last_full_key.to_vec();
+        assert!(!self.is_empty(), "sstable should not be empty");
+        let mut smallest_key = self
+            .block_metas
+            .first()
+            .map(|block_meta| block_meta.smallest_key.clone())
+            .unwrap_or_default();
+        let mut largest_key = self.last_full_key.to_vec();
+        self.range_tombstones
+            .sort_by(|a, b| a.start_user_key.cmp(&b.start_user_key));
+        for range_tombstone in &self.range_tombstones {
+            // Use the largest epoch so that the full keys sort before all versions of the user
+            // keys.
+            let start_key = key_with_epoch(range_tombstone.start_user_key.clone(), Epoch::MAX);
+            // The end key is exclusive. If it's the successor of a user key, i.e. the user key
+            // appended with a zero byte, the range ends exactly at that user key, whose last
+            // version is of the smallest epoch.
+            let end_key = match range_tombstone.end_user_key.split_last() {
+                Some((0, user_key)) => key_with_epoch(user_key.to_vec(), 0),
+                _ => key_with_epoch(range_tombstone.end_user_key.clone(), Epoch::MAX),
+            };
+            if smallest_key.is_empty()
+                || VersionedComparator::compare_key(&start_key, &smallest_key)
+                    == std::cmp::Ordering::Less
+            {
+                smallest_key = start_key;
+            }
+            if largest_key.is_empty()
+                || VersionedComparator::compare_key(&end_key, &largest_key)
+                    == std::cmp::Ordering::Greater
+            {
+                largest_key = end_key;
+            }
+        }
         self.build_block();
+        let compression_dictionary = self.compress_uncompressed_blocks();
         self.buf.put_u32_le(self.block_metas.len() as u32);
 
+        let (prefix_bloom_filter, prefix_bloom_lens) =
+            if self.options.bloom_false_positive > 0.0 && !self.prefix_hashes.is_empty() {
+                let bits_per_key = Bloom::bloom_bits_per_key(
+                    self.prefix_hashes.len(),
+                    self.options.bloom_false_positive,
+                );
+                (
+                    Bloom::build_from_key_hashes(&self.prefix_hashes, bits_per_key).to_vec(),
+                    self.prefix_bloom_lens,
+                )
+            } else {
+                (vec![], BTreeMap::new())
+            };
+
         let meta = SstableMeta {
             block_metas: self.block_metas,
             bloom_filter: if self.options.bloom_false_positive > 0.0 {
@@ -184,6 +282,10 @@ impl SSTableBuilder {
             key_count: self.key_count as u32,
             smallest_key,
             largest_key,
+            range_tombstones: self.range_tombstones,
+            compression_dictionary,
+            prefix_bloom_filter,
+            prefix_bloom_lens,
             version: VERSION,
         };
 
@@ -202,7 +304,7 @@ impl SSTableBuilder {
     }
 
     pub fn approximate_len(&self) -> usize {
-        self.buf.len() + 4
+        self.buf.len() + self.uncompressed_blocks_len + 4
     }
 
     fn build_block(&mut self) {
@@ -210,18 +312,64 @@ impl SSTableBuilder {
         if self.block_builder.is_none() {
             return;
         }
+        let block_builder = self.block_builder.take().unwrap();
+        if self.use_compression_dictionary() {
+            // Offsets and lens are filled when the blocks are compressed on `finish`.
+            let block = block_builder.build_uncompressed();
+            self.uncompressed_blocks_len += block.len();
+            self.uncompressed_blocks.push(block);
+            return;
+        }
         let mut block_meta = self.block_metas.last_mut().unwrap();
-        let block = self.block_builder.take().unwrap().build();
+        let block = block_builder.build();
         self.buf.put_slice(&block);
         block_meta.len = self.buf.len() as u32 - block_meta.offset;
     }
 
+    /// Trains the zstd dictionary from the uncompressed blocks, and writes the blocks compressed
+    /// with the dictionary. Returns the dictionary, which is empty if it's disabled or fails to
+    /// be trained, e.g. there are too few samples.
+    fn compress_uncompressed_blocks(&mut self) -> Vec<u8> {
+        if self.uncompressed_blocks.is_empty() {
+            return vec![];
+        }
+        let compression_dictionary = zstd::dict::from_samples(
+            &self.uncompressed_blocks,
+            self.options.compression_dictionary_size,
+        )
+        .unwrap_or_default();
+        // Prepare the dictionary once for all the blocks.
+        let encoder_dictionary = (!compression_dictionary.is_empty())
+            .then(|| EncoderDictionary::copy(&compression_dictionary, ZSTD_COMPRESSION_LEVEL));
+        for (block, block_meta) in self
+            .uncompressed_blocks
+            .drain(..)
+            .zip_eq(self.block_metas.iter_mut())
+        {
+            let block = compress_block(
+                block,
+                CompressionAlgorithm::Zstd,
+                encoder_dictionary.as_ref(),
+            );
+            block_meta.offset = self.buf.len() as u32;
+            self.buf.put_slice(&block);
+            block_meta.len = block.len() as u32;
+        }
+        self.uncompressed_blocks_len = 0;
+        compression_dictionary
+    }
+
     pub fn len(&self) -> usize {
         self.user_key_hashes.len()
     }
 
+    /// Returns the user key of the last added key, if any.
+    pub fn last_user_key(&self) -> Option<&[u8]> {
+        (!self.last_full_key.is_empty()).then(|| user_key(&self.last_full_key))
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.user_key_hashes.is_empty()
+        self.user_key_hashes.is_empty() && self.range_tombstones.is_empty()
     }
 
     /// Returns true if we roughly reached capacity
@@ -238,6 +386,7 @@ pub(super) mod tests {
         default_builder_opt_for_test, gen_default_test_sstable, test_key_of, test_value_of,
         TEST_KEYS_COUNT,
     };
+    use crate::hummock::Sstable;
 
     #[test]
     #[should_panic]
@@ -248,6 +397,8 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: 0.1,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_dictionary_size: 0,
+            prefix_bloom_lens: HashMap::new(),
         };
 
         let b = SSTableBuilder::new(opt);
@@ -269,6 +420,89 @@ pub(super) mod tests {
         assert_eq!(test_key_of(TEST_KEYS_COUNT - 1), meta.largest_key);
     }
 
+    #[test]
+    fn test_range_tombstone() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+
+        b.add(&test_key_of(1), HummockValue::put(&test_value_of(1)));
+        b.add_range_tombstone(RangeTombstone::new(
+            user_key(&test_key_of(2)).to_vec(),
+            user_key(&test_key_of(5)).to_vec(),
+            233,
+        ));
+        b.add_range_tombstone(RangeTombstone::new(
+            user_key(&test_key_of(0)).to_vec(),
+            user_key(&test_key_of(1)).to_vec(),
+            233,
+        ));
+
+        let (_, meta, _) = b.finish();
+
+        assert_eq!(meta.range_tombstones.len(), 2);
+        assert_eq!(
+            meta.range_tombstones[0].start_user_key,
+            user_key(&test_key_of(0))
+        );
+        assert_eq!(user_key(&meta.smallest_key), user_key(&test_key_of(0)));
+        assert_eq!(user_key(&meta.largest_key), user_key(&test_key_of(5)));
+
+        // An sstable with only range tombstones is allowed.
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+        b.add_range_tombstone(RangeTombstone::new(
+            user_key(&test_key_of(2)).to_vec(),
+            user_key(&test_key_of(5)).to_vec(),
+            233,
+        ));
+        let (_, meta, _) = b.finish();
+        assert!(meta.block_metas.is_empty());
+        assert_eq!(user_key(&meta.smallest_key), user_key(&test_key_of(2)));
+        assert_eq!(user_key(&meta.largest_key), user_key(&test_key_of(5)));
+    }
+
+    #[test]
+    fn test_prefix_bloom_filter() {
+        let table_key = |table_id: u32, group: u32, row: u32| {
+            let mut key = vec![b't'];
+            key.put_u32(table_id);
+            key.put_u32(group);
+            key.put_u32(row);
+            key
+        };
+        let mut opts = default_builder_opt_for_test();
+        opts.bloom_false_positive = 0.01;
+        // Prefixes of table 1 consist of the table prefix and the group.
+        opts.prefix_bloom_lens = [(1, 9)].into_iter().collect();
+        let mut b = SSTableBuilder::new(opts);
+        for group in 0..100 {
+            for row in 0..10 {
+                b.add(
+                    &key_with_epoch(table_key(1, group, row), 233),
+                    HummockValue::put(&test_value_of(1)),
+                );
+            }
+        }
+        b.add(
+            &key_with_epoch(table_key(2, 0, 0), 233),
+            HummockValue::put(&test_value_of(1)),
+        );
+        let (_, meta, _) = b.finish();
+        assert_eq!(meta.prefix_bloom_lens, [(1, 9)].into_iter().collect());
+        let table = Sstable::new(0, meta);
+
+        for group in 0..100 {
+            assert!(!table.surely_not_have_prefix(&table_key(1, group, 0)[..9]));
+            assert!(!table.surely_not_have_prefix(&table_key(1, group, 0)));
+        }
+        let true_negatives = (100..1100)
+            .filter(|&group| table.surely_not_have_prefix(&table_key(1, group, 0)[..9]))
+            .count();
+        assert!(true_negatives > 900);
+        // Prefixes shorter than the prefix length, or of tables without prefix bloom filter.
+        assert!(!table.surely_not_have_prefix(&table_key(1, 200, 0)[..7]));
+        assert!(!table.surely_not_have_prefix(&table_key(2, 200, 0)));
+        assert!(!table.surely_not_have_prefix(b"t"));
+    }
+
     async fn test_with_bloom_filter(with_blooms: bool) {
         let key_count = 1000;
 
@@ -278,6 +512,8 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: if with_blooms { 0.01 } else { 0.0 },
             compression_algorithm: CompressionAlgorithm::None,
+            compression_dictionary_size: 0,
+            prefix_bloom_lens: HashMap::new(),
         };
 
         // build remote table
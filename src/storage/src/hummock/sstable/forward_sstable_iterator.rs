@@ -185,9 +185,9 @@ mod tests {
     use crate::hummock::iterator::test_utils::mock_sstable_store;
     use crate::hummock::test_utils::{
         create_small_table_cache, default_builder_opt_for_test, gen_default_test_sstable,
-        gen_test_sstable_data, test_key_of, test_value_of, TEST_KEYS_COUNT,
+        gen_test_sstable_data, gen_test_sstable_inner, test_key_of, test_value_of, TEST_KEYS_COUNT,
     };
-    use crate::hummock::{CachePolicy, Sstable};
+    use crate::hummock::{CachePolicy, CompressionAlgorithm, SSTableBuilderOptions, Sstable};
 
     #[tokio::test]
     async fn test_table_iterator() {
@@ -220,6 +220,44 @@ mod tests {
         assert_eq!(cnt, TEST_KEYS_COUNT);
     }
 
+    #[tokio::test]
+    async fn test_table_iterator_with_zstd_dictionary() {
+        let sstable_store = mock_sstable_store();
+        let opts = SSTableBuilderOptions {
+            compression_algorithm: CompressionAlgorithm::Zstd,
+            compression_dictionary_size: 16 * 1024,
+            ..default_builder_opt_for_test()
+        };
+        // Read the blocks from the object store rather than the block cache.
+        let table = gen_test_sstable_inner(
+            opts,
+            1,
+            (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i)))),
+            sstable_store.clone(),
+            CachePolicy::Disable,
+        )
+        .await;
+        assert!(table.meta.block_metas.len() > 10);
+        assert!(!table.meta.compression_dictionary.is_empty());
+
+        let cache = create_small_table_cache();
+        let handle = cache.insert(1, 1, 1, Box::new(table));
+        let mut sstable_iter =
+            SSTableIterator::new(handle, sstable_store, Arc::new(ReadOptions::default()));
+        let mut cnt = 0;
+        sstable_iter.rewind().await.unwrap();
+        while sstable_iter.is_valid() {
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(cnt));
+            assert_bytes_eq!(
+                sstable_iter.value().into_user_value().unwrap(),
+                test_value_of(cnt)
+            );
+            cnt += 1;
+            sstable_iter.next().await.unwrap();
+        }
+        assert_eq!(cnt, TEST_KEYS_COUNT);
+    }
+
     #[tokio::test]
     async fn test_table_seek() {
         let sstable_store = mock_sstable_store();
@@ -297,7 +335,7 @@ mod tests {
         let kv_iter =
             (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i))));
         let (data, meta, _) = gen_test_sstable_data(default_builder_opt_for_test(), kv_iter);
-        let table = Sstable { id: 0, meta };
+        let table = Sstable::new(0, meta);
         sstable_store
             .put(table.clone(), data, CachePolicy::NotFill)
             .await
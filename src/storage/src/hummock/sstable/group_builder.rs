@@ -25,7 +25,7 @@ use risingwave_pb::hummock::VNodeBitmap;
 
 use crate::hummock::multi_builder::CapacitySplitTableBuilder;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{HummockResult, SSTableBuilder, SstableMeta};
+use crate::hummock::{HummockResult, RangeTombstone, SSTableBuilder, SstableMeta};
 
 pub type KeyValueGroupId = u64;
 const DEFAULT_KEY_VALUE_GROUP_ID: KeyValueGroupId = KeyValueGroupId::MAX;
@@ -160,6 +160,18 @@ where
         entry.add_full_key(full_key, value, allow_split).await
     }
 
+    /// Range tombstones are not grouped, and always go to the default group.
+    pub async fn add_range_tombstone(
+        &mut self,
+        range_tombstone: RangeTombstone,
+    ) -> HummockResult<()> {
+        self.builders
+            .get_mut(&DEFAULT_KEY_VALUE_GROUP_ID)
+            .unwrap()
+            .add_range_tombstone(range_tombstone)
+            .await
+    }
+
     pub fn seal_current(&mut self) {
         self.builders
             .iter_mut()
@@ -199,6 +211,8 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_dictionary_size: 0,
+                    prefix_bloom_lens: HashMap::new(),
                 }),
             ))
         };
@@ -25,15 +25,23 @@ pub mod builder;
 pub use builder::*;
 mod forward_sstable_iterator;
 pub mod multi_builder;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
 use bytes::{Buf, BufMut};
 use fail::fail_point;
 pub use forward_sstable_iterator::*;
 mod backward_sstable_iterator;
 pub use backward_sstable_iterator::*;
+use risingwave_hummock_sdk::key::{get_table_id, TABLE_PREFIX_LEN};
 use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::hummock::{KeyRange, SstableInfo};
+use zstd::dict::DecoderDictionary;
 
 pub mod group_builder;
+mod range_tombstone;
+pub use range_tombstone::*;
 mod utils;
 
 pub use utils::CompressionAlgorithm;
@@ -44,18 +52,51 @@ use super::{HummockError, HummockResult};
 
 const DEFAULT_META_BUFFER_CAPACITY: usize = 4096;
 const MAGIC: u32 = 0x5785ab73;
-const VERSION: u32 = 1;
+const VERSION: u32 = 4;
+/// The first format version that has the range tombstone block in [`SstableMeta`].
+const RANGE_TOMBSTONE_VERSION: u32 = 2;
+/// The first format version that has the compression dictionary in [`SstableMeta`].
+const COMPRESSION_DICTIONARY_VERSION: u32 = 3;
+/// The first format version that has the prefix bloom filter in [`SstableMeta`].
+const PREFIX_BLOOM_FILTER_VERSION: u32 = 4;
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 /// [`Sstable`] is a handle for accessing SST.
 pub struct Sstable {
     pub id: HummockSSTableId,
     pub meta: SstableMeta,
+    /// The zstd dictionary in `meta` prepared once for decoding all the blocks. `None` if the
+    /// dictionary is not used.
+    decoder_dictionary: Option<Arc<DecoderDictionary<'static>>>,
+}
+
+impl fmt::Debug for Sstable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sstable")
+            .field("id", &self.id)
+            .field("meta", &self.meta)
+            .finish()
+    }
 }
 
 impl Sstable {
     pub fn new(id: HummockSSTableId, meta: SstableMeta) -> Self {
-        Self { id, meta }
+        let decoder_dictionary = if meta.compression_dictionary.is_empty() {
+            None
+        } else {
+            Some(Arc::new(DecoderDictionary::copy(
+                &meta.compression_dictionary,
+            )))
+        };
+        Self {
+            id,
+            meta,
+            decoder_dictionary,
+        }
+    }
+
+    pub fn decoder_dictionary(&self) -> Option<&DecoderDictionary<'static>> {
+        self.decoder_dictionary.as_deref()
     }
 
     pub fn has_bloom_filter(&self) -> bool {
@@ -76,6 +117,24 @@ impl Sstable {
         }
     }
 
+    /// Returns whether no user key in the SST starts with `prefix`, according to the prefix bloom
+    /// filter. It's only able to tell so when `prefix` is no shorter than the prefix length of its
+    /// table.
+    pub fn surely_not_have_prefix(&self, prefix: &[u8]) -> bool {
+        if self.meta.prefix_bloom_filter.is_empty() || prefix.len() < TABLE_PREFIX_LEN {
+            return false;
+        }
+        let prefix_len = match get_table_id(prefix)
+            .and_then(|table_id| self.meta.prefix_bloom_lens.get(&table_id))
+        {
+            Some(&prefix_len) if prefix.len() >= prefix_len as usize => prefix_len as usize,
+            _ => return false,
+        };
+        let hash = farmhash::fingerprint32(&prefix[..prefix_len]);
+        let bloom = Bloom::new(&self.meta.prefix_bloom_filter);
+        bloom.surely_not_have_hash(hash)
+    }
+
     pub fn block_count(&self) -> usize {
         self.meta.block_metas.len()
     }
@@ -95,6 +154,7 @@ impl Sstable {
             }),
             file_size: self.meta.estimated_size as u64,
             vnode_bitmaps: vec![],
+            has_range_tombstones: !self.meta.range_tombstones.is_empty(),
         }
     }
 }
@@ -143,6 +203,14 @@ pub struct SstableMeta {
     pub key_count: u32,
     pub smallest_key: Vec<u8>,
     pub largest_key: Vec<u8>,
+    /// Range tombstones written into the SST, sorted by their start keys.
+    pub range_tombstones: Vec<RangeTombstone>,
+    /// Zstd dictionary that all the blocks are compressed with. Empty if not used.
+    pub compression_dictionary: Vec<u8>,
+    /// `table_id` -> Length of the user key prefixes added to `prefix_bloom_filter`.
+    pub prefix_bloom_lens: BTreeMap<u32, u32>,
+    /// Bloom filter on the user key prefixes. Empty if not built.
+    pub prefix_bloom_filter: Vec<u8>,
     /// Format version, for further compatibility.
     pub version: u32,
 }
@@ -157,6 +225,10 @@ impl SstableMeta {
     /// | estimated size (4B) | key count (4B) |
     /// | smallest key len (4B) | smallest key |
     /// | largest key len (4B) | largest key |
+    /// | M (4B) | range tombstone 0 | ... | range tombstone M-1 |
+    /// | compression dictionary len (4B) | compression dictionary |
+    /// | K (4B) | table id 0 (4B) | prefix len 0 (4B) | ... | table id K-1 | prefix len K-1 |
+    /// | prefix bloom filter len (4B) | prefix bloom filter |
     /// | checksum (8B) | version (4B) | magic (4B) |
     /// ```
     pub fn encode_to_bytes(&self) -> Vec<u8> {
@@ -170,6 +242,17 @@ impl SstableMeta {
         buf.put_u32_le(self.key_count as u32);
         put_length_prefixed_slice(&mut buf, &self.smallest_key);
         put_length_prefixed_slice(&mut buf, &self.largest_key);
+        buf.put_u32_le(self.range_tombstones.len() as u32);
+        for range_tombstone in &self.range_tombstones {
+            range_tombstone.encode(&mut buf);
+        }
+        put_length_prefixed_slice(&mut buf, &self.compression_dictionary);
+        buf.put_u32_le(self.prefix_bloom_lens.len() as u32);
+        for (table_id, prefix_len) in &self.prefix_bloom_lens {
+            buf.put_u32_le(*table_id);
+            buf.put_u32_le(*prefix_len);
+        }
+        put_length_prefixed_slice(&mut buf, &self.prefix_bloom_filter);
         let checksum = xxhash64_checksum(&buf);
         buf.put_u64_le(checksum);
         buf.put_u32_le(VERSION);
@@ -188,7 +271,7 @@ impl SstableMeta {
 
         cursor -= 4;
         let version = (&buf[cursor..cursor + 4]).get_u32_le();
-        if version != VERSION {
+        if version > VERSION {
             return Err(HummockError::invalid_format_version(version));
         }
 
@@ -207,6 +290,30 @@ impl SstableMeta {
         let key_count = buf.get_u32_le();
         let smallest_key = get_length_prefixed_slice(buf);
         let largest_key = get_length_prefixed_slice(buf);
+        let mut range_tombstones = vec![];
+        if version >= RANGE_TOMBSTONE_VERSION {
+            let range_tombstone_count = buf.get_u32_le() as usize;
+            range_tombstones.reserve(range_tombstone_count);
+            for _ in 0..range_tombstone_count {
+                range_tombstones.push(RangeTombstone::decode(buf));
+            }
+        }
+        let compression_dictionary = if version >= COMPRESSION_DICTIONARY_VERSION {
+            get_length_prefixed_slice(buf)
+        } else {
+            vec![]
+        };
+        let mut prefix_bloom_lens = BTreeMap::new();
+        let mut prefix_bloom_filter = vec![];
+        if version >= PREFIX_BLOOM_FILTER_VERSION {
+            let prefix_bloom_len_count = buf.get_u32_le() as usize;
+            for _ in 0..prefix_bloom_len_count {
+                let table_id = buf.get_u32_le();
+                let prefix_len = buf.get_u32_le();
+                prefix_bloom_lens.insert(table_id, prefix_len);
+            }
+            prefix_bloom_filter = get_length_prefixed_slice(buf);
+        }
 
         Ok(Self {
             block_metas,
@@ -215,6 +322,10 @@ impl SstableMeta {
             key_count,
             smallest_key,
             largest_key,
+            range_tombstones,
+            compression_dictionary,
+            prefix_bloom_lens,
+            prefix_bloom_filter,
             version,
         })
     }
@@ -235,6 +346,18 @@ impl SstableMeta {
             + self.smallest_key.len()
             + 4 // key len
             + self.largest_key.len()
+            + 4 // range tombstone count
+            + self
+            .range_tombstones
+            .iter()
+            .map(|range_tombstone| range_tombstone.encoded_size())
+            .sum::<usize>()
+            + 4 // compression dictionary len
+            + self.compression_dictionary.len()
+            + 4 // prefix len count
+            + 8 * self.prefix_bloom_lens.len()
+            + 4 // prefix bloom filter len
+            + self.prefix_bloom_filter.len()
             + 8 // checksum
             + 4 // version
             + 4 // magic
@@ -265,6 +388,14 @@ mod tests {
             key_count: 123,
             smallest_key: b"0-smallest-key".to_vec(),
             largest_key: b"9-largest-key".to_vec(),
+            range_tombstones: vec![RangeTombstone::new(
+                b"1-start-key".to_vec(),
+                b"3-end-key".to_vec(),
+                233,
+            )],
+            compression_dictionary: b"dictionary".to_vec(),
+            prefix_bloom_lens: [(1, 9), (2, 13)].into_iter().collect(),
+            prefix_bloom_filter: b"9876543210".to_vec(),
             version: VERSION,
         };
         let buf = meta.encode_to_bytes();
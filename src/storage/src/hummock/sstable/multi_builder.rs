@@ -14,11 +14,12 @@
 
 use bytes::Bytes;
 use futures::Future;
+use itertools::Itertools;
 use risingwave_hummock_sdk::key::{Epoch, FullKey};
 use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::hummock::VNodeBitmap;
 
-use super::SstableMeta;
+use super::{RangeTombstone, SstableMeta};
 use crate::hummock::value::HummockValue;
 use crate::hummock::{HummockResult, SSTableBuilder};
 
@@ -39,6 +40,9 @@ pub struct CapacitySplitTableBuilder<B> {
 
     /// Wrapped [`SSTableBuilder`]s. The last one is what we are operating on.
     builders: Vec<SSTableBuilderWrapper>,
+
+    /// Range tombstones to be split among the builders on `finish`.
+    range_tombstones: Vec<RangeTombstone>,
 }
 
 impl<B, F> CapacitySplitTableBuilder<B>
@@ -51,6 +55,7 @@ where
         Self {
             get_id_and_builder,
             builders: Vec::new(),
+            range_tombstones: Vec::new(),
         }
     }
 
@@ -114,6 +119,28 @@ where
         Ok(())
     }
 
+    /// Adds a range tombstone, which is split among the tables by their key ranges on `finish`, so
+    /// that the tables never overlap.
+    ///
+    /// If there's no builder created, this function will create a new one with the configuration
+    /// generated by the closure provided earlier.
+    pub async fn add_range_tombstone(
+        &mut self,
+        range_tombstone: RangeTombstone,
+    ) -> HummockResult<()> {
+        if self.builders.is_empty() {
+            let (id, builder) = (self.get_id_and_builder)().await?;
+            self.builders.push(SSTableBuilderWrapper {
+                id,
+                builder,
+                sealed: false,
+            });
+        }
+
+        self.range_tombstones.push(range_tombstone);
+        Ok(())
+    }
+
     /// Marks the current builder as sealed. Next call of `add` will always create a new table.
     ///
     /// If there's no builder created, or current one is already sealed before, then this function
@@ -125,7 +152,8 @@ where
     }
 
     /// Finalizes all the tables to be ids, blocks and metadata.
-    pub fn finish(self) -> Vec<(HummockSSTableId, Bytes, SstableMeta, Vec<VNodeBitmap>)> {
+    pub fn finish(mut self) -> Vec<(HummockSSTableId, Bytes, SstableMeta, Vec<VNodeBitmap>)> {
+        self.split_range_tombstones();
         self.builders
             .into_iter()
             .map(|b| {
@@ -134,14 +162,60 @@ where
             })
             .collect()
     }
+
+    /// Splits the range tombstones among the builders. The `i`-th builder covers the user keys in
+    /// `[successor of last user key of builder i - 1, successor of its own last user key)`, where
+    /// the first one and the last one are unbounded on the left and the right respectively.
+    fn split_range_tombstones(&mut self) {
+        let range_tombstones = std::mem::take(&mut self.range_tombstones);
+        if range_tombstones.is_empty() {
+            return;
+        }
+        // The successor of a user key is the key itself appended with a zero byte, which is the
+        // smallest user key larger than it.
+        let bounds = self
+            .builders
+            .iter()
+            .map(|b| {
+                b.builder.last_user_key().map(|user_key| {
+                    let mut bound = user_key.to_vec();
+                    bound.push(0);
+                    bound
+                })
+            })
+            .collect_vec();
+        let len = self.builders.len();
+        for (i, b) in self.builders.iter_mut().enumerate() {
+            let left = if i == 0 { None } else { bounds[i - 1].as_ref() };
+            let right = if i == len - 1 {
+                None
+            } else {
+                bounds[i].as_ref()
+            };
+            for range_tombstone in &range_tombstones {
+                let mut range_tombstone = range_tombstone.clone();
+                if let Some(left) = left && &range_tombstone.start_user_key < left {
+                    range_tombstone.start_user_key = left.clone();
+                }
+                if let Some(right) = right && &range_tombstone.end_user_key > right {
+                    range_tombstone.end_user_key = right.clone();
+                }
+                if range_tombstone.start_user_key < range_tombstone.end_user_key {
+                    b.builder.add_range_tombstone(range_tombstone);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::atomic::AtomicU64;
     use std::sync::atomic::Ordering::SeqCst;
 
     use itertools::Itertools;
+    use risingwave_hummock_sdk::key::user_key;
 
     use super::*;
     use crate::hummock::sstable::utils::CompressionAlgorithm;
@@ -162,6 +236,8 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_dictionary_size: 0,
+                    prefix_bloom_lens: HashMap::new(),
                 }),
             ))
         };
@@ -185,6 +261,8 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_dictionary_size: 0,
+                    prefix_bloom_lens: HashMap::new(),
                 }),
             ))
         };
@@ -247,6 +325,48 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_split_range_tombstones() {
+        let next_id = AtomicU64::new(1001);
+        let mut builder = CapacitySplitTableBuilder::new(|| async {
+            Ok((
+                next_id.fetch_add(1, SeqCst),
+                SSTableBuilder::new(default_builder_opt_for_test()),
+            ))
+        });
+
+        for user_key in [b"b", b"d", b"f"] {
+            builder
+                .add_user_key(user_key.to_vec(), HummockValue::put(b"v"), 233)
+                .await
+                .unwrap();
+            builder.seal_current();
+        }
+        builder
+            .add_range_tombstone(RangeTombstone::new(b"a".to_vec(), b"e".to_vec(), 234))
+            .await
+            .unwrap();
+
+        let results = builder.finish();
+        assert_eq!(results.len(), 3);
+        let range_tombstones = results
+            .iter()
+            .map(|(_, _, meta, _)| meta.range_tombstones.clone())
+            .collect_vec();
+        assert_eq!(
+            range_tombstones,
+            vec![
+                vec![RangeTombstone::new(b"a".to_vec(), b"b\0".to_vec(), 234)],
+                vec![RangeTombstone::new(b"b\0".to_vec(), b"d\0".to_vec(), 234)],
+                vec![RangeTombstone::new(b"d\0".to_vec(), b"e".to_vec(), 234)],
+            ]
+        );
+        // The tables don't overlap.
+        for (prev, next) in results.iter().tuple_windows() {
+            assert!(user_key(&prev.2.largest_key) < user_key(&next.2.smallest_key));
+        }
+    }
+
     #[tokio::test]
     async fn test_initial_not_allowed_split() {
         let next_id = AtomicU64::new(1001);
@@ -0,0 +1,145 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::{Buf, BufMut};
+use risingwave_hummock_sdk::HummockEpoch;
+
+use super::utils::{get_length_prefixed_slice, put_length_prefixed_slice};
+
+/// A [`RangeTombstone`] deletes all versions of the user keys in `[start_user_key,
+/// end_user_key)` whose epoch is not larger than `epoch`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeTombstone {
+    pub start_user_key: Vec<u8>,
+    pub end_user_key: Vec<u8>,
+    pub epoch: HummockEpoch,
+}
+
+impl RangeTombstone {
+    pub fn new(start_user_key: Vec<u8>, end_user_key: Vec<u8>, epoch: HummockEpoch) -> Self {
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    /// Returns whether the version of `user_key` written in `epoch` is deleted by this tombstone.
+    pub fn covers(&self, user_key: &[u8], epoch: HummockEpoch) -> bool {
+        epoch <= self.epoch
+            && self.start_user_key.as_slice() <= user_key
+            && user_key < self.end_user_key.as_slice()
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | start key len (4B) | start key | end key len (4B) | end key | epoch (8B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.start_user_key);
+        put_length_prefixed_slice(buf, &self.end_user_key);
+        buf.put_u64_le(self.epoch);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let start_user_key = get_length_prefixed_slice(buf);
+        let end_user_key = get_length_prefixed_slice(buf);
+        let epoch = buf.get_u64_le();
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        8 /* key lens */ + self.start_user_key.len() + self.end_user_key.len() + 8 // epoch
+    }
+}
+
+/// [`RangeTombstonesAggregator`] collects the range tombstones of all the sources read by an
+/// iterator or a point get, and tells whether a version of a key is deleted by any of them.
+///
+/// Only tombstones written no later than `read_epoch` take effect.
+#[derive(Debug)]
+pub struct RangeTombstonesAggregator {
+    /// Tombstones sorted by their start keys.
+    tombstones: Vec<RangeTombstone>,
+    read_epoch: HummockEpoch,
+}
+
+impl RangeTombstonesAggregator {
+    pub fn new(read_epoch: HummockEpoch) -> Self {
+        Self {
+            tombstones: vec![],
+            read_epoch,
+        }
+    }
+
+    pub fn add_tombstones<'a>(&mut self, tombstones: impl IntoIterator<Item = &'a RangeTombstone>) {
+        let read_epoch = self.read_epoch;
+        let len = self.tombstones.len();
+        self.tombstones.extend(
+            tombstones
+                .into_iter()
+                .filter(|tombstone| tombstone.epoch <= read_epoch)
+                .cloned(),
+        );
+        if self.tombstones.len() != len {
+            self.tombstones
+                .sort_by(|a, b| a.start_user_key.cmp(&b.start_user_key));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tombstones.is_empty()
+    }
+
+    /// Returns whether the version of `user_key` written in `epoch` is deleted.
+    pub fn should_delete(&self, user_key: &[u8], epoch: HummockEpoch) -> bool {
+        let candidates = self
+            .tombstones
+            .partition_point(|tombstone| tombstone.start_user_key.as_slice() <= user_key);
+        self.tombstones[..candidates]
+            .iter()
+            .any(|tombstone| tombstone.covers(user_key, epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_tombstones_aggregator() {
+        let mut aggregator = RangeTombstonesAggregator::new(10);
+        aggregator.add_tombstones(&[
+            RangeTombstone::new(b"b".to_vec(), b"d".to_vec(), 5),
+            RangeTombstone::new(b"a".to_vec(), b"c".to_vec(), 8),
+            // Invisible to the aggregator since it's written after the read epoch.
+            RangeTombstone::new(b"a".to_vec(), b"z".to_vec(), 20),
+        ]);
+
+        assert!(aggregator.should_delete(b"a", 8));
+        assert!(!aggregator.should_delete(b"a", 9));
+        assert!(aggregator.should_delete(b"b", 8));
+        assert!(aggregator.should_delete(b"c", 5));
+        assert!(!aggregator.should_delete(b"c", 6));
+        // End keys are exclusive.
+        assert!(!aggregator.should_delete(b"d", 1));
+        assert!(!aggregator.should_delete(b"e", 1));
+    }
+}
@@ -21,8 +21,9 @@ use fail::fail_point;
 use futures::channel::oneshot::{channel, Sender};
 use futures::future::try_join_all;
 use risingwave_hummock_sdk::{is_remote_sst_id, HummockSSTableId};
+use zstd::dict::DecoderDictionary;
 
-use super::{Block, BlockCache, Sstable, SstableMeta};
+use super::{Block, BlockCache, DiskBlockCache, Sstable, SstableMeta};
 use crate::hummock::{BlockHolder, CachableEntry, HummockError, HummockResult, LruCache};
 use crate::monitor::StateStoreMetrics;
 use crate::object::{get_local_path, BlockLocation, ObjectStoreRef};
@@ -48,6 +49,8 @@ pub struct SstableStore {
     path: String,
     store: ObjectStoreRef,
     block_cache: BlockCache,
+    /// Optional cache tier on the local disk, which is consulted on misses of `block_cache`.
+    disk_cache: Option<DiskBlockCache>,
     meta_cache: Arc<LruCache<HummockSSTableId, Box<Sstable>>>,
     /// Statistics.
     stats: Arc<StateStoreMetrics>,
@@ -71,12 +74,19 @@ impl SstableStore {
             path,
             store,
             block_cache: BlockCache::new(block_cache_capacity),
+            disk_cache: None,
             meta_cache,
             stats,
             prefetch_request: Arc::new(Default::default()),
         }
     }
 
+    /// Enables the block cache tier on the local disk.
+    pub fn with_disk_cache(mut self, disk_cache: DiskBlockCache) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
     pub async fn put(&self, sst: Sstable, data: Bytes, policy: CachePolicy) -> HummockResult<()> {
         self.put_sst_data(sst.id, data.clone()).await?;
 
@@ -91,9 +101,14 @@ impl SstableStore {
             for (block_idx, meta) in sst.meta.block_metas.iter().enumerate() {
                 let offset = meta.offset as usize;
                 let len = meta.len as usize;
-                self.add_block_cache(sst.id, block_idx as u64, data.slice(offset..offset + len))
-                    .await
-                    .unwrap();
+                self.add_block_cache(
+                    sst.id,
+                    block_idx as u64,
+                    data.slice(offset..offset + len),
+                    sst.decoder_dictionary(),
+                )
+                .await
+                .unwrap();
             }
             self.meta_cache
                 .insert(sst.id, sst.id, sst.encoded_size(), Box::new(sst.clone()));
@@ -172,8 +187,9 @@ impl SstableStore {
         sst_id: HummockSSTableId,
         block_idx: u64,
         block_data: Bytes,
+        dictionary: Option<&DecoderDictionary<'_>>,
     ) -> HummockResult<()> {
-        let block = Box::new(Block::decode(block_data)?);
+        let block = Box::new(Block::decode_with_dictionary(block_data, dictionary)?);
         self.block_cache.insert(sst_id, block_idx, block);
         Ok(())
     }
@@ -184,6 +200,13 @@ impl SstableStore {
             .block_metas
             .get(block_index)
             .ok_or_else(HummockError::invalid_block)?;
+        if let Some(block) = self
+            .get_block_from_disk_cache(sst, block_index as u64)
+            .await
+        {
+            return Ok(self.block_cache.insert(sst.id, block_index as u64, block));
+        }
+
         let mut read_size = block_meta.len;
         let end_index = std::cmp::min(
             block_index + 1 + PREFETCH_BLOCK_COUNT,
@@ -205,7 +228,15 @@ impl SstableStore {
             .read(&data_path, Some(block_loc))
             .await
             .map_err(HummockError::object_io_error)?;
-        let block = Block::decode(block_data.slice(..block_meta.len as usize))?;
+        self.fill_disk_cache(
+            sst.id,
+            block_index as u64,
+            block_data.slice(..block_meta.len as usize),
+        );
+        let block = Block::decode_with_dictionary(
+            block_data.slice(..block_meta.len as usize),
+            sst.decoder_dictionary(),
+        )?;
         let ret = self
             .block_cache
             .insert(sst.id, block_index as u64, Box::new(block));
@@ -214,7 +245,10 @@ impl SstableStore {
             let mut offset = block_meta.len as usize;
             for block_meta in &sst.meta.block_metas[(block_index + 1)..end_index] {
                 let end_offset = offset + block_meta.len as usize;
-                let block = Block::decode(block_data.slice(offset..end_offset))?;
+                let block = Block::decode_with_dictionary(
+                    block_data.slice(offset..end_offset),
+                    sst.decoder_dictionary(),
+                )?;
                 self.block_cache
                     .insert(sst.id, index_offset, Box::new(block));
                 offset = end_offset;
@@ -232,7 +266,24 @@ impl SstableStore {
     ) -> HummockResult<BlockHolder> {
         self.stats.sst_store_block_request_counts.inc();
 
+        let disable_cache: fn() -> bool = || {
+            fail_point!("disable_block_cache", |_| true);
+            false
+        };
+
+        let policy = if disable_cache() {
+            CachePolicy::Disable
+        } else {
+            policy
+        };
+
         let fetch_block = async move {
+            if !matches!(policy, CachePolicy::Disable)
+                && let Some(block) = self.get_block_from_disk_cache(sst, block_index).await
+            {
+                return Ok(block);
+            }
+
             let block_meta = sst
                 .meta
                 .block_metas
@@ -248,21 +299,13 @@ impl SstableStore {
                 .read(&data_path, Some(block_loc))
                 .await
                 .map_err(HummockError::object_io_error)?;
-            let block = Block::decode(block_data)?;
+            if let CachePolicy::Fill = policy {
+                self.fill_disk_cache(sst.id, block_index, block_data.clone());
+            }
+            let block = Block::decode_with_dictionary(block_data, sst.decoder_dictionary())?;
             Ok(Box::new(block))
         };
 
-        let disable_cache: fn() -> bool = || {
-            fail_point!("disable_block_cache", |_| true);
-            false
-        };
-
-        let policy = if disable_cache() {
-            CachePolicy::Disable
-        } else {
-            policy
-        };
-
         match policy {
             CachePolicy::Fill => {
                 self.block_cache
@@ -277,6 +320,43 @@ impl SstableStore {
         }
     }
 
+    /// Reads the block from the disk cache, if enabled. Corrupted blocks, e.g. those not flushed
+    /// before a crash, are removed from the disk cache.
+    async fn get_block_from_disk_cache(
+        &self,
+        sst: &Sstable,
+        block_index: u64,
+    ) -> Option<Box<Block>> {
+        let disk_cache = self.disk_cache.as_ref()?;
+        if !is_remote_sst_id(sst.id) {
+            return None;
+        }
+        let block_data = disk_cache.get(sst.id, block_index).await?;
+        match Block::decode_with_dictionary(block_data, sst.decoder_dictionary()) {
+            Ok(block) => Some(Box::new(block)),
+            Err(e) => {
+                tracing::warn!(
+                    "corrupted block {} of sst {} in disk cache: {}",
+                    block_index,
+                    sst.id,
+                    e
+                );
+                disk_cache.remove(sst.id, block_index).await;
+                None
+            }
+        }
+    }
+
+    /// Offers the block read from the object store to the disk cache in background, if enabled.
+    fn fill_disk_cache(&self, sst_id: HummockSSTableId, block_index: u64, block_data: Bytes) {
+        if let Some(disk_cache) = &self.disk_cache && is_remote_sst_id(sst_id) {
+            let disk_cache = disk_cache.clone();
+            tokio::spawn(async move {
+                disk_cache.insert(sst_id, block_index, block_data).await;
+            });
+        }
+    }
+
     pub async fn prefetch_sstables(&self, sst_ids: Vec<u64>) -> HummockResult<()> {
         let mut results = vec![];
         for id in sst_ids {
@@ -298,7 +378,7 @@ impl SstableStore {
                     .await
                     .map_err(HummockError::object_io_error)?;
                 let meta = SstableMeta::decode(&mut &buf[..])?;
-                let sst = Box::new(Sstable { id: sst_id, meta });
+                let sst = Box::new(Sstable::new(sst_id, meta));
                 let size = sst.encoded_size();
                 Ok((sst, size))
             })
@@ -26,11 +26,14 @@ use super::iterator::{
     BackwardConcatIterator, BackwardMergeIterator, BackwardUserIterator,
     BoxedForwardHummockIterator, ConcatIterator, DirectedUserIterator, MergeIterator, UserIterator,
 };
-use super::utils::{can_concat, search_sst_idx, validate_epoch, validate_table_key_range};
+use super::utils::{
+    can_concat, range_common_prefix, search_sst_idx, validate_epoch, validate_table_key_range,
+};
 use super::{BackwardSSTableIterator, HummockStorage, SSTableIterator};
 use crate::error::StorageResult;
 use crate::hummock::iterator::{BoxedBackwardHummockIterator, ReadOptions};
 use crate::hummock::utils::prune_ssts;
+use crate::hummock::RangeTombstonesAggregator;
 use crate::storage_value::StorageValue;
 use crate::store::*;
 use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
@@ -51,6 +54,10 @@ impl HummockStorage {
         // `overlapped_forward_sstable_iters`
         let mut overlapped_forward_iters = vec![];
         let mut overlapped_backward_iters = vec![];
+        let mut range_tombstones = RangeTombstonesAggregator::new(epoch);
+        // SSTs without any key of the common prefix are skipped, according to their prefix bloom
+        // filters.
+        let prefix = range_common_prefix(&key_range, backward);
 
         let (uncommitted_ssts, pinned_version) = {
             let read_version = self.local_version_manager.read_version(epoch);
@@ -63,6 +70,7 @@ impl HummockStorage {
             // Generate shared buffer iterators
             for shared_buffer in read_version.shared_buffer {
                 for batch in shared_buffer.get_overlap_batches(&key_range, backward) {
+                    range_tombstones.add_tombstones(batch.range_tombstones());
                     if backward {
                         overlapped_backward_iters
                             .push(Box::new(batch.into_backward_iter())
@@ -82,6 +90,11 @@ impl HummockStorage {
         let table_infos = prune_ssts(uncommitted_ssts.iter(), &key_range, backward, None);
         for table_info in table_infos.into_iter().rev() {
             let table = self.sstable_store.sstable(table_info.id).await?;
+            range_tombstones.add_tombstones(&table.value().meta.range_tombstones);
+            if table.value().surely_not_have_prefix(&prefix) {
+                self.stats.prefix_bloom_filter_true_negative_counts.inc();
+                continue;
+            }
             if backward {
                 overlapped_backward_iters.push(Box::new(BackwardSSTableIterator::new(
                     table,
@@ -114,7 +127,25 @@ impl HummockStorage {
                     _ => table_infos.len().saturating_sub(1),
                 };
                 assert!(start_table_idx < table_infos.len() && end_table_idx < table_infos.len());
-                let matched_table_infos = &table_infos[start_table_idx..=end_table_idx];
+                // The tables are loaded lazily by the concat iterator. Only the ones with range
+                // tombstones, which are needed upfront, or the ones possibly skipped by the prefix
+                // bloom filter are loaded here.
+                let mut matched_table_infos =
+                    Vec::with_capacity(end_table_idx + 1 - start_table_idx);
+                for &table_info in &table_infos[start_table_idx..=end_table_idx] {
+                    if table_info.has_range_tombstones || !prefix.is_empty() {
+                        let table = self.sstable_store.sstable(table_info.id).await?;
+                        range_tombstones.add_tombstones(&table.value().meta.range_tombstones);
+                        if table.value().surely_not_have_prefix(&prefix) {
+                            self.stats.prefix_bloom_filter_true_negative_counts.inc();
+                            continue;
+                        }
+                    }
+                    matched_table_infos.push(table_info);
+                }
+                if matched_table_infos.is_empty() {
+                    continue;
+                }
 
                 if backward {
                     overlapped_backward_iters.push(Box::new(BackwardConcatIterator::new(
@@ -141,6 +172,11 @@ impl HummockStorage {
             } else {
                 for table_info in table_infos.into_iter().rev() {
                     let table = self.sstable_store.sstable(table_info.id).await?;
+                    range_tombstones.add_tombstones(&table.value().meta.range_tombstones);
+                    if table.value().surely_not_have_prefix(&prefix) {
+                        self.stats.prefix_bloom_filter_true_negative_counts.inc();
+                        continue;
+                    }
                     if backward {
                         overlapped_backward_iters.push(Box::new(BackwardSSTableIterator::new(
                             table,
@@ -171,27 +207,33 @@ impl HummockStorage {
         let mut user_iterator = if backward {
             let backward_merge_iterator =
                 BackwardMergeIterator::new(overlapped_backward_iters, self.stats.clone());
-            DirectedUserIterator::Backward(BackwardUserIterator::with_epoch(
-                backward_merge_iterator,
-                (
-                    key_range.end_bound().map(|b| b.as_ref().to_owned()),
-                    key_range.start_bound().map(|b| b.as_ref().to_owned()),
-                ),
-                epoch,
-                Some(pinned_version),
-            ))
+            DirectedUserIterator::Backward(
+                BackwardUserIterator::with_epoch(
+                    backward_merge_iterator,
+                    (
+                        key_range.end_bound().map(|b| b.as_ref().to_owned()),
+                        key_range.start_bound().map(|b| b.as_ref().to_owned()),
+                    ),
+                    epoch,
+                    Some(pinned_version),
+                )
+                .with_range_tombstones(range_tombstones),
+            )
         } else {
             let merge_iterator = MergeIterator::new(overlapped_forward_iters, self.stats.clone());
 
-            DirectedUserIterator::Forward(UserIterator::new(
-                merge_iterator,
-                (
-                    key_range.start_bound().map(|b| b.as_ref().to_owned()),
-                    key_range.end_bound().map(|b| b.as_ref().to_owned()),
-                ),
-                epoch,
-                Some(pinned_version),
-            ))
+            DirectedUserIterator::Forward(
+                UserIterator::new(
+                    merge_iterator,
+                    (
+                        key_range.start_bound().map(|b| b.as_ref().to_owned()),
+                        key_range.end_bound().map(|b| b.as_ref().to_owned()),
+                    ),
+                    epoch,
+                    Some(pinned_version),
+                )
+                .with_range_tombstones(range_tombstones),
+            )
         };
 
         user_iterator.rewind().await?;
@@ -211,22 +253,35 @@ impl HummockStorage {
         epoch: u64,
         vnode_set: Option<VNodeBitmap>,
     ) -> StorageResult<Option<Bytes>> {
+        let mut range_tombstones = RangeTombstonesAggregator::new(epoch);
         let (uncommitted_ssts, pinned_version) = {
             let read_version = self.local_version_manager.read_version(epoch);
 
             // check epoch validity
             validate_epoch(read_version.pinned_version.safe_epoch(), epoch)?;
 
+            // Batches of the same epoch are not ordered, so collect the range tombstones of all
+            // batches before looking up the key.
+            let batches = read_version
+                .shared_buffer
+                .iter()
+                .flat_map(|shared_buffer| shared_buffer.get_overlap_batches(&(key..=key), false))
+                .collect_vec();
+            for batch in &batches {
+                range_tombstones.add_tombstones(batch.range_tombstones());
+            }
+
             // Query shared buffer. Return the value without iterating SSTs if found
-            for shared_buffer in read_version.shared_buffer {
-                for batch in shared_buffer.get_overlap_batches(&(key..=key), false) {
-                    match batch.get(key) {
-                        Some(v) => {
-                            self.stats.get_shared_buffer_hit_counts.inc();
-                            return Ok(v.into_user_value().map(|v| v.into()));
+            for batch in batches {
+                match batch.get(key) {
+                    Some(v) => {
+                        self.stats.get_shared_buffer_hit_counts.inc();
+                        if range_tombstones.should_delete(key, batch.epoch()) {
+                            return Ok(None);
                         }
-                        None => continue,
+                        return Ok(v.into_user_value().map(|v| v.into()));
                     }
+                    None => continue,
                 }
             }
 
@@ -248,7 +303,13 @@ impl HummockStorage {
             let table = self.sstable_store.sstable(table_info.id).await?;
             table_counts += 1;
             if let Some(v) = self
-                .get_from_table(table, &internal_key, key, read_options.clone())
+                .get_from_table(
+                    table,
+                    &internal_key,
+                    key,
+                    read_options.clone(),
+                    &mut range_tombstones,
+                )
                 .await?
             {
                 return Ok(Some(v));
@@ -269,7 +330,7 @@ impl HummockStorage {
                     for table_info in table_infos.into_iter().rev() {
                         let table = self.sstable_store.sstable(table_info.id).await?;
                         table_counts += 1;
-                        if let Some(v) = self.get_from_table(table, &internal_key, key, read_options.clone()).await? {
+                        if let Some(v) = self.get_from_table(table, &internal_key, key, read_options.clone(), &mut range_tombstones).await? {
                             return Ok(Some(v));
                         }
                     }
@@ -369,6 +430,22 @@ impl StateStore for HummockStorage {
         }
     }
 
+    /// Writes range tombstones to the shared buffer. They will be uploaded to the storage backend
+    /// along with the batches of the same epoch, and take effect on all the SSTs until the
+    /// deleted keys are dropped by compaction.
+    fn ingest_delete_ranges(
+        &self,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move {
+            self.local_version_manager
+                .write_shared_buffer_range_tombstones(epoch, delete_ranges)
+                .await?;
+            Ok(())
+        }
+    }
+
     /// Replicates a batch to shared buffer, without uploading to the storage backend.
     fn replicate_batch(
         &self,
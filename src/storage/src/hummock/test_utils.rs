@@ -14,6 +14,7 @@
 
 #![allow(dead_code)]
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use bytes::Bytes;
@@ -40,6 +41,8 @@ pub fn default_config_for_test() -> StorageConfig {
         sstable_size_mb: 256,
         block_size_kb: 64,
         bloom_false_positive: 0.1,
+        zstd_dictionary_size_kb: 0,
+        compression_algorithm_per_level: vec![],
         share_buffers_sync_parallelism: 2,
         shared_buffer_capacity_mb: 64,
         data_directory: "hummock_001".to_string(),
@@ -49,6 +52,9 @@ pub fn default_config_for_test() -> StorageConfig {
         disable_remote_compactor: false,
         enable_local_spill: false,
         local_object_store: "memory".to_string(),
+        disk_cache_dir: "".to_string(),
+        disk_cache_capacity_mb: 0,
+        disk_cache_admit_on_first_read: false,
     }
 }
 
@@ -80,6 +86,8 @@ pub fn default_builder_opt_for_test() -> SSTableBuilderOptions {
         restart_interval: DEFAULT_RESTART_INTERVAL,
         bloom_false_positive: 0.1,
         compression_algorithm: CompressionAlgorithm::None,
+        compression_dictionary_size: 0,
+        prefix_bloom_lens: HashMap::new(),
     }
 }
 
@@ -104,7 +112,7 @@ pub async fn gen_test_sstable_inner(
     policy: CachePolicy,
 ) -> Sstable {
     let (data, meta, _) = gen_test_sstable_data(opts, kv_iter);
-    let sst = Sstable { id: sst_id, meta };
+    let sst = Sstable::new(sst_id, meta);
     sstable_store.put(sst.clone(), data, policy).await.unwrap();
     sst
 }
@@ -16,6 +16,7 @@ use std::cmp::Ordering;
 use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::ops::RangeBounds;
 
+use itertools::Itertools;
 use risingwave_hummock_sdk::key::user_key;
 use risingwave_pb::hummock::{Level, SstableInfo, VNodeBitmap};
 
@@ -55,6 +56,47 @@ where
     !too_left && !too_right
 }
 
+/// Returns the longest prefix that all the keys in `search_key_range` share. It's used to consult
+/// the prefix bloom filters of SSTs on prefix scans.
+pub fn range_common_prefix<R, B>(search_key_range: &R, backward: bool) -> Vec<u8>
+where
+    R: RangeBounds<B>,
+    B: AsRef<[u8]>,
+{
+    let (start_bound, end_bound) = if backward {
+        (search_key_range.end_bound(), search_key_range.start_bound())
+    } else {
+        (search_key_range.start_bound(), search_key_range.end_bound())
+    };
+    let (start, end) = match (start_bound, end_bound) {
+        (Included(start) | Excluded(start), Included(end) | Excluded(end)) => {
+            (start.as_ref(), end.as_ref())
+        }
+        _ => return vec![],
+    };
+    let common_len = start
+        .iter()
+        .take(end.len())
+        .zip_eq(end.iter().take(start.len()))
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // A prefix scan usually ranges in `[prefix, next_key(prefix))`, where the last byte of the
+    // exclusive end key is one more than that of the prefix.
+    if let Excluded(end) = end_bound {
+        let end = end.as_ref();
+        let n = end.len();
+        if n > common_len
+            && n <= start.len()
+            && start[..n - 1] == end[..n - 1]
+            && start[n - 1].checked_add(1) == Some(end[n - 1])
+        {
+            return start[..n].to_vec();
+        }
+    }
+    start[..common_len].to_vec()
+}
+
 pub fn validate_epoch(safe_epoch: u64, epoch: u64) -> HummockResult<()> {
     if epoch < safe_epoch {
         return Err(HummockError::expired_epoch(safe_epoch, epoch));
@@ -149,3 +191,26 @@ where
     })
     .saturating_sub(1) // considering the boundary of 0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_common_prefix() {
+        let range = b"abc".to_vec()..b"abd".to_vec();
+        assert_eq!(range_common_prefix(&range, false), b"abc".to_vec());
+        let range = b"abc1".to_vec()..b"abd".to_vec();
+        assert_eq!(range_common_prefix(&range, false), b"abc".to_vec());
+        let range = b"abc".to_vec()..=b"abd".to_vec();
+        assert_eq!(range_common_prefix(&range, false), b"ab".to_vec());
+        let range = b"abc".to_vec()..b"acd".to_vec();
+        assert_eq!(range_common_prefix(&range, false), b"a".to_vec());
+        let range = b"abc".to_vec()..;
+        assert_eq!(range_common_prefix(&range, false), b"".to_vec());
+
+        // The bounds are reversed on backward scans.
+        let range = (Excluded(b"abd".to_vec()), Included(b"abc".to_vec()));
+        assert_eq!(range_common_prefix(&range, true), b"abc".to_vec());
+    }
+}
@@ -71,6 +71,20 @@ impl<S: StateStore> Keyspace<S> {
         Self { store, prefix }
     }
 
+    /// Creates a root [`Keyspace`] for an executor within the keyspace of the table `id`. The
+    /// states are attributed to the table, e.g., to be cleaned up when the table is dropped,
+    /// while not overlapping with the other executors of the same operator.
+    pub fn table_executor_root(store: S, id: &TableId, executor_id: u64) -> Self {
+        let prefix = {
+            let mut buf = BytesMut::with_capacity(13);
+            buf.put_u8(b't');
+            buf.put_u32(id.table_id);
+            buf.put_u64(executor_id);
+            buf.to_vec()
+        };
+        Self { store, prefix }
+    }
+
     /// Appends more bytes to the prefix and returns a new `Keyspace`
     #[must_use]
     pub fn append(&self, mut bytes: Vec<u8>) -> Self {
@@ -20,6 +20,7 @@ use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
 
 use bytes::Bytes;
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use parking_lot::RwLock;
 
@@ -171,6 +172,28 @@ impl StateStore for MemoryStateStore {
         async move { unimplemented!() }
     }
 
+    fn ingest_delete_ranges(
+        &self,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move {
+            let mut inner = self.inner.write();
+            for (start_key, end_key) in delete_ranges {
+                // Emulate the range tombstone by deleting every existing key in the range.
+                let keys = inner
+                    .range(to_bytes_range(start_key..end_key))
+                    .map(|((key, _), _)| key.clone())
+                    .dedup()
+                    .collect_vec();
+                for key in keys {
+                    inner.insert((key, Reverse(epoch)), None);
+                }
+            }
+            Ok(())
+        }
+    }
+
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -15,7 +15,7 @@
 mod state_store_metrics;
 
 use prometheus::core::{AtomicU64, Collector, GenericCounter, Metric};
-use prometheus::{Histogram, HistogramVec};
+use prometheus::{Histogram, HistogramVec, IntGauge};
 pub use state_store_metrics::*;
 mod monitored_store;
 pub use monitored_store::*;
@@ -43,6 +43,14 @@ impl Print for GenericCounter<AtomicU64> {
     }
 }
 
+impl Print for IntGauge {
+    fn print(&self) {
+        let desc = &self.desc()[0].fq_name;
+        let gauge = self.get();
+        println!("{desc} VALUE : {gauge}");
+    }
+}
+
 impl Print for Histogram {
     fn print(&self) {
         let desc = &self.desc()[0].fq_name;
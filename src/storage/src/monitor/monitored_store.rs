@@ -230,6 +230,19 @@ where
                 .inspect_err(|e| error!("Failed in replicate_batch: {:?}", e))
         }
     }
+
+    fn ingest_delete_ranges(
+        &self,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move {
+            self.inner
+                .ingest_delete_ranges(delete_ranges, epoch)
+                .await
+                .inspect_err(|e| error!("Failed in ingest_delete_ranges: {:?}", e))
+        }
+    }
 }
 
 /// A state store iterator wrapper for monitoring metrics.
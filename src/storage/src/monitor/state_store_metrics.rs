@@ -15,7 +15,8 @@
 use prometheus::core::{AtomicU64, GenericCounter};
 use prometheus::{
     exponential_buckets, histogram_opts, register_histogram_with_registry,
-    register_int_counter_with_registry, Histogram, Registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, IntGauge,
+    Registry,
 };
 
 use super::{monitor_process, Print};
@@ -32,6 +33,7 @@ macro_rules! for_all_metrics {
 
             bloom_filter_true_negative_counts: GenericCounter<AtomicU64>,
             bloom_filter_might_positive_counts: GenericCounter<AtomicU64>,
+            prefix_bloom_filter_true_negative_counts: GenericCounter<AtomicU64>,
 
             range_scan_size: Histogram,
             range_scan_duration: Histogram,
@@ -49,6 +51,12 @@ macro_rules! for_all_metrics {
 
             sst_store_block_request_counts: GenericCounter<AtomicU64>,
 
+            disk_cache_hit_counts: GenericCounter<AtomicU64>,
+            disk_cache_miss_counts: GenericCounter<AtomicU64>,
+            disk_cache_insert_counts: GenericCounter<AtomicU64>,
+            disk_cache_evict_counts: GenericCounter<AtomicU64>,
+            disk_cache_size: IntGauge,
+
             shared_buffer_to_l0_duration: Histogram,
             shared_buffer_to_sstable_size: Histogram,
 
@@ -129,6 +137,13 @@ impl StateStoreMetrics {
         )
         .unwrap();
 
+        let prefix_bloom_filter_true_negative_counts = register_int_counter_with_registry!(
+            "state_store_prefix_bloom_filter_true_negative_counts",
+            "Total number of sstables that have been skipped by prefix bloom filters on scans",
+            registry
+        )
+        .unwrap();
+
         // ----- range_scan -----
         let opts = histogram_opts!(
             "state_store_range_scan_size",
@@ -232,6 +247,38 @@ impl StateStoreMetrics {
         )
         .unwrap();
 
+        // ----- disk cache -----
+        let disk_cache_hit_counts = register_int_counter_with_registry!(
+            "state_store_disk_cache_hit_counts",
+            "Total number of block requests that have been fulfilled by disk cache",
+            registry
+        )
+        .unwrap();
+        let disk_cache_miss_counts = register_int_counter_with_registry!(
+            "state_store_disk_cache_miss_counts",
+            "Total number of block requests that have missed disk cache",
+            registry
+        )
+        .unwrap();
+        let disk_cache_insert_counts = register_int_counter_with_registry!(
+            "state_store_disk_cache_insert_counts",
+            "Total number of blocks that have been admitted to disk cache",
+            registry
+        )
+        .unwrap();
+        let disk_cache_evict_counts = register_int_counter_with_registry!(
+            "state_store_disk_cache_evict_counts",
+            "Total number of blocks that have been evicted from disk cache",
+            registry
+        )
+        .unwrap();
+        let disk_cache_size = register_int_gauge_with_registry!(
+            "state_store_disk_cache_size",
+            "Total size of blocks in disk cache",
+            registry
+        )
+        .unwrap();
+
         // --
         let compaction_upload_sst_counts = register_int_counter_with_registry!(
             "state_store_compaction_upload_sst_counts",
@@ -274,6 +321,7 @@ impl StateStoreMetrics {
 
             bloom_filter_true_negative_counts,
             bloom_filter_might_positive_counts,
+            prefix_bloom_filter_true_negative_counts,
 
             range_scan_size,
             range_scan_duration,
@@ -287,6 +335,11 @@ impl StateStoreMetrics {
             iter_merge_sstable_counts,
             iter_merge_seek_duration,
             sst_store_block_request_counts,
+            disk_cache_hit_counts,
+            disk_cache_miss_counts,
+            disk_cache_insert_counts,
+            disk_cache_evict_counts,
+            disk_cache_size,
             shared_buffer_to_l0_duration,
             shared_buffer_to_sstable_size,
 
@@ -87,6 +87,16 @@ impl StateStore for PanicStateStore {
         }
     }
 
+    fn ingest_delete_ranges(
+        &self,
+        _delete_ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move {
+            panic!("should not delete ranges from the state store!");
+        }
+    }
+
     fn iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
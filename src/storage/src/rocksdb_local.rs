@@ -111,6 +111,14 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
+    fn ingest_delete_ranges(
+        &self,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move { self.storage().await.delete_ranges(delete_ranges).await }
+    }
+
     fn iter<R, B>(&self, key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -304,6 +312,18 @@ impl RocksDBStorage {
         .await?
     }
 
+    async fn delete_ranges(&self, delete_ranges: Vec<(Bytes, Bytes)>) -> Result<()> {
+        let db = self.db.clone();
+        task::spawn_blocking(move || {
+            for (start_key, end_key) in delete_ranges {
+                db.delete_range(start_key.as_ref(), end_key.as_ref())
+                    .map_err(|e| RwError::from(InternalError(e)))?;
+            }
+            Ok(())
+        })
+        .await?
+    }
+
     async fn get(&self, key: &[u8]) -> Result<Option<Bytes>> {
         let db = self.db.clone();
         let seek_key = key.to_vec();
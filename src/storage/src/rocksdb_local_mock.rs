@@ -85,6 +85,14 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
+    fn ingest_delete_ranges(
+        &self,
+        _delete_ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move { unimplemented!() }
+    }
+
     fn iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -35,6 +35,7 @@ macro_rules! define_state_store_associated_type {
         type BackwardScanFuture<'a, R, B> = impl ScanFutureTrait<'a, R, B> where R: 'static + Send, B: 'static + Send;
         type IngestBatchFuture<'a> = impl IngestBatchFutureTrait<'a>;
         type ReplicateBatchFuture<'a> = impl EmptyFutureTrait<'a>;
+        type IngestDeleteRangesFuture<'a> = impl EmptyFutureTrait<'a>;
         type WaitEpochFuture<'a> = impl EmptyFutureTrait<'a>;
         type SyncFuture<'a> = impl EmptyFutureTrait<'a>;
         type IterFuture<'a, R, B> = impl Future<Output = $crate::error::StorageResult<Self::Iter>> + Send where R: 'static + Send, B: 'static + Send;
@@ -61,6 +62,8 @@ pub trait StateStore: Send + Sync + 'static + Clone {
 
     type ReplicateBatchFuture<'a>: EmptyFutureTrait<'a>;
 
+    type IngestDeleteRangesFuture<'a>: EmptyFutureTrait<'a>;
+
     type WaitEpochFuture<'a>: EmptyFutureTrait<'a>;
 
     type SyncFuture<'a>: EmptyFutureTrait<'a>;
@@ -126,6 +129,16 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         epoch: u64,
     ) -> Self::ReplicateBatchFuture<'_>;
 
+    /// Deletes all keys in each of the given `[start, end)` ranges. All versions of the keys
+    /// written no later than `epoch` will be invisible to the reads on `epoch` or later, while
+    /// the ones written after `epoch` are not affected. Like `ingest_batch`, the deletions are
+    /// associated with `epoch` and have the same all-or-nothing semantics.
+    fn ingest_delete_ranges(
+        &self,
+        delete_ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_>;
+
     /// Opens and returns an iterator for given `key_range`.
     /// The returned iterator will iterate data based on a snapshot corresponding to the given
     /// `epoch`.
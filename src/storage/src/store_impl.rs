@@ -20,7 +20,9 @@ use risingwave_common::config::StorageConfig;
 use risingwave_rpc_client::HummockMetaClient;
 
 use crate::error::StorageResult;
-use crate::hummock::{HummockStorage, SstableStore};
+use crate::hummock::{
+    DiskBlockCache, DiskBlockCacheOptions, DiskCacheAdmission, HummockStorage, SstableStore,
+};
 use crate::memory::MemoryStateStore;
 use crate::monitor::{MonitoredStateStore as Monitored, ObjectStoreMetrics, StateStoreMetrics};
 use crate::object::{parse_object_store, HybridObjectStore, ObjectStoreImpl};
@@ -106,7 +108,7 @@ impl StateStoreImpl {
                     remote_object_store
                 };
 
-                let sstable_store = Arc::new(SstableStore::new(
+                let mut sstable_store = SstableStore::new(
                     Arc::new(ObjectStoreImpl::new(
                         object_store,
                         object_store_metrics.clone(),
@@ -115,7 +117,24 @@ impl StateStoreImpl {
                     state_store_stats.clone(),
                     config.block_cache_capacity_mb * (1 << 20),
                     config.meta_cache_capacity_mb * (1 << 20),
-                ));
+                );
+                if !config.disk_cache_dir.is_empty() && config.disk_cache_capacity_mb > 0 {
+                    let disk_cache = DiskBlockCache::open(
+                        DiskBlockCacheOptions {
+                            dir: config.disk_cache_dir.clone().into(),
+                            capacity: config.disk_cache_capacity_mb * (1 << 20),
+                            admission: if config.disk_cache_admit_on_first_read {
+                                DiskCacheAdmission::All
+                            } else {
+                                DiskCacheAdmission::SecondRead
+                            },
+                        },
+                        state_store_stats.clone(),
+                    )
+                    .await?;
+                    sstable_store = sstable_store.with_disk_cache(disk_cache);
+                }
+                let sstable_store = Arc::new(sstable_store);
                 let inner = HummockStorage::new(
                     config.clone(),
                     sstable_store.clone(),
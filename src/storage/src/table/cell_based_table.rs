@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::sync::Arc;
 
 use bytes::Bytes;
@@ -187,6 +188,76 @@ impl<S: StateStore> CellBasedTable<S> {
         }
     }
 
+    /// Scans all rows whose serialized primary keys start with `pk_prefix`, in the order of the
+    /// primary key. Unlike [`CellBasedTable::iter`], it doesn't wait for `epoch` to be committed,
+    /// so an executor can read its own writes of the epochs not committed yet.
+    pub async fn scan_with_pk_prefix(
+        &self,
+        pk_prefix: &[u8],
+        epoch: u64,
+    ) -> StorageResult<Vec<Row>> {
+        let state_store_range_scan_res = self
+            .keyspace
+            .append(pk_prefix.to_vec())
+            .scan(None, epoch)
+            .await?;
+        let mut cell_based_row_deserializer =
+            CellBasedRowDeserializer::new(self.column_descs.clone());
+        let mut rows = vec![];
+        for (key, value) in state_store_range_scan_res {
+            if let Some((_pk, row)) = cell_based_row_deserializer
+                .deserialize(&key, &value)
+                .map_err(err)?
+            {
+                rows.push(row);
+            }
+        }
+        rows.extend(cell_based_row_deserializer.take().map(|(_pk, row)| row));
+        Ok(rows)
+    }
+
+    /// Scans all rows whose serialized primary keys are within `pk_range`, in the order of the
+    /// primary key, together with their serialized primary keys. Like
+    /// [`CellBasedTable::scan_with_pk_prefix`], it doesn't wait for `epoch` to be committed.
+    pub async fn scan_with_pk_range(
+        &self,
+        pk_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        epoch: u64,
+    ) -> StorageResult<Vec<(Vec<u8>, Row)>> {
+        let prefixed = |bound: Bound<Vec<u8>>| match bound {
+            Bound::Included(pk) => Bound::Included(self.keyspace.prefixed_key(pk)),
+            Bound::Excluded(pk) => Bound::Excluded(self.keyspace.prefixed_key(pk)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let start = match pk_range.0 {
+            Bound::Unbounded => Bound::Included(self.keyspace.key().to_vec()),
+            start => prefixed(start),
+        };
+        let end = match pk_range.1 {
+            Bound::Unbounded => Bound::Excluded(next_key(self.keyspace.key())),
+            end => prefixed(end),
+        };
+        let state_store_range_scan_res = self
+            .keyspace
+            .state_store()
+            .scan((start, end), None, epoch)
+            .await?;
+        let prefix_len = self.keyspace.key().len();
+        let mut cell_based_row_deserializer =
+            CellBasedRowDeserializer::new(self.column_descs.clone());
+        let mut rows = vec![];
+        for (key, value) in state_store_range_scan_res {
+            if let Some(pk_and_row) = cell_based_row_deserializer
+                .deserialize(&key.slice(prefix_len..), &value)
+                .map_err(err)?
+            {
+                rows.push(pk_and_row);
+            }
+        }
+        rows.extend(cell_based_row_deserializer.take());
+        Ok(rows)
+    }
+
     async fn batch_write_rows_inner<const WITH_VALUE_META: bool>(
         &mut self,
         buffer: BTreeMap<Row, RowOp>,
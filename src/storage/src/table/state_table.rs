@@ -12,13 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 #![allow(dead_code)]
+use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::sync::Arc;
 
 use risingwave_common::array::Row;
 use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::RwError;
-use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_common::util::ordered::{serialize_pk, OrderedRowSerializer};
 use risingwave_common::util::sort_util::OrderType;
+use risingwave_hummock_sdk::key::next_key;
 
 use super::cell_based_table::{CellBasedTable, CellBasedTableRowIter};
 use super::mem_table::{MemTable, RowOp};
@@ -102,6 +105,83 @@ impl<S: StateStore> StateTable<S> {
         Ok(())
     }
 
+    /// Iterates over the rows committed to the storage at `epoch` in the order of the primary key,
+    /// regardless of the buffered changes.
+    pub async fn iter_committed(&self, epoch: u64) -> StorageResult<CellBasedTableRowIter<S>> {
+        self.cell_based_table.iter(epoch).await
+    }
+
+    /// Scans the rows written to the storage as of `epoch` whose primary keys start with
+    /// `pk_prefix`, regardless of the buffered changes.
+    pub async fn scan_with_pk_prefix(
+        &self,
+        pk_prefix: &Row,
+        epoch: u64,
+    ) -> StorageResult<Vec<Row>> {
+        let prefix_serializer =
+            OrderedRowSerializer::new(self.order_types[..pk_prefix.size()].to_vec());
+        let mut serialized_prefix = vec![];
+        prefix_serializer.serialize(pk_prefix, &mut serialized_prefix);
+        self.cell_based_table
+            .scan_with_pk_prefix(&serialized_prefix, epoch)
+            .await
+    }
+
+    /// Scans the rows whose primary keys are within the range from `start` to `end` as of `epoch`,
+    /// with the buffered changes applied, in the order of the primary key. Both bounds are
+    /// prefixes of the primary key and are inclusive, i.e., the rows whose primary keys start with
+    /// them are within the range. `None` means unbounded.
+    pub async fn scan_with_pk_range(
+        &self,
+        start: Option<&Row>,
+        end: Option<&Row>,
+        epoch: u64,
+    ) -> StorageResult<Vec<Row>> {
+        let serialize_prefix = |pk_prefix: &Row| {
+            let prefix_serializer =
+                OrderedRowSerializer::new(self.order_types[..pk_prefix.size()].to_vec());
+            let mut serialized_prefix = vec![];
+            prefix_serializer.serialize(pk_prefix, &mut serialized_prefix);
+            serialized_prefix
+        };
+        let start = start.map(serialize_prefix);
+        // An empty key means that no key is greater than the prefix.
+        let end = end
+            .map(|end| next_key(&serialize_prefix(end)))
+            .filter(|end| !end.is_empty());
+        let in_range = |pk: &[u8]| {
+            start.as_ref().map_or(true, |start| pk >= start.as_slice())
+                && end.as_ref().map_or(true, |end| pk < end.as_slice())
+        };
+
+        let pk_range = (
+            start.clone().map_or(Bound::Unbounded, Bound::Included),
+            end.clone().map_or(Bound::Unbounded, Bound::Excluded),
+        );
+        let mut rows: BTreeMap<Vec<u8>, Row> = self
+            .cell_based_table
+            .scan_with_pk_range(pk_range, epoch)
+            .await?
+            .into_iter()
+            .collect();
+        let pk_serializer = OrderedRowSerializer::new(self.order_types.clone());
+        for (pk, row_op) in &self.mem_table.buffer {
+            let pk = serialize_pk(pk, &pk_serializer).map_err(err)?;
+            if !in_range(&pk) {
+                continue;
+            }
+            match row_op {
+                RowOp::Insert(row) | RowOp::Update((_, row)) => {
+                    rows.insert(pk, row.clone());
+                }
+                RowOp::Delete(_) => {
+                    rows.remove(&pk);
+                }
+            }
+        }
+        Ok(rows.into_values().collect())
+    }
+
     pub async fn iter(&self, _pk: Row) -> StorageResult<StateTableRowIter<S>> {
         todo!()
     }
@@ -488,4 +568,44 @@ mod tests {
         assert_eq!(row1_commit, None);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_state_table_scan_with_pk_range() -> StorageResult<()> {
+        let state_store = MemoryStateStore::new();
+        let keyspace = Keyspace::executor_root(state_store.clone(), 0x42);
+        let column_descs = vec![
+            ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+            ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+        ];
+        let order_types = vec![OrderType::Ascending];
+        let mut state_table = StateTable::new(keyspace.clone(), column_descs, order_types);
+        let row = |v: i32| Row(vec![Some(v.into()), Some((v * 10).into())]);
+        let pk = |v: i32| Row(vec![Some(v.into())]);
+
+        let epoch: u64 = 0;
+        for v in 1..=5 {
+            state_table.insert(pk(v), row(v)).unwrap();
+        }
+        state_table.commit(epoch).await.unwrap();
+
+        // The buffered changes are applied to the rows written to the storage.
+        state_table.delete(pk(3), row(3)).unwrap();
+        state_table.insert(pk(6), row(6)).unwrap();
+        let rows = state_table
+            .scan_with_pk_range(Some(&pk(2)), Some(&pk(4)), epoch)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![row(2), row(4)]);
+        let rows = state_table
+            .scan_with_pk_range(Some(&pk(4)), None, epoch)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![row(4), row(5), row(6)]);
+        let rows = state_table
+            .scan_with_pk_range(None, None, epoch)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![row(1), row(2), row(4), row(5), row(6)]);
+        Ok(())
+    }
 }
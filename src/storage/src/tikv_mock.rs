@@ -64,6 +64,14 @@ impl StateStore for TikvStateStore {
         async move { unimplemented!() }
     }
 
+    fn ingest_delete_ranges(
+        &self,
+        _delete_ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::IngestDeleteRangesFuture<'_> {
+        async move { unimplemented!() }
+    }
+
     fn backward_iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::BackwardIterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use bytes::Bytes;
+use risingwave_hummock_sdk::key::next_key;
 
 use crate::error::StorageResult;
 use crate::hummock::HummockError;
@@ -24,6 +25,9 @@ pub struct WriteBatch<S: StateStore> {
     store: S,
 
     batch: Vec<(Bytes, StorageValue)>,
+
+    /// `[start, end)` key ranges to delete.
+    delete_ranges: Vec<(Bytes, Bytes)>,
 }
 
 impl<S> WriteBatch<S>
@@ -35,6 +39,7 @@ where
         Self {
             store,
             batch: Vec::new(),
+            delete_ranges: Vec::new(),
         }
     }
 
@@ -43,6 +48,7 @@ where
         Self {
             store,
             batch: Vec::with_capacity(capacity),
+            delete_ranges: Vec::new(),
         }
     }
 
@@ -57,7 +63,8 @@ where
         self.batch.len()
     }
 
-    /// Preprocesses the batch to make it sorted. It returns `false` if duplicate keys are found.
+    /// Preprocesses the batch to make it sorted. It returns `false` if duplicate keys are found,
+    /// or any key is in the ranges to delete.
     pub fn preprocess(&mut self) -> StorageResult<()> {
         if self.is_empty() {
             return Ok(());
@@ -67,21 +74,36 @@ where
         self.batch.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
         self.batch.dedup_by(|(k1, _), (k2, _)| k1 == k2);
 
-        if original_length == self.batch.len() {
-            Ok(())
-        } else {
-            Err(HummockError::invalid_write_batch().into())
+        if original_length != self.batch.len() {
+            return Err(HummockError::invalid_write_batch().into());
+        }
+
+        self.delete_ranges.sort();
+        let delete_ranges = &self.delete_ranges;
+        let in_delete_ranges = |key: &Bytes| {
+            let candidates = delete_ranges.partition_point(|(start, _)| start <= key);
+            delete_ranges[..candidates].iter().any(|(_, end)| key < end)
+        };
+        if self.batch.iter().any(|(key, _)| in_delete_ranges(key)) {
+            return Err(HummockError::invalid_write_batch().into());
         }
+
+        Ok(())
     }
 
-    /// Returns `true` if the batch contains no key-value pairs.
+    /// Returns `true` if the batch contains no key-value pairs and no ranges to delete.
     pub fn is_empty(&self) -> bool {
-        self.batch.is_empty()
+        self.batch.is_empty() && self.delete_ranges.is_empty()
     }
 
     /// Ingests this batch into the associated state store.
     pub async fn ingest(mut self, epoch: u64) -> StorageResult<()> {
         self.preprocess()?;
+        if !self.delete_ranges.is_empty() {
+            self.store
+                .ingest_delete_ranges(self.delete_ranges, epoch)
+                .await?;
+        }
         self.store.ingest_batch(self.batch, epoch).await?;
         Ok(())
     }
@@ -150,6 +172,25 @@ impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S> {
     pub fn delete_with_value_meta(&mut self, key: impl AsRef<[u8]>, value_meta: ValueMeta) {
         self.do_push(Some(key.as_ref()), StorageValue::new_delete(value_meta));
     }
+
+    /// Deletes all keys in `[start_key, end_key)`, with both keys prepended by the prefix of
+    /// `keyspace`. No key in the range should be written in the same batch.
+    ///
+    /// Note that the deletion only takes effect on `ingest`.
+    pub fn delete_range(&mut self, start_key: impl AsRef<[u8]>, end_key: impl AsRef<[u8]>) {
+        let start_key = self.keyspace.prefixed_key(start_key).into();
+        let end_key = self.keyspace.prefixed_key(end_key).into();
+        self.global.delete_ranges.push((start_key, end_key));
+    }
+
+    /// Deletes all keys in the keyspace.
+    ///
+    /// Note that the deletion only takes effect on `ingest`.
+    pub fn delete_all(&mut self) {
+        let start_key = self.keyspace.key().to_vec().into();
+        let end_key = next_key(self.keyspace.key()).into();
+        self.global.delete_ranges.push((start_key, end_key));
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +220,50 @@ mod tests {
             .await
             .expect_err("Should panic here because of duplicate key.");
     }
+
+    #[tokio::test]
+    async fn test_write_batch_delete_range() {
+        let state_store = MemoryStateStore::new();
+        let key_space = Keyspace::executor_root(state_store.clone(), 0x118);
+
+        let mut write_batch = WriteBatch::new(state_store.clone());
+        let mut key_space_batch = write_batch.prefixify(&key_space);
+        key_space_batch.put(Bytes::from("aa"), StorageValue::new_default_put("111"));
+        key_space_batch.put(Bytes::from("bb"), StorageValue::new_default_put("222"));
+        key_space_batch.put(Bytes::from("cc"), StorageValue::new_default_put("333"));
+        write_batch.ingest(1).await.unwrap();
+
+        // Keys in the ranges to delete should not be written in the same batch.
+        let mut write_batch = WriteBatch::new(state_store.clone());
+        let mut key_space_batch = write_batch.prefixify(&key_space);
+        key_space_batch.delete_range(Bytes::from("aa"), Bytes::from("cc"));
+        key_space_batch.put(Bytes::from("bb"), StorageValue::new_default_put("444"));
+        write_batch
+            .ingest(2)
+            .await
+            .expect_err("Should panic here because of key in the deleted range.");
+
+        let mut write_batch = WriteBatch::new(state_store.clone());
+        let mut key_space_batch = write_batch.prefixify(&key_space);
+        key_space_batch.delete_range(Bytes::from("aa"), Bytes::from("cc"));
+        key_space_batch.put(Bytes::from("cc"), StorageValue::new_default_put("444"));
+        assert!(!write_batch.is_empty());
+        write_batch.ingest(2).await.unwrap();
+
+        assert_eq!(key_space.get("aa", 2).await.unwrap(), None);
+        assert_eq!(key_space.get("bb", 2).await.unwrap(), None);
+        assert_eq!(
+            key_space.get("cc", 2).await.unwrap(),
+            Some(Bytes::from("444"))
+        );
+        assert_eq!(
+            key_space.get("aa", 1).await.unwrap(),
+            Some(Bytes::from("111"))
+        );
+
+        let mut write_batch = WriteBatch::new(state_store);
+        write_batch.prefixify(&key_space).delete_all();
+        write_batch.ingest(3).await.unwrap();
+        assert!(key_space.scan(None, 3).await.unwrap().is_empty());
+    }
 }
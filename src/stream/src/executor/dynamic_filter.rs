@@ -0,0 +1,467 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::ordered::OrderedRow;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::expr::BoxedExpression;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::{barrier_align, AlignedMessage};
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::{BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef};
+
+/// `DynamicFilterExecutor` filters the left input by comparing a column of it against a column of
+/// the right input, which has at most one row, e.g., the result of a scalar subquery like
+/// `SELECT * FROM t WHERE v > (SELECT avg(v) FROM t)`.
+///
+/// The left rows are kept in a [`StateTable`] ordered by the compared column, so that only the rows
+/// within the old and the new right values are scanned and checked again when the right row
+/// changes. Only the right row is kept in memory.
+pub struct DynamicFilterExecutor<S: StateStore> {
+    input_l: BoxedExecutor,
+    input_r: BoxedExecutor,
+    info: ExecutorInfo,
+    state: DynamicFilterState<S>,
+}
+
+struct DynamicFilterState<S: StateStore> {
+    left_key: usize,
+    right_key: usize,
+    left_pk_indices: Vec<usize>,
+    right_pk_indices: Vec<usize>,
+    /// The comparison over the left columns followed by the right columns.
+    condition: BoxedExpression,
+    left_types: Vec<DataType>,
+    right_types: Vec<DataType>,
+    /// The left rows keyed by the compared column and then their stream keys.
+    left_table: StateTable<S>,
+    right_table: StateTable<S>,
+    right_row: Option<Row>,
+    /// The epoch of the rows being processed, which includes the writes of the previous epochs.
+    epoch: u64,
+}
+
+impl<S: StateStore> DynamicFilterExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: BoxedExecutor,
+        input_r: BoxedExecutor,
+        left_key: usize,
+        right_key: usize,
+        condition: BoxedExpression,
+        keyspace: Keyspace<S>,
+        pk_indices: Vec<usize>,
+        executor_id: u64,
+    ) -> Self {
+        let left_types = input_l.schema().data_types();
+        let right_types = input_r.schema().data_types();
+        let left_pk_indices = input_l.pk_indices().to_vec();
+        let right_pk_indices = input_r.pk_indices().to_vec();
+        let left_table = StateTable::new(
+            keyspace.append_u8(b'l'),
+            column_descs(&left_types),
+            vec![OrderType::Ascending; left_pk_indices.len() + 1],
+        );
+        let right_table = StateTable::new(
+            keyspace.append_u8(b'r'),
+            column_descs(&right_types),
+            vec![OrderType::Ascending; right_pk_indices.len()],
+        );
+
+        Self {
+            info: ExecutorInfo {
+                schema: input_l.schema().clone(),
+                pk_indices,
+                identity: format!("DynamicFilterExecutor {:X}", executor_id),
+            },
+            input_l,
+            input_r,
+            state: DynamicFilterState {
+                left_key,
+                right_key,
+                left_pk_indices,
+                right_pk_indices,
+                condition,
+                left_types,
+                right_types,
+                left_table,
+                right_table,
+                right_row: None,
+                epoch: 0,
+            },
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let Self {
+            input_l,
+            input_r,
+            mut state,
+            ..
+        } = self;
+        let mut first_barrier = true;
+
+        #[for_await]
+        for msg in barrier_align(input_l.execute(), input_r.execute()) {
+            match msg? {
+                AlignedMessage::Left(chunk) => {
+                    if let Some(chunk) = state.apply_left(chunk)? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Right(chunk) => {
+                    if let Some(chunk) = state.apply_right(chunk).await? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Barrier(barrier) => {
+                    state.epoch = barrier.epoch.curr;
+                    if first_barrier {
+                        state.load_right_row().await?;
+                        first_barrier = false;
+                    } else {
+                        state.commit(barrier.epoch.prev).await?;
+                    }
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+fn column_descs(data_types: &[DataType]) -> Vec<ColumnDesc> {
+    data_types
+        .iter()
+        .enumerate()
+        .map(|(idx, data_type)| ColumnDesc::unnamed(ColumnId::from(idx as i32), data_type.clone()))
+        .collect()
+}
+
+fn pk_of(row: &Row, pk_indices: &[usize]) -> Row {
+    Row(pk_indices.iter().map(|idx| row[*idx].clone()).collect())
+}
+
+impl<S: StateStore> DynamicFilterState<S> {
+    /// Loads the right row committed to the state table.
+    async fn load_right_row(&mut self) -> StreamExecutorResult<()> {
+        self.right_row = self
+            .right_table
+            .scan_with_pk_range(None, None, self.epoch)
+            .await
+            .map_err(StreamExecutorError::storage)?
+            .into_iter()
+            .next();
+        Ok(())
+    }
+
+    async fn commit(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        self.left_table
+            .commit(epoch)
+            .await
+            .map_err(StreamExecutorError::storage)?;
+        self.right_table
+            .commit(epoch)
+            .await
+            .map_err(StreamExecutorError::storage)
+    }
+
+    /// Returns the key of a left row in the state table, i.e., the compared column followed by
+    /// the stream key.
+    fn left_pk(&self, row: &Row) -> Row {
+        Row(std::iter::once(self.left_key)
+            .chain(self.left_pk_indices.iter().copied())
+            .map(|idx| row[idx].clone())
+            .collect())
+    }
+
+    /// Returns whether each of the left rows passes the filter with the `right_row`.
+    fn passes(&self, rows: &[Row], right_row: Option<&Row>) -> StreamExecutorResult<Vec<bool>> {
+        let right_row = match right_row {
+            Some(right_row) if !rows.is_empty() => right_row,
+            _ => return Ok(vec![false; rows.len()]),
+        };
+        let joined_rows = rows
+            .iter()
+            .map(|row| Row(row.0.iter().chain(right_row.0.iter()).cloned().collect()))
+            .collect_vec();
+        let joined_types = self
+            .left_types
+            .iter()
+            .chain(self.right_types.iter())
+            .cloned()
+            .collect_vec();
+        let data_chunk = DataChunk::from_rows(&joined_rows, &joined_types)
+            .map_err(StreamExecutorError::eval_error)?;
+        let result = self
+            .condition
+            .eval(&data_chunk)
+            .map_err(StreamExecutorError::eval_error)?;
+        Ok((0..rows.len())
+            .map(|idx| result.datum_at(idx) == Some(ScalarImpl::Bool(true)))
+            .collect())
+    }
+
+    fn apply_left(&mut self, chunk: StreamChunk) -> StreamExecutorResult<Option<StreamChunk>> {
+        let (ops, rows): (Vec<_>, Vec<_>) = chunk
+            .rows()
+            .map(|(op, row)| (op, row.to_owned_row()))
+            .unzip();
+        let passes = self.passes(&rows, self.right_row.as_ref())?;
+
+        let mut outputs = vec![];
+        for ((op, row), pass) in ops.into_iter().zip_eq(rows).zip_eq(passes) {
+            let pk = self.left_pk(&row);
+            let op = match op {
+                Op::Insert | Op::UpdateInsert => {
+                    self.left_table
+                        .insert(pk, row.clone())
+                        .map_err(StreamExecutorError::storage)?;
+                    Op::Insert
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    self.left_table
+                        .delete(pk, row.clone())
+                        .map_err(StreamExecutorError::storage)?;
+                    Op::Delete
+                }
+            };
+            if pass {
+                outputs.push((op, row));
+            }
+        }
+        self.build_chunk(outputs)
+    }
+
+    async fn apply_right(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let old_right_row = self.right_row.clone();
+        for (op, row) in chunk.rows() {
+            let row = row.to_owned_row();
+            let pk = pk_of(&row, &self.right_pk_indices);
+            match op {
+                Op::Insert | Op::UpdateInsert => {
+                    self.right_table
+                        .insert(pk, row.clone())
+                        .map_err(StreamExecutorError::storage)?;
+                    self.right_row = Some(row);
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    self.right_table
+                        .delete(pk, row)
+                        .map_err(StreamExecutorError::storage)?;
+                    self.right_row = None;
+                }
+            }
+        }
+
+        let old_value = old_right_row
+            .as_ref()
+            .and_then(|row| row[self.right_key].clone());
+        let new_value = self
+            .right_row
+            .as_ref()
+            .and_then(|row| row[self.right_key].clone());
+        if old_value == new_value {
+            return Ok(None);
+        }
+
+        // Only the left rows between the old and the new values may change their results, unless
+        // any of them is null, in which case all the rows are checked again.
+        let range = match (old_value, new_value) {
+            (Some(old_value), Some(new_value)) => {
+                let old_value = Row(vec![Some(old_value)]);
+                let new_value = Row(vec![Some(new_value)]);
+                let ordered = |row: &Row| OrderedRow::new(row.clone(), &[OrderType::Ascending]);
+                if ordered(&old_value) <= ordered(&new_value) {
+                    Some((old_value, new_value))
+                } else {
+                    Some((new_value, old_value))
+                }
+            }
+            _ => None,
+        };
+        let rows = self
+            .left_table
+            .scan_with_pk_range(
+                range.as_ref().map(|(lower, _)| lower),
+                range.as_ref().map(|(_, upper)| upper),
+                self.epoch,
+            )
+            .await
+            .map_err(StreamExecutorError::storage)?;
+        let old_passes = self.passes(&rows, old_right_row.as_ref())?;
+        let new_passes = self.passes(&rows, self.right_row.as_ref())?;
+
+        let mut outputs = vec![];
+        for ((row, old_pass), new_pass) in rows.into_iter().zip_eq(old_passes).zip_eq(new_passes) {
+            match (old_pass, new_pass) {
+                (true, false) => outputs.push((Op::Delete, row)),
+                (false, true) => outputs.push((Op::Insert, row)),
+                _ => {}
+            }
+        }
+        self.build_chunk(outputs)
+    }
+
+    fn build_chunk(&self, outputs: Vec<(Op, Row)>) -> StreamExecutorResult<Option<StreamChunk>> {
+        if outputs.is_empty() {
+            return Ok(None);
+        }
+        StreamChunk::from_rows(&outputs, &self.left_types)
+            .map(Some)
+            .map_err(StreamExecutorError::executor_v1)
+    }
+}
+
+impl<S: StateStore> Executor for DynamicFilterExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
+
+    #[madsim::test]
+    async fn test_dynamic_filter() {
+        // Filters `(k, v)` with `v > x`, where `x` is the only column of the right side.
+        let (mut tx_l, source_l) = MockSource::channel(
+            Schema {
+                fields: vec![
+                    Field::unnamed(DataType::Int64),
+                    Field::unnamed(DataType::Int64),
+                ],
+            },
+            vec![0],
+        );
+        let (mut tx_r, source_r) = MockSource::channel(
+            Schema {
+                fields: vec![Field::unnamed(DataType::Int64)],
+            },
+            vec![],
+        );
+        let condition = new_binary_expr(
+            Type::GreaterThan,
+            DataType::Boolean,
+            Box::new(InputRefExpression::new(DataType::Int64, 1)),
+            Box::new(InputRefExpression::new(DataType::Int64, 2)),
+        );
+        let executor = DynamicFilterExecutor::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            1,
+            0,
+            condition,
+            create_in_memory_keyspace(),
+            vec![0],
+            1,
+        );
+        let mut filter = Box::new(executor).execute();
+
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        filter.next().await.unwrap().unwrap();
+
+        // All the left rows are filtered out before the right row arrives.
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 1 10
+            + 2 20
+            + 3 30",
+        ));
+        tx_l.push_barrier(2, false);
+        tx_r.push_barrier(2, false);
+        filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_barrier()
+            .unwrap();
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I
+            + 15",
+        ));
+        let chunk = filter.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                + 2 20
+                + 3 30",
+            )
+        );
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 4 5
+            + 5 25
+            - 3 30",
+        ));
+        let chunk = filter.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                + 5 25
+                - 3 30",
+            )
+        );
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            "  I
+            U- 15
+            U+ 22",
+        ));
+        let chunk = filter.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                - 2 20",
+            )
+        );
+    }
+}
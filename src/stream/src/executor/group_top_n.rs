@@ -0,0 +1,331 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use risingwave_common::array::{Row, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_common::util::ordered::{OrderedRow, OrderedRowDeserializer};
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::top_n::{generate_internal_key, TopNRangeState};
+use super::top_n_executor::{generate_output, TopNExecutorBase, TopNExecutorWrapper};
+use super::{BoxedMessageStream, Executor, ExecutorInfo, PkIndices, PkIndicesRef};
+
+/// `GroupTopNExecutor` works like [`super::TopNExecutor`], but maintains a top-N for each group
+/// of the input rows, e.g., `ORDER BY .. LIMIT ..` in each partition of the `ROW_NUMBER()`
+/// pattern.
+pub type GroupTopNExecutor<S> = TopNExecutorWrapper<InnerGroupTopNExecutor<S>>;
+
+impl<S: StateStore> GroupTopNExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input: Box<dyn Executor>,
+        order_pairs: Vec<OrderPair>,
+        offset_and_limit: (usize, Option<usize>),
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        cache_size: Option<usize>,
+        group_cache_size: usize,
+        executor_id: u64,
+        group_key: Vec<usize>,
+    ) -> Result<Self> {
+        let info = input.info();
+        let schema = input.schema().clone();
+
+        Ok(TopNExecutorWrapper {
+            input,
+            inner: InnerGroupTopNExecutor::new(
+                info,
+                schema,
+                order_pairs,
+                offset_and_limit,
+                pk_indices,
+                keyspace,
+                cache_size,
+                group_cache_size,
+                executor_id,
+                group_key,
+            )?,
+        })
+    }
+}
+
+pub struct InnerGroupTopNExecutor<S: StateStore> {
+    info: ExecutorInfo,
+
+    /// Schema of the executor.
+    schema: Schema,
+
+    /// `LIMIT XXX`. `None` means no limit.
+    limit: Option<usize>,
+    /// `OFFSET XXX`. `0` means no offset.
+    offset: usize,
+
+    /// The primary key indices of the `GroupTopNExecutor`
+    pk_indices: PkIndices,
+
+    /// The internal key indices of the `GroupTopNExecutor`
+    internal_key_indices: PkIndices,
+
+    /// The order of internal keys of the `GroupTopNExecutor`
+    internal_key_order_types: Vec<OrderType>,
+
+    /// For deserializing the internal keys of each group.
+    ordered_row_deserializer: OrderedRowDeserializer,
+
+    /// The data types of the input rows.
+    row_data_types: Vec<DataType>,
+
+    /// The indices of the group key columns.
+    group_key: Vec<usize>,
+
+    /// The keyspace of the executor. Each group lives in a sub-keyspace of its serialized group
+    /// key.
+    keyspace: Keyspace<S>,
+
+    /// Number of entries to retain in memory after each flush, for each range of a group.
+    cache_size: Option<usize>,
+
+    /// The states of the recently accessed groups. Groups are only evicted after flushing, so
+    /// that no dirty state is lost.
+    groups: EvictableHashMap<Row, TopNRangeState<S>>,
+}
+
+impl<S: StateStore> InnerGroupTopNExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_info: ExecutorInfo,
+        schema: Schema,
+        order_pairs: Vec<OrderPair>,
+        offset_and_limit: (usize, Option<usize>),
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        cache_size: Option<usize>,
+        group_cache_size: usize,
+        executor_id: u64,
+        group_key: Vec<usize>,
+    ) -> Result<Self> {
+        let (internal_key_indices, internal_key_data_types, internal_key_order_types) =
+            generate_internal_key(&order_pairs, &pk_indices, &schema);
+
+        let ordered_row_deserializer =
+            OrderedRowDeserializer::new(internal_key_data_types, internal_key_order_types.clone());
+
+        let row_data_types = schema.data_types();
+        Ok(Self {
+            info: ExecutorInfo {
+                schema: input_info.schema,
+                pk_indices: input_info.pk_indices,
+                identity: format!("GroupTopNExecutor {:X}", executor_id),
+            },
+            schema,
+            offset: offset_and_limit.0,
+            limit: offset_and_limit.1,
+            pk_indices,
+            internal_key_indices,
+            internal_key_order_types,
+            ordered_row_deserializer,
+            row_data_types,
+            group_key,
+            keyspace,
+            cache_size,
+            groups: EvictableHashMap::new(group_cache_size),
+        })
+    }
+
+    /// Returns the state of the group, which is recovered from storage if not cached.
+    async fn get_group_state(
+        &mut self,
+        group_key: &Row,
+        epoch: u64,
+    ) -> StreamExecutorResult<&mut TopNRangeState<S>> {
+        if !self.groups.contains(group_key) {
+            let keyspace = self.keyspace.append(
+                group_key
+                    .serialize()
+                    .map_err(StreamExecutorError::top_n_state_error)?,
+            );
+            let mut state = TopNRangeState::new(
+                (self.offset, self.limit),
+                keyspace,
+                self.cache_size,
+                (0, 0, 0),
+                self.row_data_types.clone(),
+                self.ordered_row_deserializer.clone(),
+            );
+            state.init(epoch).await?;
+            self.groups.put(group_key.clone(), state);
+        }
+        Ok(self.groups.get_mut(group_key).unwrap())
+    }
+}
+
+impl<S: StateStore> Executor for InnerGroupTopNExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        panic!("Should execute by wrapper");
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> TopNExecutorBase for InnerGroupTopNExecutor<S> {
+    async fn apply_chunk(
+        &mut self,
+        chunk: StreamChunk,
+        epoch: u64,
+    ) -> StreamExecutorResult<StreamChunk> {
+        let mut new_ops = vec![];
+        let mut new_rows = vec![];
+
+        for (op, row_ref) in chunk.rows() {
+            let group_key = row_ref.row_by_indices(&self.group_key);
+            let pk_row = row_ref.row_by_indices(&self.internal_key_indices);
+            let ordered_pk_row = OrderedRow::new(pk_row, &self.internal_key_order_types);
+            let row = row_ref.to_owned_row();
+            self.get_group_state(&group_key, epoch)
+                .await?
+                .apply(op, ordered_pk_row, row, epoch, &mut new_ops, &mut new_rows)
+                .await?;
+        }
+        generate_output(new_rows, new_ops, &self.schema)
+    }
+
+    async fn flush_data(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        for state in self.groups.values_mut() {
+            if state.is_dirty() {
+                state.flush(epoch).await?;
+            }
+        }
+        self.groups.evict_to_target_cap();
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
+    use crate::executor::{Barrier, Message};
+
+    fn create_source(chunks: Vec<StreamChunk>) -> Box<MockSource> {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let mut messages = vec![Message::Barrier(Barrier::new_test_barrier(1))];
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            messages.push(Message::Chunk(chunk));
+            messages.push(Message::Barrier(Barrier::new_test_barrier(i as u64 + 2)));
+        }
+        Box::new(MockSource::with_messages(schema, vec![2], messages))
+    }
+
+    #[madsim::test]
+    async fn test_group_top_n_executor() {
+        // Columns of (group key, order key, stream key).
+        let source = create_source(vec![
+            StreamChunk::from_pretty(
+                " I I I
+                + 1 2 1
+                + 1 4 2
+                + 1 3 3
+                + 2 5 4",
+            ),
+            StreamChunk::from_pretty(
+                " I I I
+                - 1 2 1
+                + 2 1 5
+                + 2 6 6",
+            ),
+        ]);
+        // Top 2 of each group, and only one group is kept in memory after each flush.
+        let top_n_executor = Box::new(
+            GroupTopNExecutor::new(
+                source as Box<dyn Executor>,
+                vec![OrderPair::new(1, OrderType::Ascending)],
+                (0, Some(2)),
+                vec![2],
+                create_in_memory_keyspace(),
+                Some(1),
+                1,
+                1,
+                vec![0],
+            )
+            .unwrap(),
+        );
+        let mut top_n_executor = top_n_executor.execute();
+
+        // consume the init barrier
+        top_n_executor.next().await.unwrap().unwrap();
+        let res = top_n_executor.next().await.unwrap().unwrap();
+        assert_eq!(
+            *res.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I
+                + 1 2 1
+                + 1 4 2
+                - 1 4 2
+                + 1 3 3
+                + 2 5 4",
+            )
+        );
+        // barrier
+        top_n_executor.next().await.unwrap().unwrap();
+        let res = top_n_executor.next().await.unwrap().unwrap();
+        assert_eq!(
+            *res.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I
+                - 1 2 1
+                + 1 4 2
+                + 2 1 5",
+            )
+        );
+    }
+}
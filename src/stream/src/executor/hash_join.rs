@@ -296,11 +296,11 @@ impl<const T: JoinTypePrimitive, const SIDE: SideTypePrimitive> HashJoinChunkBui
             }
         // Outer sides
         } else if matched_row.is_zero_degree() && outer_side_null(T, SIDE) {
-            // if the matched_row does not have any current
-            // matches
+            // if the matched_row does not have any current matches, retract the joined row and
+            // pad the matched_row with nulls again
             if self
                 .stream_chunk_builder
-                .append_row_matched(Op::UpdateDelete, &matched_row.row)?
+                .append_row(Op::UpdateDelete, row, &matched_row.row)?
                 .is_some()
             {
                 return Err(internal_error("`Op::UpdateDelete` should not yield chunk"));
@@ -631,11 +631,17 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
 
         let keys = K::build(&side_update.key_indices, &data_chunk)?;
         for (idx, (row, op)) in data_chunk.rows().zip_eq(ops.iter()).enumerate() {
+            // The rows forwarded without matches may not be paired as the updates of the input,
+            // so the updates are always treated as deletes and inserts.
+            let op = match op {
+                Op::Insert | Op::UpdateInsert => Op::Insert,
+                Op::Delete | Op::UpdateDelete => Op::Delete,
+            };
             let key = &keys[idx];
             let value = row.to_owned_row();
             let pk = row.row_by_indices(&side_update.pk_indices);
             let mut matched_rows = Self::hash_eq_match(key, &mut side_match.ht).await;
-            match *op {
+            match op {
                 Op::Insert | Op::UpdateInsert => {
                     let entry_value = side_update.ht.get_or_init_without_cache(key).await?;
                     let mut degree = 0;
@@ -663,17 +669,17 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                         }
                         if degree == 0 {
                             if let Some(chunk) =
-                                hashjoin_chunk_builder.forward_if_not_matched(*op, &row)?
+                                hashjoin_chunk_builder.forward_if_not_matched(op, &row)?
                             {
                                 yield Message::Chunk(chunk);
                             }
                         } else if let Some(chunk) =
-                            hashjoin_chunk_builder.forward_exactly_once_if_matched(*op, &row)?
+                            hashjoin_chunk_builder.forward_exactly_once_if_matched(op, &row)?
                         {
                             yield Message::Chunk(chunk);
                         }
                     } else if let Some(chunk) =
-                        hashjoin_chunk_builder.forward_if_not_matched(*op, &row)?
+                        hashjoin_chunk_builder.forward_if_not_matched(op, &row)?
                     {
                         yield Message::Chunk(chunk);
                     }
@@ -717,17 +723,17 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                         }
                         if !matched {
                             if let Some(chunk) =
-                                hashjoin_chunk_builder.forward_if_not_matched(*op, &row)?
+                                hashjoin_chunk_builder.forward_if_not_matched(op, &row)?
                             {
                                 yield Message::Chunk(chunk);
                             }
                         } else if let Some(chunk) =
-                            hashjoin_chunk_builder.forward_exactly_once_if_matched(*op, &row)?
+                            hashjoin_chunk_builder.forward_exactly_once_if_matched(op, &row)?
                         {
                             yield Message::Chunk(chunk);
                         }
                     } else if let Some(chunk) =
-                        hashjoin_chunk_builder.forward_if_not_matched(*op, &row)?
+                        hashjoin_chunk_builder.forward_if_not_matched(op, &row)?
                     {
                         yield Message::Chunk(chunk);
                     }
@@ -1905,6 +1911,99 @@ mod tests {
         );
     }
 
+    #[madsim::test]
+    async fn test_streaming_hash_left_join_with_retraction() {
+        let chunk_l1 = StreamChunk::from_pretty(
+            "  I I
+             + 1 4
+             + 2 5",
+        );
+        let chunk_l2 = StreamChunk::from_pretty(
+            "  I I
+            U- 1 4
+            U+ 1 6",
+        );
+        let chunk_r1 = StreamChunk::from_pretty(
+            "  I I
+             + 2 7
+             + 2 8",
+        );
+        let chunk_r2 = StreamChunk::from_pretty(
+            "  I I
+             - 2 7",
+        );
+        let chunk_r3 = StreamChunk::from_pretty(
+            "  I I
+             - 2 8",
+        );
+        let (mut tx_l, mut tx_r, mut hash_join) = create_executor::<{ JoinType::LeftOuter }>(false);
+
+        // push the init barrier for left and right
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        // push the 1st left chunk
+        tx_l.push_chunk(chunk_l1);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                + 1 4 . .
+                + 2 5 . ."
+            )
+        );
+
+        // push the 2nd left chunk, where the unmatched updates are forwarded as deletes and inserts
+        tx_l.push_chunk(chunk_l2);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                - 1 4 . .
+                + 1 6 . ."
+            )
+        );
+
+        // push the 1st right chunk
+        tx_r.push_chunk(chunk_r1);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I I
+                U- 2 5 . .
+                U+ 2 5 2 7
+                +  2 5 2 8"
+            )
+        );
+
+        // push the 2nd right chunk, where the left row is still matched
+        tx_r.push_chunk(chunk_r2);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                - 2 5 2 7"
+            )
+        );
+
+        // push the 3rd right chunk, where the left row is padded with nulls again
+        tx_r.push_chunk(chunk_r3);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I I
+                U- 2 5 2 8
+                U+ 2 5 . ."
+            )
+        );
+    }
+
     #[madsim::test]
     async fn test_streaming_hash_right_join() {
         let chunk_l1 = StreamChunk::from_pretty(
@@ -130,6 +130,8 @@ async fn create_arrangement(
         keyspace,
         arrangement_col_arrange_rules(),
         column_ids,
+        false,
+        1,
         1,
     ))
 }
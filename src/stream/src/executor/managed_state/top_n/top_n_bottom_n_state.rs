@@ -278,6 +278,15 @@ impl<S: StateStore> ManagedTopNBottomNState<S> {
         Ok(())
     }
 
+    /// Recovers both the number of elements and the cache from storage, for a state created
+    /// without knowing its total count.
+    pub async fn init(&mut self, epoch: u64) -> Result<()> {
+        self.fill_in_cache(epoch).await?;
+        // All the elements are cached as we have a all-or-nothing policy for now.
+        self.total_count = self.bottom_n.len();
+        Ok(())
+    }
+
     /// `Flush` can be called by the executor when it receives a barrier and thus needs to
     /// checkpoint.
     pub async fn flush(&mut self, epoch: u64) -> Result<()> {
@@ -321,6 +321,31 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         Ok(())
     }
 
+    /// Recovers both the number of elements and the cache from storage, for a state created
+    /// without knowing its total count. Like `fill_in_cache`, it can only be called when the state
+    /// is not dirty.
+    pub async fn init(&mut self, epoch: u64) -> Result<()> {
+        debug_assert!(!self.is_dirty());
+        let iter = self.keyspace.iter(epoch).await?;
+        let mut pk_and_row_iter = PkAndRowIterator::<_, TOP_N_TYPE>::new(
+            iter,
+            &mut self.ordered_row_deserializer,
+            &mut self.cell_based_row_deserializer,
+        );
+        let mut total_count = 0;
+        while let Some((pk, row)) = pk_and_row_iter.next().await? {
+            total_count += 1;
+            if self
+                .top_n_count
+                .map_or(true, |count| self.top_n.len() < count)
+            {
+                self.top_n.insert(pk, row);
+            }
+        }
+        self.total_count = total_count;
+        Ok(())
+    }
+
     async fn flush_inner(
         &mut self,
         iterator: impl Iterator<Item = (OrderedRow, FlushStatus<Row>)>,
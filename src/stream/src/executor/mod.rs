@@ -45,9 +45,11 @@ mod batch_query;
 mod chain;
 mod debug;
 pub mod dispatch;
+mod dynamic_filter;
 mod error;
 mod filter;
 mod global_simple_agg;
+mod group_top_n;
 mod hash_agg;
 pub mod hash_join;
 mod hop_window;
@@ -58,10 +60,13 @@ mod managed_state;
 pub mod merge;
 pub mod monitor;
 mod mview;
+mod nested_loop_join;
+mod over_window;
 mod project;
 mod rearranged_chain;
 pub mod receiver;
 mod simple;
+mod sink;
 mod source;
 mod top_n;
 mod top_n_appendonly;
@@ -78,8 +83,10 @@ pub use batch_query::BatchQueryExecutor;
 pub use chain::ChainExecutor;
 pub use debug::DebugExecutor;
 pub use dispatch::DispatchExecutor;
+pub use dynamic_filter::DynamicFilterExecutor;
 pub use filter::FilterExecutor;
 pub use global_simple_agg::SimpleAggExecutor;
+pub use group_top_n::GroupTopNExecutor;
 pub use hash_agg::HashAggExecutor;
 pub use hash_join::*;
 pub use hop_window::HopWindowExecutor;
@@ -88,9 +95,12 @@ pub use lookup::*;
 pub use lookup_union::LookupUnionExecutor;
 pub use merge::MergeExecutor;
 pub use mview::*;
+pub use nested_loop_join::NestedLoopJoinExecutor;
+pub use over_window::OverWindowExecutor;
 pub use project::ProjectExecutor;
 pub use rearranged_chain::RearrangedChainExecutor;
 use simple::{SimpleExecutor, SimpleExecutorWrapper};
+pub use sink::SinkExecutor;
 pub use source::*;
 pub use top_n::TopNExecutor;
 pub use top_n_appendonly::AppendOnlyTopNExecutor;
@@ -207,6 +217,8 @@ pub struct Barrier {
     pub epoch: Epoch,
     pub mutation: Option<Arc<Mutation>>,
     pub span: tracing::Span,
+    /// The max epoch committed to the state store when the barrier is injected.
+    pub committed_epoch: u64,
 }
 
 impl Default for Barrier {
@@ -215,6 +227,7 @@ impl Default for Barrier {
             span: tracing::Span::none(),
             epoch: Epoch::default(),
             mutation: None,
+            committed_epoch: INVALID_EPOCH,
         }
     }
 }
@@ -282,7 +295,10 @@ impl Mutation {
 impl Barrier {
     pub fn to_protobuf(&self) -> ProstBarrier {
         let Barrier {
-            epoch, mutation, ..
+            epoch,
+            mutation,
+            committed_epoch,
+            ..
         }: Barrier = self.clone();
         ProstBarrier {
             epoch: Some(ProstEpoch {
@@ -318,6 +334,7 @@ impl Barrier {
                 })),
             },
             span: vec![],
+            committed_epoch,
         }
     }
 
@@ -366,6 +383,7 @@ impl Barrier {
             },
             epoch: Epoch::new(epoch.curr, epoch.prev),
             mutation,
+            committed_epoch: prost.committed_epoch,
         })
     }
 }
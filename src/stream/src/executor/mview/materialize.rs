@@ -16,16 +16,18 @@ use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::Op::*;
-use risingwave_common::array::Row;
+use risingwave_common::array::{Op, Row, StreamChunk};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
 use risingwave_common::util::sort_util::OrderPair;
 use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 
 use crate::executor::error::StreamExecutorError;
 use crate::executor::{
     BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef,
 };
+use crate::task::ActorId;
 
 /// `MaterializeExecutor` materializes changes in stream into a materialized view on storage.
 pub struct MaterializeExecutor<S: StateStore> {
@@ -33,9 +35,22 @@ pub struct MaterializeExecutor<S: StateStore> {
 
     state_table: StateTable<S>,
 
+    /// Keyspace of the materialized view, which is range-deleted once the actor is stopped, i.e.,
+    /// the materialized view is dropped.
+    keyspace: Keyspace<S>,
+
     /// Columns of arrange keys (including pk, group keys, join keys, etc.)
     arrange_columns: Vec<usize>,
 
+    /// Whether to resolve the conflicts of the input on the arrange keys, i.e., inserts of
+    /// existing keys and deletes of nonexistent keys, like an upsert source emits.
+    handle_pk_conflict: bool,
+
+    /// The epoch to read the existing rows with when handling pk conflicts.
+    epoch: u64,
+
+    actor_id: ActorId,
+
     info: ExecutorInfo,
 }
 
@@ -45,6 +60,8 @@ impl<S: StateStore> MaterializeExecutor<S> {
         keyspace: Keyspace<S>,
         keys: Vec<OrderPair>,
         column_ids: Vec<ColumnId>,
+        handle_pk_conflict: bool,
+        actor_id: ActorId,
         executor_id: u64,
     ) -> Self {
         let arrange_columns: Vec<usize> = keys.iter().map(|k| k.column_idx).collect();
@@ -63,8 +80,12 @@ impl<S: StateStore> MaterializeExecutor<S> {
             .collect_vec();
         Self {
             input,
-            state_table: StateTable::new(keyspace, column_descs, arrange_order_types),
+            state_table: StateTable::new(keyspace.clone(), column_descs, arrange_order_types),
+            keyspace,
             arrange_columns: arrange_columns.clone(),
+            handle_pk_conflict,
+            epoch: 0,
+            actor_id,
             info: ExecutorInfo {
                 schema,
                 pk_indices: arrange_columns,
@@ -80,6 +101,19 @@ impl<S: StateStore> MaterializeExecutor<S> {
         for msg in input {
             let msg = msg?;
             yield match msg {
+                Message::Chunk(chunk) if self.handle_pk_conflict => {
+                    // `self.input` has been moved, so only the fields can be borrowed here.
+                    Message::Chunk(
+                        Self::apply_chunk_with_pk_conflict(
+                            &mut self.state_table,
+                            &self.arrange_columns,
+                            self.epoch,
+                            &self.info.schema,
+                            chunk,
+                        )
+                        .await?,
+                    )
+                }
                 Message::Chunk(chunk) => {
                     for (idx, op) in chunk.ops().iter().enumerate() {
                         // check visibility
@@ -124,6 +158,17 @@ impl<S: StateStore> MaterializeExecutor<S> {
                         .commit_with_value_meta(b.epoch.prev)
                         .await
                         .map_err(StreamExecutorError::executor_v1)?;
+                    if b.is_to_stop_actor(self.actor_id) {
+                        // The materialized view is dropped. Delete the whole keyspace so that the
+                        // space can be reclaimed by compaction.
+                        let mut write_batch = WriteBatch::new(self.keyspace.state_store());
+                        write_batch.prefixify(&self.keyspace).delete_all();
+                        write_batch
+                            .ingest(b.epoch.prev)
+                            .await
+                            .map_err(StreamExecutorError::executor_v1)?;
+                    }
+                    self.epoch = b.epoch.curr;
                     Message::Barrier(b)
                 }
             }
@@ -131,6 +176,66 @@ impl<S: StateStore> MaterializeExecutor<S> {
     }
 }
 
+impl<S: StateStore> MaterializeExecutor<S> {
+    /// Applies a chunk whose rows may conflict with the existing ones on the arrange keys. An
+    /// insert overwrites the existing row of its key, and a delete removes the existing row of its
+    /// key regardless of the other columns, or is ignored if there's no such row. Returns the
+    /// chunk of the changes actually made.
+    async fn apply_chunk_with_pk_conflict(
+        state_table: &mut StateTable<S>,
+        arrange_columns: &[usize],
+        epoch: u64,
+        schema: &Schema,
+        chunk: StreamChunk,
+    ) -> Result<StreamChunk, StreamExecutorError> {
+        let mut changes = Vec::with_capacity(chunk.capacity());
+        for (idx, op) in chunk.ops().iter().enumerate() {
+            let visible = chunk
+                .visibility()
+                .as_ref()
+                .map(|x| x.is_set(idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+
+            let arrange_row = Row(arrange_columns
+                .iter()
+                .map(|col_idx| chunk.column_at(*col_idx).array_ref().datum_at(idx))
+                .collect_vec());
+            let row = Row(chunk
+                .columns()
+                .iter()
+                .map(|x| x.array_ref().datum_at(idx))
+                .collect_vec());
+            let old_row = state_table.get_row(&arrange_row, epoch).await?;
+
+            match (op, old_row) {
+                (Insert | UpdateInsert, Some(old_row)) => {
+                    if old_row != row {
+                        state_table.delete(arrange_row.clone(), old_row.clone())?;
+                        state_table.insert(arrange_row, row.clone())?;
+                        changes.push((Op::UpdateDelete, old_row));
+                        changes.push((Op::UpdateInsert, row));
+                    }
+                }
+                (Insert | UpdateInsert, None) => {
+                    state_table.insert(arrange_row, row.clone())?;
+                    changes.push((Op::Insert, row));
+                }
+                (Delete | UpdateDelete, Some(old_row)) => {
+                    state_table.delete(arrange_row, old_row.clone())?;
+                    changes.push((Op::Delete, old_row));
+                }
+                (Delete | UpdateDelete, None) => {}
+            }
+        }
+
+        StreamChunk::from_rows(&changes, &schema.data_types())
+            .map_err(StreamExecutorError::executor_v1)
+    }
+}
+
 impl<S: StateStore> Executor for MaterializeExecutor<S> {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
         self.execute_inner().boxed()
@@ -223,6 +328,8 @@ mod tests {
             keyspace,
             vec![OrderPair::new(0, OrderType::Ascending)],
             column_ids,
+            false,
+            1,
             1,
         ))
         .execute();
@@ -253,4 +360,165 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[madsim::test]
+    async fn test_materialize_executor_handle_pk_conflict() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+
+        let chunk1 = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5",
+        );
+        // Overwrite 1, delete 2 regardless of the value, ignore the delete of nonexistent 3, and
+        // insert 4 twice in the same epoch.
+        let chunk2 = StreamChunk::from_pretty(
+            " i i
+            + 1 6
+            - 2 0
+            - 3 0
+            + 4 7
+            + 4 8
+            + 1 6",
+        );
+
+        let source = MockSource::with_messages(
+            schema.clone(),
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk2),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let column_descs = vec![
+            ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+            ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ];
+        let table = CellBasedTable::new_for_test(
+            keyspace.clone(),
+            column_descs,
+            vec![OrderType::Ascending],
+        );
+        let mut materialize_executor = Box::new(MaterializeExecutor::new(
+            Box::new(source),
+            keyspace,
+            vec![OrderPair::new(0, OrderType::Ascending)],
+            column_ids,
+            true,
+            1,
+            1,
+        ))
+        .execute();
+
+        materialize_executor.next().await.transpose().unwrap();
+        match materialize_executor.next().await.transpose().unwrap() {
+            Some(Message::Chunk(chunk)) => assert_eq!(
+                chunk,
+                StreamChunk::from_pretty(
+                    " i i
+                    + 1 4
+                    + 2 5"
+                )
+            ),
+            _ => unreachable!(),
+        }
+        materialize_executor.next().await.transpose().unwrap();
+        match materialize_executor.next().await.transpose().unwrap() {
+            Some(Message::Chunk(chunk)) => assert_eq!(
+                chunk,
+                StreamChunk::from_pretty(
+                    "  i i
+                    U- 1 4
+                    U+ 1 6
+                    -  2 5
+                    +  4 7
+                    U- 4 7
+                    U+ 4 8"
+                )
+            ),
+            _ => unreachable!(),
+        }
+        materialize_executor.next().await.transpose().unwrap();
+
+        let row = table
+            .get_row(&Row(vec![Some(1_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(row, Some(Row(vec![Some(1_i32.into()), Some(6_i32.into())])));
+        let row = table
+            .get_row(&Row(vec![Some(2_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(row, None);
+        let row = table
+            .get_row(&Row(vec![Some(4_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(row, Some(Row(vec![Some(4_i32.into()), Some(8_i32.into())])));
+    }
+
+    #[madsim::test]
+    async fn test_materialize_executor_drop() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+
+        let chunk = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5",
+        );
+
+        let source = MockSource::with_messages(
+            schema.clone(),
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Barrier(
+                    Barrier::new_test_barrier(3)
+                        .with_mutation(Mutation::Stop([1].into_iter().collect())),
+                ),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let mut materialize_executor = Box::new(MaterializeExecutor::new(
+            Box::new(source),
+            keyspace.clone(),
+            vec![OrderPair::new(0, OrderType::Ascending)],
+            column_ids,
+            false,
+            1,
+            1,
+        ))
+        .execute();
+
+        for _ in 0..3 {
+            materialize_executor.next().await.transpose().unwrap();
+        }
+        assert!(!keyspace.scan(None, u64::MAX).await.unwrap().is_empty());
+
+        // The keyspace is range-deleted on the stop barrier, while the old snapshot is still
+        // readable.
+        materialize_executor.next().await.transpose().unwrap();
+        assert!(keyspace.scan(None, u64::MAX).await.unwrap().is_empty());
+        assert!(!keyspace.scan(None, 1).await.unwrap().is_empty());
+    }
 }
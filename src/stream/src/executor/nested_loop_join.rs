@@ -0,0 +1,645 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::expr::BoxedExpression;
+use risingwave_pb::plan_common::JoinType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::{barrier_align, AlignedMessage};
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::{BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef};
+
+/// How the rows of one side of a [`NestedLoopJoinExecutor`] contribute to its output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SideOutput {
+    /// Only output when joined with the rows of the other side.
+    Matched,
+    /// Also output padded with nulls when not matched by any row of the other side.
+    Outer,
+    /// Output alone when matched by any row of the other side.
+    Semi,
+    /// Output alone when not matched by any row of the other side.
+    Anti,
+    /// Not output at all.
+    Hidden,
+}
+
+impl SideOutput {
+    /// Returns the outputs of the left side and the right side of the join type.
+    fn of_join_type(join_type: JoinType) -> (Self, Self) {
+        match join_type {
+            JoinType::Inner => (Self::Matched, Self::Matched),
+            JoinType::LeftOuter => (Self::Outer, Self::Matched),
+            JoinType::RightOuter => (Self::Matched, Self::Outer),
+            JoinType::FullOuter => (Self::Outer, Self::Outer),
+            JoinType::LeftSemi => (Self::Semi, Self::Hidden),
+            JoinType::LeftAnti => (Self::Anti, Self::Hidden),
+            JoinType::RightSemi => (Self::Hidden, Self::Semi),
+            JoinType::RightAnti => (Self::Hidden, Self::Anti),
+        }
+    }
+
+    fn outputs_joined_rows(self) -> bool {
+        matches!(self, Self::Matched | Self::Outer)
+    }
+}
+
+struct JoinRow {
+    row: Row,
+    /// The number of the rows of the other side matched with this row.
+    degree: usize,
+}
+
+impl JoinRow {
+    /// Splits a row of the state table into the row and its degree in the last column.
+    fn from_stored(mut row: Row) -> Self {
+        let degree = match row.0.pop() {
+            Some(Some(ScalarImpl::Int64(degree))) => degree as usize,
+            degree => unreachable!("invalid degree {:?}", degree),
+        };
+        Self { row, degree }
+    }
+
+    fn stored(&self, degree: usize) -> Row {
+        let mut row = self.row.clone();
+        row.0.push(Some(ScalarImpl::Int64(degree as i64)));
+        row
+    }
+}
+
+struct JoinSide<S: StateStore> {
+    output: SideOutput,
+    pk_indices: Vec<usize>,
+    data_types: Vec<DataType>,
+    /// The rows of this side keyed by their stream keys, followed by their degrees.
+    state_table: StateTable<S>,
+}
+
+impl<S: StateStore> JoinSide<S> {
+    fn new(
+        output: SideOutput,
+        pk_indices: Vec<usize>,
+        data_types: Vec<DataType>,
+        keyspace: Keyspace<S>,
+    ) -> Self {
+        let column_descs = data_types
+            .iter()
+            .chain(std::iter::once(&DataType::Int64))
+            .enumerate()
+            .map(|(idx, data_type)| {
+                ColumnDesc::unnamed(ColumnId::from(idx as i32), data_type.clone())
+            })
+            .collect_vec();
+        let order_types = vec![OrderType::Ascending; pk_indices.len()];
+        Self {
+            output,
+            pk_indices,
+            data_types,
+            state_table: StateTable::new(keyspace, column_descs, order_types),
+        }
+    }
+
+    fn pk(&self, row: &Row) -> Row {
+        Row(self
+            .pk_indices
+            .iter()
+            .map(|idx| row[*idx].clone())
+            .collect())
+    }
+
+    fn null_row(&self) -> Row {
+        Row(vec![None; self.data_types.len()])
+    }
+}
+
+/// `NestedLoopJoinExecutor` joins two inputs without equal conditions. Each row is matched against
+/// all the rows of the other side with the join condition, so it's only used when there's no
+/// better choice.
+///
+/// The rows of both sides are kept in [`StateTable`]s keyed by their stream keys, together with
+/// the number of rows matched from the other side, which is used for outer, semi and anti joins.
+/// Nothing is cached: the rows of the other side are scanned from its state table for each chunk.
+pub struct NestedLoopJoinExecutor<S: StateStore> {
+    input_l: BoxedExecutor,
+    input_r: BoxedExecutor,
+    info: ExecutorInfo,
+    state: NestedLoopJoinState<S>,
+}
+
+struct NestedLoopJoinState<S: StateStore> {
+    left: JoinSide<S>,
+    right: JoinSide<S>,
+    /// The join condition over the left columns followed by the right columns. `None` means
+    /// always true.
+    condition: Option<BoxedExpression>,
+    output_types: Vec<DataType>,
+    /// The epoch of the rows being processed, which includes the writes of the previous epochs.
+    epoch: u64,
+}
+
+impl<S: StateStore> NestedLoopJoinExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: BoxedExecutor,
+        input_r: BoxedExecutor,
+        join_type: JoinType,
+        condition: Option<BoxedExpression>,
+        keyspace: Keyspace<S>,
+        pk_indices: Vec<usize>,
+        executor_id: u64,
+    ) -> Self {
+        let (output_l, output_r) = SideOutput::of_join_type(join_type);
+        let left = JoinSide::new(
+            output_l,
+            input_l.pk_indices().to_vec(),
+            input_l.schema().data_types(),
+            keyspace.append_u8(b'l'),
+        );
+        let right = JoinSide::new(
+            output_r,
+            input_r.pk_indices().to_vec(),
+            input_r.schema().data_types(),
+            keyspace.append_u8(b'r'),
+        );
+
+        let mut fields = vec![];
+        if output_l != SideOutput::Hidden {
+            fields.extend(input_l.schema().fields.iter().cloned());
+        }
+        if output_r != SideOutput::Hidden {
+            fields.extend(input_r.schema().fields.iter().cloned());
+        }
+        let schema = Schema { fields };
+        let output_types = schema.data_types();
+
+        Self {
+            input_l,
+            input_r,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("NestedLoopJoinExecutor {:X}", executor_id),
+            },
+            state: NestedLoopJoinState {
+                left,
+                right,
+                condition,
+                output_types,
+                epoch: 0,
+            },
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let Self {
+            input_l,
+            input_r,
+            mut state,
+            ..
+        } = self;
+        let mut first_barrier = true;
+
+        #[for_await]
+        for msg in barrier_align(input_l.execute(), input_r.execute()) {
+            match msg? {
+                AlignedMessage::Left(chunk) => {
+                    if let Some(chunk) = state.apply_chunk(chunk, true).await? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Right(chunk) => {
+                    if let Some(chunk) = state.apply_chunk(chunk, false).await? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Barrier(barrier) => {
+                    if first_barrier {
+                        first_barrier = false;
+                    } else {
+                        state.commit(barrier.epoch.prev).await?;
+                    }
+                    state.epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> NestedLoopJoinState<S> {
+    async fn commit(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        self.left
+            .state_table
+            .commit(epoch)
+            .await
+            .map_err(StreamExecutorError::storage)?;
+        self.right
+            .state_table
+            .commit(epoch)
+            .await
+            .map_err(StreamExecutorError::storage)
+    }
+
+    fn side(&self, is_left: bool) -> &JoinSide<S> {
+        if is_left {
+            &self.left
+        } else {
+            &self.right
+        }
+    }
+
+    fn side_mut(&mut self, is_left: bool) -> &mut JoinSide<S> {
+        if is_left {
+            &mut self.left
+        } else {
+            &mut self.right
+        }
+    }
+
+    /// Returns the indices of the `other_rows` matched with the `row`.
+    fn matched_rows(
+        &self,
+        row: &Row,
+        is_left: bool,
+        other_rows: &[JoinRow],
+    ) -> StreamExecutorResult<Vec<usize>> {
+        let condition = match &self.condition {
+            Some(condition) => condition,
+            None => return Ok((0..other_rows.len()).collect()),
+        };
+        if other_rows.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let joined_rows = other_rows
+            .iter()
+            .map(|other_row| join_rows(row, &other_row.row, is_left))
+            .collect_vec();
+        let joined_types = self
+            .left
+            .data_types
+            .iter()
+            .chain(self.right.data_types.iter())
+            .cloned()
+            .collect_vec();
+        let data_chunk = DataChunk::from_rows(&joined_rows, &joined_types)
+            .map_err(StreamExecutorError::eval_error)?;
+        let result = condition
+            .eval(&data_chunk)
+            .map_err(StreamExecutorError::eval_error)?;
+
+        Ok((0..other_rows.len())
+            .filter(|idx| result.datum_at(*idx) == Some(ScalarImpl::Bool(true)))
+            .collect())
+    }
+
+    /// Returns the output row of a row of one side alone, i.e., padded with nulls for outer joins.
+    fn output_single_row(&self, row: &Row, is_left: bool) -> Row {
+        match self.side(is_left).output {
+            SideOutput::Outer => join_rows(row, &self.side(!is_left).null_row(), is_left),
+            _ => row.clone(),
+        }
+    }
+
+    /// Applies the chunk of one side to the state, and returns the changes of the outputs.
+    async fn apply_chunk(
+        &mut self,
+        chunk: StreamChunk,
+        is_left: bool,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let mut other_rows = self
+            .side(!is_left)
+            .state_table
+            .scan_with_pk_range(None, None, self.epoch)
+            .await
+            .map_err(StreamExecutorError::storage)?
+            .into_iter()
+            .map(JoinRow::from_stored)
+            .collect_vec();
+        let old_degrees = other_rows.iter().map(|r| r.degree).collect_vec();
+
+        let mut outputs = vec![];
+        for (op, row) in chunk.rows() {
+            let row = row.to_owned_row();
+            let matched = self.matched_rows(&row, is_left, &other_rows)?;
+            match op {
+                Op::Insert | Op::UpdateInsert => {
+                    self.insert(row, is_left, &matched, &mut other_rows, &mut outputs)?
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    self.delete(row, is_left, &matched, &mut other_rows, &mut outputs)?
+                }
+            }
+        }
+
+        // Write back the degrees of the other side changed by the chunk.
+        let other = self.side_mut(!is_left);
+        for (other_row, old_degree) in other_rows.iter().zip_eq(old_degrees) {
+            if other_row.degree != old_degree {
+                let pk = other.pk(&other_row.row);
+                other
+                    .state_table
+                    .delete(pk.clone(), other_row.stored(old_degree))
+                    .map_err(StreamExecutorError::storage)?;
+                other
+                    .state_table
+                    .insert(pk, other_row.stored(other_row.degree))
+                    .map_err(StreamExecutorError::storage)?;
+            }
+        }
+
+        if outputs.is_empty() {
+            return Ok(None);
+        }
+        StreamChunk::from_rows(&outputs, &self.output_types)
+            .map(Some)
+            .map_err(StreamExecutorError::executor_v1)
+    }
+
+    fn insert(
+        &mut self,
+        row: Row,
+        is_left: bool,
+        matched: &[usize],
+        other_rows: &mut [JoinRow],
+        outputs: &mut Vec<(Op, Row)>,
+    ) -> StreamExecutorResult<()> {
+        let side_output = self.side(is_left).output;
+        let other_output = self.side(!is_left).output;
+
+        for &idx in matched {
+            let other_row = &mut other_rows[idx];
+            other_row.degree += 1;
+            let first_match = other_row.degree == 1;
+            let other_row = &other_row.row;
+
+            if side_output.outputs_joined_rows() && other_output.outputs_joined_rows() {
+                outputs.push((Op::Insert, join_rows(&row, other_row, is_left)));
+            }
+            if first_match {
+                match other_output {
+                    SideOutput::Outer => {
+                        outputs.push((Op::Delete, self.output_single_row(other_row, !is_left)))
+                    }
+                    SideOutput::Semi => outputs.push((Op::Insert, other_row.clone())),
+                    SideOutput::Anti => outputs.push((Op::Delete, other_row.clone())),
+                    SideOutput::Matched | SideOutput::Hidden => {}
+                }
+            }
+        }
+
+        match side_output {
+            SideOutput::Outer | SideOutput::Anti if matched.is_empty() => {
+                outputs.push((Op::Insert, self.output_single_row(&row, is_left)))
+            }
+            SideOutput::Semi if !matched.is_empty() => outputs.push((Op::Insert, row.clone())),
+            _ => {}
+        }
+
+        let side = self.side_mut(is_left);
+        let join_row = JoinRow {
+            row,
+            degree: matched.len(),
+        };
+        side.state_table
+            .insert(side.pk(&join_row.row), join_row.stored(join_row.degree))
+            .map_err(StreamExecutorError::storage)
+    }
+
+    fn delete(
+        &mut self,
+        row: Row,
+        is_left: bool,
+        matched: &[usize],
+        other_rows: &mut [JoinRow],
+        outputs: &mut Vec<(Op, Row)>,
+    ) -> StreamExecutorResult<()> {
+        let side_output = self.side(is_left).output;
+        let other_output = self.side(!is_left).output;
+
+        for &idx in matched {
+            let other_row = &mut other_rows[idx];
+            other_row.degree -= 1;
+            let last_match = other_row.degree == 0;
+            let other_row = &other_row.row;
+
+            if side_output.outputs_joined_rows() && other_output.outputs_joined_rows() {
+                outputs.push((Op::Delete, join_rows(&row, other_row, is_left)));
+            }
+            if last_match {
+                match other_output {
+                    SideOutput::Outer => {
+                        outputs.push((Op::Insert, self.output_single_row(other_row, !is_left)))
+                    }
+                    SideOutput::Semi => outputs.push((Op::Delete, other_row.clone())),
+                    SideOutput::Anti => outputs.push((Op::Insert, other_row.clone())),
+                    SideOutput::Matched | SideOutput::Hidden => {}
+                }
+            }
+        }
+
+        match side_output {
+            SideOutput::Outer | SideOutput::Anti if matched.is_empty() => {
+                outputs.push((Op::Delete, self.output_single_row(&row, is_left)))
+            }
+            SideOutput::Semi if !matched.is_empty() => outputs.push((Op::Delete, row.clone())),
+            _ => {}
+        }
+
+        // The degree of a row is always the number of its matched rows.
+        let side = self.side_mut(is_left);
+        let join_row = JoinRow {
+            row,
+            degree: matched.len(),
+        };
+        side.state_table
+            .delete(side.pk(&join_row.row), join_row.stored(join_row.degree))
+            .map_err(StreamExecutorError::storage)
+    }
+}
+
+/// Concatenates a row with a row of the other side, with the left row first.
+fn join_rows(row: &Row, other_row: &Row, is_left: bool) -> Row {
+    let (left, right) = if is_left {
+        (row, other_row)
+    } else {
+        (other_row, row)
+    };
+    Row(left.0.iter().chain(right.0.iter()).cloned().collect())
+}
+
+impl<S: StateStore> Executor for NestedLoopJoinExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MessageSender, MockSource};
+
+    /// Joins `(k, v)` with `(k, v)` on `left.v > right.v`, where `k` is the stream key.
+    fn create_executor(join_type: JoinType) -> (MessageSender, MessageSender, BoxedMessageStream) {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let (tx_l, source_l) = MockSource::channel(schema.clone(), vec![0]);
+        let (tx_r, source_r) = MockSource::channel(schema, vec![0]);
+        let condition = new_binary_expr(
+            Type::GreaterThan,
+            DataType::Boolean,
+            Box::new(InputRefExpression::new(DataType::Int64, 1)),
+            Box::new(InputRefExpression::new(DataType::Int64, 3)),
+        );
+        let executor = NestedLoopJoinExecutor::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            join_type,
+            Some(condition),
+            create_in_memory_keyspace(),
+            vec![0, 2],
+            1,
+        );
+        (tx_l, tx_r, Box::new(executor).execute())
+    }
+
+    #[madsim::test]
+    async fn test_nested_loop_inner_join() {
+        let (mut tx_l, mut tx_r, mut join) = create_executor(JoinType::Inner);
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        join.next().await.unwrap().unwrap();
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 1 10
+            + 2 20",
+        ));
+        // No output is produced for the right rows since the left side is empty.
+        tx_l.push_barrier(2, false);
+        tx_r.push_barrier(2, false);
+        join.next().await.unwrap().unwrap().into_barrier().unwrap();
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 3 15
+            + 4 25",
+        ));
+        let chunk = join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                + 3 15 1 10
+                + 4 25 1 10
+                + 4 25 2 20",
+            )
+        );
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I I
+            - 1 10",
+        ));
+        let chunk = join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                - 3 15 1 10
+                - 4 25 1 10",
+            )
+        );
+    }
+
+    #[madsim::test]
+    async fn test_nested_loop_left_outer_join() {
+        let (mut tx_l, mut tx_r, mut join) = create_executor(JoinType::LeftOuter);
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        join.next().await.unwrap().unwrap();
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 3 15",
+        ));
+        let chunk = join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                + 3 15 . .",
+            )
+        );
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I I
+            + 1 10
+            + 2 20",
+        ));
+        let chunk = join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                + 3 15 1 10
+                - 3 15 . .",
+            )
+        );
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I I
+            - 1 10",
+        ));
+        let chunk = join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                - 3 15 1 10
+                + 3 15 . .",
+            )
+        );
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashSet};
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema};
+use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::types::DataType;
+use risingwave_common::util::ordered::OrderedRow;
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
+use risingwave_expr::window_function::{evaluate_partition, WindowFunctionEvaluator};
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::{BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef};
+
+/// `OverWindowExecutor` computes the window functions over the partitions of the input
+/// incrementally. For each partition changed by a chunk, the outputs of the partition after the
+/// changes are computed and their differences from the previous outputs are emitted.
+///
+/// The output rows, i.e., the input rows with the results of the window functions, are kept in a
+/// [`StateTable`] keyed by the partition keys, the order keys and the stream key. The recently
+/// accessed partitions are cached in memory, and loaded from the state table on demand.
+pub struct OverWindowExecutor<S: StateStore> {
+    input: BoxedExecutor,
+    info: ExecutorInfo,
+    state: OverWindowState<S>,
+}
+
+/// The output rows of a partition, keyed by the sort keys of the rows.
+type Partition = BTreeMap<OrderedRow, Row>;
+
+struct OverWindowState<S: StateStore> {
+    calls: Vec<WindowFunctionEvaluator>,
+    partition_by: Vec<usize>,
+    order_key_indices: Vec<usize>,
+    /// The columns to sort the rows of a partition with, i.e., the order keys followed by the
+    /// stream key.
+    sort_key_indices: Vec<usize>,
+    sort_order_types: Vec<OrderType>,
+    input_types: Vec<DataType>,
+    output_types: Vec<DataType>,
+    state_table: StateTable<S>,
+    /// The recently accessed partitions. Partitions are only evicted after the state table is
+    /// committed, so a partition missing here has no uncommitted changes.
+    partitions: EvictableHashMap<Row, Partition>,
+    /// The epoch to read the partitions from the state table with.
+    epoch: u64,
+}
+
+impl<S: StateStore> OverWindowExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input: BoxedExecutor,
+        calls: Vec<WindowFunctionEvaluator>,
+        partition_by: Vec<usize>,
+        order_pairs: Vec<OrderPair>,
+        keyspace: Keyspace<S>,
+        pk_indices: Vec<usize>,
+        cache_size: usize,
+        executor_id: u64,
+    ) -> Self {
+        let input_types = input.schema().data_types();
+        let mut schema = input.schema().clone();
+        schema
+            .fields
+            .extend(calls.iter().map(|call| Field::unnamed(call.return_type())));
+        let output_types = schema.data_types();
+
+        let order_key_indices = order_pairs.iter().map(|o| o.column_idx).collect_vec();
+        let mut sort_key_indices = order_key_indices.clone();
+        let mut sort_order_types = order_pairs.iter().map(|o| o.order_type).collect_vec();
+        for idx in &pk_indices {
+            sort_key_indices.push(*idx);
+            sort_order_types.push(OrderType::Ascending);
+        }
+
+        let column_descs = output_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| {
+                ColumnDesc::unnamed(ColumnId::from(idx as i32), data_type.clone())
+            })
+            .collect_vec();
+        let state_order_types = partition_by
+            .iter()
+            .map(|_| OrderType::Ascending)
+            .chain(sort_order_types.iter().copied())
+            .collect_vec();
+
+        Self {
+            input,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("OverWindowExecutor {:X}", executor_id),
+            },
+            state: OverWindowState {
+                calls,
+                partition_by,
+                order_key_indices,
+                sort_key_indices,
+                sort_order_types,
+                input_types,
+                output_types,
+                state_table: StateTable::new(keyspace, column_descs, state_order_types),
+                partitions: EvictableHashMap::new(cache_size),
+                epoch: 0,
+            },
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let mut input = self.input.execute();
+        let barrier = input
+            .next()
+            .await
+            .unwrap()?
+            .into_barrier()
+            .expect("the first message received by over window executor must be a barrier");
+        self.state.epoch = barrier.epoch.curr;
+        yield Message::Barrier(barrier);
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    if let Some(chunk) = self.state.apply_chunk(chunk).await? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    self.state
+                        .state_table
+                        .commit(barrier.epoch.prev)
+                        .await
+                        .map_err(StreamExecutorError::storage)?;
+                    self.state.partitions.evict_to_target_cap();
+                    self.state.epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> OverWindowState<S> {
+    fn partition_key(&self, row: &Row) -> Row {
+        Row(self
+            .partition_by
+            .iter()
+            .map(|idx| row[*idx].clone())
+            .collect())
+    }
+
+    fn sort_key(&self, row: &Row) -> OrderedRow {
+        let key = Row(self
+            .sort_key_indices
+            .iter()
+            .map(|idx| row[*idx].clone())
+            .collect());
+        OrderedRow::new(key, &self.sort_order_types)
+    }
+
+    fn state_pk(&self, row: &Row) -> Row {
+        Row(self
+            .partition_by
+            .iter()
+            .chain(self.sort_key_indices.iter())
+            .map(|idx| row[*idx].clone())
+            .collect())
+    }
+
+    /// Returns the output rows of the partition, which are loaded from the state table if not
+    /// cached.
+    async fn get_partition(&mut self, partition_key: &Row) -> StreamExecutorResult<&mut Partition> {
+        if !self.partitions.contains(partition_key) {
+            let rows = self
+                .state_table
+                .scan_with_pk_prefix(partition_key, self.epoch)
+                .await
+                .map_err(StreamExecutorError::storage)?;
+            let partition = rows
+                .into_iter()
+                .map(|row| (self.sort_key(&row), row))
+                .collect();
+            self.partitions.put(partition_key.clone(), partition);
+        }
+        Ok(self.partitions.get_mut(partition_key).unwrap())
+    }
+
+    /// Computes the output rows of a partition from the input columns of the rows.
+    fn evaluate(&self, partition: &Partition) -> StreamExecutorResult<Partition> {
+        let input_len = self.input_types.len();
+        let rows = partition
+            .values()
+            .map(|row| Row(row.0[..input_len].to_vec()))
+            .collect_vec();
+        let results = evaluate_partition(
+            &self.calls,
+            &rows,
+            &self.input_types,
+            &self.order_key_indices,
+        )
+        .map_err(StreamExecutorError::eval_error)?;
+
+        Ok(partition
+            .keys()
+            .cloned()
+            .zip_eq(rows.into_iter().zip_eq(results))
+            .map(|(key, (mut row, result))| {
+                row.0.extend(result.0);
+                (key, row)
+            })
+            .collect())
+    }
+
+    /// Applies the chunk to the partitions and the state table, and returns the changes of the
+    /// outputs.
+    async fn apply_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let chunk = chunk.compact().map_err(StreamExecutorError::executor_v1)?;
+        let (data_chunk, ops) = chunk.into_parts();
+        let result_len = self.calls.len();
+
+        // The outputs of the changed partitions before the changes, in the order of their first
+        // changes.
+        let mut old_outputs = vec![];
+        let mut changed_partitions = HashSet::new();
+        for (op, row) in ops.into_iter().zip_eq(data_chunk.rows()) {
+            let mut row = row.to_owned_row();
+            let partition_key = self.partition_key(&row);
+            let sort_key = self.sort_key(&row);
+            let partition = self.get_partition(&partition_key).await?;
+            if changed_partitions.insert(partition_key.clone()) {
+                old_outputs.push((partition_key, partition.clone()));
+            }
+            match op {
+                Op::Insert | Op::UpdateInsert => {
+                    // The results of the window functions are filled in by the evaluation below.
+                    row.0.extend(std::iter::repeat(None).take(result_len));
+                    partition.insert(sort_key, row);
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    partition.remove(&sort_key);
+                }
+            }
+        }
+
+        // Deletions come first so that a row moved in its partition is deleted before it's
+        // inserted again.
+        let mut deletes = vec![];
+        let mut inserts = vec![];
+        for (partition_key, old_output) in old_outputs {
+            let partition = self.partitions.peek(&partition_key).unwrap();
+            let new_output = self.evaluate(partition)?;
+            for (key, old_row) in &old_output {
+                match new_output.get(key) {
+                    Some(new_row) if new_row == old_row => {}
+                    Some(new_row) => {
+                        deletes.push((Op::UpdateDelete, old_row.clone()));
+                        deletes.push((Op::UpdateInsert, new_row.clone()));
+                    }
+                    None => deletes.push((Op::Delete, old_row.clone())),
+                }
+            }
+            inserts.extend(
+                new_output
+                    .iter()
+                    .filter(|(key, _)| !old_output.contains_key(key))
+                    .map(|(_, row)| (Op::Insert, row.clone())),
+            );
+            self.write_state(&old_output, &new_output)?;
+            self.partitions.put(partition_key, new_output);
+        }
+        deletes.extend(inserts);
+
+        if deletes.is_empty() {
+            return Ok(None);
+        }
+        StreamChunk::from_rows(&deletes, &self.output_types)
+            .map(Some)
+            .map_err(StreamExecutorError::executor_v1)
+    }
+
+    /// Writes the differences between the old and new outputs of a partition to the state table.
+    fn write_state(
+        &mut self,
+        old_output: &Partition,
+        new_output: &Partition,
+    ) -> StreamExecutorResult<()> {
+        for (key, old_row) in old_output {
+            if new_output.get(key) != Some(old_row) {
+                self.state_table
+                    .delete(self.state_pk(old_row), old_row.clone())
+                    .map_err(StreamExecutorError::storage)?;
+            }
+        }
+        for (key, new_row) in new_output {
+            if old_output.get(key) != Some(new_row) {
+                self.state_table
+                    .insert(self.state_pk(new_row), new_row.clone())
+                    .map_err(StreamExecutorError::storage)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: StateStore> Executor for OverWindowExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::window_frame::{Bound, BoundType, Type as FrameType};
+    use risingwave_pb::expr::window_function::Type as WindowFunctionType;
+    use risingwave_pb::expr::{WindowFrame, WindowFunction};
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
+    use crate::executor::Barrier;
+
+    fn row_number() -> WindowFunctionEvaluator {
+        let bound = |r#type: BoundType| {
+            Some(Bound {
+                r#type: r#type as i32,
+                offset: 0,
+            })
+        };
+        WindowFunctionEvaluator::new(&WindowFunction {
+            r#type: WindowFunctionType::RowNumber as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Int64 as i32,
+                ..Default::default()
+            }),
+            frame: Some(WindowFrame {
+                r#type: FrameType::Rows as i32,
+                start: bound(BoundType::UnboundedPreceding),
+                end: bound(BoundType::CurrentRow),
+            }),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn create_source(chunks: Vec<StreamChunk>) -> BoxedExecutor {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ]);
+        let mut messages = vec![Message::Barrier(Barrier::new_test_barrier(1))];
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            messages.push(Message::Chunk(chunk));
+            messages.push(Message::Barrier(Barrier::new_test_barrier(idx as u64 + 2)));
+        }
+        Box::new(MockSource::with_messages(schema, vec![2], messages))
+    }
+
+    async fn collect_chunks(executor: BoxedExecutor) -> Vec<StreamChunk> {
+        let mut stream = executor.execute();
+        let mut chunks = vec![];
+        while let Some(msg) = stream.next().await {
+            if let Message::Chunk(chunk) = msg.unwrap() {
+                chunks.push(chunk);
+            }
+        }
+        chunks
+    }
+
+    #[madsim::test]
+    async fn test_over_window_executor() {
+        test_over_window_executor_with_cache_size(1024).await;
+    }
+
+    /// The partitions evicted from the cache are loaded from the state table again.
+    #[madsim::test]
+    async fn test_over_window_executor_evicted() {
+        test_over_window_executor_with_cache_size(1).await;
+    }
+
+    async fn test_over_window_executor_with_cache_size(cache_size: usize) {
+        // Columns of (partition key, order key, stream key).
+        let source = create_source(vec![
+            StreamChunk::from_pretty(
+                " I I I
+                + 1 2 1
+                + 1 4 2
+                + 2 1 3",
+            ),
+            StreamChunk::from_pretty(
+                " I I I
+                + 1 3 4
+                - 1 2 1",
+            ),
+        ]);
+        let executor = Box::new(OverWindowExecutor::new(
+            source,
+            vec![row_number()],
+            vec![0],
+            vec![OrderPair::new(1, OrderType::Ascending)],
+            create_in_memory_keyspace(),
+            vec![2],
+            cache_size,
+            1,
+        ));
+
+        let chunks = collect_chunks(executor).await;
+        assert_eq!(
+            chunks,
+            vec![
+                StreamChunk::from_pretty(
+                    " I I I I
+                    + 1 2 1 1
+                    + 1 4 2 2
+                    + 2 1 3 1",
+                ),
+                StreamChunk::from_pretty(
+                    " I I I I
+                    - 1 2 1 1
+                    + 1 3 4 1",
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use prost::Message as _;
+use risingwave_common::array::StreamChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::ErrorCode::ConnectorError;
+use risingwave_common::error::RwError;
+use risingwave_connector::sink::{Sink, SinkConfig, SinkImpl};
+use risingwave_pb::data::StreamChunk as ProstStreamChunk;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::KeySpaceWriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+use crate::executor::error::{StreamExecutorError, StreamExecutorResult};
+use crate::executor::{
+    Barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef,
+};
+
+fn sink_error(error: anyhow::Error) -> StreamExecutorError {
+    StreamExecutorError::sink_error(RwError::from(ConnectorError(error.to_string())))
+}
+
+/// `SinkExecutor` delivers the changes in stream to an external system.
+///
+/// By default, the changes of an epoch are flushed to the sink when the barrier arrives, before
+/// the barrier is passed downstream, so that all the changes before a checkpoint are delivered at
+/// least once.
+///
+/// If the sink delivers exactly once, the changes are logged into the state store with the
+/// checkpoint instead, keyed by `epoch | seq`. Only after the checkpoint of an epoch is committed,
+/// which is told by the committed epoch carried by the later barriers, the changes of the epoch are
+/// read back from the log and committed to the sink atomically. The log survives recovery, and the
+/// sink skips the epochs committed before, so that each epoch is delivered exactly once.
+pub struct SinkExecutor<S: StateStore> {
+    input: BoxedExecutor,
+    config: SinkConfig,
+    info: ExecutorInfo,
+
+    /// The log of the changes not delivered yet. Only used when the sink delivers exactly once.
+    keyspace: Keyspace<S>,
+}
+
+impl<S: StateStore> SinkExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        config: SinkConfig,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+    ) -> Self {
+        let info = ExecutorInfo {
+            schema: input.schema().clone(),
+            pk_indices: input.pk_indices().to_vec(),
+            identity: format!("SinkExecutor {:X}", executor_id),
+        };
+        Self {
+            input,
+            config,
+            info,
+            keyspace,
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let exactly_once = self.config.is_exactly_once();
+        let mut sink = SinkImpl::new(self.config, self.info.schema.clone())
+            .await
+            .map_err(sink_error)?;
+        let keyspace = self.keyspace;
+
+        // The chunks of the current epoch, which are logged on the next barrier.
+        let mut chunks = vec![];
+        // The epochs in the log, which are not delivered yet.
+        let mut pending_epochs = BTreeSet::new();
+        let mut first_barrier = true;
+
+        let input = self.input.execute();
+        #[for_await]
+        for msg in input {
+            let msg = msg?;
+            match &msg {
+                Message::Chunk(chunk) => {
+                    if exactly_once {
+                        chunks.push(chunk.clone());
+                    } else {
+                        sink.write_batch(chunk.clone()).await.map_err(sink_error)?;
+                    }
+                }
+                Message::Barrier(barrier) if exactly_once => {
+                    let prev_epoch = barrier.epoch.prev;
+                    let mut write_batch = keyspace.state_store().start_write_batch();
+                    let mut local = write_batch.prefixify(&keyspace);
+                    if first_barrier {
+                        pending_epochs = recover_log(&keyspace, &mut local, barrier).await?;
+                        first_barrier = false;
+                    }
+
+                    let delivered_epoch =
+                        deliver_committed(&mut sink, &keyspace, &pending_epochs, barrier).await?;
+                    if let Some(delivered_epoch) = delivered_epoch {
+                        local.delete_range(0u64.to_be_bytes(), (delivered_epoch + 1).to_be_bytes());
+                        pending_epochs = pending_epochs.split_off(&(delivered_epoch + 1));
+                    }
+                    if !chunks.is_empty() {
+                        for (seq, chunk) in chunks.drain(..).enumerate() {
+                            let value = chunk
+                                .compact()
+                                .map_err(StreamExecutorError::executor_v1)?
+                                .to_protobuf()
+                                .encode_to_vec();
+                            local.put(
+                                encode_log_key(prev_epoch, seq as u32),
+                                StorageValue::new_default_put(value),
+                            );
+                        }
+                        pending_epochs.insert(prev_epoch);
+                    }
+                    write_batch.ingest(prev_epoch).await?;
+                }
+                Message::Barrier(barrier) => {
+                    sink.commit().await.map_err(sink_error)?;
+                    sink.begin_epoch(barrier.epoch.curr)
+                        .await
+                        .map_err(sink_error)?;
+                }
+            }
+            yield msg;
+        }
+    }
+}
+
+fn encode_log_key(epoch: u64, seq: u32) -> Vec<u8> {
+    let mut key = epoch.to_be_bytes().to_vec();
+    key.extend_from_slice(&seq.to_be_bytes());
+    key
+}
+
+fn decode_log_epoch(key: &[u8]) -> u64 {
+    u64::from_be_bytes(key[..8].try_into().unwrap())
+}
+
+/// Finds out the epochs left undelivered in the log on the first barrier, e.g., after recovery.
+/// The epochs after the committed one are dropped from the log, since their changes are replayed
+/// from the last checkpoint and would otherwise be delivered twice.
+async fn recover_log<S: StateStore>(
+    keyspace: &Keyspace<S>,
+    local: &mut KeySpaceWriteBatch<'_, S>,
+    barrier: &Barrier,
+) -> StreamExecutorResult<BTreeSet<u64>> {
+    let mut pending_epochs = BTreeSet::new();
+    for (key, _) in keyspace.scan(None, barrier.epoch.prev).await? {
+        pending_epochs.insert(decode_log_epoch(&key));
+    }
+    let first_uncommitted_epoch = barrier.committed_epoch + 1;
+    if !pending_epochs
+        .split_off(&first_uncommitted_epoch)
+        .is_empty()
+    {
+        // The changes of the current epoch are logged from `prev_epoch`, which is not deleted.
+        local.delete_range(
+            first_uncommitted_epoch.to_be_bytes(),
+            barrier.epoch.prev.to_be_bytes(),
+        );
+    }
+    Ok(pending_epochs)
+}
+
+/// Delivers the logged epochs whose checkpoints are committed, and returns the last delivered
+/// epoch, if any.
+async fn deliver_committed<S: StateStore>(
+    sink: &mut SinkImpl,
+    keyspace: &Keyspace<S>,
+    pending_epochs: &BTreeSet<u64>,
+    barrier: &Barrier,
+) -> StreamExecutorResult<Option<u64>> {
+    let committed_epoch = barrier.committed_epoch;
+    let last_epoch = match pending_epochs.range(..=committed_epoch).next_back() {
+        Some(epoch) => *epoch,
+        None => return Ok(None),
+    };
+
+    let mut current_epoch = None;
+    for (key, value) in keyspace.scan(None, barrier.epoch.prev).await? {
+        let epoch = decode_log_epoch(&key);
+        if epoch > last_epoch {
+            break;
+        }
+        if current_epoch != Some(epoch) {
+            if current_epoch.is_some() {
+                sink.commit().await.map_err(sink_error)?;
+            }
+            sink.begin_epoch(epoch).await.map_err(sink_error)?;
+            current_epoch = Some(epoch);
+        }
+        let chunk = ProstStreamChunk::decode(value)
+            .map_err(|e| StreamExecutorError::executor_v1(RwError::from(e)))?;
+        let chunk = StreamChunk::from_protobuf(&chunk).map_err(StreamExecutorError::executor_v1)?;
+        sink.write_batch(chunk).await.map_err(sink_error)?;
+    }
+    if current_epoch.is_some() {
+        sink.commit().await.map_err(sink_error)?;
+    }
+
+    Ok(Some(last_epoch))
+}
+
+impl<S: StateStore> Executor for SinkExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        self.info.identity.as_str()
+    }
+}
+
+impl<S: StateStore> std::fmt::Debug for SinkExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SinkExecutor")
+            .field("input info", &self.info())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+    use risingwave_connector::sink::{FileSinkConfig, SinkConfig};
+    use risingwave_storage::memory::MemoryStateStore;
+    use risingwave_storage::storage_value::StorageValue;
+    use risingwave_storage::Keyspace;
+
+    use super::{decode_log_epoch, encode_log_key, recover_log};
+    use crate::executor::test_utils::*;
+    use crate::executor::*;
+
+    #[madsim::test]
+    async fn test_sink_executor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sink.json");
+        let schema = Schema::new(vec![Field::with_name(DataType::Int32, "v1")]);
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i
+                    + 1
+                    + 2",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i
+                    - 1",
+                )),
+            ],
+        );
+        let config = SinkConfig::File(FileSinkConfig {
+            path: Some(path.to_str().unwrap().to_string()),
+        });
+        let keyspace = Keyspace::executor_root(MemoryStateStore::new(), 0x2333);
+        let mut sink = Box::new(SinkExecutor::new(Box::new(source), config, keyspace, 1)).execute();
+
+        // The changes are not delivered before the barrier.
+        sink.next().await.unwrap().unwrap();
+        sink.next().await.unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        sink.next().await.unwrap().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"data\":{\"v1\":1},\"op\":\"insert\"}\n{\"data\":{\"v1\":2},\"op\":\"insert\"}\n"
+        );
+    }
+
+    #[madsim::test]
+    async fn test_recover_log() {
+        let keyspace = Keyspace::executor_root(MemoryStateStore::new(), 0x2333);
+        // The checkpoint of epoch 3 is not committed before the failure.
+        for epoch in 1..=3 {
+            let mut write_batch = keyspace.state_store().start_write_batch();
+            write_batch.prefixify(&keyspace).put(
+                encode_log_key(epoch, 0),
+                StorageValue::new_default_put(vec![]),
+            );
+            write_batch.ingest(epoch).await.unwrap();
+        }
+
+        // The first barrier after recovery, whose changes are logged from epoch 4.
+        let barrier = Barrier {
+            committed_epoch: 2,
+            ..Barrier::new_test_barrier(5)
+        };
+        let prev_epoch = barrier.epoch.prev;
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        let mut local = write_batch.prefixify(&keyspace);
+        let pending_epochs = recover_log(&keyspace, &mut local, &barrier).await.unwrap();
+        local.put(
+            encode_log_key(prev_epoch, 0),
+            StorageValue::new_default_put(vec![]),
+        );
+        write_batch.ingest(prev_epoch).await.unwrap();
+        assert_eq!(pending_epochs.into_iter().collect::<Vec<_>>(), vec![1, 2]);
+
+        // Epoch 3 is replayed, so it's dropped from the log to not be delivered twice.
+        let logged_epochs = keyspace
+            .scan(None, prev_epoch)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| decode_log_epoch(&key))
+            .collect::<Vec<_>>();
+        assert_eq!(logged_epochs, vec![1, 2, prev_epoch]);
+    }
+}
@@ -14,7 +14,7 @@
 
 use async_trait::async_trait;
 use madsim::collections::HashSet;
-use risingwave_common::array::{Op, StreamChunk};
+use risingwave_common::array::{Op, Row, StreamChunk};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
 use risingwave_common::error::Result;
 use risingwave_common::types::DataType;
@@ -73,11 +73,6 @@ pub struct InnerTopNExecutor<S: StateStore> {
     /// Schema of the executor.
     schema: Schema,
 
-    /// `LIMIT XXX`. `None` means no limit.
-    limit: Option<usize>,
-    /// `OFFSET XXX`. `0` means no offset.
-    offset: usize,
-
     /// The primary key indices of the `TopNExecutor`
     pk_indices: PkIndices,
 
@@ -87,11 +82,8 @@ pub struct InnerTopNExecutor<S: StateStore> {
     /// The order of internal keys of the `TopNExecutor`
     internal_key_order_types: Vec<OrderType>,
 
-    /// We are interested in which element is in the range of [offset, offset+limit). But we
-    /// still need to record elements in the other two ranges.
-    managed_lowest_state: ManagedTopNState<S, TOP_N_MAX>,
-    managed_middle_state: ManagedTopNBottomNState<S>,
-    managed_highest_state: ManagedTopNState<S, TOP_N_MIN>,
+    /// The elements seen so far, split into the ranges around `[offset, offset+limit)`.
+    state: TopNRangeState<S>,
 
     /// Marks whether this is first-time execution. If yes, we need to fill in the cache from
     /// storage.
@@ -132,31 +124,29 @@ pub fn generate_internal_key(
     )
 }
 
-impl<S: StateStore> InnerTopNExecutor<S> {
-    #[allow(clippy::too_many_arguments)]
+/// `TopNRangeState` keeps all the elements of one top-N. We are interested in which element is in
+/// the range of `[offset, offset+limit)`, but we still need to record elements in the other two
+/// ranges. `TopNExecutor` keeps a single one, while `GroupTopNExecutor` keeps one for each group.
+pub struct TopNRangeState<S: StateStore> {
+    /// `LIMIT XXX`. `None` means no limit.
+    limit: Option<usize>,
+    /// `OFFSET XXX`. `0` means no offset.
+    offset: usize,
+
+    managed_lowest_state: ManagedTopNState<S, TOP_N_MAX>,
+    managed_middle_state: ManagedTopNBottomNState<S>,
+    managed_highest_state: ManagedTopNState<S, TOP_N_MIN>,
+}
+
+impl<S: StateStore> TopNRangeState<S> {
     pub fn new(
-        input_info: ExecutorInfo,
-        schema: Schema,
-        order_pairs: Vec<OrderPair>,
         offset_and_limit: (usize, Option<usize>),
-        pk_indices: PkIndices,
         keyspace: Keyspace<S>,
         cache_size: Option<usize>,
         total_count: (usize, usize, usize),
-        executor_id: u64,
-        key_indices: Vec<usize>,
-    ) -> Result<Self> {
-        let (internal_key_indices, internal_key_data_types, internal_key_order_types) =
-            generate_internal_key(&order_pairs, &pk_indices, &schema);
-
-        let ordered_row_deserializer =
-            OrderedRowDeserializer::new(internal_key_data_types, internal_key_order_types.clone());
-
-        let row_data_types = schema
-            .fields
-            .iter()
-            .map(|field| field.data_type.clone())
-            .collect::<Vec<_>>();
+        row_data_types: Vec<DataType>,
+        ordered_row_deserializer: OrderedRowDeserializer,
+    ) -> Self {
         let table_column_descs = row_data_types
             .iter()
             .enumerate()
@@ -192,27 +182,50 @@ impl<S: StateStore> InnerTopNExecutor<S> {
             ordered_row_deserializer,
             cell_based_row_deserializer,
         );
-        Ok(Self {
-            info: ExecutorInfo {
-                schema: input_info.schema,
-                pk_indices: input_info.pk_indices,
-                identity: format!("TopNExecutor {:X}", executor_id),
-            },
-            schema,
+        Self {
             offset: offset_and_limit.0,
             limit: offset_and_limit.1,
             managed_lowest_state,
             managed_middle_state,
             managed_highest_state,
-            pk_indices,
-            internal_key_indices,
-            internal_key_order_types,
-            first_execution: true,
-            key_indices,
-        })
+        }
+    }
+
+    /// Fills in the caches of the three ranges from storage.
+    pub async fn fill_in_cache(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        self.managed_lowest_state
+            .fill_in_cache(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)?;
+        self.managed_middle_state
+            .fill_in_cache(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)?;
+        self.managed_highest_state
+            .fill_in_cache(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)
     }
 
-    async fn flush_inner(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+    /// Like `fill_in_cache`, but also recovers the number of elements of the three ranges from
+    /// storage. Used when the state is created without knowing its total count, e.g., when a
+    /// group evicted before is accessed again.
+    pub async fn init(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        self.managed_lowest_state
+            .init(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)?;
+        self.managed_middle_state
+            .init(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)?;
+        self.managed_highest_state
+            .init(epoch)
+            .await
+            .map_err(StreamExecutorError::top_n_state_error)
+    }
+
+    pub async fn flush(&mut self, epoch: u64) -> StreamExecutorResult<()> {
         self.managed_highest_state
             .flush(epoch)
             .await
@@ -226,6 +239,250 @@ impl<S: StateStore> InnerTopNExecutor<S> {
             .await
             .map_err(StreamExecutorError::top_n_state_error)
     }
+
+    pub fn is_dirty(&self) -> bool {
+        self.managed_lowest_state.is_dirty()
+            || self.managed_middle_state.is_dirty()
+            || self.managed_highest_state.is_dirty()
+    }
+
+    /// Applies an inserted or deleted element, and appends the changes of `[offset, offset+limit)`
+    /// to `new_ops` and `new_rows`.
+    pub async fn apply(
+        &mut self,
+        op: Op,
+        ordered_pk_row: OrderedRow,
+        row: Row,
+        epoch: u64,
+        new_ops: &mut Vec<Op>,
+        new_rows: &mut Vec<Row>,
+    ) -> StreamExecutorResult<()> {
+        let num_limit = self.limit.unwrap_or(usize::MAX);
+        match op {
+            Op::Insert | Op::UpdateInsert => {
+                if self.managed_lowest_state.total_count() < self.offset {
+                    // `elem` is in the range of `[0, offset)`,
+                    // we ignored it for now as it is not in the result set.
+                    self.managed_lowest_state
+                        .insert(ordered_pk_row, row, epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?;
+                    return Ok(());
+                }
+
+                // We remark that when offset is 0, every input row has nothing to do with
+                // `managed_lower_state`.
+                let element_to_compare_with_middle = if self.offset > 0
+                    && &ordered_pk_row < self.managed_lowest_state.top_element().unwrap().0
+                {
+                    // If the new element is smaller than the largest element in [0, offset),
+                    // the largest element need to move to [offset, offset+limit).
+                    let res = self
+                        .managed_lowest_state
+                        .pop_top_element(epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?
+                        .unwrap();
+                    self.managed_lowest_state
+                        .insert(ordered_pk_row, row, epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?;
+                    res
+                } else {
+                    (ordered_pk_row, row)
+                };
+
+                if self.managed_middle_state.total_count() < num_limit {
+                    // `elem` is in the range of `[offset, offset+limit)`,
+                    self.managed_middle_state
+                        .insert(
+                            element_to_compare_with_middle.0,
+                            element_to_compare_with_middle.1.clone(),
+                        )
+                        .await;
+                    new_ops.push(Op::Insert);
+                    new_rows.push(element_to_compare_with_middle.1);
+                    return Ok(());
+                }
+
+                let element_to_compare_with_highest = if &element_to_compare_with_middle.0
+                    < self.managed_middle_state.top_element().unwrap().0
+                {
+                    let res = self
+                        .managed_middle_state
+                        .pop_top_element(epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?
+                        .unwrap();
+                    new_ops.push(Op::Delete);
+                    new_rows.push(res.1.clone());
+                    new_ops.push(Op::Insert);
+                    new_rows.push(element_to_compare_with_middle.1.clone());
+                    self.managed_middle_state
+                        .insert(
+                            element_to_compare_with_middle.0,
+                            element_to_compare_with_middle.1,
+                        )
+                        .await;
+                    res
+                } else {
+                    element_to_compare_with_middle
+                };
+
+                // `elem` is in the range of `[offset+limit, +inf)`.
+                self.managed_highest_state
+                    .insert(
+                        element_to_compare_with_highest.0,
+                        element_to_compare_with_highest.1,
+                        epoch,
+                    )
+                    .await
+                    .map_err(StreamExecutorError::top_n_state_error)?;
+            }
+            Op::Delete | Op::UpdateDelete => {
+                // The extra care we need to take for deletion is that when we delete an element
+                // from a managed state, we may need to move an element from
+                // a higher range to the current range. And this process may
+                // be recursive. Since this is a delete operator, the key
+                // must already exist in one of the three managed states. We
+                // first check whether the element is in the highest state.
+                if self.managed_middle_state.total_count() == num_limit
+                    && ordered_pk_row > *self.managed_middle_state.top_element().unwrap().0
+                {
+                    // The current element in in the range of `[offset+limit, +inf)`
+                    self.managed_highest_state
+                        .delete(&ordered_pk_row, epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?;
+                } else if self.managed_lowest_state.total_count() == self.offset
+                    && (self.offset == 0
+                        || ordered_pk_row > *self.managed_lowest_state.top_element().unwrap().0)
+                {
+                    // The current element in in the range of `[offset, offset+limit)`
+                    self.managed_middle_state
+                        .delete(&ordered_pk_row, epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?;
+                    new_ops.push(Op::Delete);
+                    new_rows.push(row.clone());
+                    // We need to bring one, if any, from highest to lowest.
+                    if self.managed_highest_state.total_count() > 0 {
+                        let smallest_element_from_highest_state = self
+                            .managed_highest_state
+                            .pop_top_element(epoch)
+                            .await
+                            .map_err(StreamExecutorError::top_n_state_error)?
+                            .unwrap();
+                        new_ops.push(Op::Insert);
+                        new_rows.push(smallest_element_from_highest_state.1.clone());
+                        self.managed_middle_state
+                            .insert(
+                                smallest_element_from_highest_state.0,
+                                smallest_element_from_highest_state.1,
+                            )
+                            .await;
+                    }
+                } else {
+                    // The current element in in the range of `[0, offset)`
+                    self.managed_lowest_state
+                        .delete(&ordered_pk_row, epoch)
+                        .await
+                        .map_err(StreamExecutorError::top_n_state_error)?;
+                    // We need to bring one, if any, from middle to lowest.
+                    if self.managed_middle_state.total_count() > 0 {
+                        let smallest_element_from_middle_state = self
+                            .managed_middle_state
+                            .pop_bottom_element(epoch)
+                            .await
+                            .map_err(StreamExecutorError::top_n_state_error)?
+                            .unwrap();
+                        new_ops.push(Op::Delete);
+                        new_rows.push(smallest_element_from_middle_state.1.clone());
+                        self.managed_lowest_state
+                            .insert(
+                                smallest_element_from_middle_state.0,
+                                smallest_element_from_middle_state.1,
+                                epoch,
+                            )
+                            .await
+                            .map_err(StreamExecutorError::top_n_state_error)?;
+                    }
+                    // We check whether we need to/can bring one from highest to middle.
+                    // We remark that if `self.limit` is Some, it cannot be 0 as this should be
+                    // optimized away in the frontend.
+                    if self.managed_middle_state.total_count() == (num_limit - 1)
+                        && self.managed_highest_state.total_count() > 0
+                    {
+                        let smallest_element_from_highest_state = self
+                            .managed_highest_state
+                            .pop_top_element(epoch)
+                            .await
+                            .map_err(StreamExecutorError::top_n_state_error)?
+                            .unwrap();
+                        new_ops.push(Op::Insert);
+                        new_rows.push(smallest_element_from_highest_state.1.clone());
+                        self.managed_middle_state
+                            .insert(
+                                smallest_element_from_highest_state.0,
+                                smallest_element_from_highest_state.1,
+                            )
+                            .await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: StateStore> InnerTopNExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_info: ExecutorInfo,
+        schema: Schema,
+        order_pairs: Vec<OrderPair>,
+        offset_and_limit: (usize, Option<usize>),
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        cache_size: Option<usize>,
+        total_count: (usize, usize, usize),
+        executor_id: u64,
+        key_indices: Vec<usize>,
+    ) -> Result<Self> {
+        let (internal_key_indices, internal_key_data_types, internal_key_order_types) =
+            generate_internal_key(&order_pairs, &pk_indices, &schema);
+
+        let ordered_row_deserializer =
+            OrderedRowDeserializer::new(internal_key_data_types, internal_key_order_types.clone());
+
+        let row_data_types = schema
+            .fields
+            .iter()
+            .map(|field| field.data_type.clone())
+            .collect::<Vec<_>>();
+        let state = TopNRangeState::new(
+            offset_and_limit,
+            keyspace,
+            cache_size,
+            total_count,
+            row_data_types,
+            ordered_row_deserializer,
+        );
+        Ok(Self {
+            info: ExecutorInfo {
+                schema: input_info.schema,
+                pk_indices: input_info.pk_indices,
+                identity: format!("TopNExecutor {:X}", executor_id),
+            },
+            schema,
+            state,
+            pk_indices,
+            internal_key_indices,
+            internal_key_order_types,
+            first_execution: true,
+            key_indices,
+        })
+    }
 }
 
 impl<S: StateStore> Executor for InnerTopNExecutor<S> {
@@ -254,22 +511,10 @@ impl<S: StateStore> TopNExecutorBase for InnerTopNExecutor<S> {
         epoch: u64,
     ) -> StreamExecutorResult<StreamChunk> {
         if self.first_execution {
-            self.managed_lowest_state
-                .fill_in_cache(epoch)
-                .await
-                .map_err(StreamExecutorError::top_n_state_error)?;
-            self.managed_middle_state
-                .fill_in_cache(epoch)
-                .await
-                .map_err(StreamExecutorError::top_n_state_error)?;
-            self.managed_highest_state
-                .fill_in_cache(epoch)
-                .await
-                .map_err(StreamExecutorError::top_n_state_error)?;
+            self.state.fill_in_cache(epoch).await?;
             self.first_execution = false;
         }
 
-        let num_limit = self.limit.unwrap_or(usize::MAX);
         let mut new_ops = vec![];
         let mut new_rows = vec![];
 
@@ -277,186 +522,15 @@ impl<S: StateStore> TopNExecutorBase for InnerTopNExecutor<S> {
             let pk_row = row_ref.row_by_indices(&self.internal_key_indices);
             let ordered_pk_row = OrderedRow::new(pk_row, &self.internal_key_order_types);
             let row = row_ref.to_owned_row();
-
-            match op {
-                Op::Insert | Op::UpdateInsert => {
-                    if self.managed_lowest_state.total_count() < self.offset {
-                        // `elem` is in the range of `[0, offset)`,
-                        // we ignored it for now as it is not in the result set.
-                        self.managed_lowest_state
-                            .insert(ordered_pk_row, row, epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?;
-                        continue;
-                    }
-
-                    // We remark that when offset is 0, every input row has nothing to do with
-                    // `managed_lower_state`.
-                    let element_to_compare_with_middle = if self.offset > 0
-                        && &ordered_pk_row < self.managed_lowest_state.top_element().unwrap().0
-                    {
-                        // If the new element is smaller than the largest element in [0, offset),
-                        // the largest element need to move to [offset, offset+limit).
-                        let res = self
-                            .managed_lowest_state
-                            .pop_top_element(epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?
-                            .unwrap();
-                        self.managed_lowest_state
-                            .insert(ordered_pk_row, row, epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?;
-                        res
-                    } else {
-                        (ordered_pk_row, row)
-                    };
-
-                    if self.managed_middle_state.total_count() < num_limit {
-                        // `elem` is in the range of `[offset, offset+limit)`,
-                        self.managed_middle_state
-                            .insert(
-                                element_to_compare_with_middle.0,
-                                element_to_compare_with_middle.1.clone(),
-                            )
-                            .await;
-                        new_ops.push(Op::Insert);
-                        new_rows.push(element_to_compare_with_middle.1);
-                        continue;
-                    }
-
-                    let element_to_compare_with_highest = if &element_to_compare_with_middle.0
-                        < self.managed_middle_state.top_element().unwrap().0
-                    {
-                        let res = self
-                            .managed_middle_state
-                            .pop_top_element(epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?
-                            .unwrap();
-                        new_ops.push(Op::Delete);
-                        new_rows.push(res.1.clone());
-                        new_ops.push(Op::Insert);
-                        new_rows.push(element_to_compare_with_middle.1.clone());
-                        self.managed_middle_state
-                            .insert(
-                                element_to_compare_with_middle.0,
-                                element_to_compare_with_middle.1,
-                            )
-                            .await;
-                        res
-                    } else {
-                        element_to_compare_with_middle
-                    };
-
-                    // `elem` is in the range of `[offset+limit, +inf)`.
-                    self.managed_highest_state
-                        .insert(
-                            element_to_compare_with_highest.0,
-                            element_to_compare_with_highest.1,
-                            epoch,
-                        )
-                        .await
-                        .map_err(StreamExecutorError::top_n_state_error)?;
-                }
-                Op::Delete | Op::UpdateDelete => {
-                    // The extra care we need to take for deletion is that when we delete an element
-                    // from a managed state, we may need to move an element from
-                    // a higher range to the current range. And this process may
-                    // be recursive. Since this is a delete operator, the key
-                    // must already exist in one of the three managed states. We
-                    // first check whether the element is in the highest state.
-                    if self.managed_middle_state.total_count() == num_limit
-                        && ordered_pk_row > *self.managed_middle_state.top_element().unwrap().0
-                    {
-                        // The current element in in the range of `[offset+limit, +inf)`
-                        self.managed_highest_state
-                            .delete(&ordered_pk_row, epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?;
-                    } else if self.managed_lowest_state.total_count() == self.offset
-                        && (self.offset == 0
-                            || ordered_pk_row > *self.managed_lowest_state.top_element().unwrap().0)
-                    {
-                        // The current element in in the range of `[offset, offset+limit)`
-                        self.managed_middle_state
-                            .delete(&ordered_pk_row, epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?;
-                        new_ops.push(Op::Delete);
-                        new_rows.push(row.clone());
-                        // We need to bring one, if any, from highest to lowest.
-                        if self.managed_highest_state.total_count() > 0 {
-                            let smallest_element_from_highest_state = self
-                                .managed_highest_state
-                                .pop_top_element(epoch)
-                                .await
-                                .map_err(StreamExecutorError::top_n_state_error)?
-                                .unwrap();
-                            new_ops.push(Op::Insert);
-                            new_rows.push(smallest_element_from_highest_state.1.clone());
-                            self.managed_middle_state
-                                .insert(
-                                    smallest_element_from_highest_state.0,
-                                    smallest_element_from_highest_state.1,
-                                )
-                                .await;
-                        }
-                    } else {
-                        // The current element in in the range of `[0, offset)`
-                        self.managed_lowest_state
-                            .delete(&ordered_pk_row, epoch)
-                            .await
-                            .map_err(StreamExecutorError::top_n_state_error)?;
-                        // We need to bring one, if any, from middle to lowest.
-                        if self.managed_middle_state.total_count() > 0 {
-                            let smallest_element_from_middle_state = self
-                                .managed_middle_state
-                                .pop_bottom_element(epoch)
-                                .await
-                                .map_err(StreamExecutorError::top_n_state_error)?
-                                .unwrap();
-                            new_ops.push(Op::Delete);
-                            new_rows.push(smallest_element_from_middle_state.1.clone());
-                            self.managed_lowest_state
-                                .insert(
-                                    smallest_element_from_middle_state.0,
-                                    smallest_element_from_middle_state.1,
-                                    epoch,
-                                )
-                                .await
-                                .map_err(StreamExecutorError::top_n_state_error)?;
-                        }
-                        // We check whether we need to/can bring one from highest to middle.
-                        // We remark that if `self.limit` is Some, it cannot be 0 as this should be
-                        // optimized away in the frontend.
-                        if self.managed_middle_state.total_count() == (num_limit - 1)
-                            && self.managed_highest_state.total_count() > 0
-                        {
-                            let smallest_element_from_highest_state = self
-                                .managed_highest_state
-                                .pop_top_element(epoch)
-                                .await
-                                .map_err(StreamExecutorError::top_n_state_error)?
-                                .unwrap();
-                            new_ops.push(Op::Insert);
-                            new_rows.push(smallest_element_from_highest_state.1.clone());
-                            self.managed_middle_state
-                                .insert(
-                                    smallest_element_from_highest_state.0,
-                                    smallest_element_from_highest_state.1,
-                                )
-                                .await;
-                        }
-                    }
-                }
-            }
+            self.state
+                .apply(op, ordered_pk_row, row, epoch, &mut new_ops, &mut new_rows)
+                .await?;
         }
         generate_output(new_rows, new_ops, &self.schema)
     }
 
     async fn flush_data(&mut self, epoch: u64) -> StreamExecutorResult<()> {
-        self.flush_inner(epoch).await
+        self.state.flush(epoch).await
     }
 
     fn schema(&self) -> &Schema {
@@ -16,6 +16,7 @@
 
 mod batch_query;
 mod chain;
+mod dynamic_filter;
 mod filter;
 mod global_simple_agg;
 mod hash_agg;
@@ -26,7 +27,10 @@ mod lookup;
 mod lookup_union;
 mod merge;
 mod mview;
+mod nested_loop_join;
+mod over_window;
 mod project;
+mod sink;
 mod source;
 mod top_n;
 mod top_n_appendonly;
@@ -42,6 +46,7 @@ use risingwave_storage::{Keyspace, StateStore};
 
 use self::batch_query::*;
 use self::chain::*;
+use self::dynamic_filter::*;
 use self::filter::*;
 use self::global_simple_agg::*;
 use self::hash_agg::*;
@@ -52,7 +57,10 @@ use self::lookup::*;
 use self::lookup_union::*;
 use self::merge::*;
 use self::mview::*;
+use self::nested_loop_join::*;
+use self::over_window::*;
 use self::project::*;
+use self::sink::*;
 use self::source::*;
 use self::top_n::*;
 use self::top_n_appendonly::*;
@@ -103,11 +111,14 @@ pub fn create_executor(
         NodeBody::Source => SourceExecutorBuilder,
         NodeBody::Project => ProjectExecutorBuilder,
         NodeBody::TopN => TopNExecutorBuilder,
+        NodeBody::OverWindow => OverWindowExecutorBuilder,
         NodeBody::AppendOnlyTopN => AppendOnlyTopNExecutorBuilder,
         NodeBody::LocalSimpleAgg => LocalSimpleAggExecutorBuilder,
         NodeBody::GlobalSimpleAgg => SimpleAggExecutorBuilder,
         NodeBody::HashAgg => HashAggExecutorBuilder,
         NodeBody::HashJoin => HashJoinExecutorBuilder,
+        NodeBody::NestedLoopJoin => NestedLoopJoinExecutorBuilder,
+        NodeBody::DynamicFilter => DynamicFilterExecutorBuilder,
         NodeBody::HopWindow => HopWindowExecutorBuilder,
         NodeBody::Chain => ChainExecutorBuilder,
         NodeBody::BatchPlan => BatchQueryExecutorBuilder,
@@ -118,5 +129,6 @@ pub fn create_executor(
         NodeBody::Lookup => LookupExecutorBuilder,
         NodeBody::Union => UnionExecutorBuilder,
         NodeBody::LookupUnion => LookupUnionExecutorBuilder,
+        NodeBody::Sink => SinkExecutorBuilder,
     }
 }
@@ -48,6 +48,8 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             keyspace,
             keys,
             column_ids,
+            node.handle_pk_conflict,
+            params.actor_id,
             params.executor_id,
         );
 
@@ -87,6 +89,8 @@ impl ExecutorBuilder for ArrangeExecutorBuilder {
             keyspace,
             keys,
             column_ids,
+            false,
+            params.actor_id,
             params.executor_id,
         );
 
@@ -0,0 +1,52 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_expr::expr::build_from_prost;
+
+use super::*;
+use crate::executor::NestedLoopJoinExecutor;
+
+pub struct NestedLoopJoinExecutorBuilder;
+
+impl ExecutorBuilder for NestedLoopJoinExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::NestedLoopJoin)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+        let join_type = node.get_join_type()?;
+        let condition = match node.get_condition() {
+            Ok(cond_prost) => Some(build_from_prost(cond_prost)?),
+            Err(_) => None,
+        };
+        let keyspace =
+            Keyspace::table_executor_root(store, &TableId::new(node.table_id), params.executor_id);
+
+        Ok(NestedLoopJoinExecutor::new(
+            source_l,
+            source_r,
+            join_type,
+            condition,
+            keyspace,
+            params.pk_indices,
+            params.executor_id,
+        )
+        .boxed())
+    }
+}
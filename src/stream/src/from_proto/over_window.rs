@@ -0,0 +1,63 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_common::util::sort_util::OrderPair;
+use risingwave_expr::window_function::WindowFunctionEvaluator;
+
+use super::*;
+use crate::executor::OverWindowExecutor;
+
+pub struct OverWindowExecutorBuilder;
+
+impl ExecutorBuilder for OverWindowExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::OverWindow)?;
+        let calls = node
+            .get_calls()
+            .iter()
+            .map(WindowFunctionEvaluator::new)
+            .try_collect()?;
+        let partition_by = node
+            .get_partition_by()
+            .iter()
+            .map(|idx| *idx as usize)
+            .collect();
+        let order_pairs = node
+            .get_order_by()
+            .iter()
+            .map(OrderPair::from_prost)
+            .collect();
+        let keyspace =
+            Keyspace::table_executor_root(store, &TableId::new(node.table_id), params.executor_id);
+        let cache_size = 1024;
+
+        Ok(OverWindowExecutor::new(
+            params.input.remove(0),
+            calls,
+            partition_by,
+            order_pairs,
+            keyspace,
+            params.pk_indices,
+            cache_size,
+            params.executor_id,
+        )
+        .boxed())
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_connector::sink::SinkConfig;
+
+use super::*;
+use crate::executor::SinkExecutor;
+
+pub struct SinkExecutorBuilder;
+
+impl ExecutorBuilder for SinkExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::Sink)?;
+        let mut config = SinkConfig::from_hashmap(node.properties.clone())
+            .map_err(|e| RwError::from(ErrorCode::ConnectorError(e.to_string())))?;
+        if let SinkConfig::Kafka(config) = &mut config {
+            // The actor ids are kept after recovery, so that the rebuilt actor fences the
+            // transactional producers of the failed one.
+            config.transactional_id = Some(format!(
+                "risingwave-sink-{}-{}",
+                node.sink_id, params.actor_id
+            ));
+        }
+
+        let keyspace = Keyspace::executor_root(store, params.executor_id);
+
+        Ok(SinkExecutor::new(params.input.remove(0), config, keyspace, params.executor_id).boxed())
+    }
+}
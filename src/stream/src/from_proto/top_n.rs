@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::TableId;
 use risingwave_common::util::sort_util::OrderPair;
 
 use super::*;
-use crate::executor::TopNExecutor;
+use crate::executor::{GroupTopNExecutor, TopNExecutor};
 
 pub struct TopNExecutorBuilder;
 
@@ -39,7 +40,30 @@ impl ExecutorBuilder for TopNExecutorBuilder {
         };
         let cache_size = Some(1024);
         let total_count = (0, 0, 0);
-        let keyspace = Keyspace::executor_root(store, params.executor_id);
+        let keyspace =
+            Keyspace::table_executor_root(store, &TableId::new(node.table_id), params.executor_id);
+
+        if !node.get_group_key().is_empty() {
+            let group_key = node
+                .get_group_key()
+                .iter()
+                .map(|idx| *idx as usize)
+                .collect();
+            let group_cache_size = 1024;
+            return Ok(GroupTopNExecutor::new(
+                params.input.remove(0),
+                order_pairs,
+                (node.offset as usize, limit),
+                params.pk_indices,
+                keyspace,
+                cache_size,
+                group_cache_size,
+                params.executor_id,
+                group_key,
+            )?
+            .boxed());
+        }
+
         let key_indices = node
             .get_distribution_keys()
             .iter()
@@ -235,7 +235,7 @@ impl LocalStreamManager {
         let barrier = Barrier {
             epoch,
             mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
-            span: tracing::Span::none(),
+            ..Default::default()
         };
 
         self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect, false)
@@ -17,13 +17,13 @@ use thiserror::Error;
 /// Error type used in pgwire crates.
 #[derive(Error, Debug)]
 pub enum PsqlError {
-    #[error("Encode error {0}.")]
-    CancelError(String),
+    #[error("{0}")]
+    PasswordError(String),
 }
 
 impl PsqlError {
-    /// Construct a Cancel error. Used when Ctrl-c a processing query. Similar to PG.
-    pub fn cancel() -> Self {
-        PsqlError::CancelError("ERROR:  canceling statement due to user request".to_string())
+    /// Construct a password error. Used when the password sent by client is wrong.
+    pub fn password_error() -> Self {
+        PsqlError::PasswordError("Invalid username/password".to_string())
     }
 }
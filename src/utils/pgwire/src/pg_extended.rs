@@ -0,0 +1,372 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prepared statements and portals of the extended query protocol, and the conversion of values
+//! between the text and binary formats.
+//!
+//! See: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY.
+
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use crate::pg_message::Format;
+use crate::pg_response::PgResponse;
+use crate::types::Row;
+
+/// A prepared statement created by a `Parse` message.
+#[derive(Debug, Clone)]
+pub struct PgStatement {
+    sql: String,
+    param_types: Vec<TypeOid>,
+    row_description: Vec<PgFieldDescriptor>,
+}
+
+impl PgStatement {
+    pub fn new(
+        sql: String,
+        param_types: Vec<TypeOid>,
+        row_description: Vec<PgFieldDescriptor>,
+    ) -> Self {
+        Self {
+            sql,
+            param_types,
+            row_description,
+        }
+    }
+
+    pub fn param_types(&self) -> &[TypeOid] {
+        &self.param_types
+    }
+
+    pub fn row_description(&self) -> &[PgFieldDescriptor] {
+        &self.row_description
+    }
+
+    /// Bind the statement with the parameter values and format codes from a `Bind` message.
+    pub fn bind(
+        &self,
+        params: &[Option<Bytes>],
+        param_format_codes: &[i16],
+        result_format_codes: &[i16],
+    ) -> Result<PgPortal> {
+        if params.len() != self.param_types.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "bind message supplies {} parameters, but prepared statement requires {}",
+                    params.len(),
+                    self.param_types.len()
+                ),
+            ));
+        }
+        let param_formats = Format::from_codes(param_format_codes, params.len())?;
+        let params = params
+            .iter()
+            .zip(self.param_types.iter())
+            .zip(param_formats)
+            .map(|((param, type_oid), format)| {
+                param
+                    .as_ref()
+                    .map(|raw| decode_value(*type_oid, format, raw))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result_formats = Format::from_codes(result_format_codes, self.row_description.len())?;
+        let mut row_description = self.row_description.clone();
+        for (field, format) in row_description.iter_mut().zip(result_formats.iter()) {
+            field.set_format_code(format.to_i16());
+        }
+
+        Ok(PgPortal {
+            sql: self.sql.clone(),
+            param_types: self.param_types.clone(),
+            params,
+            row_description,
+            result_formats,
+            result: None,
+            row_offset: 0,
+        })
+    }
+}
+
+/// A portal created by a `Bind` message, i.e. a prepared statement with its parameters bound. A
+/// portal may be executed in several rounds when `Execute` limits the number of rows to return.
+#[derive(Debug)]
+pub struct PgPortal {
+    sql: String,
+    param_types: Vec<TypeOid>,
+    /// Parameter values in text format.
+    params: Vec<Option<String>>,
+    /// Row description with the format codes requested by `Bind`.
+    row_description: Vec<PgFieldDescriptor>,
+    result_formats: Vec<Format>,
+    /// The result of the statement, available after the first `Execute`.
+    result: Option<PgResponse>,
+    /// Number of rows of `result` that have been sent.
+    row_offset: usize,
+}
+
+impl PgPortal {
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn param_types(&self) -> &[TypeOid] {
+        &self.param_types
+    }
+
+    pub fn params(&self) -> &[Option<String>] {
+        &self.params
+    }
+
+    pub fn row_description(&self) -> &[PgFieldDescriptor] {
+        &self.row_description
+    }
+
+    pub fn result(&self) -> Option<&PgResponse> {
+        self.result.as_ref()
+    }
+
+    pub fn set_result(&mut self, result: PgResponse) {
+        self.result = Some(result);
+        self.row_offset = 0;
+    }
+
+    pub fn row_offset(&self) -> usize {
+        self.row_offset
+    }
+
+    /// Returns whether all rows of the result have been sent.
+    pub fn is_exhausted(&self) -> bool {
+        match &self.result {
+            Some(result) => self.row_offset >= result.row_count(),
+            None => false,
+        }
+    }
+
+    /// Take at most `max_rows` (unlimited if zero) unsent rows from the result, encoded in the
+    /// formats requested by `Bind`.
+    pub fn fetch(&mut self, max_rows: usize) -> Result<Vec<Vec<Option<Bytes>>>> {
+        let result = match &self.result {
+            Some(result) => result,
+            None => return Ok(vec![]),
+        };
+        let limit = if max_rows == 0 { usize::MAX } else { max_rows };
+        let rows = result
+            .iter()
+            .skip(self.row_offset)
+            .take(limit)
+            .map(|row| encode_row(row, &self.row_description, &self.result_formats))
+            .collect::<Result<Vec<_>>>()?;
+        self.row_offset += rows.len();
+        Ok(rows)
+    }
+}
+
+fn encode_row(
+    row: &Row,
+    row_description: &[PgFieldDescriptor],
+    formats: &[Format],
+) -> Result<Vec<Option<Bytes>>> {
+    row.values()
+        .iter()
+        .zip(row_description.iter())
+        .zip(formats.iter())
+        .map(|((value, field), format)| {
+            value
+                .as_ref()
+                .map(|v| encode_value(field.get_type_oid(), *format, v))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Postgres counts dates and timestamps in binary format from 2000-01-01.
+fn pg_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0)
+}
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+fn invalid_binary(type_oid: TypeOid) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Invalid binary value for type {:?}", type_oid),
+    )
+}
+
+fn unsupported_binary(type_oid: TypeOid) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Binary format of type {:?} is not supported", type_oid),
+    )
+}
+
+fn invalid_text(type_oid: TypeOid, text: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Invalid value {:?} for type {:?}", text, type_oid),
+    )
+}
+
+fn fixed_size<const N: usize>(type_oid: TypeOid, raw: &[u8]) -> Result<[u8; N]> {
+    raw.try_into().map_err(|_| invalid_binary(type_oid))
+}
+
+/// Decodes a value sent by the client in `format` into its text representation.
+pub fn decode_value(type_oid: TypeOid, format: Format, raw: &[u8]) -> Result<String> {
+    if format == Format::Text {
+        return String::from_utf8(raw.to_vec()).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid UTF-8 sequence: {}", e),
+            )
+        });
+    }
+    let text = match type_oid {
+        TypeOid::Boolean => match fixed_size::<1>(type_oid, raw)?[0] {
+            0 => "f".to_string(),
+            _ => "t".to_string(),
+        },
+        TypeOid::SmallInt => i16::from_be_bytes(fixed_size(type_oid, raw)?).to_string(),
+        TypeOid::Int => i32::from_be_bytes(fixed_size(type_oid, raw)?).to_string(),
+        TypeOid::BigInt => i64::from_be_bytes(fixed_size(type_oid, raw)?).to_string(),
+        TypeOid::Float4 => f32::from_be_bytes(fixed_size(type_oid, raw)?).to_string(),
+        TypeOid::Float8 => f64::from_be_bytes(fixed_size(type_oid, raw)?).to_string(),
+        TypeOid::Varchar => {
+            return decode_value(type_oid, Format::Text, raw);
+        }
+        TypeOid::Date => {
+            let days = i32::from_be_bytes(fixed_size(type_oid, raw)?);
+            (pg_epoch().date() + chrono::Duration::days(days as i64))
+                .format("%Y-%m-%d")
+                .to_string()
+        }
+        TypeOid::Time => {
+            let micros = i64::from_be_bytes(fixed_size(type_oid, raw)?);
+            (NaiveTime::from_hms(0, 0, 0) + chrono::Duration::microseconds(micros))
+                .format(TIME_FORMAT)
+                .to_string()
+        }
+        TypeOid::Timestamp => {
+            let micros = i64::from_be_bytes(fixed_size(type_oid, raw)?);
+            (pg_epoch() + chrono::Duration::microseconds(micros))
+                .format(TIMESTAMP_FORMAT)
+                .to_string()
+        }
+        TypeOid::CharArray | TypeOid::Timestampz | TypeOid::Decimal => {
+            return Err(unsupported_binary(type_oid))
+        }
+    };
+    Ok(text)
+}
+
+/// Encodes a value in text representation into `format` to be sent to the client.
+pub fn encode_value(type_oid: TypeOid, format: Format, text: &str) -> Result<Bytes> {
+    if format == Format::Text {
+        return Ok(Bytes::copy_from_slice(text.as_bytes()));
+    }
+    let mut buf = BytesMut::new();
+    match type_oid {
+        TypeOid::Boolean => match text {
+            "t" | "true" => buf.put_u8(1),
+            "f" | "false" => buf.put_u8(0),
+            _ => return Err(invalid_text(type_oid, text)),
+        },
+        TypeOid::SmallInt => buf.put_i16(text.parse().map_err(|_| invalid_text(type_oid, text))?),
+        TypeOid::Int => buf.put_i32(text.parse().map_err(|_| invalid_text(type_oid, text))?),
+        TypeOid::BigInt => buf.put_i64(text.parse().map_err(|_| invalid_text(type_oid, text))?),
+        TypeOid::Float4 => buf.put_f32(text.parse().map_err(|_| invalid_text(type_oid, text))?),
+        TypeOid::Float8 => buf.put_f64(text.parse().map_err(|_| invalid_text(type_oid, text))?),
+        TypeOid::Varchar => buf.put_slice(text.as_bytes()),
+        TypeOid::Date => {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map_err(|_| invalid_text(type_oid, text))?;
+            buf.put_i32((date - pg_epoch().date()).num_days() as i32);
+        }
+        TypeOid::Time => {
+            let time = NaiveTime::parse_from_str(text, TIME_FORMAT)
+                .map_err(|_| invalid_text(type_oid, text))?;
+            let micros = (time - NaiveTime::from_hms(0, 0, 0))
+                .num_microseconds()
+                .ok_or_else(|| invalid_text(type_oid, text))?;
+            buf.put_i64(micros);
+        }
+        TypeOid::Timestamp => {
+            let timestamp = NaiveDateTime::parse_from_str(text, TIMESTAMP_FORMAT)
+                .map_err(|_| invalid_text(type_oid, text))?;
+            let micros = (timestamp - pg_epoch())
+                .num_microseconds()
+                .ok_or_else(|| invalid_text(type_oid, text))?;
+            buf.put_i64(micros);
+        }
+        TypeOid::CharArray | TypeOid::Timestampz | TypeOid::Decimal => {
+            return Err(unsupported_binary(type_oid))
+        }
+    }
+    Ok(buf.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let cases = [
+            (TypeOid::Boolean, "t"),
+            (TypeOid::SmallInt, "-3"),
+            (TypeOid::Int, "42"),
+            (TypeOid::BigInt, "9000000000"),
+            (TypeOid::Float8, "1.5"),
+            (TypeOid::Varchar, "hello"),
+            (TypeOid::Date, "2022-05-20"),
+            (TypeOid::Time, "12:34:56.789"),
+            (TypeOid::Timestamp, "1999-12-31 23:59:59"),
+        ];
+        for (type_oid, text) in cases {
+            let encoded = encode_value(type_oid, Format::Binary, text).unwrap();
+            assert_eq!(
+                decode_value(type_oid, Format::Binary, &encoded).unwrap(),
+                text
+            );
+        }
+        assert_eq!(
+            encode_value(TypeOid::Int, Format::Binary, "1").unwrap(),
+            Bytes::from_static(&[0, 0, 0, 1])
+        );
+        assert!(decode_value(TypeOid::Int, Format::Binary, &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_bind_param_count() {
+        let statement = PgStatement::new(
+            "SELECT $1".to_string(),
+            vec![TypeOid::Int],
+            vec![PgFieldDescriptor::new("?column?".to_string(), TypeOid::Int)],
+        );
+        assert!(statement.bind(&[], &[], &[]).is_err());
+
+        let portal = statement
+            .bind(&[Some(Bytes::from_static(&[0, 0, 0, 7]))], &[1], &[1])
+            .unwrap();
+        assert_eq!(portal.params(), &[Some("7".to_string())]);
+        assert_eq!(portal.row_description()[0].get_format_code(), 1);
+    }
+}
@@ -85,9 +85,15 @@ impl PgFieldDescriptor {
     pub fn get_format_code(&self) -> i16 {
         self.format_code
     }
+
+    /// Set the format code of the column, which is decided by the `Bind` message in the extended
+    /// query protocol.
+    pub fn set_format_code(&mut self, format_code: i16) {
+        self.format_code = format_code;
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum TypeOid {
     Boolean,
     BigInt,
@@ -122,4 +128,25 @@ impl TypeOid {
             TypeOid::Decimal => 1231,
         }
     }
+
+    /// Returns `None` for oids which are not supported. Oid of `text` is treated as `varchar` and
+    /// oid of `numeric` is treated as `decimal`.
+    pub fn from_number(oid: i32) -> Option<TypeOid> {
+        match oid {
+            16 => Some(TypeOid::Boolean),
+            20 => Some(TypeOid::BigInt),
+            21 => Some(TypeOid::SmallInt),
+            23 => Some(TypeOid::Int),
+            700 => Some(TypeOid::Float4),
+            701 => Some(TypeOid::Float8),
+            1002 => Some(TypeOid::CharArray),
+            25 | 1043 => Some(TypeOid::Varchar),
+            1082 => Some(TypeOid::Date),
+            1083 => Some(TypeOid::Time),
+            1114 => Some(TypeOid::Timestamp),
+            1184 => Some(TypeOid::Timestampz),
+            1231 | 1700 => Some(TypeOid::Decimal),
+            _ => None,
+        }
+    }
 }
@@ -12,17 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::io::{Error, ErrorKind, IoSlice, Result, Write};
 
 use byteorder::{BigEndian, ByteOrder};
 /// Part of code learned from https://github.com/zenithdb/zenith/blob/main/zenith_utils/src/pq_proto.rs.
-use bytes::{BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
-use crate::pg_field_descriptor::PgFieldDescriptor;
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_response::StatementType;
-use crate::pg_server::BoxedError;
+use crate::pg_server::{BoxedError, SessionId};
 use crate::types::Row;
 
 /// Messages that can be sent from pg client to server. Implement `read`.
@@ -30,11 +31,52 @@ pub enum FeMessage {
     Ssl,
     Startup(FeStartupMessage),
     Query(FeQueryMessage),
-    CancelQuery,
+    Password(FePasswordMessage),
+    Parse(FeParseMessage),
+    Bind(FeBindMessage),
+    Describe(FeDescribeMessage),
+    Execute(FeExecuteMessage),
+    Close(FeCloseMessage),
+    Sync,
+    Flush,
+    CancelQuery(FeCancelMessage),
     Terminate,
 }
 
-pub struct FeStartupMessage {}
+/// Startup message, which carries the run-time parameters of the connection such as `user` and
+/// `database`.
+pub struct FeStartupMessage {
+    pub config: HashMap<String, String>,
+}
+
+/// Cancel request, sent by client in a new connection to cancel the running query of the session
+/// identified by the `BackendKeyData` it received at startup.
+pub struct FeCancelMessage {
+    pub target_process_id: i32,
+    pub target_secret_key: i32,
+}
+
+impl FeCancelMessage {
+    pub fn parse(mut buf: Bytes) -> Result<Self> {
+        if buf.len() != 8 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid length of cancel request",
+            ));
+        }
+        let target_process_id = buf.get_i32();
+        let target_secret_key = buf.get_i32();
+        Ok(Self {
+            target_process_id,
+            target_secret_key,
+        })
+    }
+}
+
+/// Password message, sent by client in response to an authentication request.
+pub struct FePasswordMessage {
+    pub password: Bytes,
+}
 
 /// Query message contains the string sql.
 pub struct FeQueryMessage {
@@ -43,16 +85,95 @@ pub struct FeQueryMessage {
 
 impl FeQueryMessage {
     pub fn get_sql(&self) -> Result<&str> {
-        match CStr::from_bytes_with_nul(&self.sql_bytes) {
-            Ok(cstr) => cstr.to_str().map_err(|err| {
-                Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("Invalid UTF-8 sequence: {}", err),
-                )
-            }),
-            Err(err) => Err(Error::new(
+        cstr_to_str(&self.sql_bytes)
+    }
+}
+
+/// Parse message of the extended query protocol. Creates a prepared statement named
+/// `statement_name` (empty for the unnamed statement) from `sql_bytes`.
+pub struct FeParseMessage {
+    pub statement_name: Bytes,
+    pub sql_bytes: Bytes,
+    /// Type oids of the parameters specified by the client. Zero means unspecified.
+    pub type_ids: Vec<i32>,
+}
+
+impl FeParseMessage {
+    pub fn get_sql(&self) -> Result<&str> {
+        cstr_to_str(&self.sql_bytes)
+    }
+}
+
+/// Bind message of the extended query protocol. Creates a portal named `portal_name` from the
+/// prepared statement `statement_name` and the given parameter values.
+pub struct FeBindMessage {
+    pub portal_name: Bytes,
+    pub statement_name: Bytes,
+    /// Format codes of the parameters, see [`Format::from_codes`].
+    pub param_format_codes: Vec<i16>,
+    /// Parameter values in their wire representation. `None` stands for NULL.
+    pub params: Vec<Option<Bytes>>,
+    /// Format codes of the result columns, see [`Format::from_codes`].
+    pub result_format_codes: Vec<i16>,
+}
+
+/// Describe message of the extended query protocol. `kind` is `b'S'` for a prepared statement and
+/// `b'P'` for a portal.
+pub struct FeDescribeMessage {
+    pub kind: u8,
+    pub name: Bytes,
+}
+
+/// Execute message of the extended query protocol. `max_rows` of zero means no limit.
+pub struct FeExecuteMessage {
+    pub portal_name: Bytes,
+    pub max_rows: i32,
+}
+
+/// Close message of the extended query protocol. `kind` is `b'S'` for a prepared statement and
+/// `b'P'` for a portal.
+pub struct FeCloseMessage {
+    pub kind: u8,
+    pub name: Bytes,
+}
+
+/// The format of a parameter or a result column in the extended query protocol.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Binary,
+}
+
+impl Format {
+    pub fn from_i16(code: i16) -> Result<Self> {
+        match code {
+            0 => Ok(Format::Text),
+            1 => Ok(Format::Binary),
+            _ => Err(Error::new(
                 ErrorKind::InvalidInput,
-                format!("Input end error: {}", err),
+                format!("Unsupported format code: {}", code),
+            )),
+        }
+    }
+
+    pub fn to_i16(self) -> i16 {
+        match self {
+            Format::Text => 0,
+            Format::Binary => 1,
+        }
+    }
+
+    /// Expands the format codes of a `Bind` message to one format per value. No code means all
+    /// values are in text, a single code applies to all values, otherwise there must be exactly
+    /// one code per value.
+    pub fn from_codes(codes: &[i16], len: usize) -> Result<Vec<Self>> {
+        match codes.len() {
+            0 => Ok(vec![Format::Text; len]),
+            1 => Ok(vec![Format::from_i16(codes[0])?; len]),
+            n if n == len => codes.iter().map(|c| Format::from_i16(*c)).collect(),
+            n => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Expected {} format codes, got {}", len, n),
             )),
         }
     }
@@ -69,10 +190,29 @@ impl FeMessage {
         if payload_len > 0 {
             stream.read_exact(&mut payload).await?;
         }
-        let sql_bytes = Bytes::from(payload);
+        let mut payload = Bytes::from(payload);
 
         match val {
-            b'Q' => Ok(FeMessage::Query(FeQueryMessage { sql_bytes })),
+            b'Q' => Ok(FeMessage::Query(FeQueryMessage { sql_bytes: payload })),
+            b'p' => Ok(FeMessage::Password(FePasswordMessage {
+                password: read_cstr(&mut payload)?,
+            })),
+            b'P' => Ok(FeMessage::Parse(FeParseMessage::parse(payload)?)),
+            b'B' => Ok(FeMessage::Bind(FeBindMessage::parse(payload)?)),
+            b'D' => Ok(FeMessage::Describe(FeDescribeMessage {
+                kind: read_u8(&mut payload)?,
+                name: read_cstr(&mut payload)?,
+            })),
+            b'E' => Ok(FeMessage::Execute(FeExecuteMessage {
+                portal_name: read_cstr(&mut payload)?,
+                max_rows: read_i32(&mut payload)?,
+            })),
+            b'C' => Ok(FeMessage::Close(FeCloseMessage {
+                kind: read_u8(&mut payload)?,
+                name: read_cstr(&mut payload)?,
+            })),
+            b'S' => Ok(FeMessage::Sync),
+            b'H' => Ok(FeMessage::Flush),
             b'X' => Ok(FeMessage::Terminate),
             _ => Err(std::io::Error::new(
                 ErrorKind::InvalidInput,
@@ -82,6 +222,97 @@ impl FeMessage {
     }
 }
 
+impl FeStartupMessage {
+    // StartupMessage
+    // +-----------+----------------+----------+-----------+-----+------+
+    // | int32 len | int32 protocol | str name | str value | ... | '\0' |
+    // +-----------+----------------+----------+-----------+-----+------+
+    fn parse(mut buf: Bytes) -> Result<Self> {
+        let read_string = |buf: &mut Bytes| -> Result<String> {
+            String::from_utf8(read_cstr(buf)?.to_vec()).map_err(|err| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid UTF-8 sequence: {}", err),
+                )
+            })
+        };
+        let mut config = HashMap::new();
+        while buf.has_remaining() && buf[0] != 0 {
+            let name = read_string(&mut buf)?;
+            let value = read_string(&mut buf)?;
+            config.insert(name, value);
+        }
+        Ok(Self { config })
+    }
+}
+
+impl FeParseMessage {
+    // Parse
+    // +-----+-----------+----------------+---------+-------------+-------+-----+-------+
+    // | 'P' | int32 len | str stmt name  | str sql | int16 count | int32 | ... | int32 |
+    // +-----+-----------+----------------+---------+-------------+-------+-----+-------+
+    fn parse(mut buf: Bytes) -> Result<Self> {
+        let statement_name = read_cstr(&mut buf)?;
+        // Keep the trailing '\0' so that `get_sql` is shared with `FeQueryMessage`.
+        let sql_len = cstr_len(&buf)?;
+        let sql_bytes = buf.split_to(sql_len + 1);
+        let count = read_i16(&mut buf)?;
+        let type_ids = (0..count)
+            .map(|_| read_i32(&mut buf))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            statement_name,
+            sql_bytes,
+            type_ids,
+        })
+    }
+}
+
+impl FeBindMessage {
+    // Bind
+    // +-----+-----------+-----------------+----------------+-------------+-------+-----+
+    // | 'B' | int32 len | str portal name | str stmt name  | int16 count | int16 | ... |
+    // +-----+-----------+-----------------+----------------+-------------+-------+-----+
+    // +-------------+-----------+-------+-----+-------------+-------+-----+
+    // | int16 count | int32 len | bytes | ... | int16 count | int16 | ... |
+    // +-------------+-----------+-------+-----+-------------+-------+-----+
+    fn parse(mut buf: Bytes) -> Result<Self> {
+        let portal_name = read_cstr(&mut buf)?;
+        let statement_name = read_cstr(&mut buf)?;
+
+        let count = read_i16(&mut buf)?;
+        let param_format_codes = (0..count)
+            .map(|_| read_i16(&mut buf))
+            .collect::<Result<_>>()?;
+
+        let count = read_i16(&mut buf)?;
+        let params = (0..count)
+            .map(|_| {
+                let len = read_i32(&mut buf)?;
+                if len < 0 {
+                    Ok(None)
+                } else {
+                    ensure_remaining(&buf, len as usize)?;
+                    Ok(Some(buf.split_to(len as usize)))
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        let count = read_i16(&mut buf)?;
+        let result_format_codes = (0..count)
+            .map(|_| read_i16(&mut buf))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            portal_name,
+            statement_name,
+            param_format_codes,
+            params,
+            result_format_codes,
+        })
+    }
+}
+
 impl FeStartupMessage {
     /// Read startup message from the stream.
     pub async fn read(stream: &mut (impl AsyncRead + Unpin)) -> Result<FeMessage> {
@@ -94,10 +325,14 @@ impl FeStartupMessage {
         }
         match protocol_num {
             // code from: https://www.postgresql.org/docs/current/protocol-message-formats.html
-            196608 => Ok(FeMessage::Startup(FeStartupMessage {})),
+            196608 => Ok(FeMessage::Startup(FeStartupMessage::parse(Bytes::from(
+                payload,
+            ))?)),
             80877103 => Ok(FeMessage::Ssl),
             // Cancel request code.
-            80877102 => Ok(FeMessage::CancelQuery),
+            80877102 => Ok(FeMessage::CancelQuery(FeCancelMessage::parse(
+                Bytes::from(payload),
+            )?)),
             _ => Err(std::io::Error::new(
                 ErrorKind::InvalidInput,
                 format!(
@@ -114,14 +349,26 @@ impl FeStartupMessage {
 #[derive(Debug)]
 pub enum BeMessage<'a> {
     AuthenticationOk,
+    AuthenticationCleartextPassword,
+    AuthenticationMd5Password(&'a [u8; 4]),
+    /// The process id and the secret key of the session, used by client to cancel queries.
+    BackendKeyData(SessionId),
     CommandComplete(BeCommandCompleteMessage),
     // Single byte - used in response to SSLRequest/GSSENCRequest.
     EncryptionResponse,
     EmptyQueryResponse,
     DataRow(&'a Row),
+    /// A data row whose values are already encoded in the formats requested by `Bind`.
+    EncodedDataRow(&'a [Option<Bytes>]),
     ParameterStatus(BeParameterStatusMessage<'a>),
     ReadyForQuery,
     RowDescription(&'a [PgFieldDescriptor]),
+    ParameterDescription(&'a [TypeOid]),
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    NoData,
+    PortalSuspended,
     ErrorResponse(BoxedError),
 }
 
@@ -153,6 +400,38 @@ impl<'a> BeMessage<'a> {
                 buf.put_i32(0);
             }
 
+            // AuthenticationCleartextPassword
+            // +-----+----------+-----------+
+            // | 'R' | int32(8) | int32(3)  |
+            // +-----+----------+-----------+
+            BeMessage::AuthenticationCleartextPassword => {
+                buf.put_u8(b'R');
+                buf.put_i32(8);
+                buf.put_i32(3);
+            }
+
+            // AuthenticationMD5Password
+            // +-----+-----------+-----------+-------------+
+            // | 'R' | int32(12) | int32(5)  | byte4 salt  |
+            // +-----+-----------+-----------+-------------+
+            BeMessage::AuthenticationMd5Password(salt) => {
+                buf.put_u8(b'R');
+                buf.put_i32(12);
+                buf.put_i32(5);
+                buf.put_slice(&salt[..]);
+            }
+
+            // BackendKeyData
+            // +-----+-----------+-----------------+------------------+
+            // | 'K' | int32(12) | int32 processId | int32 secretKey  |
+            // +-----+-----------+-----------------+------------------+
+            BeMessage::BackendKeyData((process_id, secret_key)) => {
+                buf.put_u8(b'K');
+                buf.put_i32(12);
+                buf.put_i32(*process_id);
+                buf.put_i32(*secret_key);
+            }
+
             // ParameterStatus
             // +-----+-----------+----------+------+-----------+------+
             // | 'S' | int32 len | str name | '\0' | str value | '\0' |
@@ -223,29 +502,17 @@ impl<'a> BeMessage<'a> {
                 })?;
             }
 
-            // DataRow
-            // +-----+-----------+--------------+--------+-----+--------+
-            // | 'D' | int32 len | int16 colNum | column | ... | column |
-            // +-----+-----------+--------------+----+---+-----+--------+
-            //                                       |
-            //                          +-----------+v------+
-            //                          | int32 len | bytes |
-            //                          +-----------+-------+
+            // DataRow, see `write_data_row`.
             BeMessage::DataRow(vals) => {
-                buf.put_u8(b'D');
-                write_body(buf, |buf| {
-                    buf.put_u16(vals.len() as u16); // num of cols
-                    for val_opt in vals.values() {
-                        if let Some(val) = val_opt {
-                            buf.put_u32(val.len() as u32);
-                            buf.put_slice(val.as_bytes());
-                        } else {
-                            buf.put_i32(-1);
-                        }
-                    }
-                    Ok(())
-                })
-                .unwrap();
+                write_data_row(
+                    buf,
+                    vals.values()
+                        .iter()
+                        .map(|v| v.as_ref().map(|v| v.as_bytes())),
+                );
+            }
+            BeMessage::EncodedDataRow(vals) => {
+                write_data_row(buf, vals.iter().map(|v| v.as_deref()));
             }
             // RowDescription
             // +-----+-----------+--------------+-------+-----+-------+
@@ -276,6 +543,46 @@ impl<'a> BeMessage<'a> {
                     Ok(())
                 })?;
             }
+            // ParameterDescription
+            // +-----+-----------+-------------+-----------+-----+-----------+
+            // | 't' | int32 len | int16 count | int32 oid | ... | int32 oid |
+            // +-----+-----------+-------------+-----------+-----+-----------+
+            BeMessage::ParameterDescription(types) => {
+                buf.put_u8(b't');
+                write_body(buf, |buf| {
+                    buf.put_i16(types.len() as i16);
+                    for ty in types.iter() {
+                        buf.put_i32(ty.as_number());
+                    }
+                    Ok(())
+                })?;
+            }
+
+            // ParseComplete, BindComplete, CloseComplete, NoData and PortalSuspended
+            // +-----+----------+
+            // | tag | int32(4) |
+            // +-----+----------+
+            BeMessage::ParseComplete => {
+                buf.put_u8(b'1');
+                buf.put_i32(4);
+            }
+            BeMessage::BindComplete => {
+                buf.put_u8(b'2');
+                buf.put_i32(4);
+            }
+            BeMessage::CloseComplete => {
+                buf.put_u8(b'3');
+                buf.put_i32(4);
+            }
+            BeMessage::NoData => {
+                buf.put_u8(b'n');
+                buf.put_i32(4);
+            }
+            BeMessage::PortalSuspended => {
+                buf.put_u8(b's');
+                buf.put_i32(4);
+            }
+
             // ReadyForQuery
             // +-----+----------+---------------------------+
             // | 'Z' | int32(5) | byte1(transaction status) |
@@ -366,6 +673,31 @@ where
     Ok(())
 }
 
+// DataRow
+// +-----+-----------+--------------+--------+-----+--------+
+// | 'D' | int32 len | int16 colNum | column | ... | column |
+// +-----+-----------+--------------+----+---+-----+--------+
+//                                       |
+//                          +-----------+v------+
+//                          | int32 len | bytes |
+//                          +-----------+-------+
+fn write_data_row<'a>(buf: &mut BytesMut, vals: impl ExactSizeIterator<Item = Option<&'a [u8]>>) {
+    buf.put_u8(b'D');
+    write_body(buf, |buf| {
+        buf.put_u16(vals.len() as u16); // num of cols
+        for val_opt in vals {
+            if let Some(val) = val_opt {
+                buf.put_u32(val.len() as u32);
+                buf.put_slice(val);
+            } else {
+                buf.put_i32(-1);
+            }
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
 /// Safe write of s into buf as cstring (String in the protocol).
 fn write_cstr(buf: &mut BytesMut, s: &[u8]) -> Result<()> {
     if s.contains(&0) {
@@ -379,11 +711,69 @@ fn write_cstr(buf: &mut BytesMut, s: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Converts a null-terminated string to `&str`.
+fn cstr_to_str(b: &Bytes) -> Result<&str> {
+    match CStr::from_bytes_with_nul(b) {
+        Ok(cstr) => cstr.to_str().map_err(|err| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid UTF-8 sequence: {}", err),
+            )
+        }),
+        Err(err) => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Input end error: {}", err),
+        )),
+    }
+}
+
+fn ensure_remaining(buf: &Bytes, len: usize) -> Result<()> {
+    if buf.remaining() < len {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Unexpected end of message",
+        ));
+    }
+    Ok(())
+}
+
+fn read_u8(buf: &mut Bytes) -> Result<u8> {
+    ensure_remaining(buf, 1)?;
+    Ok(buf.get_u8())
+}
+
+fn read_i16(buf: &mut Bytes) -> Result<i16> {
+    ensure_remaining(buf, 2)?;
+    Ok(buf.get_i16())
+}
+
+fn read_i32(buf: &mut Bytes) -> Result<i32> {
+    ensure_remaining(buf, 4)?;
+    Ok(buf.get_i32())
+}
+
+/// Returns the length of the null-terminated string at the front of `buf`, excluding the '\0'.
+fn cstr_len(buf: &Bytes) -> Result<usize> {
+    buf.iter()
+        .position(|b| *b == 0)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Missing null terminator of string"))
+}
+
+/// Reads a null-terminated string from the front of `buf`, without the '\0'.
+fn read_cstr(buf: &mut Bytes) -> Result<Bytes> {
+    let len = cstr_len(buf)?;
+    let s = buf.split_to(len);
+    buf.advance(1);
+    Ok(s)
+}
+
 #[cfg(test)]
 mod tests {
-    use bytes::Bytes;
+    use bytes::{BufMut, Bytes, BytesMut};
 
-    use crate::pg_message::FeQueryMessage;
+    use crate::pg_message::{
+        BeMessage, FeCancelMessage, FeMessage, FeQueryMessage, FeStartupMessage,
+    };
 
     #[tokio::test]
     async fn test_get_sql() {
@@ -396,4 +786,33 @@ mod tests {
         };
         assert!(fe.get_sql().is_err(), "{}", true);
     }
+
+    #[tokio::test]
+    async fn test_cancel_request() {
+        let mut buf = BytesMut::new();
+        buf.put_i32(16);
+        buf.put_i32(80877102);
+        buf.put_i32(42);
+        buf.put_i32(-7);
+        let msg = FeStartupMessage::read(&mut &buf[..]).await.unwrap();
+        match msg {
+            FeMessage::CancelQuery(FeCancelMessage {
+                target_process_id,
+                target_secret_key,
+            }) => assert_eq!((target_process_id, target_secret_key), (42, -7)),
+            _ => unreachable!(),
+        }
+
+        assert!(FeCancelMessage::parse(Bytes::from(vec![0; 4])).is_err());
+    }
+
+    #[test]
+    fn test_backend_key_data() {
+        let mut buf = BytesMut::new();
+        BeMessage::write(&mut buf, &BeMessage::BackendKeyData((42, -7))).unwrap();
+        assert_eq!(
+            &buf[..],
+            &[b'K', 0, 0, 0, 12, 0, 0, 0, 42, 255, 255, 255, 249]
+        );
+    }
 }
@@ -12,19 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::{Error as IoError, Result};
+use std::collections::HashMap;
+use std::io::{Error as IoError, ErrorKind, Result};
 use std::sync::Arc;
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
-use crate::error::PsqlError;
+use crate::pg_extended::{PgPortal, PgStatement};
+use crate::pg_field_descriptor::TypeOid;
 use crate::pg_message::{
-    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeMessage, FeQueryMessage,
-    FeStartupMessage,
+    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeBindMessage, FeCloseMessage,
+    FeDescribeMessage, FeExecuteMessage, FeMessage, FeParseMessage, FePasswordMessage,
+    FeQueryMessage, FeStartupMessage,
 };
 use crate::pg_response::PgResponse;
-use crate::pg_server::{Session, SessionManager};
+use crate::pg_server::{BoxedError, Session, SessionManager, UserAuthenticator};
 
 /// The state machine for each psql connection.
 /// Read pg messages from tcp stream and write results back.
@@ -43,11 +46,20 @@ where
 
     session_mgr: Arc<SM>,
     session: Option<Arc<SM::Session>>,
+
+    /// Prepared statements created by `Parse`. The unnamed statement has an empty name.
+    prepared_statements: HashMap<String, PgStatement>,
+    /// Portals created by `Bind`. The unnamed portal has an empty name.
+    portals: HashMap<String, PgPortal>,
+    /// After an error in the extended query protocol, all messages are discarded until `Sync`.
+    ignore_till_sync: bool,
 }
 
 /// States flow happened from top to down.
 enum PgProtocolState {
     Startup,
+    /// Waiting for the password of the user after startup.
+    Authenticating,
     Regular,
 }
 
@@ -64,6 +76,9 @@ where
             buf_out: BytesMut::with_capacity(10 * 1024),
             session_mgr,
             session: None,
+            prepared_statements: HashMap::new(),
+            portals: HashMap::new(),
+            ignore_till_sync: false,
         }
     }
 
@@ -88,6 +103,18 @@ where
                 return Ok(false);
             }
         };
+        if self.ignore_till_sync && !matches!(msg, FeMessage::Sync | FeMessage::Terminate) {
+            return Ok(false);
+        }
+        if matches!(self.state, PgProtocolState::Authenticating)
+            && !matches!(msg, FeMessage::Password(_) | FeMessage::Terminate)
+        {
+            self.write_message(&BeMessage::ErrorResponse(invalid_input(
+                "expected password response".to_string(),
+            )))
+            .await?;
+            return Ok(true);
+        }
         match msg {
             FeMessage::Ssl => {
                 self.write_message_no_flush(&BeMessage::EncryptionResponse)
@@ -97,19 +124,44 @@ where
                     })?;
             }
             FeMessage::Startup(msg) => {
-                self.process_startup_msg(msg).map_err(|e| {
+                if let Err(e) = self.process_startup_msg(msg) {
                     tracing::error!("failed to set up pg session: {}", e);
-                    e
-                })?;
-                self.state = PgProtocolState::Regular;
+                    self.write_message(&BeMessage::ErrorResponse(e)).await?;
+                    return Ok(true);
+                }
+            }
+            FeMessage::Password(msg) => {
+                if let Err(e) = self.process_password_msg(msg) {
+                    tracing::error!("failed to authenticate: {}", e);
+                    self.write_message(&BeMessage::ErrorResponse(e)).await?;
+                    return Ok(true);
+                }
             }
             FeMessage::Query(query_msg) => {
                 self.process_query_msg(query_msg).await?;
             }
-            FeMessage::CancelQuery => {
-                self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(
-                    PsqlError::cancel(),
-                )))?;
+            FeMessage::Parse(_)
+            | FeMessage::Bind(_)
+            | FeMessage::Describe(_)
+            | FeMessage::Execute(_)
+            | FeMessage::Close(_) => {
+                if let Err(e) = self.process_extended_query_msg(msg).await {
+                    self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
+                    self.ignore_till_sync = true;
+                }
+            }
+            FeMessage::Sync => {
+                self.ignore_till_sync = false;
+                self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
+            }
+            // Pending messages are flushed after every message.
+            FeMessage::Flush => {}
+            FeMessage::CancelQuery(msg) => {
+                // A cancel request comes from a new connection, which is closed without any
+                // response, same as PG.
+                self.session_mgr
+                    .cancel_queries_in_session((msg.target_process_id, msg.target_secret_key));
+                return Ok(true);
             }
             FeMessage::Terminate => {
                 self.process_terminate();
@@ -122,14 +174,58 @@ where
     async fn read_message(&mut self) -> Result<FeMessage> {
         match self.state {
             PgProtocolState::Startup => FeStartupMessage::read(&mut self.stream).await,
-            PgProtocolState::Regular => FeMessage::read(&mut self.stream).await,
+            PgProtocolState::Authenticating | PgProtocolState::Regular => {
+                FeMessage::read(&mut self.stream).await
+            }
         }
     }
 
-    fn process_startup_msg(&mut self, _msg: FeStartupMessage) -> Result<()> {
+    fn process_startup_msg(
+        &mut self,
+        msg: FeStartupMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let user_name = msg.config.get("user").ok_or_else(|| {
+            invalid_input("user name is not specified in startup message".to_string())
+        })?;
         // TODO: Replace `DEFAULT_DATABASE_NAME` with true database name in `FeStartupMessage`.
-        self.session = Some(self.session_mgr.connect("dev").map_err(IoError::other)?);
+        let session = self.session_mgr.connect("dev", user_name)?;
+        self.session = Some(session.clone());
+        match session.user_authenticator() {
+            UserAuthenticator::None => {
+                self.write_message_no_flush(&BeMessage::AuthenticationOk)?;
+                self.write_startup_parameters()?;
+                self.state = PgProtocolState::Regular;
+            }
+            UserAuthenticator::ClearText(_) => {
+                self.write_message_no_flush(&BeMessage::AuthenticationCleartextPassword)?;
+                self.state = PgProtocolState::Authenticating;
+            }
+            UserAuthenticator::Md5WithSalt { salt, .. } => {
+                self.write_message_no_flush(&BeMessage::AuthenticationMd5Password(salt))?;
+                self.state = PgProtocolState::Authenticating;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_password_msg(
+        &mut self,
+        msg: FePasswordMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        if !matches!(self.state, PgProtocolState::Authenticating) {
+            return Err(invalid_input("unexpected password message".to_string()));
+        }
+        let authenticator = self.session.as_ref().unwrap().user_authenticator();
+        authenticator.authenticate(&msg.password)?;
         self.write_message_no_flush(&BeMessage::AuthenticationOk)?;
+        self.write_startup_parameters()?;
+        self.state = PgProtocolState::Regular;
+        Ok(())
+    }
+
+    /// Reports the run-time parameters and the key to cancel queries to client, and gets ready for
+    /// queries, which is the last step of startup.
+    fn write_startup_parameters(&mut self) -> Result<()> {
         self.write_message_no_flush(&BeMessage::ParameterStatus(
             BeParameterStatusMessage::ClientEncoding("utf8"),
         ))?;
@@ -139,6 +235,8 @@ where
         self.write_message_no_flush(&BeMessage::ParameterStatus(
             BeParameterStatusMessage::ServerVersion("9.5.0"),
         ))?;
+        let session_id = self.session.as_ref().unwrap().id();
+        self.write_message_no_flush(&BeMessage::BackendKeyData(session_id))?;
         self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
         Ok(())
     }
@@ -148,6 +246,9 @@ where
     }
 
     async fn process_query_msg(&mut self, query: FeQueryMessage) -> Result<()> {
+        // A simple query destroys the unnamed statement and portal, same as PG.
+        self.prepared_statements.remove("");
+        self.portals.remove("");
         match query.get_sql() {
             Ok(sql) => {
                 tracing::trace!("receive query: {}", sql);
@@ -202,6 +303,194 @@ where
         Ok(())
     }
 
+    async fn process_extended_query_msg(
+        &mut self,
+        msg: FeMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        match msg {
+            FeMessage::Parse(msg) => self.process_parse_msg(msg),
+            FeMessage::Bind(msg) => self.process_bind_msg(msg),
+            FeMessage::Describe(msg) => self.process_describe_msg(msg),
+            FeMessage::Execute(msg) => self.process_execute_msg(msg).await,
+            FeMessage::Close(msg) => self.process_close_msg(msg),
+            _ => unreachable!(),
+        }
+    }
+
+    fn process_parse_msg(&mut self, msg: FeParseMessage) -> std::result::Result<(), BoxedError> {
+        let name = bytes_to_string(&msg.statement_name)?;
+        if !name.is_empty() && self.prepared_statements.contains_key(&name) {
+            return Err(invalid_input(format!(
+                "prepared statement \"{}\" already exists",
+                name
+            )));
+        }
+        let sql = msg.get_sql()?.to_string();
+        tracing::trace!("receive parse: {}", sql);
+        let param_types = msg
+            .type_ids
+            .iter()
+            .map(|oid| match *oid {
+                0 => Ok(None),
+                oid => TypeOid::from_number(oid)
+                    .map(Some)
+                    .ok_or_else(|| invalid_input(format!("unsupported parameter type {}", oid))),
+            })
+            .collect::<std::result::Result<Vec<_>, BoxedError>>()?;
+
+        let session = self.session.clone().unwrap();
+        let (param_types, row_description) = session.describe_statement(&sql, &param_types)?;
+        self.prepared_statements
+            .insert(name, PgStatement::new(sql, param_types, row_description));
+        self.write_message_no_flush(&BeMessage::ParseComplete)?;
+        Ok(())
+    }
+
+    fn process_bind_msg(&mut self, msg: FeBindMessage) -> std::result::Result<(), BoxedError> {
+        let statement_name = bytes_to_string(&msg.statement_name)?;
+        let portal_name = bytes_to_string(&msg.portal_name)?;
+        let statement = self
+            .prepared_statements
+            .get(&statement_name)
+            .ok_or_else(|| {
+                invalid_input(format!(
+                    "prepared statement \"{}\" does not exist",
+                    statement_name
+                ))
+            })?;
+        let portal = statement.bind(
+            &msg.params,
+            &msg.param_format_codes,
+            &msg.result_format_codes,
+        )?;
+        self.portals.insert(portal_name, portal);
+        self.write_message_no_flush(&BeMessage::BindComplete)?;
+        Ok(())
+    }
+
+    fn process_describe_msg(
+        &mut self,
+        msg: FeDescribeMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let name = bytes_to_string(&msg.name)?;
+        let (param_types, row_description) = match msg.kind {
+            b'S' => {
+                let statement = self.prepared_statements.get(&name).ok_or_else(|| {
+                    invalid_input(format!("prepared statement \"{}\" does not exist", name))
+                })?;
+                (
+                    Some(statement.param_types().to_vec()),
+                    statement.row_description().to_vec(),
+                )
+            }
+            b'P' => {
+                let portal = self
+                    .portals
+                    .get(&name)
+                    .ok_or_else(|| invalid_input(format!("portal \"{}\" does not exist", name)))?;
+                (None, portal.row_description().to_vec())
+            }
+            kind => {
+                return Err(invalid_input(format!(
+                    "invalid describe message type {}",
+                    kind
+                )))
+            }
+        };
+        if let Some(param_types) = param_types {
+            self.write_message_no_flush(&BeMessage::ParameterDescription(&param_types))?;
+        }
+        if row_description.is_empty() {
+            self.write_message_no_flush(&BeMessage::NoData)?;
+        } else {
+            self.write_message_no_flush(&BeMessage::RowDescription(&row_description))?;
+        }
+        Ok(())
+    }
+
+    async fn process_execute_msg(
+        &mut self,
+        msg: FeExecuteMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let name = bytes_to_string(&msg.portal_name)?;
+        let mut portal = self
+            .portals
+            .remove(&name)
+            .ok_or_else(|| invalid_input(format!("portal \"{}\" does not exist", name)))?;
+        let result = self.execute_portal(&mut portal, msg.max_rows).await;
+        // The portal can be executed again to fetch more rows unless it has been exhausted.
+        self.portals.insert(name, portal);
+        result
+    }
+
+    async fn execute_portal(
+        &mut self,
+        portal: &mut PgPortal,
+        max_rows: i32,
+    ) -> std::result::Result<(), BoxedError> {
+        if portal.result().is_none() {
+            let session = self.session.clone().unwrap();
+            let res = session
+                .run_statement_with_params(portal.sql(), portal.param_types(), portal.params())
+                .await?;
+            portal.set_result(res);
+        }
+        let res = portal.result().unwrap();
+        let stmt_type = res.get_stmt_type();
+        let notice = res.get_notice();
+        let is_empty = res.is_empty();
+        let is_query = res.is_query();
+        let rows_cnt = res.get_effected_rows_cnt();
+
+        if is_empty {
+            self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
+        } else if is_query {
+            let rows = portal.fetch(max_rows.max(0) as usize)?;
+            for row in &rows {
+                self.write_message_no_flush(&BeMessage::EncodedDataRow(row))?;
+            }
+            if portal.is_exhausted() {
+                self.write_message_no_flush(&BeMessage::CommandComplete(
+                    BeCommandCompleteMessage {
+                        stmt_type,
+                        notice,
+                        rows_cnt: portal.row_offset() as i32,
+                    },
+                ))?;
+            } else {
+                self.write_message_no_flush(&BeMessage::PortalSuspended)?;
+            }
+        } else {
+            self.write_message_no_flush(&BeMessage::CommandComplete(BeCommandCompleteMessage {
+                stmt_type,
+                notice,
+                rows_cnt,
+            }))?;
+        }
+        Ok(())
+    }
+
+    fn process_close_msg(&mut self, msg: FeCloseMessage) -> std::result::Result<(), BoxedError> {
+        let name = bytes_to_string(&msg.name)?;
+        // Closing a nonexistent statement or portal is not an error.
+        match msg.kind {
+            b'S' => {
+                self.prepared_statements.remove(&name);
+            }
+            b'P' => {
+                self.portals.remove(&name);
+            }
+            kind => {
+                return Err(invalid_input(format!(
+                    "invalid close message type {}",
+                    kind
+                )))
+            }
+        }
+        self.write_message_no_flush(&BeMessage::CloseComplete)?;
+        Ok(())
+    }
+
     fn is_terminate(&self) -> bool {
         self.is_terminate
     }
@@ -223,3 +512,11 @@ where
         Ok(())
     }
 }
+
+fn invalid_input(msg: String) -> BoxedError {
+    Box::new(IoError::new(ErrorKind::InvalidInput, msg))
+}
+
+fn bytes_to_string(b: &Bytes) -> std::result::Result<String, BoxedError> {
+    Ok(String::from_utf8(b.to_vec())?)
+}
@@ -32,14 +32,21 @@ pub enum StatementType {
     CREATE_TABLE,
     CREATE_MATERIALIZED_VIEW,
     CREATE_SOURCE,
+    CREATE_SINK,
     CREATE_DATABASE,
     CREATE_SCHEMA,
+    CREATE_USER,
     DESCRIBE_TABLE,
     DROP_TABLE,
     DROP_MATERIALIZED_VIEW,
     DROP_SOURCE,
+    DROP_SINK,
     DROP_SCHEMA,
     DROP_DATABASE,
+    DROP_USER,
+    ALTER_USER,
+    GRANT_PRIVILEGE,
+    REVOKE_PRIVILEGE,
     // Introduce ORDER_BY statement type cuz Calcite unvalidated AST has SqlKind.ORDER_BY. Note
     // that Statement Type is not designed to be one to one mapping with SqlKind.
     ORDER_BY,
@@ -144,6 +151,11 @@ impl PgResponse {
         self.row_desc.clone()
     }
 
+    /// Returns the number of rows in the result set.
+    pub fn row_count(&self) -> usize {
+        self.values.len()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Row> + '_ {
         self.values.iter()
     }
@@ -19,17 +19,27 @@ use std::sync::Arc;
 
 use tokio::net::{TcpListener, TcpStream};
 
+use crate::error::PsqlError;
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_protocol::PgProtocol;
 use crate::pg_response::PgResponse;
 
 pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
 
+/// The process id and the secret key of a session. They are sent to the client in
+/// `BackendKeyData` at startup, and identify the session in cancel requests.
+pub type SessionId = (i32, i32);
+
 /// The interface for a database system behind pgwire protocol.
 /// We can mock it for testing purpose.
 pub trait SessionManager: Send + Sync + 'static {
     type Session: Session;
 
-    fn connect(&self, database: &str) -> Result<Arc<Self::Session>, BoxedError>;
+    fn connect(&self, database: &str, user_name: &str) -> Result<Arc<Self::Session>, BoxedError>;
+
+    /// Cancels the running queries of the session, which is requested by another connection.
+    /// Unknown sessions are ignored.
+    fn cancel_queries_in_session(&self, session_id: SessionId);
 }
 
 /// A psql connection. Each connection binds with a database. Switching database will need to
@@ -37,6 +47,69 @@ pub trait SessionManager: Send + Sync + 'static {
 #[async_trait::async_trait]
 pub trait Session: Send + Sync {
     async fn run_statement(self: Arc<Self>, sql: &str) -> Result<PgResponse, BoxedError>;
+
+    /// How to authenticate the user of this session at startup.
+    fn user_authenticator(&self) -> &UserAuthenticator;
+
+    /// The id that clients use to cancel the queries of this session.
+    fn id(&self) -> SessionId;
+
+    /// Infers the types of the parameters `$1`, `$2`, ... and the result columns of `sql`, which
+    /// is used to prepare a statement in the extended query protocol. `param_types` are the types
+    /// specified by the client, where `None` means to infer from the context.
+    fn describe_statement(
+        &self,
+        sql: &str,
+        param_types: &[Option<TypeOid>],
+    ) -> Result<(Vec<TypeOid>, Vec<PgFieldDescriptor>), BoxedError>;
+
+    /// Runs `sql` with the parameter `$i` bound to `params[i - 1]` of type `param_types[i - 1]`.
+    /// Parameters are given in text format.
+    async fn run_statement_with_params(
+        self: Arc<Self>,
+        sql: &str,
+        param_types: &[TypeOid],
+        params: &[Option<String>],
+    ) -> Result<PgResponse, BoxedError>;
+}
+
+/// The way to authenticate a user at startup.
+#[derive(Debug, Clone)]
+pub enum UserAuthenticator {
+    /// No need to authenticate.
+    None,
+    /// The password is sent by client in clear text.
+    ClearText(Vec<u8>),
+    /// The password is sent by client as `"md5" + md5(encrypted_password + salt)`, where
+    /// `encrypted_password` is the hex of `md5(password + user_name)` stored in the catalog.
+    Md5WithSalt {
+        encrypted_password: Vec<u8>,
+        salt: [u8; 4],
+    },
+}
+
+impl UserAuthenticator {
+    /// Checks the password sent by client in `PasswordMessage`.
+    pub fn authenticate(&self, password: &[u8]) -> Result<(), PsqlError> {
+        let success = match self {
+            UserAuthenticator::None => true,
+            UserAuthenticator::ClearText(text) => password == text.as_slice(),
+            UserAuthenticator::Md5WithSalt {
+                encrypted_password,
+                salt,
+            } => {
+                let mut salted = encrypted_password.clone();
+                salted.extend_from_slice(salt);
+                let expected = format!("md5{:x}", md5::compute(salted));
+                password == expected.as_bytes()
+            }
+        };
+        if success {
+            Ok(())
+        } else {
+            Err(PsqlError::password_error())
+        }
+    }
 }
 
 /// Binds a Tcp listener at `addr`. Spawn a coroutine to serve every new connection.